@@ -0,0 +1,77 @@
+use std::hint::black_box;
+
+use blackjack_tui::{
+    config::Config,
+    game::{Game, Insurance},
+    rules::Rules,
+    simulate::{self, Strategy},
+    widgets::{Hand, Player, Shoe},
+};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+fn hand_evaluation(c: &mut Criterion) {
+    let mut deck = Shoe::new(6);
+    let hands: Vec<Hand<Player>> = (0..64)
+        .map(|_| {
+            let mut hand = Hand::new([deck.draw(), deck.draw()]);
+            hand.hit(&mut deck);
+            hand
+        })
+        .collect();
+
+    c.bench_function("hand evaluation", |b| {
+        b.iter(|| {
+            for hand in &hands {
+                black_box((hand.count_value(), hand.is_soft(), hand.is_bust()));
+            }
+        })
+    });
+}
+
+fn settlement(c: &mut Criterion) {
+    let config = Config::default();
+    c.bench_function("settlement", |b| {
+        b.iter_batched(
+            || {
+                let mut game = Game::new(&config);
+                game.place_bet(config.bet_unit).unwrap();
+                // against an ace insurance is answered first, and a natural is settled as
+                // it's dealt, with nothing to hold
+                if game.insurance == Insurance::Offered {
+                    game.insure(false).unwrap();
+                }
+                if game.is_player_turn() {
+                    game.hold().unwrap();
+                }
+                game
+            },
+            |mut game| {
+                while game.is_dealer_turn() {
+                    game.dealer_step();
+                }
+                black_box(game.payout)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn round_throughput(c: &mut Criterion) {
+    const HANDS: u64 = 10_000;
+
+    let mut group = c.benchmark_group("rounds");
+    group.throughput(Throughput::Elements(HANDS));
+    for (name, rules) in Rules::PRESETS {
+        let config = Config {
+            rules,
+            ..Config::default()
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| simulate::simulate(&config, HANDS, &Strategy::Basic, None))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, hand_evaluation, settlement, round_throughput);
+criterion_main!(benches);
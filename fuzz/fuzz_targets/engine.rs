@@ -0,0 +1,175 @@
+//! Feeds random action sequences to the game engine and checks that it never panics,
+//! never refuses nor accepts an action it shouldn't, and never makes or loses chips
+//! outside of settling a hand.
+#![no_main]
+
+use blackjack_tui::{
+    config::Config,
+    game::{Game, GameState, Insurance, SPLIT_HANDS},
+    rules::Rules,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let config = Config {
+        bankroll: 100,
+        rules: Rules {
+            surrender: true,
+            double_after_split: true,
+            ..Rules::default()
+        },
+        ..Config::default()
+    };
+    let mut game = Game::new(&config);
+
+    for &byte in data {
+        let bankroll = game.bankroll;
+        let was_settled = matches!(game.state, GameState::HandScoreScreen(_));
+        // a move on the hand, before insurance is answered, has to wait for it
+        let can_move = game.is_player_turn() && game.insurance != Insurance::Offered;
+        let first_two = game.player_hand.cards().len() == 2;
+        let hands = 1 + game.split_hands.len() + game.split_cards.len();
+        // another bet the size of the first, to double or split on
+        let covered = u64::from(bankroll)
+            >= u64::from(game.stake()) + u64::from(insured(&game)) + u64::from(game.bet);
+        let before = illegal_snapshot(&game);
+
+        let legal = match byte % 9 {
+            0 => {
+                let bet = u32::from(byte / 8);
+                let legal = matches!(game.state, GameState::Betting) && bet > 0 && bet <= bankroll;
+                assert_eq!(
+                    game.place_bet(bet).is_ok(),
+                    legal,
+                    "bet {bet} from {bankroll}"
+                );
+                if legal {
+                    assert_eq!(game.bet, bet);
+                }
+                legal
+            }
+            1 => {
+                let legal = can_move;
+                assert_eq!(game.hit().is_ok(), legal, "hit refused or taken wrongly");
+                legal
+            }
+            2 => {
+                let legal = can_move;
+                assert_eq!(game.hold().is_ok(), legal, "hold refused or taken wrongly");
+                legal
+            }
+            3 => {
+                let after_split = !game.is_split() || game.rules.double_after_split;
+                let legal = can_move && first_two && after_split && covered;
+                let waiting = !game.split_cards.is_empty();
+                assert_eq!(
+                    game.double().is_ok(),
+                    legal,
+                    "double refused or taken wrongly"
+                );
+                // the doubled hand is done, and play moves on to the next hand of a split
+                if legal && waiting {
+                    assert!(game.split_hands.last().is_some_and(|split| split.doubled));
+                } else if legal {
+                    assert!(game.doubled && !game.is_player_turn());
+                }
+                legal
+            }
+            4 => {
+                let legal = can_move && first_two && !game.is_split();
+                assert_eq!(
+                    game.surrender().is_ok(),
+                    legal,
+                    "surrender refused or taken wrongly"
+                );
+                legal
+            }
+            5 => {
+                let take = byte & 8 != 0;
+                let covered = bankroll >= game.bet + game.bet / 2 || !take;
+                let legal = game.insurance == Insurance::Offered && covered;
+                assert_eq!(
+                    game.insure(take).is_ok(),
+                    legal,
+                    "insurance refused or taken wrongly"
+                );
+                if legal {
+                    assert_ne!(game.insurance, Insurance::Offered);
+                }
+                legal
+            }
+            6 => {
+                let pair = game.player_hand.is_pair();
+                let legal = can_move && first_two && pair && hands < SPLIT_HANDS && covered;
+                assert_eq!(
+                    game.split().is_ok(),
+                    legal,
+                    "split refused or taken wrongly"
+                );
+                if legal {
+                    assert_eq!(game.split_hands.len() + game.split_cards.len(), hands);
+                }
+                legal
+            }
+            7 => {
+                let legal = game.is_dealer_turn();
+                game.dealer_step();
+                legal
+            }
+            _ => {
+                game.new_hand();
+                if was_settled {
+                    assert!(matches!(game.state, GameState::Betting));
+                }
+                was_settled
+            }
+        };
+        if !legal {
+            assert_eq!(illegal_snapshot(&game), before, "illegal move {}", byte % 9);
+        }
+
+        // chips only move when a hand is settled, by exactly its payout
+        let settled = !was_settled && matches!(game.state, GameState::HandScoreScreen(_));
+        let expected = if settled {
+            i64::from(bankroll) + game.payout
+        } else {
+            i64::from(bankroll)
+        };
+        assert_eq!(i64::from(game.bankroll), expected, "bankroll not conserved");
+        if settled {
+            // at worst every bet lost along with the insurance, and at best a blackjack
+            // paid 2 to 1 or every bet won along with it
+            let most = u64::from(game.stake()) * 2 + u64::from(game.bet / 2);
+            assert!(game.payout.unsigned_abs() <= most);
+        }
+        assert!(game.bet <= game.bankroll || !matches!(game.state, GameState::Betting));
+    }
+});
+
+/// Chips staked on insurance on the hand in play.
+fn insured(game: &Game) -> u32 {
+    match game.insurance {
+        Insurance::Taken { stake } => stake,
+        _ => 0,
+    }
+}
+
+/// What an illegal action must leave untouched.
+fn illegal_snapshot(game: &Game) -> (String, u32, u32, Vec<usize>, usize, usize, Insurance, bool) {
+    let split = game
+        .split_hands
+        .iter()
+        .map(|split| split.hand.cards().len());
+    (
+        format!("{:?}", game.state),
+        game.bankroll,
+        game.bet,
+        split
+            .chain([game.player_hand.cards().len(), game.split_cards.len()])
+            .collect(),
+        game.dealer_hand.cards().len(),
+        game.deck.remaining(),
+        game.insurance,
+        game.doubled,
+    )
+}
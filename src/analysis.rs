@@ -0,0 +1,78 @@
+use crate::{game::*, strategy::Action};
+
+/// Expected value of each legal action, per unit bet, estimated by playing the hand out
+/// many times against the cards the player hasn't seen.
+pub fn action_evs(game: &Game, trials: u32) -> Vec<(Action, f64)> {
+    game.legal_actions()
+        .into_iter()
+        .filter(|action| !matches!(action, Action::Bet(_)))
+        .map(|action| {
+            let total: f64 = (0..trials).map(|_| play_out(game, action)).sum();
+            (action, total / f64::from(trials.max(1)))
+        })
+        .collect()
+}
+
+/// Expected value of insurance per chip staked on it, when `tens` is the chance the hole
+/// card is a ten. It pays 2 to 1, so it only comes out ahead with more than a third tens.
+pub fn insurance_ev(tens: f64) -> f64 {
+    2.0 * tens - (1.0 - tens)
+}
+
+/// Play a copy of the hand to the end, taking `action` first and following basic strategy
+/// after, and return the result per unit bet.
+fn play_out(game: &Game, action: Action) -> f64 {
+    let mut game = game.clone();
+
+    // the hole card is as unknown as the rest of the deck, unless it's dealt face up
+    let hidden = game.hole_card_hidden();
+    game.deck.restock(game.unseen_cards());
+    game.deck.shuffle(&mut rand::thread_rng());
+    if hidden {
+        let hole_card = game.deck.draw();
+        game.dealer_hand.replace_hole_card(hole_card);
+    }
+
+    let mut action = action;
+    while matches!(game.state, GameState::PlayingHand) {
+        if game.is_dealer_turn() {
+            game.dealer_step();
+            continue;
+        }
+        // it's the player's turn, and basic strategy only plays what's allowed
+        let _ = game.play(action);
+        action = game.hint().0;
+    }
+    game.payout as f64 / f64::from(game.bet.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, shuffle::Shuffler};
+
+    #[test]
+    fn standing_on_twenty_beats_hitting_it() {
+        let config = Config {
+            shuffle: Shuffler::stacked("10S 10H 7D 9C"),
+            ..Config::default()
+        };
+        let mut game = Game::new(&config);
+        game.place_bet(10).unwrap();
+        let evs = action_evs(&game, 200);
+        let ev = |taken| evs.iter().find(|(action, _)| *action == taken).unwrap().1;
+        assert!(ev(Action::Stand) > 0.0, "{evs:?}");
+        assert!(ev(Action::Hit) < ev(Action::Stand), "{evs:?}");
+        // played on copies, so the hand itself is untouched
+        assert_eq!(game.player_hand.cards().len(), 2);
+        assert!(game.is_player_turn());
+    }
+
+    #[test]
+    fn insurance_breaks_even_on_a_third_tens() {
+        assert!(insurance_ev(1.0 / 3.0).abs() < 1e-9);
+        // a full deck has four tens in thirteen cards
+        assert!(insurance_ev(4.0 / 13.0) < 0.0);
+        assert_eq!(insurance_ev(0.5), 0.5);
+    }
+}
@@ -0,0 +1,1798 @@
+use std::{
+    borrow::Cow,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::{
+    layout::{Flex, Margin},
+    prelude::{Backend, Color, Constraint, Frame, Layout, Line, Modifier, Style, Stylize},
+    widgets::{Block, Clear, List, ListItem, Paragraph},
+    Terminal,
+};
+
+#[cfg(feature = "serde")]
+use crate::events::EventLog;
+use crate::{
+    analysis,
+    autosave::Autosave,
+    betting::{self, AdviceTracker},
+    challenge::{Challenge, Leaderboard, SpeedClock},
+    chatter::Chatter,
+    clipboard,
+    config::Config,
+    counting::{self, Composition},
+    drill::{CountQuiz, DrillStats},
+    events::{GameEvent, Subscriber},
+    explain::Explanation,
+    game::*,
+    history::{self, History, Round},
+    jackpot::Jackpot,
+    keyboard,
+    limits::{Break, Limits},
+    money::Money,
+    rewind::Rewind,
+    shuffle::{self, Shuffler},
+    simulate::{self, Strategy},
+    sound::Sounds,
+    stats::{self, GameStats, SessionStats, SessionSummary},
+    strategy::{Action, ChartRow},
+    suspend,
+    term::{self, Event, KeyCode, KeyEvent},
+    trainer::{ActionStats, Trainer},
+    widgets::{
+        ActionChart, AnalysisPanel, BetScreen, CompositionPanel, CountPanel, KeyedHand,
+        MistakeHeatmap, NextCardPanel, Notice, StatsScreen, StatsTab, StrategyChart,
+    },
+};
+
+/// Chart-shaped overlays drawn over the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Chart {
+    Strategy,
+    Mistakes,
+    /// how often the player hits in each cell, this session's or every session's
+    Actions {
+        session: bool,
+    },
+}
+
+/// Tips shown in turn under the attract mode's demo.
+const TIPS: [&str; 8] = [
+    "h shows the basic strategy play for your hand",
+    "c opens the strategy chart for the table's rules",
+    "n shows the Hi-Lo running and true count",
+    "d shows what's left in the shoe, and x saves its order",
+    "e shows the expected value of hitting and standing",
+    "p hands your play to the autopilot, leaving you the bets",
+    "w steps back through the last hand",
+    "s opens your statistics, for this session and every one",
+];
+/// How long each tip stays up.
+const TIP_LENGTH: Duration = Duration::from_secs(8);
+
+/// A demo played by the computer while the first betting screen sits idle, like an
+/// arcade cabinet's attract mode.
+struct Attract {
+    /// the player's game, put back when a key is pressed
+    game: Game,
+    started: Instant,
+}
+impl Attract {
+    /// The tip up now.
+    fn tip(&self) -> &'static str {
+        let turns = self.started.elapsed().as_secs() / TIP_LENGTH.as_secs();
+        TIPS[turns as usize % TIPS.len()]
+    }
+}
+
+/// What the result popup offers to do next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum NextStep {
+    /// deal the next hand straight away, with the same bet
+    #[default]
+    Deal,
+    ChangeBet,
+    Stats,
+    Quit,
+}
+impl NextStep {
+    const ALL: [NextStep; 4] = [
+        NextStep::Deal,
+        NextStep::ChangeBet,
+        NextStep::Stats,
+        NextStep::Quit,
+    ];
+
+    /// The option's text, `finished` once the last hand of the session has been played.
+    fn label(self, finished: bool) -> &'static str {
+        match self {
+            NextStep::Deal if finished => "Enter) Finish the session",
+            NextStep::Deal => "Enter) Next hand, same bet",
+            NextStep::ChangeBet => "b) Change bet",
+            NextStep::Stats => "s) Stats",
+            NextStep::Quit => "q) Quit",
+        }
+    }
+
+    /// The step `by` places down the menu from this one, wrapping around.
+    fn step(self, by: isize) -> Self {
+        let index = Self::ALL.iter().position(|step| *step == self).unwrap_or(0);
+        Self::ALL[(index as isize + by).rem_euclid(Self::ALL.len() as isize) as usize]
+    }
+}
+
+/// Builds the settings again from the config file at a path, and everything that
+/// overrides it.
+type LoadConfig<'a> = Box<dyn Fn(&Path) -> Result<Config, String> + 'a>;
+
+/// A config file watched for changes to its live settings.
+struct WatchedConfig<'a> {
+    path: PathBuf,
+    /// when it last changed
+    modified: Option<SystemTime>,
+    load: LoadConfig<'a>,
+}
+
+/// State of the grid UI around a game.
+pub struct App<'a> {
+    config: Cow<'a, Config>,
+    /// the config file watched for changes to its live settings
+    config_file: Option<WatchedConfig<'a>>,
+    /// how chip amounts are written
+    money: Money,
+    game: Game,
+    show_hint: bool,
+    show_count: bool,
+    show_analysis: bool,
+    /// show what's left in the shoe
+    show_shoe: bool,
+    /// expected value of each action, worked out again whenever the hand changes
+    analysis: Option<Vec<(Action, f64)>>,
+    /// the open chart overlay and its scroll position
+    chart: Option<(Chart, usize)>,
+    trainer: Option<Trainer>,
+    /// why basic strategy plays the last mistake differently, offered with its notice
+    explanation: Option<Explanation>,
+    /// the explanation is open
+    explaining: bool,
+    /// every hit and stand the player made, by cell of the strategy chart
+    actions: ActionStats,
+    notice: Option<Notice>,
+    quiz: Option<CountQuiz>,
+    quiz_stats: DrillStats,
+    advice: AdviceTracker,
+    /// a session left by an unclean exit, offered to be restored at start
+    restore: Option<Autosave>,
+    /// whether the hand on the table has been autosaved since it was settled
+    saved: bool,
+    /// the jackpot as last saved
+    saved_jackpot: Option<Jackpot>,
+    session: SessionStats,
+    history: History,
+    /// rounds of earlier sessions, for the lifetime chart
+    past_rounds: Vec<Round>,
+    /// saved results of earlier sessions
+    lifetime: GameStats,
+    /// the open statistics tab
+    stats_tab: Option<StatsTab>,
+    /// the option highlighted in the result popup
+    next_step: NextStep,
+    /// the rules lifetime statistics are narrowed to, or `None` for every table played
+    stats_rules: Option<String>,
+    /// the events of the round in progress, or the last one between rounds
+    round: Vec<GameEvent>,
+    /// the last round being stepped through
+    rewind: Option<Rewind>,
+    chatter: Option<Chatter>,
+    /// what the dealer last said this round
+    dealer_line: Option<String>,
+    /// basic strategy's house edge under the table rules and the standard deviation of a
+    /// hand, simulated when first needed
+    odds: Option<(f64, f64)>,
+    /// the simulation of `odds` running in the background, until it's done
+    odds_estimate: Option<Receiver<Option<(f64, f64)>>>,
+    /// everything told of the game's events as they happen, like its sounds and the
+    /// event log. The sounds come first, so a reloaded config can replace them.
+    subscribers: Vec<Box<dyn Subscriber>>,
+    /// best challenge runs, loaded when playing a challenge
+    leaderboard: Leaderboard,
+    /// session hands played and net result before the challenge run in progress started
+    run_start: (u64, i64),
+    /// the challenge run just ended, with its place on the leaderboard if it made it
+    run_over: Option<Option<usize>>,
+    /// the decision clock of a speed run
+    speed: Option<SpeedClock>,
+    limits: Limits,
+    /// the break called for by the last limit reached, until it's dismissed
+    limit_break: Option<Break>,
+    /// the terminal lost focus and play waits for it to come back
+    paused: bool,
+    /// basic strategy plays the player's hands, leaving only the bets to the player
+    autopilot: bool,
+    /// when the autopilot makes its next move
+    autopilot_move: Option<Instant>,
+    /// when the dealer draws their next card
+    dealer_move: Option<Instant>,
+    /// when the player last pressed a key
+    last_input: Instant,
+    /// the demo playing in place of the player's game
+    attract: Option<Attract>,
+}
+impl<'a> App<'a> {
+    const ANALYSIS_TRIALS: u32 = 2000;
+    const EDGE_HANDS: u64 = 200_000;
+
+    pub fn new(config: &'a Config) -> Self {
+        let mut game = Game::new(config);
+        // a demo shouldn't touch the player's jackpot either
+        if !config.autoplay {
+            game.jackpot = Jackpot::load();
+        }
+        // lifetime totals that can't be read are shown as none, and left alone on disk
+        let (lifetime, notice) = match GameStats::load() {
+            Ok(lifetime) => (lifetime, None),
+            Err(err) => {
+                let notice = Notice {
+                    title: "Statistics".to_string(),
+                    message: capitalize(&err),
+                    color: Color::Red,
+                };
+                (GameStats::default(), Some(notice))
+            }
+        };
+        Self {
+            config: Cow::Borrowed(config),
+            config_file: None,
+            money: config.money(),
+            game,
+            show_hint: false,
+            show_count: config.show_count,
+            show_analysis: config.show_analysis,
+            show_shoe: false,
+            analysis: None,
+            chart: None,
+            trainer: config.trainer.then(Trainer::new),
+            explanation: None,
+            explaining: false,
+            // a demo's decisions aren't the player's habits
+            actions: match config.autoplay {
+                true => ActionStats::default(),
+                false => ActionStats::load(),
+            },
+            notice,
+            quiz: None,
+            quiz_stats: DrillStats::default(),
+            advice: AdviceTracker::default(),
+            // a demo shouldn't touch the player's saved session
+            restore: (!config.autoplay && config.challenge.is_none())
+                .then(Autosave::load)
+                .flatten(),
+            saved: false,
+            saved_jackpot: None,
+            session: SessionStats::default(),
+            history: History::default(),
+            past_rounds: History::load().unwrap_or_default(),
+            lifetime,
+            stats_tab: None,
+            next_step: NextStep::default(),
+            stats_rules: None,
+            round: Vec::new(),
+            rewind: None,
+            chatter: config.dealer_chatter.map(Chatter::new),
+            dealer_line: None,
+            odds: None,
+            odds_estimate: None,
+            subscribers: vec![Box::new(Sounds::new(config))],
+            leaderboard: match config.challenge {
+                Some(_) => Leaderboard::load(),
+                None => Leaderboard::default(),
+            },
+            run_start: (0, 0),
+            run_over: None,
+            speed: (config.challenge == Some(Challenge::Speed)).then(SpeedClock::default),
+            limits: Limits::default(),
+            limit_break: None,
+            paused: false,
+            autopilot: false,
+            autopilot_move: None,
+            dealer_move: None,
+            last_input: Instant::now(),
+            attract: None,
+        }
+    }
+
+    /// Apply changes to the live settings in the config file at `path` as it's saved,
+    /// with `load` building the settings again from it and everything that overrides it.
+    pub fn watch_config(
+        mut self,
+        path: PathBuf,
+        load: impl Fn(&Path) -> Result<Config, String> + 'a,
+    ) -> Self {
+        let modified = fs::metadata(&path).and_then(|file| file.modified()).ok();
+        self.config_file = Some(WatchedConfig {
+            path,
+            modified,
+            load: Box::new(load),
+        });
+        self
+    }
+
+    /// Write the game's events to `events` while it's played.
+    #[cfg(feature = "serde")]
+    pub fn with_events(mut self, events: EventLog) -> Self {
+        self.subscribers.push(Box::new(events));
+        self
+    }
+
+    /// Play until the player quits or the hand limit is played, returning how the session
+    /// went.
+    pub fn run(
+        mut self,
+        terminal: &mut Terminal<impl Backend + Write>,
+    ) -> std::io::Result<SessionSummary> {
+        if self.config.pause_on_blur {
+            term::report_focus(terminal.backend_mut(), true)?;
+        }
+        let result = self.play(terminal);
+        if self.config.pause_on_blur {
+            term::report_focus(terminal.backend_mut(), false)?;
+        }
+        result.map(|()| SessionSummary {
+            stats: self.session.stats,
+            graded: self
+                .trainer
+                .map(|trainer| (trainer.correct, trainer.decisions)),
+        })
+    }
+
+    fn play(&mut self, terminal: &mut Terminal<impl Backend + Write>) -> std::io::Result<()> {
+        // only draw again once something on screen may have changed
+        let mut dirty = true;
+        'frames: loop {
+            if dirty {
+                if self.show_analysis && self.analysis.is_none() {
+                    self.analysis = Some(analysis::action_evs(&self.game, Self::ANALYSIS_TRIALS));
+                }
+                terminal.draw(|frame| self.draw(frame))?;
+                dirty = false;
+            }
+            dirty |= self.follow_events()?;
+            dirty |= self.check_config()?;
+            dirty |= self.collect_odds();
+            if !self.is_demo() && self.restore.is_none() {
+                // a challenge can't be picked up again after quitting
+                if self.config.challenge.is_none() {
+                    Autosave::sync(&self.game, &mut self.saved)?;
+                }
+                self.game.jackpot.sync(&mut self.saved_jackpot)?;
+                self.history.update(&self.game)?;
+            }
+            if self.restore.is_none() && self.attract.is_none() {
+                let hands = self.session.stats.hands;
+                self.session.update(&self.game);
+                // the result popup offers to finish once the last hand is counted
+                if self.hands_played() && self.session.stats.hands != hands {
+                    dirty = true;
+                    continue;
+                }
+            }
+            // the session ends once the last hand's result is left
+            if self.hands_played() && matches!(self.game.state, GameState::Betting) {
+                break;
+            }
+            if let (Some(challenge), None) = (self.config.challenge, &self.run_over) {
+                let hands = self.session.stats.hands - self.run_start.0;
+                if challenge.is_over(&self.game, &self.config, hands) {
+                    let score = self.run_score();
+                    let place = self.leaderboard.record(challenge, score)?;
+                    self.run_over = Some(place);
+                    dirty = true;
+                }
+            }
+
+            // between hands, stop for a break at each session limit
+            let between_hands = matches!(self.game.state, GameState::Betting);
+            if between_hands && !self.is_demo() && self.restore.is_none() {
+                if let Some(limit) = self.limits.check(&self.config, self.session.stats.net) {
+                    self.session.stats.limits_hit += 1;
+                    self.limit_break = Some(Break {
+                        limit,
+                        until: Instant::now() + self.config.break_length,
+                    });
+                    dirty = true;
+                    continue;
+                }
+            }
+
+            // 21 can only be held, so hold it when asked to
+            let on_21 =
+                self.game.is_legal(Action::Stand) && self.game.player_hand.count_value() == 21;
+            if on_21 && self.config.auto_stand_21 {
+                let _ = self.game.hold();
+                self.analysis = None;
+                dirty = true;
+                continue;
+            }
+
+            // a speed run stands for the player who runs out of time
+            if let Some(clock) = &mut self.speed {
+                let waiting = self.game.is_player_turn() && self.run_over.is_none();
+                let shown = self.notice.is_none() && self.limit_break.is_none() && !self.paused;
+                if clock.expired(waiting, shown) {
+                    // insurance is turned down first, if it's still to be answered
+                    let _ = self.game.insure(false);
+                    let _ = self.game.hold();
+                    self.notice = Some(Notice {
+                        title: "Time's Up".to_string(),
+                        message: "Out of time, so the hand stands".to_string(),
+                        color: Color::Yellow,
+                    });
+                    self.analysis = None;
+                    dirty = true;
+                    continue;
+                }
+            }
+
+            if let Some(count) = self.game.shuffled_count.take() {
+                if self.config.count_quiz && !self.is_demo() {
+                    self.quiz = Some(CountQuiz::new(count.running));
+                    dirty = true;
+                    continue;
+                }
+            }
+
+            // play out the dealer's hand a card at a time, at the dealer's pace
+            let dealer_move = match self.game.is_dealer_turn() {
+                true => Some(
+                    *self
+                        .dealer_move
+                        .get_or_insert_with(|| Instant::now() + self.config.dealer_pace()),
+                ),
+                false => {
+                    self.dealer_move = None;
+                    None
+                }
+            };
+            if dealer_move.is_some_and(|at| at <= Instant::now()) {
+                self.dealer_move = None;
+                self.game.dealer_step();
+                self.analysis = None;
+                dirty = true;
+                continue;
+            }
+
+            if self.attract_at().is_some_and(|at| at <= Instant::now()) {
+                self.start_attract();
+                dirty = true;
+                continue;
+            }
+
+            if self.is_demo() && !self.paused && dealer_move.is_none() {
+                if !self.wait_for_autoplay(terminal)? {
+                    break;
+                }
+                // a key ends the attract mode, which leaves the player's game to them
+                if self.paused || !self.is_demo() {
+                    dirty = true;
+                    continue;
+                }
+                self.autoplay_step();
+                self.analysis = None;
+                dirty = true;
+                continue;
+            }
+
+            // the autopilot moves at the autoplay pace, and stops for anything on screen
+            let autopilot_turn = self.game.is_player_turn()
+                || matches!(self.game.state, GameState::HandScoreScreen(_));
+            let next_move = match self.autopilot && autopilot_turn && !self.is_interrupted() {
+                true => Some(
+                    *self
+                        .autopilot_move
+                        .get_or_insert_with(|| Instant::now() + self.config.autoplay_pace),
+                ),
+                false => {
+                    self.autopilot_move = None;
+                    None
+                }
+            };
+            if next_move.is_some_and(|at| at <= Instant::now()) {
+                self.autopilot_move = None;
+                self.autoplay_step();
+                self.analysis = None;
+                dirty = true;
+                continue;
+            }
+
+            // wake to count a break down a second at a time, a speed run's decision a
+            // tenth at a time, for the autopilot's next move, and to turn the session
+            // clock over each minute
+            let tick = match self.limit_break.map(|pause| pause.remaining()) {
+                Some(remaining) if !remaining.is_zero() => remaining.min(Duration::from_secs(1)),
+                _ => Duration::from_secs(60 - self.session.elapsed() % 60),
+            };
+            let tick = match self.speed.as_ref().and_then(SpeedClock::remaining) {
+                Some(remaining) => tick.min(remaining).min(Duration::from_millis(100)),
+                None => tick,
+            };
+            let tick = match next_move.or(dealer_move) {
+                Some(at) => tick.min(at.saturating_duration_since(Instant::now())),
+                None => tick,
+            };
+            // and to show the house edge once it's estimated
+            let tick = match self.odds_estimate {
+                Some(_) => tick.min(Duration::from_millis(100)),
+                None => tick,
+            };
+            // and to look for changes to the config file each second
+            let tick = match self.config_file {
+                Some(_) => tick.min(Duration::from_secs(1)),
+                None => tick,
+            };
+            let tick = match self.attract_at() {
+                Some(at) => tick.min(at.saturating_duration_since(Instant::now())),
+                None => tick,
+            };
+            if !term::poll(tick)? {
+                dirty = true;
+                continue;
+            }
+
+            // take every event already waiting before drawing, so a burst of them, like
+            // held keys or pasted text, costs one frame
+            loop {
+                match term::read()? {
+                    Event::Key(key) if suspend::is_suspend_key(&key) => {
+                        self.suspend(terminal)?;
+                        dirty = true;
+                    }
+                    Event::Key(key) if self.attract.is_some() && keyboard::acts_on(&key) => {
+                        self.end_attract();
+                        dirty = true;
+                    }
+                    Event::Key(key) if self.config.autoplay && is_quit(key) => break 'frames,
+                    Event::Key(_) if self.config.autoplay => (),
+                    Event::Key(key) if keyboard::acts_on(&key) => {
+                        self.last_input = Instant::now();
+                        if !self.handle_key(key) {
+                            break 'frames;
+                        }
+                        self.analysis = None;
+                        dirty = true;
+                    }
+                    Event::Resize => dirty = true,
+                    Event::FocusLost => {
+                        self.paused = true;
+                        dirty = true;
+                    }
+                    Event::FocusGained => {
+                        self.paused = false;
+                        dirty = true;
+                    }
+                    _ => (),
+                }
+                if !term::poll(Duration::ZERO)? {
+                    break;
+                }
+            }
+        }
+        if let Some(trainer) = &self.trainer {
+            trainer.mistakes.save()?;
+        }
+        if !self.config.autoplay {
+            Autosave::clear()?;
+            self.session.stats.save_session()?;
+            self.actions.save_session()?;
+        }
+        self.quiz_stats.save_session(DrillStats::QUIZ_FILE)
+    }
+
+    /// Wait out the autoplay pace, returning false if the viewer quits. The wait ends
+    /// early if the terminal loses focus.
+    fn wait_for_autoplay(
+        &mut self,
+        terminal: &mut Terminal<impl Backend + Write>,
+    ) -> std::io::Result<bool> {
+        let deadline = Instant::now() + self.config.autoplay_pace;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if !term::poll(timeout)? {
+                return Ok(true);
+            }
+            match term::read()? {
+                Event::Key(key) if self.attract.is_some() && keyboard::acts_on(&key) => {
+                    self.end_attract();
+                    return Ok(true);
+                }
+                Event::Key(key) if is_quit(key) => return Ok(false),
+                Event::Key(key) if suspend::is_suspend_key(&key) => {
+                    self.suspend(terminal)?;
+                    terminal.draw(|frame| self.draw(frame))?;
+                }
+                Event::FocusLost => {
+                    self.paused = true;
+                    return Ok(true);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Take in the game's events since the last call: keep the round's events, have the
+    /// dealer remark on them, and pass them on to the subscribers. Returns true when the
+    /// dealer said something that has yet to be drawn.
+    fn follow_events(&mut self) -> std::io::Result<bool> {
+        let events = self.game.take_events();
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Deal { .. }))
+        {
+            self.dealer_line = None;
+        }
+        let mut said = false;
+        for event in events {
+            let line = self
+                .chatter
+                .as_mut()
+                .and_then(|chatter| chatter.respond(&event));
+            let line = line.map(|line| GameEvent::DealerSays { line });
+            for event in [Some(event), line].into_iter().flatten() {
+                // a demo nobody asked for plays quietly, and stays out of the event log
+                if self.attract.is_none() {
+                    for subscriber in &mut self.subscribers {
+                        subscriber.notify(&event)?;
+                    }
+                }
+                match &event {
+                    GameEvent::Bet { .. } => self.round.clear(),
+                    GameEvent::DealerSays { line } => {
+                        self.dealer_line = Some(line.clone());
+                        said = true;
+                    }
+                    _ => (),
+                }
+                self.round.push(event);
+            }
+        }
+        Ok(said)
+    }
+
+    /// Apply the config file's live settings again if it changed since it was last looked
+    /// at, returning whether it did. How it went is shown and written to the event log.
+    fn check_config(&mut self) -> std::io::Result<bool> {
+        let Some(watched) = &mut self.config_file else {
+            return Ok(false);
+        };
+        let now = fs::metadata(&watched.path)
+            .and_then(|file| file.modified())
+            .ok();
+        if now == watched.modified {
+            return Ok(false);
+        }
+        watched.modified = now;
+        let error = match (watched.load)(&watched.path) {
+            Ok(fresh) => {
+                self.config.to_mut().reload(fresh);
+                None
+            }
+            Err(err) => Some(err),
+        };
+        self.notice = Some(match &error {
+            None => {
+                self.money = self.config.money();
+                self.subscribers[0] = Box::new(Sounds::new(&self.config));
+                Notice {
+                    title: "Config Reloaded".to_string(),
+                    message: "Animation, sound, theme, key, and number settings applied"
+                        .to_string(),
+                    color: Color::Green,
+                }
+            }
+            Some(error) => Notice {
+                title: "Config Error".to_string(),
+                message: capitalize(error),
+                color: Color::Red,
+            },
+        });
+        let event = GameEvent::ConfigReloaded { error };
+        for subscriber in &mut self.subscribers {
+            subscriber.notify(&event)?;
+        }
+        Ok(true)
+    }
+
+    /// Score of the challenge run in progress, or the one just ended.
+    fn run_score(&self) -> i64 {
+        let (hands, net) = self.run_start;
+        let points = self.speed.as_ref().map_or(0, |clock| clock.points);
+        self.config.challenge.map_or(0, |challenge| {
+            challenge.score(
+                self.session.stats.hands - hands,
+                self.session.stats.net - net,
+                points,
+            )
+        })
+    }
+
+    /// Stop for Ctrl+Z, without focus reports reaching the shell while stopped.
+    fn suspend(&self, terminal: &mut Terminal<impl Backend + Write>) -> std::io::Result<()> {
+        if self.config.pause_on_blur {
+            term::report_focus(terminal.backend_mut(), false)?;
+        }
+        suspend::suspend(terminal)?;
+        if self.config.pause_on_blur {
+            term::report_focus(terminal.backend_mut(), true)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the computer is playing a demo rather than the player's game.
+    fn is_demo(&self) -> bool {
+        self.config.autoplay || self.attract.is_some()
+    }
+
+    /// When the attract mode starts if no key is pressed first. Only the betting screen
+    /// before the session's first hand waits for it, with nothing else on screen.
+    fn attract_at(&self) -> Option<Instant> {
+        let title = matches!(self.game.state, GameState::Betting)
+            && self.session.stats.hands == 0
+            && !self.is_demo()
+            && !self.autopilot
+            && !self.is_interrupted()
+            && self.config.challenge.is_none();
+        (title && !self.config.attract_after.is_zero())
+            .then(|| self.last_input + self.config.attract_after)
+    }
+
+    /// A new game dealt by `config`, with the jackpot carried over from this one, as it
+    /// keeps growing from game to game until someone hits it.
+    fn fresh_game(&self, config: &Config) -> Game {
+        let mut game = Game::new(config);
+        game.jackpot = self.game.jackpot;
+        game
+    }
+
+    /// Put the player's game aside for a demo of its own. The demo shuffles securely,
+    /// so a seeded or saved shoe isn't dealt ahead of the player.
+    fn start_attract(&mut self) {
+        let config = Config {
+            shuffle: Shuffler::Secure,
+            ..self.config.clone().into_owned()
+        };
+        let demo = self.fresh_game(&config);
+        let game = std::mem::replace(&mut self.game, demo);
+        self.attract = Some(Attract {
+            game,
+            started: Instant::now(),
+        });
+    }
+
+    /// Give the player their game back, as it was before the demo.
+    fn end_attract(&mut self) {
+        if let Some(attract) = self.attract.take() {
+            self.game = attract.game;
+        }
+        self.round.clear();
+        self.dealer_line = None;
+        self.analysis = None;
+        self.last_input = Instant::now();
+    }
+
+    /// Whether something on screen is waiting on the player, which holds up the autopilot.
+    fn is_interrupted(&self) -> bool {
+        self.notice.is_some()
+            || self.quiz.is_some()
+            || self.chart.is_some()
+            || self.stats_tab.is_some()
+            || self.rewind.is_some()
+            || self.restore.is_some()
+            || self.limit_break.is_some()
+            || self.paused
+    }
+
+    /// Make the next move of a basic strategy player, betting the suggested bet when
+    /// counting is shown.
+    fn autoplay_step(&mut self) {
+        let suggested = self.suggested_bet();
+        let game = &mut self.game;
+        match game.state {
+            GameState::Betting => {
+                if game.bankroll == 0 {
+                    game.bankroll = self.config.bankroll;
+                }
+                let bet = suggested
+                    .unwrap_or(game.bet)
+                    .max(self.config.bet_unit)
+                    .min(game.bankroll);
+                let _ = game.place_bet(bet);
+            }
+            GameState::PlayingHand => {
+                let _ = game.play(game.hint().0);
+            }
+            GameState::HandScoreScreen(_) => game.new_hand(),
+        }
+    }
+
+    /// The bet the count suggests, when counting is shown.
+    fn suggested_bet(&self) -> Option<u32> {
+        self.show_count.then(|| {
+            self.config
+                .bet_spread()
+                .suggest(&self.game.count, counting::decks_remaining(&self.game.deck))
+        })
+    }
+
+    /// The Kelly bet for the edge the count gives, and the edge, while counting.
+    fn kelly_bet(&self) -> Option<(u32, f64)> {
+        self.show_count.then(|| {
+            let decks = counting::decks_remaining(&self.game.deck);
+            let edge = betting::count_edge(&self.game.count, decks);
+            let bet = betting::kelly_bet(self.game.bankroll, self.config.bet_unit, edge);
+            (bet.min(self.game.bankroll), edge)
+        })
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        use Constraint::{Fill, Length, Min};
+
+        let game = &self.game;
+        let vertical = Layout::vertical([Length(2), Min(0), Length(1)]);
+        let [title_area, main_area, status_area] = vertical.areas(frame.area());
+        let side_width = if self.show_count || self.show_analysis || self.config.cheat_mode {
+            18
+        } else {
+            0
+        };
+        let horizontal = Layout::horizontal([Fill(1), Fill(1), Length(side_width)]);
+        let [left_area, right_area, side_area] = horizontal.areas(main_area);
+        let count_height = if self.show_count { 5 } else { 0 };
+        let cheat_height = match self.config.cheat_mode {
+            true => NextCardPanel::HEIGHT,
+            false => 0,
+        };
+        let [count_area, cheat_area, analysis_area] =
+            Layout::vertical([Length(count_height), Length(cheat_height), Fill(1)])
+                .areas(side_area);
+
+        let mut title = match &game.scenario {
+            Some(scenario) => format!("Blackjack · Practice: {scenario}"),
+            None => "Blackjack".to_string(),
+        };
+        if self.config.autoplay {
+            title.push_str(" · Autoplay");
+        }
+        if self.attract.is_some() {
+            title.push_str(" · Demo");
+        }
+        if self.autopilot {
+            title.push_str(" · Autopilot");
+        }
+        if self.config.cheat_mode {
+            title.push_str(" · Cheat mode");
+        }
+        if let Some(challenge) = self.config.challenge {
+            let best = self.leaderboard.runs(challenge).next();
+            title.push_str(&format!(
+                " · {}: {} {}, best {}",
+                challenge.title(),
+                challenge.score_name(),
+                self.run_score(),
+                best.map_or(0, |run| run.score)
+            ));
+        }
+        let clock = stats::clock(self.session.elapsed());
+        let session = match self.session.stats.hands_per_hour() {
+            Some(hourly) => format!(" {clock} · {hourly:.0} hands/hour "),
+            None => format!(" {clock} "),
+        };
+        frame.render_widget(
+            Block::bordered()
+                .title(title)
+                .title(Line::from(session).centered())
+                .title(
+                    Line::from(format!("Bankroll: {} ", self.money.amount(game.bankroll)))
+                        .right_aligned(),
+                ),
+            title_area,
+        );
+        let keys = self.config.keymap;
+        let hand = KeyedHand {
+            hand: &game.player_hand,
+            keys,
+        };
+        frame.render_widget(hand, left_area);
+        // the other hands of a split go on the hand's top border
+        if let Some(split) = game.split_summary() {
+            let [top, _] = Layout::vertical([Length(1), Fill(1)]).areas(left_area);
+            let line = Line::from(format!(" {split} ")).centered();
+            frame.render_widget(line, top.inner(Margin::new(9, 0)));
+        }
+        // the hand's border lists hitting and holding, and the moves only some hands get
+        // go on the line above it
+        let moves: Vec<_> = Action::MOVES[2..]
+            .iter()
+            .filter(|action| game.is_legal(**action))
+            .map(|action| (keys.key(*action), action))
+            .map(|(key, action)| match action {
+                Action::Insurance(true) if game.player_hand.is_blackjack() => {
+                    format!("{key}) Even money")
+                }
+                action => format!("{key}) {action}"),
+            })
+            .collect();
+        if !moves.is_empty() {
+            let [_, area, _] = Layout::vertical([Fill(1), Length(1), Length(1)]).areas(left_area);
+            let line = Line::from(moves.join("    ")).bold();
+            frame.render_widget(line, area.inner(Margin::new(2, 0)));
+        }
+        match game.rules.double_exposure {
+            // there's no hole card to hide
+            true => {
+                let mut dealer_hand = game.dealer_hand.clone();
+                dealer_hand.reveal();
+                frame.render_widget(&dealer_hand, right_area);
+            }
+            false => frame.render_widget(&game.dealer_hand, right_area),
+        }
+        if let Some(line) = &self.dealer_line {
+            let [_, bottom] = Layout::vertical([Fill(1), Length(1)]).areas(right_area);
+            let line = Line::from(format!(" “{line}” ")).italic().centered();
+            frame.render_widget(line, bottom.inner(Margin::new(1, 0)));
+        }
+        if self.show_count {
+            frame.render_widget(
+                CountPanel {
+                    count: game.count,
+                    decks_remaining: counting::decks_remaining(&game.deck),
+                },
+                count_area,
+            );
+        }
+        if self.config.cheat_mode {
+            frame.render_widget(
+                NextCardPanel {
+                    shoe: Composition::of(game.deck.undealt()),
+                },
+                cheat_area,
+            );
+        }
+        if let (true, Some(evs)) = (self.show_analysis, &self.analysis) {
+            let insurance_tens = game
+                .insurance_tens
+                .filter(|_| !matches!(game.state, GameState::Betting));
+            frame.render_widget(
+                AnalysisPanel {
+                    evs,
+                    insurance_tens,
+                },
+                analysis_area,
+            );
+        }
+        if self.show_shoe {
+            let [area] = Layout::vertical([Length(CompositionPanel::HEIGHT)]).areas(right_area);
+            let [_, area] =
+                Layout::horizontal([Fill(1), Length(CompositionPanel::WIDTH)]).areas(area);
+            frame.render_widget(
+                CompositionPanel {
+                    unseen: game.unseen(),
+                    realistic: game.realistic_info,
+                },
+                area,
+            );
+        }
+
+        let status = match game.state {
+            _ if self.config.autoplay => Line::from(" q) Quit"),
+            GameState::PlayingHand if self.speed.is_some() => {
+                let clock = self.speed.as_ref();
+                match clock.and_then(SpeedClock::remaining) {
+                    Some(remaining) => Line::from(format!(
+                        " Stands in {:.1}s    Correct now scores ×{}",
+                        remaining.as_secs_f64(),
+                        clock.map_or(1, SpeedClock::multiplier)
+                    )),
+                    None => Line::default(),
+                }
+            }
+            GameState::PlayingHand if self.show_hint => {
+                let (action, situation) = game.hint();
+                Line::from(format!(" Hint: {action} ({situation})    h) Hide hint"))
+            }
+            GameState::PlayingHand if self.trainer.is_some() => Line::from(
+                " h) Hint    c) Chart    n) Count    d) Deck    e) EV    m) Mistakes    y) Copy",
+            ),
+            GameState::PlayingHand => Line::from(
+                " h) Show hint    c) Strategy chart    n) Count    d) Deck    e) EV    y) Copy",
+            ),
+            GameState::Betting if self.trainer.is_some() => {
+                Line::from(" n) Count    d) Deck    s) Stats    m) Mistakes    p) Autopilot")
+            }
+            GameState::Betting => Line::from(" n) Count    d) Deck    s) Stats    p) Autopilot"),
+            GameState::HandScoreScreen(_) => Line::from(" w) Rewind"),
+        };
+        let status = match &self.attract {
+            Some(attract) => Line::from(format!(" Any key) Play    Tip: {}", attract.tip())),
+            None => status,
+        };
+        frame.render_widget(status, status_area);
+        if game
+            .side_bets
+            .iter()
+            .any(|wager| wager.bet.is_progressive())
+        {
+            frame.render_widget(
+                Line::from(format!(" Jackpot {} ", game.jackpot.pool))
+                    .black()
+                    .on_yellow()
+                    .bold()
+                    .centered(),
+                status_area,
+            );
+        }
+        if let Some(trainer) = &self.trainer {
+            frame.render_widget(
+                Line::from(format!("{} ", trainer.summary())).right_aligned(),
+                status_area,
+            );
+        }
+
+        match game.state {
+            GameState::PlayingHand => (),
+            GameState::Betting => {
+                let rows = 7 + game.side_bets.len() as u16;
+                let vertical = Layout::vertical([Length(rows + 2)]).flex(Flex::Center);
+                let horizontal = Layout::horizontal([Length(44)]).flex(Flex::Center);
+                let [area] = vertical.areas(main_area);
+                let [area] = horizontal.areas(area);
+                frame.render_widget(
+                    BetScreen {
+                        bankroll: game.bankroll,
+                        bet: game.bet,
+                        money: &self.money,
+                        side_bets: game.side_bets.clone(),
+                        suggested: self.suggested_bet(),
+                        kelly: self.kelly_bet(),
+                        advice: self.show_count.then(|| self.advice.summary()),
+                    },
+                    area,
+                );
+            }
+            GameState::HandScoreScreen(hand_result) => {
+                let block = Block::bordered()
+                    .title("Hand Result")
+                    .title_bottom(Line::from("↑/↓) Choose").right_aligned());
+                let rows = 4
+                    + game.split_hands.len() as u16
+                    + game.side_results.len() as u16
+                    + NextStep::ALL.len() as u16;
+                let vertical = Layout::vertical([Length(rows + 2)]).flex(Flex::Center);
+                let horizontal =
+                    Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::Center);
+                let [area] = vertical.areas(frame.area());
+                let [area] = horizontal.areas(area);
+
+                frame.render_widget(Clear, area);
+
+                let result = format!("{hand_result:?}");
+                // the hands of a split before the last one, whose result follows
+                let mut list_items: Vec<ListItem> = game
+                    .split_hands
+                    .iter()
+                    .map(|split| {
+                        let result = split.result.map_or("", |result| result.name());
+                        let hand = split.hand.short();
+                        Line::from(format!("{hand} ({}): {result}", split.hand.count_value()))
+                            .into()
+                    })
+                    .collect();
+                list_items.extend([
+                    match hand_result {
+                        HandResult::Blackjack => Line::from(result).green().bold(),
+                        HandResult::PlayerWin => Line::from(result).green(),
+                        HandResult::DealerWin => Line::from(result).red(),
+                        HandResult::Push => Line::from(result).yellow(),
+                        HandResult::Bust => Line::from(result).red(),
+                        HandResult::Surrender => Line::from(result).yellow(),
+                    }
+                    .into(),
+                    // a hole card the table didn't turn over keeps the dealer's total hidden
+                    Line::from(match game.hole_card_hidden() {
+                        true => format!("You: {} Dealer: ?", game.player_hand.count_value()),
+                        false => format!(
+                            "You: {} Dealer: {}",
+                            game.player_hand.count_value(),
+                            game.dealer_hand.count_value()
+                        ),
+                    })
+                    .into(),
+                    Line::from(format!("Payout: {}", self.money.signed(game.payout))).into(),
+                ]);
+                for settled in &game.side_results {
+                    let line = match &settled.hand {
+                        Some(hand) => Line::from(format!(
+                            "{}: {} ({hand})",
+                            settled.name,
+                            self.money.signed(settled.payout)
+                        ))
+                        .green(),
+                        None => Line::from(format!(
+                            "{}: {}",
+                            settled.name,
+                            self.money.signed(settled.payout)
+                        )),
+                    };
+                    list_items.push(line.into());
+                }
+                list_items.push(ListItem::new(""));
+                for step in NextStep::ALL {
+                    let line = Line::from(step.label(self.hands_played()));
+                    list_items.push(
+                        match step == self.next_step {
+                            true => line.black().on_cyan().bold(),
+                            false => line,
+                        }
+                        .into(),
+                    );
+                }
+
+                frame.render_widget(List::new(list_items).block(block), area);
+            }
+        }
+
+        if let Some((chart, scroll)) = self.chart {
+            let vertical = Layout::vertical([Constraint::Percentage(80)]).flex(Flex::Center);
+            let horizontal = Layout::horizontal([Length(53)]).flex(Flex::Center);
+            let [area] = vertical.areas(frame.area());
+            let [area] = horizontal.areas(area);
+            match (chart, &self.trainer) {
+                (Chart::Strategy, _) => {
+                    let highlight =
+                        matches!(game.state, GameState::PlayingHand).then(|| game.hint().1);
+                    frame.render_widget(
+                        StrategyChart {
+                            rules: &game.rules,
+                            highlight,
+                            scroll,
+                        },
+                        area,
+                    );
+                }
+                (Chart::Mistakes, Some(trainer)) => frame.render_widget(
+                    MistakeHeatmap {
+                        mistakes: &trainer.mistakes,
+                        actions: &self.actions,
+                        scroll,
+                    },
+                    area,
+                ),
+                (Chart::Mistakes, None) => (),
+                (Chart::Actions { session }, _) => frame.render_widget(
+                    ActionChart {
+                        actions: &self.actions,
+                        rules: &game.rules,
+                        session,
+                        scroll,
+                    },
+                    area,
+                ),
+            }
+        }
+
+        if let Some(tab) = self.stats_tab {
+            let table_rules = game.rules.to_string();
+            let rounds = || self.past_rounds.iter().chain(&self.history.rounds);
+            let lifetime = match &self.stats_rules {
+                Some(rules) => {
+                    GameStats::from_rounds(rounds().filter(|round| &round.rules == rules))
+                }
+                None => self.lifetime.with_session(&self.session.stats),
+            };
+            let (stats, trajectory, rules) = match (tab, &self.stats_rules) {
+                (StatsTab::Session, _) => (
+                    &self.session.stats,
+                    history::bankrolls(&self.history.rounds),
+                    Some(table_rules.as_str()),
+                ),
+                (StatsTab::Lifetime, Some(rules)) => (
+                    &lifetime,
+                    history::net(rounds().filter(|round| &round.rules == rules)),
+                    Some(rules.as_str()),
+                ),
+                (StatsTab::Lifetime, None) => (&lifetime, history::net(rounds()), None),
+            };
+            // the edge is only what to expect of hands all played under the table's rules
+            let same_rules = match rules {
+                Some(rules) => rules == table_rules,
+                None => history::rule_sets(rounds()) == [table_rules.as_str()],
+            };
+            let rules_name = match rules {
+                Some(rules) => rounds()
+                    .find(|round| round.rules == rules)
+                    .map(|round| round.preset.as_str())
+                    .filter(|preset| !preset.is_empty() && *preset != "custom")
+                    .unwrap_or(rules),
+                None => "all",
+            };
+            let area = frame.area().inner(Margin::new(2, 1));
+            frame.render_widget(
+                StatsScreen {
+                    stats,
+                    tab,
+                    trajectory: &trajectory,
+                    odds: self.odds.filter(|_| same_rules),
+                    estimating: self.odds_estimate.is_some() && same_rules,
+                    rules: rules_name,
+                    money: &self.money,
+                },
+                area,
+            );
+        }
+
+        if let Some(explanation) = self.explanation.as_ref().filter(|_| self.explaining) {
+            frame.render_widget(explanation, frame.area());
+        }
+
+        if let Some(save) = &self.restore {
+            let prompt = Notice {
+                title: "Interrupted Session".to_string(),
+                message: format!(
+                    "Restore your bankroll of {}?    y) Restore    n) Start over",
+                    save.game.bankroll
+                ),
+                color: Color::Yellow,
+            };
+            frame.render_widget(prompt, main_area);
+        } else if let Some(quiz) = &self.quiz {
+            let prompt = Notice {
+                title: "Shuffle".to_string(),
+                message: quiz.prompt(),
+                color: Color::Cyan,
+            };
+            frame.render_widget(prompt, main_area);
+        } else if let Some(notice) = &self.notice {
+            frame.render_widget(notice.clone(), main_area);
+        }
+
+        if let (Some(challenge), Some(place)) = (self.config.challenge, self.run_over) {
+            let placed = match place {
+                Some(1) => "A new best run!".to_string(),
+                Some(place) => format!("Number {place} on the leaderboard"),
+                None => "Short of the leaderboard this time".to_string(),
+            };
+            let block = Block::bordered()
+                .title("Challenge Over")
+                .border_style(Style::new().cyan());
+            let text = vec![
+                Line::from(format!(
+                    "{} {}",
+                    capitalize(challenge.score_name()),
+                    self.run_score()
+                )),
+                Line::from(placed),
+                Line::default(),
+                Line::from("Enter) Try again    q) Quit"),
+            ];
+            let vertical = Layout::vertical([Length(6)]).flex(Flex::Center);
+            let horizontal = Layout::horizontal([Length(44)]).flex(Flex::Center);
+            let [area] = vertical.areas(frame.area());
+            let [area] = horizontal.areas(area);
+            frame.render_widget(Clear, area);
+            frame.render_widget(Paragraph::new(text).centered().block(block), area);
+        }
+
+        if let Some(rewind) = &self.rewind {
+            frame.render_widget(rewind, main_area);
+        }
+
+        if let Some(pause) = self.limit_break {
+            let remaining = pause.remaining();
+            let prompt = match remaining.is_zero() {
+                true => "Any) Play on    q) Quit".to_string(),
+                false => format!("Play on in {}s    q) Quit", remaining.as_secs() + 1),
+            };
+            let block = Block::bordered()
+                .title("Time for a Break")
+                .border_style(Style::new().yellow());
+            let text = vec![
+                Line::from(pause.limit.message(&self.config)),
+                Line::default(),
+                Line::from(prompt),
+            ];
+            let vertical = Layout::vertical([Length(5)]).flex(Flex::Center);
+            let horizontal = Layout::horizontal([Length(48)]).flex(Flex::Center);
+            let [area] = vertical.areas(frame.area());
+            let [area] = horizontal.areas(area);
+            frame.render_widget(Clear, area);
+            frame.render_widget(Paragraph::new(text).centered().block(block), area);
+        }
+
+        if self.paused {
+            let area = frame.area();
+            frame
+                .buffer_mut()
+                .set_style(area, Style::new().add_modifier(Modifier::DIM));
+            let notice = Notice {
+                title: "Paused".to_string(),
+                message: "Play resumes when the terminal has focus again".to_string(),
+                color: Color::DarkGray,
+            };
+            frame.render_widget(notice, main_area);
+        }
+
+        self.config.theme.apply(frame.buffer_mut());
+        self.config.plain().apply(frame.buffer_mut());
+    }
+
+    /// Handle a key press, returning false when the player quits.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.run_over.is_some() {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Enter | KeyCode::Char(' ') => {
+                    self.game = self.fresh_game(&self.config);
+                    self.run_start = (self.session.stats.hands, self.session.stats.net);
+                    self.run_over = None;
+                    if self.speed.is_some() {
+                        self.speed = Some(SpeedClock::default());
+                    }
+                }
+                _ => (),
+            }
+            return true;
+        }
+
+        if let Some(pause) = self.limit_break {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                _ if pause.remaining().is_zero() => self.limit_break = None,
+                _ => (),
+            }
+            return true;
+        }
+
+        if let Some((chart, scroll)) = self.chart.as_mut() {
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *scroll = (*scroll + 1).min(StrategyChart::ROWS - 1)
+                }
+                KeyCode::Char('c') if *chart == Chart::Strategy => self.chart = None,
+                KeyCode::Char('m') if *chart == Chart::Mistakes => self.chart = None,
+                KeyCode::Tab => {
+                    if let Chart::Actions { session } = chart {
+                        *session = !*session;
+                    }
+                }
+                KeyCode::Char('a') if matches!(chart, Chart::Actions { .. }) => self.chart = None,
+                KeyCode::Esc => self.chart = None,
+                _ => (),
+            }
+            return true;
+        }
+
+        if self.explaining {
+            self.explaining = false;
+            self.explanation = None;
+            return true;
+        }
+
+        if let Some(rewind) = self.rewind.as_mut() {
+            match key.code {
+                KeyCode::Left | KeyCode::Char('h') => rewind.back(),
+                KeyCode::Right | KeyCode::Char('l') => rewind.forward(),
+                KeyCode::Home => rewind.first(),
+                KeyCode::End => rewind.last(),
+                KeyCode::Char('w') | KeyCode::Esc => self.rewind = None,
+                _ => (),
+            }
+            return true;
+        }
+
+        if let Some(save) = &self.restore {
+            match key.code {
+                KeyCode::Char('y') => {
+                    save.restore(&mut self.game);
+                    self.restore = None;
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.restore = None,
+                _ => (),
+            }
+            return true;
+        }
+
+        if let Some(tab) = self.stats_tab.as_mut() {
+            match key.code {
+                KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                    *tab = match tab {
+                        StatsTab::Session => StatsTab::Lifetime,
+                        StatsTab::Lifetime => StatsTab::Session,
+                    }
+                }
+                // narrow lifetime results to each table played in turn, then all of them
+                KeyCode::Char('r') if *tab == StatsTab::Lifetime => {
+                    let rounds = self.past_rounds.iter().chain(&self.history.rounds);
+                    let rule_sets = history::rule_sets(rounds);
+                    let next = match &self.stats_rules {
+                        Some(rules) => rule_sets
+                            .iter()
+                            .skip_while(|rule_set| *rule_set != rules)
+                            .nth(1),
+                        None => rule_sets.first(),
+                    };
+                    self.stats_rules = next.map(|rules| rules.to_string());
+                }
+                KeyCode::Char('s') | KeyCode::Esc => self.stats_tab = None,
+                KeyCode::Char('a') => {
+                    let session = *tab == StatsTab::Session;
+                    self.stats_tab = None;
+                    self.chart = Some((Chart::Actions { session }, 0));
+                }
+                KeyCode::Char('x') => {
+                    self.stats_tab = None;
+                    self.notice = Some(match self.history.export() {
+                        Ok(path) => Notice {
+                            title: "Export".to_string(),
+                            message: format!(
+                                "Wrote {} hands to {}",
+                                self.history.rounds.len(),
+                                path.display()
+                            ),
+                            color: Color::Green,
+                        },
+                        Err(err) => Notice {
+                            title: "Export".to_string(),
+                            message: err,
+                            color: Color::Red,
+                        },
+                    });
+                }
+                _ => (),
+            }
+            return true;
+        }
+
+        if let Some(prompt) = self.quiz.as_mut() {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() || c == '-' || c == '+' => {
+                    prompt.input.push(c)
+                }
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Enter => {
+                    if let Some((correct, message)) = prompt.answer(&mut self.quiz_stats) {
+                        self.notice = Some(Notice {
+                            title: "Count Quiz".to_string(),
+                            message,
+                            color: if correct { Color::Green } else { Color::Red },
+                        });
+                        self.quiz = None;
+                    }
+                }
+                _ => (),
+            }
+            return true;
+        }
+
+        // any key dismisses a notice, and x opens the explanation of a mistake
+        if self.notice.take().is_some() {
+            match key.code {
+                KeyCode::Char('x') if self.explanation.is_some() => self.explaining = true,
+                _ => self.explanation = None,
+            }
+            return true;
+        }
+
+        let suggested = self.suggested_bet();
+        let kelly = self.kelly_bet();
+        let fixed_bet = self.config.challenge.is_some_and(Challenge::fixes_bet);
+        let help = self.config.challenge.is_none_or(Challenge::allows_help);
+        let keymap = self.config.keymap;
+        let game = &mut self.game;
+        match game.state {
+            GameState::Betting => {
+                let unit = self.config.bet_unit;
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('+' | '=') if !fixed_bet => {
+                        game.bet = (game.bet + unit).min(game.bankroll)
+                    }
+                    KeyCode::Down | KeyCode::Char('-') if !fixed_bet => {
+                        game.bet = game.bet.saturating_sub(unit).max(unit.min(game.bankroll))
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => self.place_bet(),
+                    KeyCode::Char('a') if !fixed_bet => {
+                        if let Some(suggested) = suggested {
+                            game.bet = suggested.min(game.bankroll);
+                        }
+                    }
+                    KeyCode::Char('k') if !fixed_bet => {
+                        if let Some((kelly, _)) = kelly {
+                            game.bet = kelly;
+                        }
+                    }
+                    KeyCode::Char('r') if game.bankroll == 0 && self.config.challenge.is_none() => {
+                        game.bankroll = self.config.bankroll;
+                        game.bet = self.config.bet_unit.min(game.bankroll);
+                    }
+                    KeyCode::Char('n') if !self.config.count_quiz => {
+                        self.show_count = !self.show_count
+                    }
+                    KeyCode::Char('m') if self.trainer.is_some() => {
+                        self.chart = Some((Chart::Mistakes, 0))
+                    }
+                    KeyCode::Char('d') if help => self.show_shoe = !self.show_shoe,
+                    KeyCode::Char('i') if self.show_shoe => {
+                        game.realistic_info = !game.realistic_info;
+                        self.analysis = None;
+                    }
+                    KeyCode::Char('x') if self.show_shoe => self.save_shoe(),
+                    // a challenge is the player's own to play
+                    KeyCode::Char('p') if self.config.challenge.is_none() => {
+                        self.autopilot = !self.autopilot
+                    }
+                    KeyCode::Char('s') => self.open_stats(),
+                    KeyCode::Char('q') | KeyCode::Esc => return false,
+                    // moves wait for the deal, and the game says so, unless
+                    // the key is one of the betting screen's own
+                    KeyCode::Char(c) if keymap.action(c).is_some() => {
+                        if let Some(Err(err)) = keymap.action(c).map(|action| game.play(action)) {
+                            self.notice = Some(not_allowed(err));
+                        }
+                    }
+                    // side bets toggle between off and the stake they were offered at, or
+                    // a unit if they were offered turned off
+                    KeyCode::Char(c) if !fixed_bet => {
+                        let offered = self.config.side_bets.iter();
+                        if let Some((wager, offered)) = game
+                            .side_bets
+                            .iter_mut()
+                            .zip(offered)
+                            .find(|(wager, _)| wager.bet.key() == c)
+                        {
+                            wager.stake = match wager.stake {
+                                0 if offered.stake > 0 => offered.stake,
+                                0 => unit,
+                                _ => 0,
+                            };
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            GameState::PlayingHand => match key.code {
+                KeyCode::Char(c) => match (keymap.action(c), c) {
+                    (Some(action), _) => {
+                        let legal = game.is_legal(action);
+                        if legal {
+                            self.actions.record(&game.hint().1, action);
+                        }
+                        if let Some(trainer) = self.trainer.as_mut().filter(|_| legal) {
+                            let correct = trainer.grade(game, action);
+                            if let Some(clock) = self.speed.as_mut() {
+                                clock.decide(correct);
+                            }
+                            if !correct {
+                                self.explanation = Explanation::new(&game.hint().1, &game.rules);
+                                let mut message = trainer.mistake.clone().unwrap_or_default();
+                                if self.explanation.is_some() {
+                                    message.push_str("    x) Explain");
+                                }
+                                self.notice = Some(Notice {
+                                    title: "Mistake".to_string(),
+                                    message,
+                                    color: Color::Red,
+                                });
+                            }
+                        }
+                        if let Err(err) = game.play(action) {
+                            self.notice = Some(not_allowed(err));
+                        }
+                    }
+                    (_, 'h') if help => self.show_hint = !self.show_hint,
+                    (_, 'n') if !self.config.count_quiz => self.show_count = !self.show_count,
+                    (_, 'e') if help => self.show_analysis = !self.show_analysis,
+                    (_, 'd') if help => self.show_shoe = !self.show_shoe,
+                    (_, 'i') if self.show_shoe => {
+                        game.realistic_info = !game.realistic_info;
+                        self.analysis = None;
+                    }
+                    (_, 'x') if self.show_shoe => self.save_shoe(),
+                    (_, 'c') if help => {
+                        let (_, situation) = game.hint();
+                        let row = ChartRow::all()
+                            .position(|row| row.contains(&situation))
+                            .unwrap_or_default();
+                        self.chart = Some((Chart::Strategy, row.saturating_sub(3)));
+                    }
+                    (_, 'm') if self.trainer.is_some() => self.chart = Some((Chart::Mistakes, 0)),
+                    (_, 'p') if self.config.challenge.is_none() => self.autopilot = !self.autopilot,
+                    (_, 'y') if help => {
+                        let summary = game.summary();
+                        self.notice = Some(match clipboard::copy(&summary) {
+                            Ok(()) => Notice {
+                                title: "Copied".to_string(),
+                                message: summary,
+                                color: Color::Green,
+                            },
+                            Err(err) => Notice {
+                                title: "Copy".to_string(),
+                                message: err.to_string(),
+                                color: Color::Red,
+                            },
+                        });
+                    }
+                    (_, 'q') => return false,
+                    _ => (),
+                },
+                KeyCode::Esc => return false,
+                _ => (),
+            },
+            // only the keys on the menu move on, so a stray key doesn't deal a hand
+            GameState::HandScoreScreen(_) => match key.code {
+                KeyCode::Up | KeyCode::Left | KeyCode::BackTab => {
+                    self.next_step = self.next_step.step(-1)
+                }
+                KeyCode::Down | KeyCode::Right | KeyCode::Tab => {
+                    self.next_step = self.next_step.step(1)
+                }
+                KeyCode::Enter | KeyCode::Char(' ') => return self.take_step(self.next_step),
+                KeyCode::Char('b') => return self.take_step(NextStep::ChangeBet),
+                KeyCode::Char('s') => return self.take_step(NextStep::Stats),
+                KeyCode::Char('q') | KeyCode::Esc => return self.take_step(NextStep::Quit),
+                KeyCode::Char('w') => self.rewind = Rewind::new(self.round.clone()),
+                _ => (),
+            },
+        }
+        true
+    }
+
+    /// Do what was picked from the result popup, returning false to quit.
+    fn take_step(&mut self, step: NextStep) -> bool {
+        self.next_step = step;
+        match step {
+            NextStep::Deal if self.hands_played() => self.game.new_hand(),
+            NextStep::Deal => {
+                self.game.new_hand();
+                self.place_bet();
+            }
+            NextStep::ChangeBet => self.game.new_hand(),
+            NextStep::Stats => self.open_stats(),
+            NextStep::Quit => return false,
+        }
+        // the next result starts back on dealing, unless stats are looked at in between
+        if !matches!(step, NextStep::Stats) {
+            self.next_step = NextStep::default();
+        }
+        true
+    }
+
+    /// Every hand of the hand limit has been played.
+    fn hands_played(&self) -> bool {
+        self.config
+            .hand_limit
+            .is_some_and(|limit| self.session.stats.hands >= limit)
+    }
+
+    /// Bet what's on the bet screen, noting how it compares with the bet the count
+    /// suggests.
+    fn place_bet(&mut self) {
+        let bet = self.game.bet;
+        if let Some(suggested) = self.suggested_bet() {
+            self.advice
+                .record(&self.config.bet_spread(), suggested, bet);
+        }
+        if let Err(err) = self.game.place_bet(bet) {
+            self.notice = Some(not_allowed(err));
+        }
+    }
+
+    /// Write the order the shoe being dealt was shuffled to, to deal it again later.
+    fn save_shoe(&mut self) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = PathBuf::from(format!("shoe-{secs}.txt"));
+        self.notice = Some(
+            match shuffle::write_shoe(&path, self.game.deck.shuffled()) {
+                Ok(()) => Notice {
+                    title: "Shoe Saved".to_string(),
+                    message: format!(
+                        "Wrote the shoe to {}, deal it again with --shuffle file:{0}",
+                        path.display()
+                    ),
+                    color: Color::Green,
+                },
+                Err(err) => Notice {
+                    title: "Shoe".to_string(),
+                    message: err,
+                    color: Color::Red,
+                },
+            },
+        );
+    }
+
+    /// Show the stats screen, estimating the house edge in the background the first time.
+    fn open_stats(&mut self) {
+        if self.odds.is_none() && self.odds_estimate.is_none() {
+            // practice deals are rigged, so leave them out of the estimate
+            let config = Config {
+                scenario: None,
+                ..(*self.config).clone()
+            };
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let odds = simulate::simulate(&config, Self::EDGE_HANDS, &Strategy::Basic, None)
+                    .ok()
+                    .map(|results| (results.house_edge(), results.std_dev()));
+                let _ = sender.send(odds);
+            });
+            self.odds_estimate = Some(receiver);
+        }
+        self.stats_tab = Some(StatsTab::Session)
+    }
+
+    /// Take the house edge once its estimate is done, returning whether it was.
+    fn collect_odds(&mut self) -> bool {
+        let Some(estimate) = &self.odds_estimate else {
+            return false;
+        };
+        match estimate.try_recv() {
+            Ok(odds) => self.odds = odds,
+            Err(TryRecvError::Empty) => return false,
+            // the simulation failed, so the stats go without it
+            Err(TryRecvError::Disconnected) => (),
+        }
+        self.odds_estimate = None;
+        true
+    }
+}
+
+/// Keys that stop a game the computer is playing.
+fn is_quit(key: KeyEvent) -> bool {
+    keyboard::acts_on(&key) && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+}
+
+/// The notice for an action the game turned down.
+fn not_allowed(err: IllegalAction) -> Notice {
+    Notice {
+        title: "Not Allowed".to_string(),
+        message: capitalize(&err.to_string()),
+        color: Color::Yellow,
+    }
+}
+
+/// `text` with its first letter in upper case.
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or(String::new(), |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+
+    #[test]
+    fn restarting_a_challenge_keeps_the_jackpot() {
+        paths::use_test_dir();
+        let config = Config {
+            autoplay: true,
+            mute: true,
+            ..Config::default()
+        };
+        let mut app = App::new(&config);
+        app.game.jackpot.pool = 5000;
+        app.run_over = Some(None);
+        assert!(app.handle_key(KeyEvent::from(KeyCode::Enter)));
+        assert!(app.run_over.is_none());
+        assert_eq!(app.game.jackpot.pool, 5000);
+    }
+}
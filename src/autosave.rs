@@ -0,0 +1,152 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    game::{Game, GameState},
+    paths,
+};
+
+/// A session in progress, saved as each hand is settled so it survives a crash and
+/// removed when the game quits cleanly.
+#[derive(Clone, Debug)]
+pub struct Autosave {
+    /// the game as its last hand was settled
+    pub game: Game,
+}
+impl Autosave {
+    const FILE: &str = "autosave.json";
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// The session left behind by an unclean exit, if there is one that can be read.
+    #[cfg(feature = "serde")]
+    pub fn load() -> Option<Self> {
+        let contents = fs::read_to_string(Self::path()?).ok()?;
+        let game = serde_json::from_str(&contents).ok()?;
+        Some(Self { game })
+    }
+
+    /// Nothing is saved without serde.
+    #[cfg(not(feature = "serde"))]
+    pub fn load() -> Option<Self> {
+        None
+    }
+
+    /// Save through a temporary file, so a crash while writing can't leave half a save.
+    #[cfg(feature = "serde")]
+    fn save(game: &Game) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string(game)?)?;
+        fs::rename(temp, path)
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn save(_: &Game) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Save the game as soon as a hand is settled, once a hand: `saved` is whether the
+    /// hand on the table already has been.
+    pub fn sync(game: &Game, saved: &mut bool) -> io::Result<()> {
+        match game.state {
+            GameState::HandScoreScreen(_) if !*saved => {
+                Self::save(game)?;
+                *saved = true;
+            }
+            GameState::HandScoreScreen(_) => (),
+            _ => *saved = false,
+        }
+        Ok(())
+    }
+
+    /// Pick the session up from the next hand, with the shoe as it was left. The jackpot
+    /// is saved on its own, and kept as it is.
+    pub fn restore(&self, game: &mut Game) {
+        let jackpot = game.jackpot;
+        *game = self.game.clone();
+        game.jackpot = jackpot;
+        game.new_hand();
+    }
+
+    /// Remove the save after a clean exit.
+    pub fn clear() -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, jackpot::Jackpot, shuffle::Shuffler};
+
+    #[test]
+    fn saves_once_a_hand_as_it_is_settled() {
+        paths::use_test_dir();
+        let config = Config {
+            shuffle: Shuffler::stacked("10S 6S 9H 10H"),
+            ..Config::default()
+        };
+        let mut game = Game::new(&config);
+        let mut saved = false;
+        game.place_bet(10).unwrap();
+        Autosave::sync(&game, &mut saved).unwrap();
+        assert!(!saved);
+
+        game.hold().unwrap();
+        while game.is_dealer_turn() {
+            game.dealer_step();
+        }
+        Autosave::sync(&game, &mut saved).unwrap();
+        assert!(saved);
+        #[cfg(feature = "serde")]
+        {
+            let save = Autosave::load().unwrap();
+            assert_eq!(save.game.bankroll, config.bankroll - 10);
+            assert_eq!(save.game.dealer_hand.count_value(), 19);
+        }
+
+        game.new_hand();
+        Autosave::sync(&game, &mut saved).unwrap();
+        assert!(!saved);
+        Autosave::clear().unwrap();
+        assert!(Autosave::load().is_none());
+    }
+
+    #[test]
+    fn restores_from_the_next_hand() {
+        let config = Config {
+            shuffle: Shuffler::stacked("10S 6S 9H 10H"),
+            ..Config::default()
+        };
+        let mut played = Game::new(&config);
+        played.place_bet(20).unwrap();
+        played.hold().unwrap();
+        while played.is_dealer_turn() {
+            played.dealer_step();
+        }
+        let save = Autosave {
+            game: played.clone(),
+        };
+
+        let mut game = Game::new(&Config::default());
+        game.jackpot.feed(100);
+        save.restore(&mut game);
+        assert!(matches!(game.state, GameState::Betting));
+        assert_eq!(game.bankroll, played.bankroll);
+        assert_eq!(game.deck.remaining(), played.deck.remaining());
+        assert_eq!(game.jackpot.pool, Jackpot::default().pool + 10);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::counting::Count;
+
+/// Bet sizing from the count, betting one unit at neutral counts and ramping up to
+/// `spread` units as the true count rises.
+#[derive(Clone, Copy, Debug)]
+pub struct BetSpread {
+    pub unit: u32,
+    pub spread: u32,
+}
+impl BetSpread {
+    /// Units to bet: the true count minus one, between one unit and the full spread.
+    /// Unbalanced systems use the running count directly.
+    pub fn units(&self, count: &Count, decks_remaining: f64) -> u32 {
+        let count = betting_count(count, decks_remaining);
+        (count.floor() - 1.0).clamp(1.0, f64::from(self.spread.max(1))) as u32
+    }
+
+    pub fn suggest(&self, count: &Count, decks_remaining: f64) -> u32 {
+        self.units(count, decks_remaining) * self.unit
+    }
+}
+
+/// player edge gained per point of true count
+const EDGE_PER_COUNT: f64 = 0.005;
+/// variance of a blackjack hand, per unit bet squared
+const VARIANCE: f64 = 1.33;
+
+/// The player's estimated edge at the count, as a share of the bet: even at a true count
+/// of one, and half a percent more for every point above it.
+pub fn count_edge(count: &Count, decks_remaining: f64) -> f64 {
+    (betting_count(count, decks_remaining) - 1.0) * EDGE_PER_COUNT
+}
+
+/// An approximately Kelly-optimal bet for an `edge`: the share of the bankroll the edge
+/// is worth over a hand's variance, in whole units and never less than one.
+pub fn kelly_bet(bankroll: u32, unit: u32, edge: f64) -> u32 {
+    let bet = f64::from(bankroll) * edge / VARIANCE;
+    ((bet / f64::from(unit.max(1))).floor() as u32).max(1) * unit
+}
+
+/// The count bets are sized from: the true count, or the running count for unbalanced
+/// systems.
+fn betting_count(count: &Count, decks_remaining: f64) -> f64 {
+    if count.system.is_balanced() {
+        count.true_count(decks_remaining)
+    } else {
+        f64::from(count.running)
+    }
+}
+
+/// Ways of sizing bets that can be compared in the simulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BettingSystem {
+    Flat,
+    /// the count's bet spread
+    Spread,
+    /// double the bet after every loss
+    Martingale,
+    /// double the bet after every win, for up to three wins
+    Paroli,
+    /// bet a share of the bankroll in proportion to the count's edge
+    Kelly,
+}
+impl BettingSystem {
+    pub const ALL: [BettingSystem; 5] = [
+        BettingSystem::Flat,
+        BettingSystem::Spread,
+        BettingSystem::Martingale,
+        BettingSystem::Paroli,
+        BettingSystem::Kelly,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BettingSystem::Flat => "flat",
+            BettingSystem::Spread => "spread",
+            BettingSystem::Martingale => "martingale",
+            BettingSystem::Paroli => "paroli",
+            BettingSystem::Kelly => "kelly",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|system| system.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names = Self::ALL.map(|system| system.name()).join(", ");
+                format!("unknown betting system '{name}', expected one of {names}")
+            })
+    }
+}
+
+/// A betting system's bet for each hand, following the results of the hands before.
+#[derive(Clone, Copy, Debug)]
+pub struct Progression {
+    pub system: BettingSystem,
+    spread: BetSpread,
+    /// current bet in units, for the progressions
+    units: u32,
+    wins: u32,
+}
+impl Progression {
+    const PAROLI_WINS: u32 = 3;
+
+    pub fn new(system: BettingSystem, spread: BetSpread) -> Self {
+        Self {
+            system,
+            spread,
+            units: 1,
+            wins: 0,
+        }
+    }
+
+    /// The next bet, which may be more than the bankroll covers.
+    pub fn bet(&self, bankroll: u32, count: &Count, decks_remaining: f64) -> u32 {
+        let unit = self.spread.unit;
+        match self.system {
+            BettingSystem::Flat => unit,
+            BettingSystem::Spread => self.spread.suggest(count, decks_remaining),
+            BettingSystem::Martingale | BettingSystem::Paroli => self.units.saturating_mul(unit),
+            BettingSystem::Kelly => kelly_bet(bankroll, unit, count_edge(count, decks_remaining)),
+        }
+    }
+
+    /// Move the progression along after a hand paid `payout`.
+    pub fn record(&mut self, payout: i64) {
+        match (self.system, payout) {
+            (_, 0) => (),
+            (BettingSystem::Martingale, ..0) => self.units = self.units.saturating_mul(2),
+            (BettingSystem::Paroli, 1..) if self.wins + 1 < Self::PAROLI_WINS => {
+                self.units *= 2;
+                self.wins += 1;
+            }
+            _ => {
+                self.units = 1;
+                self.wins = 0;
+            }
+        }
+    }
+}
+
+/// How closely the player's bets have followed the spread's suggestions.
+#[derive(Debug, Default)]
+pub struct AdviceTracker {
+    pub hands: u32,
+    pub followed: u32,
+    /// summed difference between bet and suggestion, in units
+    pub deviation: f64,
+}
+impl AdviceTracker {
+    pub fn record(&mut self, spread: &BetSpread, suggested: u32, bet: u32) {
+        self.hands += 1;
+        self.followed += u32::from(suggested == bet);
+        self.deviation += f64::from(bet.abs_diff(suggested)) / f64::from(spread.unit.max(1));
+    }
+
+    pub fn summary(&self) -> String {
+        if self.hands == 0 {
+            return "no bets yet".to_string();
+        }
+        format!(
+            "followed {}/{} bets, off by {:.1} units on average",
+            self.followed,
+            self.hands,
+            self.deviation / f64::from(self.hands)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counting::{HiLo, Ko};
+
+    fn count(system: &'static dyn crate::counting::CountingSystem, running: i32) -> Count {
+        Count {
+            running,
+            ..Count::new(system, 1, 0)
+        }
+    }
+
+    #[test]
+    fn spreads_bets_with_the_count() {
+        let spread = BetSpread { unit: 5, spread: 8 };
+        assert_eq!(spread.suggest(&count(&HiLo, 6), 2.0), 10);
+        assert_eq!(spread.suggest(&count(&HiLo, 40), 1.0), 40);
+        assert_eq!(spread.suggest(&count(&HiLo, -6), 2.0), 5);
+        // unbalanced systems bet from the running count
+        assert_eq!(spread.units(&count(&Ko, 4), 2.0), 3);
+
+        let mut advice = AdviceTracker::default();
+        assert_eq!(advice.summary(), "no bets yet");
+        advice.record(&spread, 10, 10);
+        advice.record(&spread, 10, 20);
+        assert_eq!(
+            advice.summary(),
+            "followed 1/2 bets, off by 1.0 units on average"
+        );
+    }
+
+    #[test]
+    fn progressions_follow_wins_and_losses() {
+        assert_eq!(
+            BettingSystem::parse("Martingale"),
+            Ok(BettingSystem::Martingale)
+        );
+        let err = BettingSystem::parse("fibonacci").unwrap_err();
+        assert!(
+            err.starts_with("unknown betting system 'fibonacci'"),
+            "{err}"
+        );
+
+        let spread = BetSpread { unit: 5, spread: 8 };
+        let neutral = count(&HiLo, 0);
+        let mut martingale = Progression::new(BettingSystem::Martingale, spread);
+        for payout in [-5, -10, 0] {
+            martingale.record(payout);
+        }
+        // a push leaves the bet where it was
+        assert_eq!(martingale.bet(1000, &neutral, 1.0), 20);
+        martingale.record(20);
+        assert_eq!(martingale.bet(1000, &neutral, 1.0), 5);
+
+        let mut paroli = Progression::new(BettingSystem::Paroli, spread);
+        let bets: Vec<_> = [5, 10, 20]
+            .into_iter()
+            .map(|payout| {
+                paroli.record(payout);
+                paroli.bet(1000, &neutral, 1.0)
+            })
+            .collect();
+        // back to one unit after the third win in a row
+        assert_eq!(bets, [10, 20, 5]);
+
+        let mut flat = Progression::new(BettingSystem::Flat, spread);
+        flat.record(-5);
+        assert_eq!(flat.bet(1000, &neutral, 1.0), 5);
+    }
+
+    #[test]
+    fn sizes_kelly_bets_from_the_edge() {
+        let edge = count_edge(&count(&HiLo, 4), 2.0);
+        assert!((edge - 0.005).abs() < 1e-9, "{edge}");
+        assert_eq!(count_edge(&count(&HiLo, 0), 2.0), -EDGE_PER_COUNT);
+
+        assert_eq!(kelly_bet(1000, 5, 0.02), 15);
+        // never less than a unit, even without an edge
+        assert_eq!(kelly_bet(100, 5, -0.01), 5);
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    io::{self, BufRead},
+    time::Instant,
+};
+#[cfg(feature = "serde")]
+use std::{
+    io::{BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    thread,
+};
+
+#[cfg(feature = "serde")]
+use crate::protocol;
+use crate::{
+    config::Config,
+    counting::{self, Count},
+    game::{Game, GameState},
+    history::{self, card_code},
+    stats::GameStats,
+};
+
+#[cfg(feature = "serde")]
+pub const PORT: u16 = 7778;
+
+/// What a bot sends, one JSON object per line:
+///
+/// ```text
+/// {"type":"bet","amount":10}
+/// {"type":"insurance","take":false}
+/// {"type":"hit"}
+/// {"type":"stand"}
+/// {"type":"double"}
+/// {"type":"split"}
+/// {"type":"surrender"}
+/// {"type":"quit"}
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum BotAction {
+    Bet {
+        amount: u32,
+    },
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+    /// take insurance, or even money on a blackjack, or turn it down
+    Insurance {
+        take: bool,
+    },
+    Quit,
+}
+
+/// What a bot is sent, one JSON object per line. Every connection plays its own game, and
+/// is sent the state whenever it is the bot's move:
+///
+/// ```text
+/// {"type":"state","phase":"betting","rules":"6 decks, S17, 3:2, 75% dealt",
+///  "bankroll":1000,"bet":10,"running_count":0,"decks_remaining":6.0,"player":[],
+///  "player_total":null,"soft":false,"dealer_up":null,"legal":["bet"]}
+/// {"type":"state","phase":"playing",...,"player":["10H","6S"],"player_total":16,
+///  "soft":false,"dealer_up":"9C","legal":["hit","stand","double"]}
+/// {"type":"result","result":"loss","payout":-10,"bankroll":990,"player":["10H","6S"],
+///  "dealer":["8D","9C"],"player_total":16,"dealer_total":17}
+/// {"type":"error","message":"it isn't your turn to play"}
+/// ```
+///
+/// Cards are written rank then suit, as in the hand history. The dealer plays out their
+/// hand without the bot, and its result is sent before the next betting state. A split
+/// pair's hands are played one at a time, each sent as `player` in its turn, and the
+/// result is the last hand's, with the ones before it in `split` and the payout over
+/// them all. Against a
+/// dealer ace, `insurance` is the only legal move until it's answered. A state with
+/// nothing `legal` means the bankroll is gone, and the connection is closed.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum BotEvent {
+    State(BotState),
+    Result {
+        result: String,
+        payout: i64,
+        bankroll: u32,
+        player: Vec<String>,
+        /// hands split from the one dealt and played before `player`
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        split: Vec<Vec<String>>,
+        dealer: Vec<String>,
+        player_total: u8,
+        dealer_total: u8,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BotState {
+    /// `betting` or `playing`
+    pub phase: String,
+    pub rules: String,
+    pub bankroll: u32,
+    pub bet: u32,
+    pub running_count: i32,
+    pub decks_remaining: f64,
+    pub player: Vec<String>,
+    pub player_total: Option<u8>,
+    pub soft: bool,
+    pub dealer_up: Option<String>,
+    pub legal: Vec<String>,
+}
+impl BotState {
+    fn of(game: &Game) -> Self {
+        let playing = game.is_player_turn();
+        Self {
+            phase: if playing { "playing" } else { "betting" }.to_string(),
+            rules: game.rules.to_string(),
+            bankroll: game.bankroll,
+            bet: game.bet,
+            running_count: game.count.running,
+            decks_remaining: counting::decks_remaining(&game.deck),
+            player: match playing {
+                true => game.player_hand.cards().iter().map(card_code).collect(),
+                false => Vec::new(),
+            },
+            player_total: playing.then(|| game.player_hand.count_value()),
+            soft: playing && game.player_hand.is_soft(),
+            dealer_up: playing.then(|| card_code(&game.dealer_hand.up_card())),
+            legal: {
+                // taking and turning down insurance share a name
+                let mut legal: Vec<_> = game
+                    .legal_actions()
+                    .iter()
+                    .map(|action| action.name().to_string())
+                    .collect();
+                legal.dedup();
+                legal
+            },
+        }
+    }
+}
+
+fn apply(game: &mut Game, action: BotAction) -> Result<(), String> {
+    let played = match action {
+        BotAction::Bet { amount } => game.place_bet(amount),
+        BotAction::Hit => game.hit(),
+        BotAction::Stand => game.hold(),
+        BotAction::Double => game.double(),
+        BotAction::Split => game.split(),
+        BotAction::Surrender => game.surrender(),
+        BotAction::Insurance { take } => game.insure(take),
+        BotAction::Quit => Ok(()),
+    };
+    played.map_err(|err| err.to_string())
+}
+
+/// What a [`Voice`] makes of a line from the player.
+pub(crate) enum Heard {
+    /// something to play
+    Action(BotAction),
+    /// a line the voice saw to itself, like a blank one or a question it answered
+    Handled,
+    /// a line that can't be played, and why
+    Refused(String),
+}
+
+/// How [`play`] talks with whoever is playing: what it makes of their lines, and how it
+/// writes what happens, as JSON for bots or in words for people.
+pub(crate) trait Voice {
+    /// What `line` asks of `game`.
+    fn hear(&mut self, game: &mut Game, line: &str) -> io::Result<Heard>;
+    /// Write `event`, which `game` has just come to.
+    fn send(&mut self, game: &Game, event: &BotEvent) -> io::Result<()>;
+    /// The shoe was reshuffled, after being counted to `count`.
+    fn shuffled(&mut self, _count: Count) {}
+}
+
+/// Play one game until the player quits, runs out of chips, plays the hand limit, or
+/// disconnects, reading each line from `reader` and talking through `voice`. Returns the
+/// game and its results.
+pub(crate) fn play(
+    config: &Config,
+    reader: impl BufRead,
+    voice: &mut impl Voice,
+) -> io::Result<(Game, GameStats)> {
+    let mut game = Game::new(config);
+    let mut stats = GameStats::default();
+    let started = Instant::now();
+    voice.send(&game, &BotEvent::State(BotState::of(&game)))?;
+    for line in reader.lines() {
+        let result = match voice.hear(&mut game, &line?)? {
+            Heard::Action(BotAction::Quit) => break,
+            Heard::Action(action) => apply(&mut game, action),
+            Heard::Handled => continue,
+            Heard::Refused(message) => Err(message),
+        };
+        if let Err(message) = result {
+            voice.send(&game, &BotEvent::Error { message })?;
+        }
+
+        while game.is_dealer_turn() {
+            game.dealer_step();
+        }
+        if let GameState::HandScoreScreen(result) = game.state {
+            let cards = |cards: &[_]| cards.iter().map(card_code).collect();
+            let settled = BotEvent::Result {
+                result: result.name().to_string(),
+                payout: game.payout,
+                bankroll: game.bankroll,
+                player: cards(game.player_hand.cards()),
+                split: game
+                    .split_hands
+                    .iter()
+                    .map(|split| cards(split.hand.cards()))
+                    .collect(),
+                dealer: cards(game.dealer_hand.cards()),
+                player_total: game.player_hand.count_value(),
+                dealer_total: game.dealer_hand.count_value(),
+            };
+            voice.send(&game, &settled)?;
+            let actions = history::actions(&game, result);
+            stats.record(result, game.stake(), game.payout, &actions);
+            if config.hand_limit.is_some_and(|limit| stats.hands >= limit) {
+                break;
+            }
+            game.new_hand();
+        }
+        if let Some(count) = game.shuffled_count.take() {
+            voice.shuffled(count);
+        }
+        let state = BotState::of(&game);
+        let broke = state.legal.is_empty();
+        voice.send(&game, &BotEvent::State(state))?;
+        if broke {
+            break;
+        }
+    }
+    stats.seconds = started.elapsed().as_secs();
+    Ok((game, stats))
+}
+
+/// Speaks the bot protocol, a JSON object a line.
+#[cfg(feature = "serde")]
+pub(crate) struct Json<W>(pub W);
+#[cfg(feature = "serde")]
+impl<W: Write> Voice for Json<W> {
+    fn hear(&mut self, _game: &mut Game, line: &str) -> io::Result<Heard> {
+        Ok(match line.trim().is_empty() {
+            true => Heard::Handled,
+            false => protocol::parse(line).map_or_else(Heard::Refused, Heard::Action),
+        })
+    }
+
+    fn send(&mut self, _game: &Game, event: &BotEvent) -> io::Result<()> {
+        protocol::send(&mut self.0, event)
+    }
+}
+
+/// Serve one bot per connection, each on its own thread.
+#[cfg(feature = "serde")]
+fn serve<S: io::Read + Write + Send + 'static>(
+    config: &Config,
+    connections: impl Iterator<Item = io::Result<S>>,
+    try_clone: fn(&S) -> io::Result<S>,
+) {
+    for stream in connections.flatten() {
+        let Ok(reader) = try_clone(&stream) else {
+            continue;
+        };
+        let config = config.clone();
+        thread::spawn(move || {
+            println!("A bot connected.");
+            let reader = BufReader::new(reader);
+            match play(&config, reader, &mut Json(stream)) {
+                Ok((game, _)) => println!("A bot left with a bankroll of {}.", game.bankroll),
+                Err(err) => println!("A bot disconnected: {err}"),
+            }
+        });
+    }
+}
+
+/// Take bots on TCP `port`, until the process is stopped.
+#[cfg(feature = "serde")]
+pub fn listen_tcp(config: &Config, port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Waiting for bots on port {port} with {}.", config.rules);
+    let connections = listener.incoming().map(|stream| {
+        let stream = stream?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    });
+    serve(config, connections, |stream| stream.try_clone());
+    Ok(())
+}
+
+/// Take bots on a Unix socket at `path`, until the process is stopped.
+#[cfg(all(feature = "serde", unix))]
+pub fn listen_unix(config: &Config, path: &Path) -> io::Result<()> {
+    use std::os::unix::{fs::FileTypeExt, net::UnixListener};
+
+    // a socket left behind by an earlier run
+    if std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    println!(
+        "Waiting for bots at {} with {}.",
+        path.display(),
+        config.rules
+    );
+    serve(config, listener.incoming(), |stream| stream.try_clone());
+    Ok(())
+}
+
+#[cfg(all(feature = "serde", not(unix)))]
+pub fn listen_unix(_config: &Config, _path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Unix sockets aren't available here, use --port",
+    ))
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::shuffle::Shuffler;
+
+    /// A game from a deck that deals the cards `first` names, two to the player, then the
+    /// dealer's hole card and up-card, then the draws.
+    fn stacked(first: &str) -> Config {
+        Config {
+            shuffle: Shuffler::stacked(first),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn reads_what_bots_send() {
+        let parse = protocol::parse::<BotAction>;
+        assert_eq!(
+            parse(r#"{"type":"bet","amount":10}"#),
+            Ok(BotAction::Bet { amount: 10 })
+        );
+        assert_eq!(
+            parse(r#"{"type":"insurance","take":false}"#),
+            Ok(BotAction::Insurance { take: false })
+        );
+        assert_eq!(parse(r#"{"type":"double"}"#), Ok(BotAction::Double));
+        assert_eq!(parse(r#"{"type":"split"}"#), Ok(BotAction::Split));
+        assert!(parse(r#"{"type":"fold"}"#).is_err());
+    }
+
+    #[test]
+    fn plays_a_hand_a_line_at_a_time() {
+        let lines = [
+            r#"{"type":"hit"}"#,
+            r#"{"type":"bet","amount":10}"#,
+            "",
+            r#"{"type":"stand"}"#,
+            r#"{"type":"quit"}"#,
+        ];
+        let config = stacked("10S 9S 8H 9H");
+        let mut voice = Json(Vec::new());
+        let (game, stats) = play(&config, lines.join("\n").as_bytes(), &mut voice).unwrap();
+        assert_eq!((game.bankroll, stats.hands), (1010, 1));
+
+        let sent = String::from_utf8(voice.0).unwrap();
+        let events: Vec<BotEvent> = sent
+            .lines()
+            .map(|line| protocol::parse(line).unwrap())
+            .collect();
+        let [BotEvent::State(betting), BotEvent::Error { .. }, BotEvent::State(_), BotEvent::State(playing), BotEvent::Result { result, payout, .. }, BotEvent::State(next)] =
+            events.as_slice()
+        else {
+            panic!("unexpected events {sent}");
+        };
+        assert_eq!(betting.legal, ["bet"]);
+        assert_eq!(playing.player, ["10S", "9S"]);
+        assert_eq!(playing.dealer_up.as_deref(), Some("9H"));
+        assert_eq!(playing.legal, ["hit", "stand", "double"]);
+        assert_eq!((result.as_str(), *payout), ("win", 10));
+        assert_eq!(next.phase, "betting");
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config::*,
+    game::{Game, GameState},
+    paths,
+    rules::Rules,
+    shuffle::Shuffler,
+};
+
+/// A way to play scored against earlier runs instead of an open-ended session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Challenge {
+    /// last as many hands as possible on a few minimum bets
+    Survival,
+    /// a set number of hands from a shoe everyone gets on the same day, counted in days
+    /// since the Unix epoch
+    Daily { day: u32 },
+    /// a set number of hands against a shrinking clock, scored on fast correct decisions
+    Speed,
+}
+impl Challenge {
+    /// Minimum bets a survival run starts with.
+    const SURVIVAL_BETS: u32 = 5;
+    /// Hands dealt from the daily shoe.
+    const DAILY_HANDS: u64 = 50;
+    /// Hands dealt in a speed run.
+    const SPEED_HANDS: u64 = 25;
+
+    /// Parse `survival`, `speed`, `daily` for today's deal, or `daily-YYYY-MM-DD` for
+    /// another day's. Days change over at midnight UTC, so friends anywhere get the same deal.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let expected = || {
+            format!(
+                "unknown challenge '{name}', expected survival, speed, daily, or daily-YYYY-MM-DD"
+            )
+        };
+        match name {
+            "survival" => Ok(Challenge::Survival),
+            "speed" => Ok(Challenge::Speed),
+            "daily" => Ok(Challenge::Daily { day: today() }),
+            _ => {
+                let date = name.strip_prefix("daily-").ok_or_else(expected)?;
+                let day = parse_date(date).ok_or_else(expected)?;
+                Ok(Challenge::Daily { day })
+            }
+        }
+    }
+
+    /// The name the challenge is parsed from and kept on the leaderboard under.
+    pub fn key(self) -> String {
+        match self {
+            Challenge::Survival => "survival".to_string(),
+            Challenge::Daily { day } => format!("daily-{}", date(day)),
+            Challenge::Speed => "speed".to_string(),
+        }
+    }
+
+    pub fn title(self) -> String {
+        match self {
+            Challenge::Survival => "Survival".to_string(),
+            Challenge::Daily { day } => format!("Daily deal {}", date(day)),
+            Challenge::Speed => "Speed".to_string(),
+        }
+    }
+
+    /// Set the table up for the challenge. The daily deal is played under the same rules
+    /// and stakes by everyone, and a speed run's decisions are graded by the trainer.
+    pub fn apply(self, config: &mut Config) {
+        // no run makes the leaderboard peeking at the shoe
+        config.cheat_mode = false;
+        match self {
+            Challenge::Survival => config.bankroll = config.bet_unit * Self::SURVIVAL_BETS,
+            Challenge::Daily { day } => {
+                let defaults = Config::default();
+                config.rules = Rules::default();
+                config.bankroll = defaults.bankroll;
+                config.bet_unit = defaults.bet_unit;
+                config.scenario = None;
+                config.shuffle = Shuffler::Seeded(u64::from(day));
+            }
+            Challenge::Speed => {
+                config.trainer = true;
+                config.show_analysis = false;
+            }
+        }
+    }
+
+    /// Whether the player is held to the minimum bet.
+    pub fn fixes_bet(self) -> bool {
+        match self {
+            Challenge::Survival | Challenge::Speed => true,
+            Challenge::Daily { .. } => false,
+        }
+    }
+
+    /// Whether hints, the strategy chart, and the EV panel can be looked at.
+    pub fn allows_help(self) -> bool {
+        self != Challenge::Speed
+    }
+
+    /// Whether the run is over after `hands` hands, with no more to play.
+    pub fn is_over(self, game: &Game, config: &Config, hands: u64) -> bool {
+        let broke = game.bankroll < config.bet_unit;
+        matches!(game.state, GameState::Betting)
+            && match self {
+                Challenge::Survival => broke,
+                Challenge::Daily { .. } => broke || hands >= Self::DAILY_HANDS,
+                Challenge::Speed => broke || hands >= Self::SPEED_HANDS,
+            }
+    }
+
+    /// What a run is scored on.
+    pub fn score_name(self) -> &'static str {
+        match self {
+            Challenge::Survival => "hands survived",
+            Challenge::Daily { .. } => "net",
+            Challenge::Speed => "points",
+        }
+    }
+
+    /// The score of a run that played `hands` hands for a net result of `net`, earning
+    /// `points` against the clock.
+    pub fn score(self, hands: u64, net: i64, points: u64) -> i64 {
+        match self {
+            Challenge::Survival => hands as i64,
+            Challenge::Daily { .. } => net,
+            Challenge::Speed => points as i64,
+        }
+    }
+}
+
+/// The clock each decision of a speed run is made against. It allows less time with every
+/// decision, and a correct one scores more the sooner it's made.
+#[derive(Debug, Default)]
+pub struct SpeedClock {
+    decisions: u32,
+    /// time left for the decision being waited on, as of when the clock last stopped
+    left: Option<Duration>,
+    /// when the clock last started running, none while it's stopped
+    resumed: Option<Instant>,
+    pub points: u64,
+}
+impl SpeedClock {
+    const FIRST_LIMIT: Duration = Duration::from_secs(5);
+    const LAST_LIMIT: Duration = Duration::from_millis(1500);
+    const SHRINK: Duration = Duration::from_millis(100);
+    /// Points for a correct decision made at the last moment.
+    const POINTS: u64 = 10;
+
+    /// Time allowed for the next decision.
+    pub fn limit(&self) -> Duration {
+        Self::FIRST_LIMIT
+            .saturating_sub(Self::SHRINK * self.decisions)
+            .max(Self::LAST_LIMIT)
+    }
+
+    /// Time left for the decision being waited on.
+    pub fn remaining(&self) -> Option<Duration> {
+        let left = self.left?;
+        Some(match self.resumed {
+            Some(resumed) => left.saturating_sub(resumed.elapsed()),
+            None => left,
+        })
+    }
+
+    /// How many times over a correct decision made now scores: four times in the first
+    /// quarter of the time allowed, down to once in the last.
+    pub fn multiplier(&self) -> u64 {
+        let Some(remaining) = self.remaining() else {
+            return 4;
+        };
+        let quarters = (4 * remaining.as_millis() / self.limit().as_millis().max(1)) as u64;
+        quarters.min(3) + 1
+    }
+
+    /// Run the clock while a decision is `waiting` and `shown` to the player, returning
+    /// true once the time is up. The clock stops while the decision is hidden, like
+    /// behind a notice, and picks up with the time that was left.
+    pub fn expired(&mut self, waiting: bool, shown: bool) -> bool {
+        if !waiting {
+            self.left = None;
+            self.resumed = None;
+            return false;
+        }
+        let limit = self.limit();
+        self.left.get_or_insert(limit);
+        match (shown, self.resumed) {
+            (false, Some(_)) => {
+                self.left = self.remaining();
+                self.resumed = None;
+            }
+            (true, None) => self.resumed = Some(Instant::now()),
+            (false, None) | (true, Some(_)) => (),
+        }
+        if !shown || self.remaining().is_some_and(|left| !left.is_zero()) {
+            return false;
+        }
+        self.decide(false);
+        true
+    }
+
+    /// Score the decision being waited on.
+    pub fn decide(&mut self, correct: bool) {
+        if correct {
+            self.points += Self::POINTS * self.multiplier();
+        }
+        self.decisions += 1;
+        self.left = None;
+        self.resumed = None;
+    }
+}
+
+/// Days since the Unix epoch, in UTC.
+fn today() -> u32 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH);
+    now.map_or(0, |since| (since.as_secs() / 86_400) as u32)
+}
+
+/// A day since the Unix epoch as `YYYY-MM-DD`.
+fn date(day: u32) -> String {
+    // the civil calendar from days, as in Howard Hinnant's date algorithms
+    let days = i64::from(day) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Days since the Unix epoch of a `YYYY-MM-DD` date from 1970 on.
+fn parse_date(text: &str) -> Option<u32> {
+    let mut parts = text.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    // dates like February 30th don't come back out the same
+    let days = u32::try_from(days).ok()?;
+    (date(days) == text).then_some(days)
+}
+
+/// A finished run of a challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub challenge: Challenge,
+    pub score: i64,
+    /// when the run finished, in seconds since the Unix epoch
+    pub finished: u64,
+}
+
+/// The best runs of each challenge played on this machine, best first. Each daily deal
+/// is its own challenge, so the board keeps a result for every day played.
+#[derive(Debug, Default)]
+pub struct Leaderboard(Vec<Run>);
+impl Leaderboard {
+    const FILE: &str = "leaderboard.toml";
+    /// Runs kept for each challenge.
+    const KEPT: usize = 10;
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    pub fn load() -> Self {
+        let mut runs = Vec::new();
+        let Some(Ok(Some(entries))) = Self::path().map(|path| read_key_values(&path)) else {
+            return Self(runs);
+        };
+        for (_, key, value) in entries {
+            let Ok(challenge) = Challenge::parse(&key) else {
+                continue;
+            };
+            let (score, finished) = value.split_once('@').unwrap_or((&value, "0"));
+            if let (Ok(score), Ok(finished)) =
+                (parse_number(score.trim()), parse_number(finished.trim()))
+            {
+                runs.push(Run {
+                    challenge,
+                    score,
+                    finished,
+                });
+            }
+        }
+        runs.sort_by_key(|run| -run.score);
+        Self(runs)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents: String = self
+            .0
+            .iter()
+            .map(|run| {
+                format!(
+                    "{} = {} @ {}\n",
+                    run.challenge.key(),
+                    run.score,
+                    run.finished
+                )
+            })
+            .collect();
+        fs::write(path, contents)
+    }
+
+    /// The best runs of `challenge`, best first.
+    pub fn runs(&self, challenge: Challenge) -> impl Iterator<Item = &Run> {
+        self.0.iter().filter(move |run| run.challenge == challenge)
+    }
+
+    /// Add a finished run and save the board, returning its place on it from 1, or
+    /// `None` if it didn't make the board.
+    pub fn record(&mut self, challenge: Challenge, score: i64) -> io::Result<Option<usize>> {
+        let finished = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let run = Run {
+            challenge,
+            score,
+            finished,
+        };
+        let place = self
+            .runs(challenge)
+            .filter(|run| run.score >= score)
+            .count();
+        let at = self.0.partition_point(|other| other.score >= score);
+        self.0.insert(at, run);
+        let mut kept = 0;
+        self.0.retain(|run| {
+            kept += usize::from(run.challenge == challenge);
+            run.challenge != challenge || kept <= Self::KEPT
+        });
+        self.save()?;
+        Ok((place < Self::KEPT).then_some(place + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn survival_lasts_until_the_minimum_bet_runs_out() {
+        let mut config = Config {
+            bet_unit: 10,
+            cheat_mode: true,
+            ..Config::default()
+        };
+        Challenge::Survival.apply(&mut config);
+        assert_eq!(config.bankroll, 50);
+        assert!(!config.cheat_mode);
+
+        let mut game = Game::new(&config);
+        assert!(!Challenge::Survival.is_over(&game, &config, 100));
+        game.bankroll = 9;
+        assert!(Challenge::Survival.is_over(&game, &config, 100));
+        assert_eq!(Challenge::Survival.score(12, -50, 0), 12);
+    }
+
+    #[test]
+    fn the_leaderboard_keeps_the_best_runs_of_each_challenge() {
+        paths::use_test_dir();
+        let mut board = Leaderboard::default();
+        assert_eq!(board.record(Challenge::Survival, 12).unwrap(), Some(1));
+        assert_eq!(board.record(Challenge::Survival, 30).unwrap(), Some(1));
+        assert_eq!(board.record(Challenge::Survival, 20).unwrap(), Some(2));
+        assert_eq!(board.record(Challenge::Speed, 5).unwrap(), Some(1));
+        for _ in 0..Leaderboard::KEPT {
+            board.record(Challenge::Survival, 25).unwrap();
+        }
+        assert_eq!(board.record(Challenge::Survival, 1).unwrap(), None);
+
+        let board = Leaderboard::load();
+        let scores: Vec<_> = board
+            .runs(Challenge::Survival)
+            .map(|run| run.score)
+            .collect();
+        assert_eq!(scores.len(), Leaderboard::KEPT);
+        assert_eq!(scores[..2], [30, 25]);
+        assert_eq!(board.runs(Challenge::Speed).count(), 1);
+    }
+
+    #[test]
+    fn the_daily_deal_is_named_and_dealt_by_its_date() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(parse_date("2024-02-29"), Some(19_782));
+        assert_eq!(date(19_782), "2024-02-29");
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("1969-12-31"), None);
+
+        let daily = Challenge::parse("daily-2024-02-29").unwrap();
+        assert_eq!(daily, Challenge::Daily { day: 19_782 });
+        assert_eq!(daily.key(), "daily-2024-02-29");
+        let today = Challenge::parse("daily").unwrap();
+        assert_eq!(Challenge::parse(&today.key()), Ok(today));
+        assert!(Challenge::parse("daily-tomorrow").is_err());
+
+        // everyone plays the same shoe under the same rules
+        let mut config = Config {
+            bankroll: 5,
+            rules: Rules::preset("vegas").unwrap(),
+            ..Config::default()
+        };
+        daily.apply(&mut config);
+        assert_eq!(config.shuffle, Shuffler::Seeded(19_782));
+        assert_eq!(config.rules, Rules::default());
+        assert_eq!(config.bankroll, Config::default().bankroll);
+    }
+
+    #[test]
+    fn the_speed_clock_stops_while_the_decision_is_hidden() {
+        let mut clock = SpeedClock::default();
+        assert!(!clock.expired(true, true));
+        clock.resumed = clock
+            .resumed
+            .map(|resumed| resumed - Duration::from_secs(2));
+        assert!(!clock.expired(true, false));
+        let left = clock.remaining().unwrap();
+        assert!(left <= Duration::from_secs(3), "{left:?}");
+
+        // hidden, it keeps the time that was left instead of starting over
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!clock.expired(true, false));
+        assert_eq!(clock.remaining(), Some(left));
+        assert!(!clock.expired(true, true));
+        assert!(clock.remaining().unwrap() <= left);
+
+        clock.left = Some(Duration::ZERO);
+        assert!(!clock.expired(true, false));
+        assert!(clock.expired(true, true));
+        assert_eq!(clock.limit(), SpeedClock::FIRST_LIMIT - SpeedClock::SHRINK);
+        assert_eq!(clock.remaining(), None);
+    }
+
+    #[test]
+    fn the_speed_clock_starts_over_on_the_next_decision() {
+        let mut clock = SpeedClock::default();
+        clock.expired(true, true);
+        clock.decide(true);
+        assert_eq!(clock.points, SpeedClock::POINTS * 4);
+        assert!(!clock.expired(true, false));
+        assert_eq!(clock.remaining(), Some(clock.limit()));
+
+        // a hand that ends without a decision drops its clock
+        assert!(!clock.expired(false, true));
+        assert_eq!(clock.remaining(), None);
+    }
+}
@@ -0,0 +1,173 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::events::{card_text, GameEvent};
+
+/// How the dealer talks at the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Personality {
+    /// warm and encouraging
+    Friendly,
+    /// says as little as possible
+    Dry,
+    /// has opinions about your play
+    Snarky,
+}
+impl Personality {
+    pub const ALL: [Personality; 3] =
+        [Personality::Friendly, Personality::Dry, Personality::Snarky];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Personality::Friendly => "friendly",
+            Personality::Dry => "dry",
+            Personality::Snarky => "snarky",
+        }
+    }
+
+    /// Parse a personality by name, or `none` for a dealer who keeps quiet.
+    pub fn parse(name: &str) -> Result<Option<Self>, String> {
+        if name == "none" {
+            return Ok(None);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|personality| personality.name() == name)
+            .map(Some)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(Personality::name).join(", ");
+                format!("unknown personality '{name}', expected none, {names}")
+            })
+    }
+
+    /// Lines for a moment at the table. `{card}`, `{total}`, `{payout}`, and `{shoe}`
+    /// are filled in from the event.
+    fn templates(self, moment: Moment) -> &'static [&'static str] {
+        use Moment::*;
+        use Personality::*;
+        match (self, moment) {
+            (Friendly, Blackjack) => &["Blackjack! Beautiful.", "There it is, a natural!"],
+            (Friendly, Win) => &["Nicely played, {payout} to you.", "Well done!"],
+            (Friendly, Loss) => &["Tough one. Next hand's yours.", "Unlucky, hang in there."],
+            (Friendly, Bust) => &["Ah, {total}. So close.", "Over by a little, it happens."],
+            (Friendly, Push) => &["A push, nobody loses.", "Tied up, bet stays."],
+            (Friendly, RiskyHit) => &["Brave! Here's the {card}.", "Going for it, good luck!"],
+            (Friendly, Shuffle) => &["Fresh shoe, everyone! Shoe {shoe}.", "Shuffling up."],
+            (Dry, Blackjack) => &["Blackjack.", "Natural. Pays."],
+            (Dry, Win) => &["Player wins.", "Paying {payout}."],
+            (Dry, Loss) => &["House.", "Dealer wins."],
+            (Dry, Bust) => &["{total}. Bust.", "Too many."],
+            (Dry, Push) => &["Push."],
+            (Dry, RiskyHit) => &["{card}.", "Another card. Noted."],
+            (Dry, Shuffle) => &["Shuffle.", "New shoe."],
+            (Snarky, Blackjack) => &["Blackjack. Even you couldn't mess that up.", "Lucky."],
+            (Snarky, Win) => &["Fine, take your {payout}.", "Don't let it go to your head."],
+            (Snarky, Loss) => &["The house thanks you.", "Shocking. Truly."],
+            (Snarky, Bust) => &["{total}. Who could have seen that coming?", "Bold plan."],
+            (Snarky, Push) => &["A push. Riveting.", "Nobody wins, least of all you."],
+            (Snarky, RiskyHit) => &[
+                "Hitting that? Sure, why not.",
+                "A {card}. The chart is crying.",
+            ],
+            (Snarky, Shuffle) => &["Shuffling, so count from zero. If you were.", "New shoe."],
+        }
+    }
+}
+
+/// Something at the table the dealer might have a word about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Moment {
+    Blackjack,
+    Win,
+    Loss,
+    Bust,
+    Push,
+    /// a hit on a hard 17 or more
+    RiskyHit,
+    Shuffle,
+}
+
+/// The dealer's chatter, said in response to what happens at the table.
+#[derive(Debug)]
+pub struct Chatter {
+    personality: Personality,
+    /// the player's cards so far this round, to tell a risky hit
+    cards: Vec<String>,
+}
+impl Chatter {
+    /// Chance the dealer comments on an ordinary win, loss, or push.
+    const SETTLE_CHANCE: f64 = 0.4;
+
+    pub fn new(personality: Personality) -> Self {
+        Self {
+            personality,
+            cards: Vec::new(),
+        }
+    }
+
+    /// What the dealer says about `event`, if anything.
+    pub fn respond(&mut self, event: &GameEvent) -> Option<String> {
+        let mut rng = rand::thread_rng();
+        let moment = match event {
+            GameEvent::Deal { player, .. } => {
+                self.cards = player.clone();
+                return None;
+            }
+            GameEvent::Hit { card, .. } => {
+                let risky = matches!(count(&self.cards), (17.., false));
+                self.cards.push(card.clone());
+                risky.then_some(Moment::RiskyHit)?
+            }
+            GameEvent::Double { card, .. } => {
+                self.cards.push(card.clone());
+                return None;
+            }
+            GameEvent::Settle { result, .. } => match result.as_str() {
+                "blackjack" => Moment::Blackjack,
+                "bust" => Moment::Bust,
+                "win" => Moment::Win,
+                "loss" | "surrender" => Moment::Loss,
+                _ => Moment::Push,
+            },
+            GameEvent::Shuffle { .. } => Moment::Shuffle,
+            _ => return None,
+        };
+        let chatty = !matches!(moment, Moment::Win | Moment::Loss | Moment::Push);
+        if !chatty && !rng.gen_bool(Self::SETTLE_CHANCE) {
+            return None;
+        }
+        let template = self.personality.templates(moment).choose(&mut rng)?;
+        Some(fill(template, event, &self.cards))
+    }
+}
+
+/// `template` with its placeholders filled in from `event`.
+fn fill(template: &str, event: &GameEvent, cards: &[String]) -> String {
+    let (card, total, payout, shoe) = match event {
+        GameEvent::Hit { card, player_total } => (card_text(card), *player_total, 0, 0),
+        GameEvent::Settle { payout, .. } => (String::new(), count(cards).0, *payout, 0),
+        GameEvent::Shuffle { shoe, .. } => (String::new(), 0, 0, *shoe),
+        _ => (String::new(), 0, 0, 0),
+    };
+    template
+        .replace("{card}", &card)
+        .replace("{total}", &total.to_string())
+        .replace("{payout}", &payout.abs().to_string())
+        .replace("{shoe}", &shoe.to_string())
+}
+
+/// The best total of cards written like `10H`, and whether an ace is counted as eleven.
+fn count(cards: &[String]) -> (u8, bool) {
+    let hard: u8 = cards
+        .iter()
+        .map(|card| match &card[..card.len() - 1] {
+            "A" => 1,
+            "J" | "Q" | "K" => 10,
+            rank => rank.parse().unwrap_or(10),
+        })
+        .sum();
+    let soft = cards.iter().any(|card| card.starts_with('A')) && hard + 10 <= 21;
+    match soft {
+        true => (hard + 10, true),
+        false => (hard, false),
+    }
+}
@@ -0,0 +1,322 @@
+use std::{net::IpAddr, path::PathBuf};
+
+const USAGE: &str = "\
+Usage: blackjack-tui [OPTIONS] [COMMAND]
+
+Commands:
+  bot         Play against a program over a line-delimited JSON socket, see --socket
+  drill       Practice keeping the running count as cards flash by
+  flashcards  Quiz basic strategy on random hands, repeating the ones you miss
+  host        Deal a table for players connecting over the network, see --port
+  join <HOST:PORT>
+              Take a seat at a table hosted over the network, see --name
+  shoe        Shuffle a shoe for the table and write its order to --output [default:
+              shoe.txt], to deal with --shuffle file:PATH
+  ssh         Play as the command sshd runs for every login to an account set aside
+              for the game, giving each player a game of their own or, with
+              ssh -t play@host table NAME, a seat at the table hosted here on --port
+  simulate    Play many hands without the UI and report the house edge
+  stats       Print the lifetime statistics and the last session's, see --json
+  stats export
+              Write every hand in the history to a file, see --format and --output,
+              or only those played under --rules
+  stats reset Forget the saved lifetime statistics
+
+Options:
+      --ascii           Draw with ASCII in place of suits, box drawing, and other symbols
+      --autoplay        Let the computer play basic strategy by itself, as a demo
+      --autoplay-pace <MS>
+                        Milliseconds between the computer's moves when autoplaying
+      --betting <SYSTEMS>
+                        Compare betting systems over --sessions: flat, spread, martingale,
+                        paroli, kelly, or all
+      --boxes <N>       Play 2 to 4 boxes at once from one bankroll, each bet and played
+                        in turn
+      --challenge <NAME>
+                        Play a challenge scored on the local leaderboard: survival, lasting
+                        as many hands as possible on five minimum bets; speed, 25 hands
+                        against a shrinking clock, scoring fast correct decisions; or
+                        daily, the day's shoe shared by everyone, scored on the net over
+                        50 hands; daily-YYYY-MM-DD replays an earlier day
+      --cheat           Show the odds of the next card's value, read off the shoe, to
+                        learn from. Off in challenges
+      --config <PATH>   Read settings from PATH instead of the default config file
+      --count-quiz      Hide the count and quiz the running count at each shuffle
+      --counting-system <NAME>
+                        Count with Hi-Lo, KO, Hi-Opt I, Omega II, or Zen
+      --data-dir <PATH> Keep settings, statistics, saves, and logs in PATH, for a portable
+                        install, instead of the platform's directories
+      --drill-pace <MS> Milliseconds each card is shown in the counting drill
+      --format <FORMAT> Format of stats export, csv or json [default: csv], or of
+                        --no-tui output, text or json [default: text]
+      --hands <N>       Hands to play in a simulation [default: 1000000], or before a
+                        game ends by itself, ending with a summary
+      --hands-per-hour <N>
+                        Table speed for --sessions [default: 80]
+      --json            Print stats as JSON
+      --json-events <PATH>
+                        Write every deal, move, and settlement as a JSON line to PATH, or
+                        to stdout for -, drawing the game on stderr
+      --log-file <PATH> Log what the game does to PATH, filtered by RUST_LOG [default: the
+                        log directory when only RUST_LOG is set]
+      --metrics-addr <IP>
+                        Address to serve --metrics-port on, like 0.0.0.0 for every
+                        network [default: 127.0.0.1, this machine only]
+      --metrics-port <PORT>
+                        Serve Prometheus metrics of the table hosted with host over HTTP
+                        on PORT, at /metrics
+      --mute            Play without sound effects, in builds with the audio feature
+      --name <NAME>     Name to play under at a network table [default: $USER]
+      --no-color        Draw without colors
+      --no-tui          Play with commands like 'bet 10', hit, and stand from stdin,
+                        writing each change to stdout, see --format
+      --output <PATH>   Write simulation results to a .csv or .json file, or stats export
+                        [default: hands.csv] or a shoe to PATH
+      --penetration <PCT>
+                        Percent of the shoe dealt before it is reshuffled
+      --profile <NAME>  Keep statistics, history, saves, and automation.toml under a
+                        profile of their own, for more than one player on a computer
+      --port <PORT>     Port to host a table on [default: 7777], or to take bots on
+                        [default: 7778]
+      --players <N>     Play hotseat at one terminal with 2 to 4 players
+      --per-hand        Also write every simulated hand, next to the --output file
+      --record <PATH>   Record the game as drawn to PATH as an asciinema cast, to replay
+                        with asciinema play or share
+      --reduced-motion  Disable animations and dealer pacing
+      --rules <NAME>    Table rules, single-deck, vegas, downtown, double-exposure, or
+                        super-fun-21
+      --sessions <N>    Simulate N playing sessions from the bankroll, with the bet spread,
+                        to estimate risk of ruin
+      --session-hours <HOURS>
+                        Length of each simulated session [default: 4]
+      --scenario <SPEC> Rig every deal to a practice situation, like 'soft 18 vs 9-A'
+                        or a preset: pairs-vs-strong, soft-18, soft-doubles, stiffs, doubles
+      --socket <PATH>   Take bots on a Unix socket at PATH instead of a TCP port
+      --spectate        Watch a network table with join instead of taking a seat
+      --show-count      Show the Hi-Lo count panel
+      --shuffle <SOURCE>
+                        How shoes are shuffled, secure, seed:N to deal the same cards
+                        again, scripted:N,N,…, or file:PATH to deal the first shoe in the
+                        order of a shoe file saved with x on the shoe panel or by the shoe
+                        command [default: secure]
+      --sweep <RULE=VALUES>
+                        Compare rule variations in a simulation, like decks=1-8,
+                        soft17=stand,hit, or payout=3:2,6:5. Can be given more than once
+      --strategy <NAME> How a simulation plays, basic, dealer, never-bust, counting, or
+                        the path of a .csv chart
+      --summary         Print how the session went, its hands, net, and trainer accuracy,
+                        once the game is over
+      --screen-reader   Play with plain line-by-line text instead of the grid UI
+      --turn-timer <SECS>
+                        Seconds each seat at a hosted table has to decide before it
+                        stands or sits out, 0 for no limit [default: 30]
+      --trainer         Grade every decision against basic strategy
+  -h, --help            Print this help
+
+Environment:
+  BLACKJACK_CONFIG      Read settings from this file, like --config
+  BLACKJACK_DATA_DIR    Keep settings, statistics, saves, and logs in this directory,
+                        like --data-dir
+  BLACKJACK_SEED        Shuffle every shoe from this seed, like --shuffle seed:N
+  BLACKJACK_NO_COLOR    Draw without colors when set, like --no-color
+  BLACKJACK_ASCII       Draw with ASCII when set, like --ascii
+
+These override the config file, and are overridden by the flags.
+";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub enum Command {
+    #[default]
+    Play,
+    Bot,
+    Drill,
+    Flashcards,
+    Host,
+    Join,
+    Shoe,
+    Simulate,
+    Ssh,
+    Stats,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StatsAction {
+    Export,
+    Reset,
+}
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub address: Option<String>,
+    pub ascii: bool,
+    pub autoplay: bool,
+    pub autoplay_pace: Option<u64>,
+    pub betting: Option<String>,
+    pub boxes: Option<u8>,
+    pub challenge: Option<String>,
+    pub cheat: bool,
+    pub command: Command,
+    pub config: Option<PathBuf>,
+    pub count_quiz: bool,
+    pub counting_system: Option<String>,
+    pub data_dir: Option<PathBuf>,
+    pub drill_pace: Option<u64>,
+    pub format: Option<String>,
+    pub hands: Option<u64>,
+    pub hands_per_hour: Option<u32>,
+    pub json: bool,
+    pub json_events: Option<PathBuf>,
+    pub log_file: Option<PathBuf>,
+    pub metrics_addr: Option<IpAddr>,
+    pub metrics_port: Option<u16>,
+    pub mute: bool,
+    pub name: Option<String>,
+    pub no_color: bool,
+    pub no_tui: bool,
+    pub output: Option<PathBuf>,
+    pub penetration: Option<String>,
+    pub per_hand: bool,
+    pub players: Option<u8>,
+    pub port: Option<u16>,
+    pub profile: Option<String>,
+    pub record: Option<PathBuf>,
+    pub reduced_motion: bool,
+    pub rules: Option<String>,
+    pub scenario: Option<String>,
+    pub sessions: Option<u32>,
+    pub session_hours: Option<f64>,
+    pub screen_reader: bool,
+    pub show_count: bool,
+    pub shuffle: Option<String>,
+    pub socket: Option<PathBuf>,
+    pub spectate: bool,
+    pub stats_action: Option<StatsAction>,
+    pub strategy: Option<String>,
+    pub summary: bool,
+    pub sweeps: Vec<String>,
+    pub trainer: bool,
+    pub turn_timer: Option<u64>,
+}
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        let mut args = Args::default();
+        let mut argv = std::env::args().skip(1);
+        while let Some(arg) = argv.next() {
+            match arg.as_str() {
+                "--ascii" => args.ascii = true,
+                "--autoplay" => args.autoplay = true,
+                "--autoplay-pace" => {
+                    let pace = value(&arg, argv.next())?;
+                    args.autoplay_pace = Some(blackjack_tui::config::parse_number(&pace)?);
+                }
+                "--betting" => args.betting = Some(value(&arg, argv.next())?),
+                "--boxes" => {
+                    let boxes = value(&arg, argv.next())?;
+                    args.boxes = Some(blackjack_tui::config::parse_number(&boxes)?);
+                }
+                "--challenge" => args.challenge = Some(value(&arg, argv.next())?),
+                "--cheat" => args.cheat = true,
+                "--config" => args.config = Some(value(&arg, argv.next())?.into()),
+                "--count-quiz" => args.count_quiz = true,
+                "--counting-system" => args.counting_system = Some(value(&arg, argv.next())?),
+                "--data-dir" => args.data_dir = Some(value(&arg, argv.next())?.into()),
+                "--drill-pace" => {
+                    let pace = value(&arg, argv.next())?;
+                    args.drill_pace = Some(blackjack_tui::config::parse_number(&pace)?);
+                }
+                "--format" => args.format = Some(value(&arg, argv.next())?),
+                "--hands" => {
+                    let hands = value(&arg, argv.next())?;
+                    args.hands = Some(blackjack_tui::config::parse_number(&hands)?);
+                }
+                "--hands-per-hour" => {
+                    let hands = value(&arg, argv.next())?;
+                    args.hands_per_hour = Some(blackjack_tui::config::parse_number(&hands)?);
+                }
+                "--json" => args.json = true,
+                "--json-events" => args.json_events = Some(value(&arg, argv.next())?.into()),
+                "--log-file" => args.log_file = Some(value(&arg, argv.next())?.into()),
+                "--metrics-addr" => {
+                    let addr = value(&arg, argv.next())?;
+                    let addr = addr
+                        .parse()
+                        .map_err(|_| format!("'{addr}' isn't an IP address"))?;
+                    args.metrics_addr = Some(addr);
+                }
+                "--metrics-port" => {
+                    let port = value(&arg, argv.next())?;
+                    args.metrics_port = Some(blackjack_tui::config::parse_number(&port)?);
+                }
+                "--mute" => args.mute = true,
+                "--name" => args.name = Some(value(&arg, argv.next())?),
+                "--no-color" => args.no_color = true,
+                "--no-tui" => args.no_tui = true,
+                "--output" => args.output = Some(value(&arg, argv.next())?.into()),
+                "--penetration" => args.penetration = Some(value(&arg, argv.next())?),
+                "--per-hand" => args.per_hand = true,
+                "--port" => {
+                    let port = value(&arg, argv.next())?;
+                    args.port = Some(blackjack_tui::config::parse_number(&port)?);
+                }
+                "--players" => {
+                    let players = value(&arg, argv.next())?;
+                    args.players = Some(blackjack_tui::config::parse_number(&players)?);
+                }
+                "--profile" => args.profile = Some(value(&arg, argv.next())?),
+                "--record" => args.record = Some(value(&arg, argv.next())?.into()),
+                "--reduced-motion" => args.reduced_motion = true,
+                "--rules" => args.rules = Some(value(&arg, argv.next())?),
+                "--sessions" => {
+                    let sessions = value(&arg, argv.next())?;
+                    args.sessions = Some(blackjack_tui::config::parse_number(&sessions)?);
+                }
+                "--session-hours" => {
+                    let hours = value(&arg, argv.next())?;
+                    args.session_hours = Some(blackjack_tui::config::parse_number(&hours)?);
+                }
+                "--scenario" => args.scenario = Some(value(&arg, argv.next())?),
+                "--screen-reader" => args.screen_reader = true,
+                "--socket" => args.socket = Some(value(&arg, argv.next())?.into()),
+                "--spectate" => args.spectate = true,
+                "--show-count" => args.show_count = true,
+                "--shuffle" => args.shuffle = Some(value(&arg, argv.next())?),
+                "--strategy" => args.strategy = Some(value(&arg, argv.next())?),
+                "--summary" => args.summary = true,
+                "--sweep" => args.sweeps.push(value(&arg, argv.next())?),
+                "--trainer" => args.trainer = true,
+                "--turn-timer" => {
+                    let secs = value(&arg, argv.next())?;
+                    args.turn_timer = Some(blackjack_tui::config::parse_number(&secs)?);
+                }
+                "-h" | "--help" => {
+                    print!("{USAGE}");
+                    std::process::exit(0);
+                }
+                "bot" => args.command = Command::Bot,
+                "drill" => args.command = Command::Drill,
+                "flashcards" => args.command = Command::Flashcards,
+                "host" => args.command = Command::Host,
+                "join" => {
+                    args.command = Command::Join;
+                    args.address = Some(value(&arg, argv.next())?);
+                }
+                "shoe" => args.command = Command::Shoe,
+                "simulate" => args.command = Command::Simulate,
+                "ssh" => args.command = Command::Ssh,
+                "stats" => args.command = Command::Stats,
+                "export" if args.command == Command::Stats => {
+                    args.stats_action = Some(StatsAction::Export)
+                }
+                "reset" if args.command == Command::Stats => {
+                    args.stats_action = Some(StatsAction::Reset)
+                }
+                _ => return Err(format!("unknown argument '{arg}'\n\n{USAGE}")),
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn value(arg: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("'{arg}' expects a value\n\n{USAGE}"))
+}
@@ -0,0 +1,604 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader},
+    net::{TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    layout::Flex,
+    prelude::{Color, Constraint, Frame, Layout, Line, Rect, Style, Stylize},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    config::Config,
+    fairness, keyboard,
+    money::Money,
+    plain::Plain,
+    protocol::{self, ClientMessage, Phase, ServerMessage, TableState},
+    sound::{Sound, Sounds},
+    suspend,
+    term::{self, Event, KeyCode, Tui},
+    theme::Theme,
+};
+
+/// Chat messages kept for the chat panel.
+const CHAT_HISTORY: usize = 100;
+/// Colors of players in the chat, by seat.
+const SEAT_COLORS: [Color; 7] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::LightRed,
+    Color::LightGreen,
+];
+
+/// How long to wait between attempts to reconnect.
+const RETRY: Duration = Duration::from_secs(2);
+
+/// A connection to the host, with a thread passing on what it sends.
+struct Connection {
+    stream: TcpStream,
+    /// `None` once the connection drops
+    messages: Receiver<Option<ServerMessage>>,
+}
+impl Connection {
+    fn open(address: &str) -> io::Result<Self> {
+        let mut last = io::Error::new(io::ErrorKind::NotFound, "no address to connect to");
+        for address in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, RETRY) {
+                Ok(stream) => return Self::start(stream),
+                Err(err) => last = err,
+            }
+        }
+        Err(last)
+    }
+
+    fn start(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                // skip anything from a newer host this client doesn't understand
+                if let Ok(message) = protocol::parse(&line) {
+                    if sender.send(Some(message)).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = sender.send(None);
+        });
+        Ok(Self { stream, messages })
+    }
+
+    fn send(&mut self, message: &ClientMessage) {
+        // a failed write shows up as the reader seeing the connection drop
+        let _ = protocol::send(&mut self.stream, message);
+    }
+}
+
+/// The cards seen dealt from the shoe, to check against its order once the host reveals it.
+#[derive(Debug, Default)]
+struct ShoeCheck {
+    /// the shoe being dealt and the hash the host published for it
+    shoe: Option<(u32, String)>,
+    /// cards seen dealt from the shoe, oldest first
+    dealt: Vec<String>,
+    /// cards already noted in each seat's hand, and the dealer's under `None`, or `None`
+    /// until the first state since connecting
+    noted: Option<HashMap<Option<usize>, usize>>,
+}
+impl ShoeCheck {
+    /// Start on a new shoe. The host sends the hash of the current one again after
+    /// reconnecting, and the cards seen from it before are kept.
+    fn shuffled(&mut self, shoe: u32, hash: String) {
+        if self
+            .shoe
+            .as_ref()
+            .is_none_or(|(current, _)| *current != shoe)
+        {
+            self.dealt.clear();
+        }
+        self.shoe = Some((shoe, hash));
+    }
+
+    /// Note the cards dealt since the last state, in the order they were drawn. The
+    /// dealer's hole card is left out, since it is turned over long after it was drawn,
+    /// and so is everything already on the table when connecting.
+    fn note(&mut self, state: &TableState) {
+        let dealer: Vec<String> = state.dealer.iter().skip(1).flatten().cloned().collect();
+        let hands = state
+            .seats
+            .iter()
+            .map(|seat| (Some(seat.seat), &seat.cards))
+            .chain([(None, &dealer)]);
+        let connecting = self.noted.is_none();
+        let noted = self.noted.get_or_insert_with(HashMap::new);
+        for (hand, cards) in hands {
+            let before = noted.insert(hand, cards.len()).unwrap_or(0);
+            if !connecting {
+                self.dealt.extend(cards.iter().skip(before).cloned());
+            }
+        }
+    }
+
+    /// Check a revealed shoe, if it's the one whose hash was published.
+    fn check(&mut self, shoe: u32, salt: &str, cards: &[String]) -> Option<Result<(), String>> {
+        let (_, hash) = self.shoe.take().filter(|(current, _)| *current == shoe)?;
+        let dealt = std::mem::take(&mut self.dealt);
+        Some(fairness::verify(&hash, salt, cards, &dealt))
+    }
+}
+
+/// A player's view of a table hosted elsewhere.
+struct Client {
+    address: String,
+    name: String,
+    connection: Option<Connection>,
+    /// when to try connecting again after the connection dropped
+    retry_at: Instant,
+    seat: Option<usize>,
+    /// to take the seat back after reconnecting
+    token: Option<String>,
+    state: Option<TableState>,
+    /// the bet this player will place on their turn
+    bet: u32,
+    bet_unit: u32,
+    /// the last thing the host refused
+    error: Option<String>,
+    /// watching the table without a seat
+    spectating: bool,
+    /// sender, their seat, and text of recent chat messages
+    chat: Vec<(String, Option<usize>, String)>,
+    /// the chat message being typed
+    composing: Option<String>,
+    /// when the seat deciding runs out of time
+    turn_ends: Option<Instant>,
+    shoe: ShoeCheck,
+    sounds: Sounds,
+    /// how chip amounts are written
+    money: Money,
+    theme: Theme,
+    plain: Plain,
+}
+impl Client {
+    /// Ask for a seat, or for the old one back after reconnecting.
+    fn attach(&mut self, mut connection: Connection) {
+        connection.send(&if self.spectating {
+            ClientMessage::Watch
+        } else {
+            ClientMessage::Join {
+                name: self.name.clone(),
+                token: self.token.clone(),
+            }
+        });
+        self.connection = Some(connection);
+        self.error = None;
+    }
+
+    fn connect(&mut self) {
+        match Connection::open(&self.address) {
+            Ok(connection) => self.attach(connection),
+            Err(err) => {
+                self.error = Some(err.to_string());
+                self.retry_at = Instant::now() + RETRY;
+            }
+        }
+    }
+
+    /// Take in everything the host sent since last time.
+    fn receive(&mut self) {
+        let Some(connection) = &self.connection else {
+            if Instant::now() >= self.retry_at {
+                self.connect();
+            }
+            return;
+        };
+        while let Ok(message) = connection.messages.try_recv() {
+            match message {
+                Some(ServerMessage::Welcome { seat, token, .. }) => {
+                    self.seat = Some(seat);
+                    self.token = Some(token);
+                }
+                Some(ServerMessage::State(state)) => {
+                    let my_turn = self.is_my_turn(state.phase);
+                    let seat = state.seats.iter().find(|seat| Some(seat.seat) == self.seat);
+                    if state.turn.is_some() && state.turn == self.seat && !my_turn {
+                        self.sounds.play(Sound::Turn);
+                    }
+                    let settled = self.my_result().is_some();
+                    if seat.is_some_and(|seat| seat.result.as_deref() == Some("blackjack"))
+                        && !settled
+                    {
+                        self.sounds.play(Sound::Blackjack);
+                    }
+                    if let Some(seat) = seat.filter(|_| !self.is_my_turn(Phase::Betting)) {
+                        self.bet = seat.bet.max(self.bet_unit).min(seat.bankroll);
+                    }
+                    self.turn_ends = state
+                        .time_left_ms
+                        .map(|ms| Instant::now() + Duration::from_millis(ms));
+                    self.shoe.note(&state);
+                    self.state = Some(state);
+                }
+                Some(ServerMessage::Chat { from, seat, text }) => {
+                    self.chat.push((from, seat, text));
+                    let extra = self.chat.len().saturating_sub(CHAT_HISTORY);
+                    self.chat.drain(..extra);
+                }
+                Some(ServerMessage::TimedOut { seat }) if Some(seat) == self.seat => {
+                    self.error = Some("You ran out of time".to_string());
+                }
+                Some(ServerMessage::TimedOut { seat }) => {
+                    let name = self
+                        .state
+                        .iter()
+                        .flat_map(|state| &state.seats)
+                        .find(|player| player.seat == seat)
+                        .map_or("A player".to_string(), |player| player.name.clone());
+                    let text = format!("{name} ran out of time");
+                    self.chat.push(("Dealer".to_string(), None, text));
+                }
+                Some(ServerMessage::Shuffle { shoe, hash }) => {
+                    if self.shoe.noted.is_some() {
+                        self.sounds.play(Sound::Shuffle);
+                    }
+                    self.shoe.shuffled(shoe, hash);
+                }
+                Some(ServerMessage::Reveal { shoe, salt, cards }) => {
+                    let text = match self.shoe.check(shoe, &salt, &cards) {
+                        None => continue,
+                        Some(Ok(())) => format!("Shoe {} was dealt as promised", shoe + 1),
+                        Some(Err(err)) => {
+                            let text = format!("Shoe {} failed its check: {err}", shoe + 1);
+                            self.error = Some(text.clone());
+                            text
+                        }
+                    };
+                    self.chat.push(("Dealer".to_string(), None, text));
+                }
+                Some(ServerMessage::Error { message }) => self.error = Some(message),
+                None => {
+                    self.connection = None;
+                    self.shoe.noted = None;
+                    self.seat = None;
+                    self.retry_at = Instant::now() + RETRY;
+                    return;
+                }
+            }
+        }
+    }
+
+    fn is_my_turn(&self, phase: Phase) -> bool {
+        self.state.as_ref().is_some_and(|state| {
+            state.phase == phase && state.turn.is_some() && state.turn == self.seat
+        })
+    }
+
+    /// The result of this player's hand once it is settled.
+    fn my_result(&self) -> Option<&str> {
+        let state = self.state.as_ref()?;
+        let seat = state
+            .seats
+            .iter()
+            .find(|seat| Some(seat.seat) == self.seat)?;
+        seat.result.as_deref()
+    }
+
+    /// Whole seconds the seat deciding has left, if there is a limit.
+    fn seconds_left(&self) -> Option<u64> {
+        let left = self.turn_ends?.saturating_duration_since(Instant::now());
+        Some(left.as_millis().div_ceil(1000) as u64)
+    }
+
+    fn bankroll(&self) -> u32 {
+        self.state
+            .iter()
+            .flat_map(|state| &state.seats)
+            .find(|seat| Some(seat.seat) == self.seat)
+            .map_or(0, |seat| seat.bankroll)
+    }
+
+    /// Add pasted text to the chat message being typed, on one line. Pasting anywhere
+    /// else does nothing, rather than playing each character as a key.
+    fn paste(&mut self, text: &str) {
+        if let Some(composing) = &mut self.composing {
+            let text = text
+                .chars()
+                .map(|c| if c.is_whitespace() { ' ' } else { c });
+            composing.extend(text.filter(|c| !c.is_control()));
+        }
+    }
+
+    /// Handle a key press, returning false when the player quits.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(text) = &mut self.composing {
+            match code {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    let text = std::mem::take(text);
+                    self.composing = None;
+                    if let (false, Some(connection)) =
+                        (text.trim().is_empty(), &mut self.connection)
+                    {
+                        connection.send(&ClientMessage::Chat { text });
+                    }
+                }
+                KeyCode::Esc => self.composing = None,
+                _ => (),
+            }
+            return true;
+        }
+        let message = if matches!(code, KeyCode::Char('q') | KeyCode::Esc) {
+            return false;
+        } else if code == KeyCode::Char('t') {
+            self.composing = Some(String::new());
+            None
+        } else if self.is_my_turn(Phase::Betting) {
+            match code {
+                KeyCode::Up | KeyCode::Char('+' | '=') => {
+                    self.bet = (self.bet + self.bet_unit).min(self.bankroll());
+                    None
+                }
+                KeyCode::Down | KeyCode::Char('-') => {
+                    self.bet = self
+                        .bet
+                        .saturating_sub(self.bet_unit)
+                        .max(self.bet_unit.min(self.bankroll()));
+                    None
+                }
+                KeyCode::Char('s') => Some(ClientMessage::Bet { amount: 0 }),
+                KeyCode::Enter | KeyCode::Char(' ') => {
+                    Some(ClientMessage::Bet { amount: self.bet })
+                }
+                _ => None,
+            }
+        } else if self.is_my_turn(Phase::Playing) {
+            match code {
+                KeyCode::Char('1') => Some(ClientMessage::Hit),
+                KeyCode::Char('2') => Some(ClientMessage::Stand),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        if let (Some(message), Some(connection)) = (message, &mut self.connection) {
+            self.error = None;
+            connection.send(&message);
+        }
+        true
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        use Constraint::{Fill, Length};
+
+        let [title_area, dealer_area, seats_area, chat_area, status_area] =
+            Layout::vertical([Length(2), Length(4), Fill(1), Length(7), Length(1)])
+                .areas(frame.area());
+        let mut info = self
+            .state
+            .as_ref()
+            .map_or(String::new(), |state| state.rules.clone());
+        if let Some(state) = self.state.as_ref().filter(|state| state.spectators > 0) {
+            info += &format!(" · {} watching", state.spectators);
+        }
+        let title = match self.spectating {
+            true => format!("Blackjack · {} · Spectating", self.address),
+            false => format!("Blackjack · {}", self.address),
+        };
+        frame.render_widget(
+            Block::bordered()
+                .title(title)
+                .title(Line::from(format!("{info} ")).right_aligned()),
+            title_area,
+        );
+
+        let Some(state) = &self.state else {
+            frame.render_widget(Line::from(" Connecting…"), status_area);
+            return;
+        };
+
+        let dealer: Vec<_> = state
+            .dealer
+            .iter()
+            .map(|card| card.as_deref().map_or("▒▒".to_string(), card_text))
+            .collect();
+        let mut lines = vec![Line::from(dealer.join(" "))];
+        if let Some(total) = state.dealer_total {
+            lines.push(Line::from(format!("Value: {total}")));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Dealer")),
+            dealer_area,
+        );
+
+        let seat_areas = Layout::horizontal(vec![Fill(1); state.seats.len()])
+            .flex(Flex::Center)
+            .split(seats_area);
+        for (seat, area) in state.seats.iter().zip(seat_areas.iter()) {
+            let turn = state.turn == Some(seat.seat);
+            let mine = self.seat == Some(seat.seat);
+            let mut title = format!(
+                "{}{}{}",
+                if turn { "▶ " } else { "" },
+                seat.name,
+                if mine { " (you)" } else { "" }
+            );
+            if let Some(seconds) = self.seconds_left().filter(|_| turn) {
+                title += &format!(" · {seconds}s");
+            }
+            let mut block = Block::bordered().title(title);
+            if turn {
+                block = block.border_style(Style::new().fg(Color::Yellow)).bold();
+            }
+            let mut lines = vec![
+                Line::from(format!("Bankroll: {}", self.money.amount(seat.bankroll))),
+                Line::from(match seat.bet {
+                    0 => "Sitting out".to_string(),
+                    _ if mine && self.is_my_turn(Phase::Betting) => {
+                        format!("Bet: {}?", self.money.amount(self.bet))
+                    }
+                    bet => format!("Bet: {}", self.money.amount(bet)),
+                }),
+                Line::default(),
+            ];
+            if !seat.cards.is_empty() {
+                let cards: Vec<_> = seat.cards.iter().map(|card| card_text(card)).collect();
+                lines.push(Line::from(cards.join(" ")));
+            }
+            if let Some(total) = seat.total {
+                lines.push(Line::from(format!("Value: {total}")));
+            }
+            if let Some(result) = &seat.result {
+                let line = Line::from(format!("{result} {}", self.money.signed(seat.payout)));
+                lines.push(match seat.payout {
+                    1.. => line.green(),
+                    0 => line.yellow(),
+                    _ => line.red(),
+                });
+            }
+            frame.render_widget(Paragraph::new(lines).block(block), *area);
+        }
+
+        self.render_chat(frame, chat_area);
+
+        let waiting_on = state
+            .turn
+            .and_then(|turn| state.seats.iter().find(|seat| seat.seat == turn))
+            .map_or("the dealer", |seat| &seat.name);
+        let left = self
+            .seconds_left()
+            .map_or(String::new(), |seconds| format!(", {seconds}s left"));
+        let status = if self.composing.is_some() {
+            " Enter) Send    Esc) Cancel".to_string()
+        } else {
+            let prompt = if self.connection.is_none() {
+                "Connection lost, reconnecting…".to_string()
+            } else if let Some(error) = &self.error {
+                error.clone()
+            } else if self.is_my_turn(Phase::Betting) {
+                format!("Your bet{left}: Up/Down) Bet    Enter) Place    s) Sit out")
+            } else if self.is_my_turn(Phase::Playing) {
+                format!("Your turn{left}: 1) Hit    2) Hold")
+            } else if self.spectating && state.seats.is_empty() {
+                "Watching, waiting for players".to_string()
+            } else if self.spectating {
+                match state.phase {
+                    Phase::Results => "Watching, next round shortly".to_string(),
+                    _ => format!("Watching, waiting on {waiting_on}"),
+                }
+            } else if self.seat.is_none() {
+                "Taking a seat…".to_string()
+            } else {
+                match state.phase {
+                    Phase::Results => "Next round shortly".to_string(),
+                    _ => format!("Waiting on {waiting_on}"),
+                }
+            };
+            format!(" {prompt}    t) Chat    q) Quit")
+        };
+        frame.render_widget(Line::from(status), status_area);
+    }
+
+    /// Recent chat, newest at the bottom, above the message being typed.
+    fn render_chat(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title("Chat");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let mut lines: Vec<Line> = self
+            .chat
+            .iter()
+            .map(|(from, seat, text)| {
+                let color = seat.map_or(Color::Gray, |seat| SEAT_COLORS[seat % SEAT_COLORS.len()]);
+                Line::from(vec![
+                    format!("{from}: ").fg(color).bold(),
+                    text.clone().into(),
+                ])
+            })
+            .collect();
+        if let Some(text) = &self.composing {
+            lines.push(Line::from(format!("> {text}▏")).yellow());
+        }
+        let skip = lines.len().saturating_sub(usize::from(inner.height));
+        frame.render_widget(Paragraph::new(lines.split_off(skip)), inner);
+    }
+
+    fn run(&mut self, terminal: &mut Tui) -> io::Result<()> {
+        loop {
+            self.receive();
+            terminal.draw(|frame| {
+                self.render(frame);
+                self.theme.apply(frame.buffer_mut());
+                self.plain.apply(frame.buffer_mut());
+            })?;
+            if !term::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            match term::read()? {
+                Event::Key(key) if suspend::is_suspend_key(&key) => suspend::suspend(terminal)?,
+                Event::Key(key) if keyboard::acts_on(&key) && !self.handle_key(key.code) => {
+                    return Ok(());
+                }
+                Event::Paste(text) => self.paste(&text),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Rank and suit of a card code from the protocol, like `10♥` for `10H`.
+fn card_text(code: &str) -> String {
+    let (rank, suit) = code.split_at(code.len().saturating_sub(1));
+    let suit = match suit {
+        "S" => "♠",
+        "C" => "♣",
+        "D" => "♦",
+        "H" => "♥",
+        suit => suit,
+    };
+    format!("{rank}{suit}")
+}
+
+/// Play at a table hosted at `address` as `name`, or only watch it when `spectating`.
+pub fn join(config: &Config, address: &str, name: &str, spectating: bool) -> io::Result<()> {
+    let connection = Connection::open(address)?;
+    let mut client = Client {
+        address: address.to_string(),
+        name: name.to_string(),
+        connection: None,
+        retry_at: Instant::now(),
+        seat: None,
+        token: None,
+        state: None,
+        bet: config.bet_unit,
+        bet_unit: config.bet_unit,
+        error: None,
+        spectating,
+        chat: Vec::new(),
+        composing: None,
+        turn_ends: None,
+        shoe: ShoeCheck::default(),
+        sounds: Sounds::new(config),
+        money: config.money(),
+        theme: config.theme,
+        plain: config.plain(),
+    };
+    client.attach(connection);
+
+    let mut terminal = term::init()?;
+    let result = client.run(&mut terminal);
+    term::restore()?;
+    result
+}
@@ -0,0 +1,35 @@
+use std::io::{self, IsTerminal, Write};
+
+/// Put `text` on the system clipboard with the OSC 52 escape sequence, which the terminal
+/// carries out, so it works over SSH and without a clipboard tool. Terminals that don't
+/// support it ignore it.
+pub fn copy(text: &str) -> io::Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    // write on whichever of stdout and stderr the game is drawn on
+    match io::stdout().is_terminal() {
+        true => {
+            let mut stdout = io::stdout();
+            stdout.write_all(sequence.as_bytes())?;
+            stdout.flush()
+        }
+        false => io::stderr().write_all(sequence.as_bytes()),
+    }
+}
+
+/// Standard base64 with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | u32::from(*byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,501 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    betting::BetSpread,
+    challenge::Challenge,
+    chatter::Personality,
+    counting::{self, CountingSystem},
+    game::HoleCard,
+    keymap::Keymap,
+    money::{self, Currency, Money},
+    paths,
+    plain::Plain,
+    rules::Rules,
+    scenario::Scenario,
+    shuffle::Shuffler,
+    side_bets::{self, Wager, SIDE_BETS},
+    sound::Sound,
+    theme::Theme,
+};
+
+/// User settings, read from a `key = value` config file, overridden by the profile's
+/// [automation and limits](Config::PROFILE_FILE), `BLACKJACK_*` environment variables, and those by
+/// CLI flags.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// draw with ASCII in place of suits, box drawing, and other symbols
+    pub ascii: bool,
+    /// how long the betting screen before the first hand waits for a key before a demo
+    /// plays, zero for never
+    pub attract_after: Duration,
+    /// turn down insurance without asking, and even money unless `auto_even_money` takes it
+    pub auto_decline_insurance: bool,
+    /// take even money on a blackjack against a dealer ace without asking
+    pub auto_even_money: bool,
+    /// hold a hand as soon as it reaches 21, without waiting for the player
+    pub auto_stand_21: bool,
+    /// let the computer play basic strategy by itself
+    pub autoplay: bool,
+    /// delay between the computer's moves when autoplaying
+    pub autoplay_pace: Duration,
+    /// starting bankroll, in chips
+    pub bankroll: u32,
+    /// chips in one betting unit, the smallest bet step
+    pub bet_unit: u32,
+    /// largest suggested bet, in units
+    pub bet_spread: u32,
+    /// sounds that ring the terminal bell instead when there's no audio to play them
+    pub bell: Vec<Sound>,
+    /// how long the break forced by a session limit lasts before play can go on
+    pub break_length: Duration,
+    /// challenge being played, scored on the leaderboard
+    pub challenge: Option<Challenge>,
+    /// show the odds of the next card's value, read off the shoe as no player could
+    pub cheat_mode: bool,
+    pub count_quiz: bool,
+    pub counting_system: &'static dyn CountingSystem,
+    /// what chip amounts are shown as
+    pub currency: Currency,
+    /// how the dealer comments on the game, `None` for a quiet dealer
+    pub dealer_chatter: Option<Personality>,
+    /// how long each card is shown in the counting drill
+    pub drill_pace: Duration,
+    /// cards shown between each count check in the counting drill
+    pub drill_checkpoint: u32,
+    /// hands played before the session ends by itself, `None` to play on
+    pub hand_limit: Option<u64>,
+    /// when the dealer's hole card is turned over on a hand the dealer doesn't play out
+    pub hole_card: HoleCard,
+    /// the keys that make the moves of a hand
+    pub keymap: Keymap,
+    /// chips lost in a session that call for a break, zero for no limit
+    pub loss_limit: u32,
+    /// play no sound effects
+    pub mute: bool,
+    /// draw without colors
+    pub no_color: bool,
+    /// stop play and dim the table while the terminal doesn't have focus
+    pub pause_on_blur: bool,
+    /// the deck composition and analysis only know the cards the player has seen, not the
+    /// ones burned or still in the shoe
+    pub realistic_info: bool,
+    pub reduced_motion: bool,
+    pub rules: Rules,
+    /// practice situation to rig every deal to
+    pub scenario: Option<Scenario>,
+    pub show_analysis: bool,
+    pub show_count: bool,
+    /// side bets offered at the table and the chips staked on each every hand, zero for a
+    /// bet that starts turned off
+    pub side_bets: Vec<Wager>,
+    /// where the shoe's shuffles get their randomness, secure unless the same cards
+    /// should come again
+    pub shuffle: Shuffler,
+    /// the colors the table is drawn in
+    pub theme: Theme,
+    /// between groups of thousands in chip amounts, `None` to leave them ungrouped
+    pub thousands_separator: Option<char>,
+    /// time played in a session that calls for a break, zero for no limit
+    pub time_limit: Duration,
+    pub trainer: bool,
+    /// how long each seat at a network table has to decide, zero for no limit
+    pub turn_timer: Duration,
+    /// loudness of sound effects, in percent
+    pub volume: u8,
+    /// chips won in a session that call for a break to bank them, zero for no goal
+    pub win_goal: u32,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            attract_after: Duration::from_secs(120),
+            auto_decline_insurance: false,
+            auto_even_money: false,
+            auto_stand_21: false,
+            autoplay: false,
+            autoplay_pace: Duration::from_millis(800),
+            bankroll: 1000,
+            bet_unit: 10,
+            bet_spread: 8,
+            bell: Vec::new(),
+            break_length: Duration::from_secs(60),
+            challenge: None,
+            cheat_mode: false,
+            count_quiz: false,
+            counting_system: &counting::HiLo,
+            currency: Currency::Plain,
+            dealer_chatter: None,
+            drill_pace: Duration::from_millis(1000),
+            drill_checkpoint: 10,
+            hand_limit: None,
+            hole_card: HoleCard::default(),
+            keymap: Keymap::default(),
+            loss_limit: 0,
+            mute: false,
+            no_color: false,
+            pause_on_blur: false,
+            realistic_info: false,
+            reduced_motion: false,
+            rules: Rules::default(),
+            scenario: None,
+            show_analysis: false,
+            show_count: false,
+            side_bets: SIDE_BETS.map(|bet| Wager { bet, stake: 0 }).to_vec(),
+            shuffle: Shuffler::Secure,
+            theme: Theme::default(),
+            thousands_separator: Money::default().separator,
+            time_limit: Duration::ZERO,
+            trainer: false,
+            turn_timer: Duration::from_secs(30),
+            volume: 70,
+            win_goal: 0,
+        }
+    }
+}
+impl Config {
+    const DEALER_PACE: Duration = Duration::from_millis(500);
+    /// The answers to prompts a player always answers the same way and their session
+    /// limits, kept in the data directory so each `--profile` has its own, in the config
+    /// file's `key = value` format.
+    pub const PROFILE_FILE: &str = "automation.toml";
+    /// The settings the profile's file can hold.
+    const PROFILE_KEYS: [&str; 6] = [
+        "auto_decline_insurance",
+        "auto_even_money",
+        "auto_stand_21",
+        "loss_limit",
+        "time_limit_mins",
+        "win_goal",
+    ];
+
+    /// Override the automation settings and limits with the profile's own, if it has any.
+    pub fn apply_profile(&mut self) -> Result<(), String> {
+        let Some(path) = paths::data_dir().map(|dir| dir.join(Self::PROFILE_FILE)) else {
+            return Ok(());
+        };
+        let Some(entries) = read_key_values(&path)? else {
+            return Ok(());
+        };
+        for (number, key, value) in entries {
+            let set = match Self::PROFILE_KEYS.contains(&key.as_str()) {
+                true => self.set(&key, &value),
+                false => Err(format!(
+                    "'{key}' isn't kept per profile, expected {}",
+                    Self::PROFILE_KEYS.join(", ")
+                )),
+            };
+            set.map_err(|err| format!("{}:{number}: {err}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Override settings from the environment: `BLACKJACK_SEED` to shuffle every shoe
+    /// from a seed, and `BLACKJACK_NO_COLOR` and `BLACKJACK_ASCII` set to anything but
+    /// `0` or `false`.
+    pub fn apply_env(&mut self) -> Result<(), String> {
+        self.apply_vars(env_var)
+    }
+
+    /// [`Config::apply_env`] with the variables `var` looks up.
+    fn apply_vars(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        if let Some(seed) = var("BLACKJACK_SEED") {
+            self.shuffle = Shuffler::parse(&format!("seed:{seed}"))
+                .map_err(|_| format!("BLACKJACK_SEED: expected a number, found '{seed}'"))?;
+        }
+        if let Some(value) = var("BLACKJACK_NO_COLOR") {
+            self.no_color = !matches!(value.as_str(), "0" | "false");
+        }
+        if let Some(value) = var("BLACKJACK_ASCII") {
+            self.ascii = !matches!(value.as_str(), "0" | "false");
+        }
+        Ok(())
+    }
+
+    /// How frames are drawn for terminals that can't show everything.
+    pub fn plain(&self) -> Plain {
+        Plain {
+            no_color: self.no_color,
+            ascii: self.ascii,
+        }
+    }
+
+    /// Take the settings that can change in the middle of a session from `fresh`, the
+    /// settings built again from the config file, the profile, the environment, and the
+    /// command line, so each still overrides the one before: the autoplay pace and reduced
+    /// motion, the sounds, the theme and keymap, and how chip amounts are written. The
+    /// rest only change on a restart.
+    pub fn reload(&mut self, fresh: Config) {
+        self.autoplay_pace = fresh.autoplay_pace;
+        self.bell = fresh.bell;
+        self.currency = fresh.currency;
+        self.keymap = fresh.keymap;
+        self.mute = fresh.mute;
+        self.reduced_motion = fresh.reduced_motion;
+        self.theme = fresh.theme;
+        self.thousands_separator = fresh.thousands_separator;
+        self.volume = fresh.volume;
+    }
+
+    /// Load the config file at `path`, falling back to defaults if it doesn't exist.
+    pub fn load(path: Option<PathBuf>) -> Result<Self, String> {
+        let mut config = Config::default();
+        let Some(path) = path.or_else(paths::config_file) else {
+            return Ok(config);
+        };
+        let Some(entries) = read_key_values(&path)? else {
+            return Ok(config);
+        };
+
+        for (number, key, value) in entries {
+            config
+                .set(&key, &value)
+                .map_err(|err| format!("{}:{number}: {err}", path.display()))?;
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "ascii" => self.ascii = parse_bool(value)?,
+            "attract_secs" => self.attract_after = Duration::from_secs(parse_number(value)?),
+            "auto_decline_insurance" => self.auto_decline_insurance = parse_bool(value)?,
+            "auto_even_money" => self.auto_even_money = parse_bool(value)?,
+            "auto_stand_21" => self.auto_stand_21 = parse_bool(value)?,
+            "autoplay" => self.autoplay = parse_bool(value)?,
+            "autoplay_pace_ms" => self.autoplay_pace = Duration::from_millis(parse_number(value)?),
+            "bankroll" => self.bankroll = parse_number(value)?,
+            "bet_unit" => self.bet_unit = parse_number::<u32>(value)?.max(1),
+            "bet_spread" => self.bet_spread = parse_number::<u32>(value)?.max(1),
+            "bell" => self.bell = Sound::parse_list(value)?,
+            "break_secs" => self.break_length = Duration::from_secs(parse_number(value)?),
+            "cheat_mode" => self.cheat_mode = parse_bool(value)?,
+            "count_quiz" => self.count_quiz = parse_bool(value)?,
+            "counting_system" => self.counting_system = parse_counting_system(value)?,
+            "currency" => self.currency = Currency::parse(value)?,
+            "dealer_chatter" => self.dealer_chatter = Personality::parse(value)?,
+            "drill_pace_ms" => self.drill_pace = Duration::from_millis(parse_number(value)?),
+            "drill_checkpoint" => self.drill_checkpoint = parse_number(value)?,
+            "hole_card" => self.hole_card = HoleCard::parse(value)?,
+            "keymap" => self.keymap = Keymap::parse(value)?,
+            "loss_limit" => self.loss_limit = parse_number(value)?,
+            "mute" => self.mute = parse_bool(value)?,
+            "no_color" => self.no_color = parse_bool(value)?,
+            "pause_on_blur" => self.pause_on_blur = parse_bool(value)?,
+            "realistic_info" => self.realistic_info = parse_bool(value)?,
+            "reduced_motion" => self.reduced_motion = parse_bool(value)?,
+            "rules" => self.rules = parse_rules(value)?,
+            "scenario" => self.scenario = Some(Scenario::parse(value)?),
+            "show_analysis" => self.show_analysis = parse_bool(value)?,
+            "show_count" => self.show_count = parse_bool(value)?,
+            "shuffle" => self.shuffle = Shuffler::parse(value)?,
+            "side_bets" => self.side_bets = side_bets::parse_side_bets(value)?,
+            "theme" => self.theme = Theme::parse(value)?,
+            "thousands_separator" => self.thousands_separator = money::parse_separator(value)?,
+            "time_limit_mins" => {
+                self.time_limit = Duration::from_secs(parse_number::<u64>(value)? * 60)
+            }
+            "trainer" => self.trainer = parse_bool(value)?,
+            "turn_timer_secs" => self.turn_timer = Duration::from_secs(parse_number(value)?),
+            "volume" => {
+                self.volume = parse_number(value)
+                    .ok()
+                    .filter(|volume| *volume <= 100)
+                    .ok_or_else(|| format!("expected 0 to 100 percent, found '{value}'"))?
+            }
+            "win_goal" => self.win_goal = parse_number(value)?,
+            _ => return Err(format!("unknown setting '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// How chip amounts are written on screen.
+    pub fn money(&self) -> Money {
+        Money {
+            currency: self.currency.clone(),
+            separator: self.thousands_separator,
+        }
+    }
+
+    pub fn bet_spread(&self) -> BetSpread {
+        BetSpread {
+            unit: self.bet_unit,
+            spread: self.bet_spread,
+        }
+    }
+
+    /// Delay between dealer draws while the dealer plays out their hand.
+    pub fn dealer_pace(&self) -> Duration {
+        if self.reduced_motion {
+            Duration::ZERO
+        } else {
+            Self::DEALER_PACE
+        }
+    }
+}
+
+/// A `(line number, key, value)` entry of a settings file.
+pub type KeyValue = (usize, String, String);
+
+/// Read a `key = value` file into entries, or `None` if it doesn't exist.
+pub fn read_key_values(path: &Path) -> Result<Option<Vec<KeyValue>>, String> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+
+    let mut entries = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!(
+                "{}:{}: expected `key = value`",
+                path.display(),
+                number + 1
+            ));
+        };
+        entries.push((
+            number + 1,
+            key.trim().to_string(),
+            value.trim().trim_matches('"').to_string(),
+        ));
+    }
+    Ok(Some(entries))
+}
+
+pub fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, found '{value}'"))
+}
+
+/// The environment variable `name`, if it's set to anything.
+pub fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+pub(crate) fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false, found '{value}'")),
+    }
+}
+
+pub fn parse_counting_system(value: &str) -> Result<&'static dyn CountingSystem, String> {
+    counting::system_by_name(value).ok_or_else(|| {
+        let names = counting::SYSTEMS.map(|system| system.name()).join(", ");
+        format!("unknown counting system '{value}', expected one of {names}")
+    })
+}
+
+pub fn parse_rules(value: &str) -> Result<Rules, String> {
+    Rules::preset(value).ok_or_else(|| {
+        let names = Rules::PRESETS.map(|(name, _)| name).join(", ");
+        format!("unknown rules '{value}', expected one of {names}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `config` with the environment variables `vars` applied.
+    fn with_vars(mut config: Config, vars: &[(&str, &str)]) -> Result<Config, String> {
+        config.apply_vars(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })?;
+        Ok(config)
+    }
+
+    #[test]
+    fn the_environment_overrides_settings() {
+        let config = with_vars(
+            Config::default(),
+            &[("BLACKJACK_SEED", "42"), ("BLACKJACK_NO_COLOR", "1")],
+        )
+        .unwrap();
+        assert_eq!(config.shuffle, Shuffler::Seeded(42));
+        assert!(config.no_color);
+        assert!(!config.ascii);
+
+        let colored = Config {
+            no_color: true,
+            ascii: true,
+            ..Config::default()
+        };
+        let config = with_vars(
+            colored,
+            &[("BLACKJACK_NO_COLOR", "false"), ("BLACKJACK_ASCII", "0")],
+        )
+        .unwrap();
+        assert!(!config.no_color && !config.ascii);
+
+        let err = with_vars(Config::default(), &[("BLACKJACK_SEED", "lucky")]).unwrap_err();
+        assert_eq!(err, "BLACKJACK_SEED: expected a number, found 'lucky'");
+    }
+
+    #[test]
+    fn sets_settings_from_their_values() {
+        let mut config = Config::default();
+        config.set("bet_unit", "0").unwrap();
+        assert_eq!(config.bet_unit, 1);
+        config.set("auto_even_money", "true").unwrap();
+        assert!(config.auto_even_money);
+        assert!(config.set("auto_even_money", "yes").is_err());
+        assert!(config.set("bankrol", "100").is_err());
+    }
+
+    #[test]
+    fn profiles_keep_only_automation_and_limits() {
+        paths::use_test_dir();
+        let dir = paths::data_dir().unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(Config::PROFILE_FILE);
+
+        fs::write(
+            &path,
+            "auto_even_money = true\nloss_limit = 200\ntime_limit_mins = 45\nwin_goal = 300\n",
+        )
+        .unwrap();
+        let mut config = Config::default();
+        config.apply_profile().unwrap();
+        assert!(config.auto_even_money);
+        assert_eq!(config.loss_limit, 200);
+        assert_eq!(config.time_limit, Duration::from_secs(45 * 60));
+        assert_eq!(config.win_goal, 300);
+
+        fs::write(&path, "bankroll = 5\n").unwrap();
+        let err = config.apply_profile().unwrap_err();
+        assert!(err.contains("'bankroll' isn't kept per profile"), "{err}");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reloads_only_the_live_settings() {
+        let mut config = Config {
+            mute: true,
+            ..Config::default()
+        };
+        let mut fresh = Config::default();
+        fresh.set("theme", "high-contrast").unwrap();
+        fresh.set("keymap", "hit:j, stand:k").unwrap();
+        fresh.set("bankroll", "5").unwrap();
+        config.reload(fresh);
+        assert_eq!(config.theme, Theme::HighContrast);
+        assert_eq!(config.keymap.key(crate::strategy::Action::Stand), 'k');
+        assert_eq!(config.bankroll, Config::default().bankroll);
+        assert!(!config.mute);
+
+        let err = config.set("theme", "neon").unwrap_err();
+        assert!(err.starts_with("unknown theme 'neon'"), "{err}");
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt::Debug;
+
+use crate::widgets::*;
+
+/// A card counting system, defined by the tag it gives each rank.
+pub trait CountingSystem: Debug + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Tags for 2 through 9, tens, and aces.
+    fn tags(&self) -> [i32; 10];
+
+    /// Balanced systems sum to zero over a full deck and are converted to a true count.
+    fn is_balanced(&self) -> bool {
+        true
+    }
+
+    /// The running count a fresh shoe starts at.
+    fn initial_count(&self, _decks: u32) -> i32 {
+        0
+    }
+
+    fn tag(&self, rank: Rank) -> i32 {
+        let index = match rank {
+            Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 8,
+            Rank::Ace => 9,
+            rank => usize::from(rank.get_value() - 2),
+        };
+        self.tags()[index]
+    }
+}
+
+#[derive(Debug)]
+pub struct HiLo;
+impl CountingSystem for HiLo {
+    fn name(&self) -> &'static str {
+        "Hi-Lo"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 1, 1, 1, 0, 0, 0, -1, -1]
+    }
+}
+
+#[derive(Debug)]
+pub struct Ko;
+impl CountingSystem for Ko {
+    fn name(&self) -> &'static str {
+        "KO"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 1, 1, 1, 1, 0, 0, -1, -1]
+    }
+
+    fn is_balanced(&self) -> bool {
+        false
+    }
+
+    fn initial_count(&self, decks: u32) -> i32 {
+        4 - 4 * decks as i32
+    }
+}
+
+#[derive(Debug)]
+pub struct HiOptI;
+impl CountingSystem for HiOptI {
+    fn name(&self) -> &'static str {
+        "Hi-Opt I"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [0, 1, 1, 1, 1, 0, 0, 0, -1, 0]
+    }
+}
+
+#[derive(Debug)]
+pub struct OmegaII;
+impl CountingSystem for OmegaII {
+    fn name(&self) -> &'static str {
+        "Omega II"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 2, 2, 2, 1, 0, -1, -2, 0]
+    }
+}
+
+#[derive(Debug)]
+pub struct Zen;
+impl CountingSystem for Zen {
+    fn name(&self) -> &'static str {
+        "Zen"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 2, 2, 2, 1, 0, 0, -2, -1]
+    }
+}
+
+pub const SYSTEMS: [&dyn CountingSystem; 5] = [&HiLo, &Ko, &HiOptI, &OmegaII, &Zen];
+
+/// Find a counting system by name, ignoring case and punctuation.
+pub fn system_by_name(name: &str) -> Option<&'static dyn CountingSystem> {
+    let normalize = |name: &str| {
+        name.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_lowercase()
+    };
+    SYSTEMS
+        .into_iter()
+        .find(|system| normalize(system.name()) == normalize(name))
+}
+
+/// Count of the cards seen since the last shuffle.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Count {
+    #[cfg_attr(feature = "serde", serde(with = "system_name"))]
+    pub system: &'static dyn CountingSystem,
+    pub running: i32,
+    /// the deck's shuffle number this count belongs to
+    pub shoe: u32,
+}
+impl Count {
+    /// A fresh count of a shoe of `decks` decks.
+    pub fn new(system: &'static dyn CountingSystem, decks: u8, shoe: u32) -> Self {
+        Self {
+            system,
+            running: system.initial_count(u32::from(decks)),
+            shoe,
+        }
+    }
+
+    pub fn observe(&mut self, card: Card) {
+        self.running += self.system.tag(card.rank());
+    }
+
+    /// Running count divided by the number of decks left to be dealt.
+    pub fn true_count(&self, decks_remaining: f64) -> f64 {
+        if decks_remaining > 0.0 {
+            f64::from(self.running) / decks_remaining
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Counting systems are saved by name.
+#[cfg(feature = "serde")]
+mod system_name {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::CountingSystem;
+
+    pub fn serialize<S: Serializer>(
+        system: &&'static dyn CountingSystem,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(system.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<&'static dyn CountingSystem, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        super::system_by_name(&name)
+            .ok_or_else(|| D::Error::custom(format!("unknown counting system '{name}'")))
+    }
+}
+
+pub fn decks_remaining(deck: &Shoe) -> f64 {
+    deck.remaining() as f64 / 52.0
+}
+
+/// Cards of each rank left to come, to work out the chances of the next card.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Composition([u32; 13]);
+impl Composition {
+    pub fn of<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Self {
+        let mut composition = Self::default();
+        for card in cards {
+            composition.add(*card);
+        }
+        composition
+    }
+
+    pub fn add(&mut self, card: Card) {
+        self.0[card.rank() as usize] += 1;
+    }
+
+    pub fn count(&self, rank: Rank) -> u32 {
+        self.0[rank as usize]
+    }
+
+    pub fn total(&self) -> u32 {
+        self.0.iter().sum()
+    }
+
+    /// Chance the next card is one of `ranks`, as a fraction.
+    pub fn chance(&self, ranks: &[Rank]) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => {
+                let count: u32 = ranks.iter().map(|rank| self.count(*rank)).sum();
+                f64::from(count) / f64::from(total)
+            }
+        }
+    }
+
+    /// Share of the cards left that count ten.
+    pub fn ten_density(&self) -> f64 {
+        self.chance(&[Rank::Ten, Rank::Jack, Rank::Queen, Rank::King])
+    }
+
+    /// Share of the cards left that are aces.
+    pub fn ace_density(&self) -> f64 {
+        self.chance(&[Rank::Ace])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::parse_card_code;
+
+    fn counted(system: &'static dyn CountingSystem, decks: u8, cards: &str) -> Count {
+        let mut count = Count::new(system, decks, 0);
+        for card in cards.split(' ').filter_map(parse_card_code) {
+            count.observe(card);
+        }
+        count
+    }
+
+    #[test]
+    fn counts_each_system() {
+        let cards = "5S 5H 4D KC AS 7H 9D";
+        let running: Vec<_> = SYSTEMS
+            .into_iter()
+            .map(|system| counted(system, 1, cards).running)
+            .collect();
+        assert_eq!(running, [1, 2, 2, 4, 4]);
+
+        // the true count is the running count per deck left
+        let count = counted(&HiLo, 6, cards);
+        assert_eq!(count.true_count(0.5), 2.0);
+        assert_eq!(count.true_count(2.0), 0.5);
+        assert_eq!(count.true_count(0.0), 0.0);
+        assert_eq!(counted(&OmegaII, 6, cards).true_count(2.0), 2.0);
+    }
+
+    #[test]
+    fn ko_starts_below_zero_for_each_deck_after_the_first() {
+        assert_eq!(Count::new(&Ko, 1, 0).running, 0);
+        assert_eq!(Count::new(&Ko, 6, 0).running, -20);
+        assert_eq!(counted(&Ko, 2, "5S 5H 4D KC AS 7H 9D").running, -2);
+        assert_eq!(Count::new(&HiLo, 6, 0).running, 0);
+
+        // over whole decks, balanced systems come back to zero and KO ends on +4
+        let deck = Rank::ALL.map(|rank| {
+            [Suit::Spade, Suit::Heart, Suit::Diamond, Suit::Club].map(|suit| Card::new(rank, suit))
+        });
+        for system in SYSTEMS {
+            let mut count = Count::new(system, 2, 0);
+            for card in deck.iter().chain(&deck).flatten() {
+                count.observe(*card);
+            }
+            let expected = if system.is_balanced() { 0 } else { 4 };
+            assert_eq!(count.running, expected, "{}", system.name());
+        }
+    }
+
+    #[test]
+    fn finds_systems_by_name() {
+        assert_eq!(
+            system_by_name("hi-lo").map(|system| system.name()),
+            Some("Hi-Lo")
+        );
+        assert!(system_by_name("Omega 2").is_none());
+        assert_eq!(
+            system_by_name("omegaii").map(|system| system.name()),
+            Some("Omega II")
+        );
+    }
+}
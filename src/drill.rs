@@ -0,0 +1,396 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    layout::Flex,
+    prelude::{Constraint, Frame, Layout, Line, Stylize},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    config::*,
+    counting::Count,
+    keyboard, paths, suspend,
+    term::{self, Event, KeyCode},
+    widgets::*,
+};
+
+/// Lifetime results of the counting drill, kept apart from game statistics.
+#[derive(Debug, Default)]
+pub struct DrillStats {
+    pub sessions: u32,
+    pub checkpoints: u32,
+    pub correct: u32,
+    /// total time spent answering, in milliseconds
+    pub answer_ms: u64,
+}
+impl DrillStats {
+    pub const DRILL_FILE: &str = "drill_stats.toml";
+    pub const QUIZ_FILE: &str = "quiz_stats.toml";
+
+    fn path(file: &str) -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(file))
+    }
+
+    pub fn load(file: &str) -> Self {
+        let mut stats = DrillStats::default();
+        let Some(Ok(Some(entries))) = Self::path(file).map(|path| read_key_values(&path)) else {
+            return stats;
+        };
+        for (_, key, value) in entries {
+            match key.as_str() {
+                "sessions" => stats.sessions = parse_number(&value).unwrap_or_default(),
+                "checkpoints" => stats.checkpoints = parse_number(&value).unwrap_or_default(),
+                "correct" => stats.correct = parse_number(&value).unwrap_or_default(),
+                "answer_ms" => stats.answer_ms = parse_number(&value).unwrap_or_default(),
+                _ => (),
+            }
+        }
+        stats
+    }
+
+    pub fn save(&self, file: &str) -> std::io::Result<()> {
+        let Some(path) = Self::path(file) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            path,
+            format!(
+                "sessions = {}\ncheckpoints = {}\ncorrect = {}\nanswer_ms = {}\n",
+                self.sessions, self.checkpoints, self.correct, self.answer_ms
+            ),
+        )
+    }
+
+    pub fn summary(&self) -> String {
+        if self.checkpoints == 0 {
+            return "no checkpoints yet".to_string();
+        }
+        format!(
+            "{}/{} correct ({:.0}%), {:.1}s per answer",
+            self.correct,
+            self.checkpoints,
+            f64::from(self.correct) / f64::from(self.checkpoints) * 100.0,
+            self.answer_ms as f64 / f64::from(self.checkpoints) / 1000.0
+        )
+    }
+
+    fn record(&mut self, correct: bool, answer_time: Duration) {
+        self.checkpoints += 1;
+        self.correct += u32::from(correct);
+        self.answer_ms += answer_time.as_millis() as u64;
+    }
+
+    /// Add a session's results to the saved lifetime totals.
+    pub fn save_session(&self, file: &str) -> std::io::Result<()> {
+        if self.checkpoints == 0 {
+            return Ok(());
+        }
+        let mut lifetime = DrillStats::load(file);
+        lifetime.sessions += 1;
+        lifetime.checkpoints += self.checkpoints;
+        lifetime.correct += self.correct;
+        lifetime.answer_ms += self.answer_ms;
+        lifetime.save(file)
+    }
+}
+
+/// Asks for the running count of a shoe that was just shuffled away, while the count is
+/// hidden during play.
+#[derive(Debug)]
+pub struct CountQuiz {
+    pub expected: i32,
+    pub input: String,
+    since: Instant,
+}
+impl CountQuiz {
+    pub fn new(expected: i32) -> Self {
+        Self {
+            expected,
+            input: String::new(),
+            since: Instant::now(),
+        }
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("What was the running count? {}_", self.input)
+    }
+
+    /// Grade the typed answer, returning a message for the player.
+    pub fn answer(&self, stats: &mut DrillStats) -> Option<(bool, String)> {
+        let answer = self.input.trim_start_matches('+').parse::<i32>().ok()?;
+        let correct = answer == self.expected;
+        stats.record(correct, self.since.elapsed());
+        Some(if correct {
+            (true, format!("Correct, the count was {:+}", self.expected))
+        } else {
+            (
+                false,
+                format!("Not quite, the count was {:+}", self.expected),
+            )
+        })
+    }
+}
+
+#[derive(Debug)]
+enum DrillState {
+    Flashing { next_at: Instant },
+    Answering { input: String, since: Instant },
+}
+
+/// Cards flash by one at a time, and every few cards the player is asked for the running
+/// count.
+#[derive(Debug)]
+struct Drill {
+    deck: Shoe,
+    count: Count,
+    card: Option<Card>,
+    shown: u32,
+    state: DrillState,
+    feedback: Option<(bool, String)>,
+    session: DrillStats,
+}
+impl Drill {
+    /// Cards are flashed from a single deck.
+    const DECKS: u8 = 1;
+
+    fn new(config: &Config) -> Self {
+        Self {
+            deck: Shoe::new(Self::DECKS),
+            count: Count::new(config.counting_system, Self::DECKS, 0),
+            card: None,
+            shown: 0,
+            state: DrillState::Flashing {
+                next_at: Instant::now(),
+            },
+            feedback: None,
+            session: DrillStats::default(),
+        }
+    }
+
+    /// Show the next card once its time has come.
+    fn tick(&mut self, config: &Config) {
+        let DrillState::Flashing { next_at } = self.state else {
+            return;
+        };
+        if Instant::now() < next_at {
+            return;
+        }
+
+        self.deck.discard(self.card);
+        let card = self.deck.draw();
+        if self.count.shoe != self.deck.shuffles() {
+            self.count = Count::new(self.count.system, Self::DECKS, self.deck.shuffles());
+        }
+        self.count.observe(card);
+        self.card = Some(card);
+        self.shown += 1;
+
+        self.state = if self.shown.is_multiple_of(config.drill_checkpoint.max(1)) {
+            DrillState::Answering {
+                input: String::new(),
+                since: Instant::now(),
+            }
+        } else {
+            DrillState::Flashing {
+                next_at: Instant::now() + config.drill_pace,
+            }
+        };
+    }
+
+    fn answer(&mut self, config: &Config) {
+        let DrillState::Answering { input, since } = &self.state else {
+            return;
+        };
+        let Ok(answer) = input.trim_start_matches('+').parse::<i32>() else {
+            return;
+        };
+
+        let correct = answer == self.count.running;
+        self.session.record(correct, since.elapsed());
+        self.feedback = Some(if correct {
+            (
+                true,
+                format!("Correct, the count is {:+}", self.count.running),
+            )
+        } else {
+            (
+                false,
+                format!("Not quite, the count is {:+}", self.count.running),
+            )
+        });
+        self.state = DrillState::Flashing {
+            next_at: Instant::now() + config.drill_pace,
+        };
+    }
+
+    fn render(&self, frame: &mut Frame, lifetime: &DrillStats) {
+        use Constraint::{Length, Min};
+
+        let block = Block::bordered()
+            .title(format!("Counting Drill ({})", self.count.system.name()))
+            .title_bottom(Line::from("Enter) Submit count").left_aligned())
+            .title_bottom(Line::from("Esc) Quit").right_aligned());
+        let inner_area = block.inner(frame.area());
+        frame.render_widget(block, frame.area());
+
+        let [card_area, info_area] =
+            Layout::vertical([Min(Card::HEIGHT), Length(5)]).areas(inner_area);
+        let [card_area] = Layout::vertical([Length(Card::HEIGHT)])
+            .flex(Flex::Center)
+            .areas(card_area);
+        let [card_area] = Layout::horizontal([Length(Card::WIDTH)])
+            .flex(Flex::Center)
+            .areas(card_area);
+        if let Some(card) = self.card {
+            frame.render_widget(card, card_area);
+        }
+
+        let prompt = match &self.state {
+            DrillState::Answering { input, .. } => Line::from(format!("Running count? {input}_")),
+            DrillState::Flashing { .. } => Line::from(format!("Cards seen: {}", self.shown)),
+        };
+        let feedback = match &self.feedback {
+            Some((true, message)) => Line::from(message.as_str()).green(),
+            Some((false, message)) => Line::from(message.as_str()).red(),
+            None => Line::default(),
+        };
+        let lines = vec![
+            prompt,
+            feedback,
+            Line::default(),
+            Line::from(format!("Session: {}", self.session.summary())),
+            Line::from(format!("Lifetime: {}", lifetime.summary())),
+        ];
+        frame.render_widget(Paragraph::new(lines).centered(), info_area);
+    }
+}
+
+/// Run the counting drill until the player leaves, returning the session's results.
+pub fn run(config: &Config) -> std::io::Result<DrillStats> {
+    let mut terminal = term::init()?;
+    let mut drill = Drill::new(config);
+    let lifetime = DrillStats::load(DrillStats::DRILL_FILE);
+
+    loop {
+        drill.tick(config);
+        terminal.draw(|frame| {
+            drill.render(frame, &lifetime);
+            config.theme.apply(frame.buffer_mut());
+            config.plain().apply(frame.buffer_mut());
+        })?;
+
+        let timeout = match drill.state {
+            DrillState::Flashing { next_at } => next_at.saturating_duration_since(Instant::now()),
+            DrillState::Answering { .. } => Duration::from_secs(1),
+        };
+        if !term::poll(timeout)? {
+            continue;
+        }
+
+        if let Event::Key(key) = term::read()? {
+            if suspend::is_suspend_key(&key) {
+                suspend::suspend(&mut terminal)?;
+                continue;
+            }
+            if !keyboard::acts_on(&key) {
+                continue;
+            }
+            match (&mut drill.state, key.code) {
+                (_, KeyCode::Esc) | (DrillState::Flashing { .. }, KeyCode::Char('q')) => break,
+                (DrillState::Answering { input, .. }, KeyCode::Char(c))
+                    if c.is_ascii_digit() || c == '-' || c == '+' =>
+                {
+                    input.push(c)
+                }
+                (DrillState::Answering { input, .. }, KeyCode::Backspace) => {
+                    input.pop();
+                }
+                (DrillState::Answering { .. }, KeyCode::Enter) => drill.answer(config),
+                _ => (),
+            }
+        }
+    }
+    term::restore()?;
+
+    drill.session.save_session(DrillStats::DRILL_FILE)?;
+    Ok(drill.session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asks_for_the_count_at_each_checkpoint() {
+        let config = Config {
+            drill_checkpoint: 3,
+            drill_pace: Duration::ZERO,
+            ..Config::default()
+        };
+        let mut drill = Drill::new(&config);
+        for _ in 0..3 {
+            assert!(matches!(drill.state, DrillState::Flashing { .. }));
+            drill.tick(&config);
+        }
+        assert_eq!(drill.shown, 3);
+        let DrillState::Answering { input, .. } = &mut drill.state else {
+            panic!("expected a checkpoint, found {:?}", drill.state);
+        };
+        *input = format!("{:+}", drill.count.running);
+        drill.answer(&config);
+        assert!(matches!(drill.feedback, Some((true, _))));
+        assert!(matches!(drill.state, DrillState::Flashing { .. }));
+        // no card flashes while the next checkpoint waits on an answer
+        drill.tick(&config);
+        drill.tick(&config);
+        drill.tick(&config);
+        assert_eq!(drill.shown, 6);
+        drill.tick(&config);
+        assert_eq!(drill.shown, 6);
+    }
+
+    #[test]
+    fn adds_sessions_to_the_lifetime_results() {
+        paths::use_test_dir();
+        let mut session = DrillStats::default();
+        assert_eq!(session.summary(), "no checkpoints yet");
+        session.record(true, Duration::from_millis(1500));
+        session.record(false, Duration::from_millis(2500));
+        assert_eq!(session.summary(), "1/2 correct (50%), 2.0s per answer");
+
+        session.save_session(DrillStats::DRILL_FILE).unwrap();
+        session.save_session(DrillStats::DRILL_FILE).unwrap();
+        let lifetime = DrillStats::load(DrillStats::DRILL_FILE);
+        assert_eq!((lifetime.sessions, lifetime.checkpoints), (2, 4));
+        assert_eq!((lifetime.correct, lifetime.answer_ms), (2, 8000));
+        // the quiz keeps its own
+        assert_eq!(DrillStats::load(DrillStats::QUIZ_FILE).checkpoints, 0);
+    }
+
+    #[test]
+    fn grades_the_count_quiz() {
+        let mut stats = DrillStats::default();
+        let mut quiz = CountQuiz::new(2);
+        quiz.input = "x".to_string();
+        assert_eq!(quiz.answer(&mut stats), None);
+        quiz.input = "+2".to_string();
+        assert_eq!(quiz.prompt(), "What was the running count? +2_");
+        assert_eq!(
+            quiz.answer(&mut stats),
+            Some((true, "Correct, the count was +2".to_string()))
+        );
+        quiz.input = "-1".to_string();
+        assert_eq!(
+            quiz.answer(&mut stats),
+            Some((false, "Not quite, the count was +2".to_string()))
+        );
+        assert_eq!((stats.correct, stats.checkpoints), (1, 2));
+    }
+}
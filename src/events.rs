@@ -0,0 +1,162 @@
+use std::io;
+#[cfg(feature = "serde")]
+use std::{fs::File, io::Write, path::Path};
+
+#[cfg(feature = "serde")]
+use crate::protocol;
+
+/// Something that happened at the table, as the game tells it. `--json-events` writes
+/// them one JSON object per line:
+///
+/// ```text
+/// {"type":"bet","amount":10,"bankroll":1000}
+/// {"type":"deal","player":["10H","6S"],"dealer_up":"9C","player_total":16}
+/// {"type":"side_bet","name":"Lucky Ladies","stake":5,"hand":null,"payout":-5,"bankroll":995}
+/// {"type":"insurance","taken":false,"stake":5}
+/// {"type":"hit","card":"5D","player_total":21}
+/// {"type":"stand","player_total":21}
+/// {"type":"double","card":"9C","player_total":20,"bet":20}
+/// {"type":"split","hands":2}
+/// {"type":"play_hand","player":["8S","3D"],"player_total":11}
+/// {"type":"surrender"}
+/// {"type":"dealer_draw","card":"4C"}
+/// {"type":"reveal","card":"8D","dealer_total":21}
+/// {"type":"settle","result":"win","payout":10,"bankroll":1010}
+/// {"type":"shuffle_needed","remaining":78}
+/// {"type":"shuffle","shoe":1,"riffles":0,"burned":1}
+/// {"type":"dealer_says","line":"Fresh shoe, everyone! Shoe 1."}
+/// {"type":"config_reloaded","error":null}
+/// ```
+///
+/// Cards are written rank then suit, as in the hand history. The dealer draws with the
+/// hole card still face down, so the dealer's total is only given once it is revealed.
+/// What the dealer says is only written with `dealer_chatter` set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum GameEvent {
+    Bet {
+        amount: u32,
+        bankroll: u32,
+    },
+    Deal {
+        player: Vec<String>,
+        dealer_up: String,
+        /// the hole card, when it's dealt face up under double exposure
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        dealer_hole: Option<String>,
+        player_total: u8,
+    },
+    Hit {
+        card: String,
+        player_total: u8,
+    },
+    Stand {
+        player_total: u8,
+    },
+    /// the bet was doubled to `bet`, and one last card drawn
+    Double {
+        card: String,
+        player_total: u8,
+        bet: u32,
+    },
+    /// the pair in play was split, making this many hands
+    Split {
+        hands: u8,
+    },
+    /// a hand split from a pair was dealt its second card, and is played next
+    PlayHand {
+        player: Vec<String>,
+        player_total: u8,
+    },
+    Surrender,
+    /// insurance of `stake` was taken, or turned down, before the dealer peeked
+    Insurance {
+        taken: bool,
+        stake: u32,
+    },
+    DealerDraw {
+        card: String,
+    },
+    Reveal {
+        card: String,
+        dealer_total: u8,
+    },
+    /// a side bet was settled on the cards just dealt, won on `hand` or lost on none
+    SideBet {
+        name: String,
+        stake: u32,
+        hand: Option<String>,
+        payout: i64,
+        bankroll: u32,
+    },
+    Settle {
+        result: String,
+        payout: i64,
+        bankroll: u32,
+    },
+    /// the cut card came out, with this many cards left, and the shoe is shuffled after
+    /// the hand
+    ShuffleNeeded {
+        remaining: usize,
+    },
+    /// the shoe was shuffled, in this many riffles or perfectly for none, and this many
+    /// cards were burned face down
+    Shuffle {
+        shoe: u32,
+        riffles: u8,
+        burned: u8,
+    },
+    DealerSays {
+        line: String,
+    },
+    /// the config file changed and its live settings were applied, or weren't for `error`
+    ConfigReloaded {
+        error: Option<String>,
+    },
+}
+
+/// Something that follows a game through its events, like the sound effects or the
+/// event log.
+pub trait Subscriber {
+    fn notify(&mut self, event: &GameEvent) -> io::Result<()>;
+}
+
+/// Writes the events of a game as they happen, for tools following along live.
+#[cfg(feature = "serde")]
+pub struct EventLog {
+    writer: Box<dyn Write>,
+}
+#[cfg(feature = "serde")]
+impl EventLog {
+    /// Write to the file at `path`, or to stdout for `-`.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let writer: Box<dyn Write> = match path.to_str() {
+            Some("-") => Box::new(io::stdout()),
+            _ => Box::new(File::create(path)?),
+        };
+        Ok(Self { writer })
+    }
+}
+#[cfg(feature = "serde")]
+impl Subscriber for EventLog {
+    fn notify(&mut self, event: &GameEvent) -> io::Result<()> {
+        protocol::send(&mut self.writer, event)
+    }
+}
+
+/// A card code like `10H` with its suit drawn, like `10♥`.
+pub fn card_text(code: &str) -> String {
+    let (rank, suit) = code.split_at(code.len().saturating_sub(1));
+    let suit = match suit {
+        "S" => "♠",
+        "C" => "♣",
+        "D" => "♦",
+        "H" => "♥",
+        other => other,
+    };
+    format!("{rank}{suit}")
+}
@@ -0,0 +1,225 @@
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    prelude::{Buffer, Line, Rect, Style, Stylize},
+    widgets::{Block, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    rules::Rules,
+    strategy::{card_value_label, recommend, HandKind, Situation},
+};
+
+/// The rule basic strategy follows in one region of the chart: hands of `kind` with a
+/// total in `totals`, or a pair of cards worth one of them, against up cards in `ups`.
+struct Rule {
+    kind: HandKind,
+    totals: RangeInclusive<u8>,
+    ups: RangeInclusive<u8>,
+    rule: &'static str,
+}
+
+/// Rules of basic strategy, the first one covering a cell explaining it.
+const RULES: [Rule; 14] = [
+    Rule {
+        kind: HandKind::Hard,
+        totals: 4..=11,
+        ups: 2..=11,
+        rule: "No card can bust a hand of 11 or less, so the next one comes free.",
+    },
+    Rule {
+        kind: HandKind::Hard,
+        totals: 12..=12,
+        ups: 2..=3,
+        rule: "12 is the one stiff hand to hit against a 2 or 3: only the tens bust it, and \
+               those dealers make a hand too often to wait on.",
+    },
+    Rule {
+        kind: HandKind::Hard,
+        totals: 12..=16,
+        ups: 2..=6,
+        rule: "Stand on a stiff hand against a weak up card, and let the dealer take the \
+               risk of busting.",
+    },
+    Rule {
+        kind: HandKind::Hard,
+        totals: 12..=16,
+        ups: 7..=11,
+        rule: "Against a strong up card the dealer usually makes 17 or more, so a stiff \
+               hand that stands mostly loses. Hitting busts often too, but loses less.",
+    },
+    Rule {
+        kind: HandKind::Hard,
+        totals: 17..=21,
+        ups: 2..=11,
+        rule: "Always stand on a hard 17 or more. Most cards bust it, and it already beats \
+               every dealer who busts.",
+    },
+    Rule {
+        kind: HandKind::Soft,
+        totals: 13..=17,
+        ups: 2..=11,
+        rule: "A soft 17 or less never wins standing. The ace can count as one, so no card \
+               busts it, and it can only improve.",
+    },
+    Rule {
+        kind: HandKind::Soft,
+        totals: 18..=18,
+        ups: 2..=8,
+        rule: "Soft 18 stands against a 2 through 8, which it beats or ties more often than \
+               a hit would improve it.",
+    },
+    Rule {
+        kind: HandKind::Soft,
+        totals: 18..=18,
+        ups: 9..=11,
+        rule: "Soft 18 is an underdog to a 9, ten, or ace. No card busts it, so it's worth \
+               trying to improve.",
+    },
+    Rule {
+        kind: HandKind::Soft,
+        totals: 19..=21,
+        ups: 2..=11,
+        rule: "Soft 19 and up are strong enough to stand on against anything.",
+    },
+    Rule {
+        kind: HandKind::Pair,
+        totals: 11..=11,
+        ups: 2..=11,
+        rule: "Always split aces: two hands starting on 11 beat one soft 12.",
+    },
+    Rule {
+        kind: HandKind::Pair,
+        totals: 8..=8,
+        ups: 2..=11,
+        rule: "Always split eights: 16 is the worst hand there is, and two hands starting \
+               on 8 lose less.",
+    },
+    Rule {
+        kind: HandKind::Pair,
+        totals: 10..=10,
+        ups: 2..=11,
+        rule: "Never split tens: 20 already wins most hands.",
+    },
+    Rule {
+        kind: HandKind::Pair,
+        totals: 5..=5,
+        ups: 2..=11,
+        rule: "Play a pair of fives as a hard 10, which two hands starting on 5 can't beat.",
+    },
+    Rule {
+        kind: HandKind::Pair,
+        totals: 2..=9,
+        ups: 2..=11,
+        rule: "Split small pairs against a weak dealer, where each hand can be built on, \
+               and play them as their total against a strong one.",
+    },
+];
+
+/// About how often the dealer busts showing each up card, 2 through ace, standing on soft
+/// 17.
+const DEALER_BUSTS: [f64; 10] = [35.3, 37.6, 40.3, 42.9, 42.1, 26.0, 23.9, 23.3, 21.4, 11.7];
+
+/// Why basic strategy plays a situation the way it does, from the rule covering its cell
+/// of the chart and the odds behind it.
+#[derive(Clone, Debug)]
+pub struct Explanation {
+    title: String,
+    lines: Vec<String>,
+}
+impl Explanation {
+    pub fn new(situation: &Situation, rules: &Rules) -> Option<Self> {
+        let rule = RULES.iter().find(|rule| {
+            rule.kind == situation.kind
+                && rule.totals.contains(&situation.total)
+                && rule.ups.contains(&situation.up)
+        })?;
+        let up = card_value_label(situation.up);
+        let recommended = recommend(situation, rules).to_string().to_lowercase();
+        let mut lines = vec![rule.rule.to_string(), String::new()];
+        if let (HandKind::Hard, 12..=20) = (situation.kind, situation.total) {
+            // counting aces as one, every rank worth more than 21 less the total busts
+            let busting = 13 - (21 - situation.total);
+            lines.push(format!(
+                "Hitting {} busts {:.0}% of the time, {busting} ranks of 13.",
+                situation.total,
+                f64::from(busting) / 13.0 * 100.0
+            ));
+        }
+        let dealer_busts = DEALER_BUSTS[usize::from(situation.up.clamp(2, 11) - 2)];
+        lines.push(format!(
+            "The dealer busts about {dealer_busts:.0}% of the time showing {up}."
+        ));
+        Some(Self {
+            title: format!("Why {recommended} on {situation}"),
+            lines,
+        })
+    }
+}
+impl Widget for &Explanation {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        const WIDTH: u16 = 60;
+
+        let mut lines: Vec<Line> = self
+            .lines
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        lines.push(Line::default());
+        lines.push(Line::from("Any key) Close").dark_gray());
+        // wrapped lines take about as many rows as their length over the width
+        let rows: u16 = self
+            .lines
+            .iter()
+            .map(|line| (line.chars().count() as u16).div_ceil(WIDTH - 4).max(1))
+            .sum();
+
+        let block = Block::bordered()
+            .title(self.title.as_str())
+            .border_style(Style::new().red());
+        let vertical = Layout::vertical([Constraint::Length(rows + 4)]);
+        let horizontal = Layout::horizontal([Constraint::Length(WIDTH)]);
+        let [area] = vertical.flex(Flex::Center).areas(area);
+        let [area] = horizontal.flex(Flex::Center).areas(area);
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hard(total: u8, up: u8) -> Situation {
+        Situation {
+            kind: HandKind::Hard,
+            total,
+            up,
+            first_two: true,
+        }
+    }
+
+    #[test]
+    fn explains_a_cell_with_its_odds() {
+        let explanation = Explanation::new(&hard(16, 10), &Rules::default()).unwrap();
+        assert!(explanation.title.starts_with("Why hit on"));
+        assert_eq!(explanation.lines[0], RULES[3].rule);
+        assert!(explanation
+            .lines
+            .contains(&"Hitting 16 busts 62% of the time, 8 ranks of 13.".to_string()));
+        assert!(explanation.lines.last().unwrap().contains("21%"));
+    }
+
+    #[test]
+    fn takes_the_first_rule_covering_a_cell() {
+        let explanation = Explanation::new(&hard(12, 2), &Rules::default()).unwrap();
+        assert_eq!(explanation.lines[0], RULES[1].rule);
+        // no odds of busting below a stiff hand
+        let explanation = Explanation::new(&hard(9, 2), &Rules::default()).unwrap();
+        assert_eq!(explanation.lines.len(), 3);
+    }
+}
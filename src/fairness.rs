@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::{history::card_code, widgets::Shoe};
+
+/// The order of a freshly shuffled shoe, which a host publishes the hash of before any of
+/// it is seen and reveals once the shoe is done, so players can check the cards weren't
+/// changed while it was dealt.
+#[derive(Clone, Debug)]
+pub struct Commitment {
+    /// the deck's shuffle number this shoe belongs to
+    pub shoe: u32,
+    /// random text hashed with the cards, so the order can't be guessed from the hash
+    pub salt: String,
+    /// card codes in the order they're drawn
+    pub cards: Vec<String>,
+}
+impl Commitment {
+    pub fn new(deck: &Shoe) -> Self {
+        Self {
+            shoe: deck.shuffles(),
+            salt: format!("{:032x}", rand::random::<u128>()),
+            cards: deck.shuffled().map(card_code).collect(),
+        }
+    }
+
+    pub fn hash(&self) -> String {
+        hash(&self.salt, &self.cards)
+    }
+}
+
+/// Hex SHA-256 of the salt, a colon, and the card codes separated by commas.
+pub fn hash(salt: &str, cards: &[String]) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(":")
+        .chain_update(cards.join(","))
+        .finalize();
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Check a revealed shoe against the hash published before it was dealt, that it holds
+/// whole decks, and that `dealt`, the cards seen dealt from it oldest first, came out of
+/// it in order.
+pub fn verify(hash: &str, salt: &str, cards: &[String], dealt: &[String]) -> Result<(), String> {
+    if self::hash(salt, cards) != hash {
+        return Err("the revealed shoe doesn't match the hash published for it".to_string());
+    }
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for card in cards {
+        *counts.entry(card).or_default() += 1;
+    }
+    let decks = cards.len() / 52;
+    if counts.len() != 52 || counts.values().any(|&n| n != decks) {
+        return Err("the revealed shoe isn't made of whole decks".to_string());
+    }
+    let mut shoe = cards.iter();
+    match dealt.iter().find(|card| !shoe.any(|next| next == *card)) {
+        Some(card) => Err(format!("{card} was dealt out of the order promised")),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_the_salt_and_cards() {
+        let cards = ["AS".to_string(), "KS".to_string()];
+        assert_eq!(
+            hash("salt", &cards),
+            "6ea7408f092975d3ad38a3d2af435f9e27293ff9c9fbb3569aaa8ba1a15a409d"
+        );
+    }
+
+    #[test]
+    fn verifies_what_was_revealed_against_the_hash() {
+        let commitment = Commitment::new(&Shoe::new(1));
+        let published = commitment.hash();
+        let (salt, cards) = (&commitment.salt, &commitment.cards);
+        let dealt = [cards[0].clone(), cards[5].clone()];
+        assert_eq!(verify(&published, salt, cards, &dealt), Ok(()));
+
+        let out_of_order = [cards[5].clone(), cards[0].clone()];
+        assert!(verify(&published, salt, cards, &out_of_order).is_err());
+        let mut swapped = cards.clone();
+        swapped.swap(0, 1);
+        assert!(verify(&published, salt, &swapped, &dealt).is_err());
+        let short = &cards[1..];
+        assert!(verify(&hash(salt, short), salt, short, &[]).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng, Rng};
+use ratatui::{
+    layout::Flex,
+    prelude::{Constraint, Frame, Layout, Line, Stylize},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    config::*,
+    keyboard, paths,
+    rules::Rules,
+    scenario,
+    strategy::{self, Action, ChartRow, HandKind, Situation},
+    suspend,
+    term::{self, Event, KeyCode},
+    widgets::*,
+};
+
+/// How well each chart cell is known, as a Leitner box from 0 (just missed) to
+/// [`Flashcards::MAX_BOX`]. Lower boxes come up more often.
+#[derive(Debug, Default)]
+struct Boxes(HashMap<String, u32>);
+impl Boxes {
+    const FILE: &str = "flashcards.toml";
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    fn load() -> Self {
+        let mut boxes = Boxes::default();
+        let Some(Ok(Some(entries))) = Self::path().map(|path| read_key_values(&path)) else {
+            return boxes;
+        };
+        for (_, key, value) in entries {
+            if let Ok(level) = parse_number(&value) {
+                boxes.0.insert(key, level);
+            }
+        }
+        boxes
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort();
+        let contents: String = entries
+            .into_iter()
+            .map(|(key, level)| format!("{key} = {level}\n"))
+            .collect();
+        fs::write(path, contents)
+    }
+
+    fn level(&self, key: &str) -> u32 {
+        self.0
+            .get(key)
+            .copied()
+            .unwrap_or(Flashcards::NEW_BOX)
+            .min(Flashcards::MAX_BOX)
+    }
+}
+
+/// One chart cell to be quizzed on.
+#[derive(Clone, Copy, Debug)]
+struct Cell {
+    row: ChartRow,
+    up: u8,
+}
+impl Cell {
+    /// Name of the cell in the saved boxes.
+    fn key(&self) -> String {
+        self.row.cell_name(self.up)
+    }
+
+    /// Deal a player hand and dealer up-card that fall in this cell.
+    fn deal(&self, deck: &mut Shoe) -> (Hand<Player>, Card) {
+        let total = match (self.row.kind, self.row.total) {
+            (HandKind::Hard, 8) => thread_rng().gen_range(5..=8),
+            (HandKind::Hard, 17) => thread_rng().gen_range(17..=19),
+            (_, total) => total,
+        };
+        let [first, second] = scenario::hand_values(self.row.kind, total);
+        let hand = Hand::new([
+            scenario::draw_value(deck, first),
+            scenario::draw_value(deck, second),
+        ]);
+        (hand, scenario::draw_value(deck, self.up))
+    }
+}
+
+/// Shows a hand against an up-card and asks for the basic strategy play, bringing back
+/// missed hands more often.
+#[derive(Debug)]
+struct Flashcards {
+    rules: Rules,
+    deck: Shoe,
+    cells: Vec<Cell>,
+    boxes: Boxes,
+    cell: Cell,
+    hand: Hand<Player>,
+    up_card: Card,
+    answered: u32,
+    correct: u32,
+    feedback: Option<(bool, String)>,
+}
+impl Flashcards {
+    const MAX_BOX: u32 = 4;
+    const NEW_BOX: u32 = 1;
+
+    fn new(rules: Rules) -> Self {
+        let cells: Vec<Cell> = ChartRow::all()
+            .flat_map(|row| (2..=11).map(move |up| Cell { row, up }))
+            .collect();
+        let boxes = Boxes::load();
+        let mut deck = Shoe::new(1);
+        let cell = Self::pick(&cells, &boxes);
+        let (hand, up_card) = cell.deal(&mut deck);
+        Self {
+            rules,
+            deck,
+            cells,
+            boxes,
+            cell,
+            hand,
+            up_card,
+            answered: 0,
+            correct: 0,
+            feedback: None,
+        }
+    }
+
+    /// Pick a cell at random, weighted toward the least known.
+    fn pick(cells: &[Cell], boxes: &Boxes) -> Cell {
+        let weights = cells
+            .iter()
+            .map(|cell| 1 << (Self::MAX_BOX - boxes.level(&cell.key())));
+        let index = WeightedIndex::new(weights)
+            .map(|weights| weights.sample(&mut thread_rng()))
+            .unwrap_or_default();
+        cells[index]
+    }
+
+    fn situation(&self) -> Situation {
+        Situation::new(&self.hand, self.up_card)
+    }
+
+    /// The actions that can be answered for the current card.
+    fn choices(&self) -> Vec<(char, Action)> {
+        let mut choices = vec![('h', Action::Hit), ('s', Action::Stand)];
+        if self.rules.double {
+            choices.push(('d', Action::Double));
+        }
+        if self.rules.surrender {
+            choices.push(('r', Action::Surrender));
+        }
+        if self.rules.split && self.hand.is_pair() {
+            choices.push(('p', Action::Split));
+        }
+        choices
+    }
+
+    fn answer(&mut self, taken: Action) {
+        let situation = self.situation();
+        let recommended = strategy::recommend(&situation, &self.rules);
+        let correct = taken == recommended;
+        let key = self.cell.key();
+        let level = if correct {
+            (self.boxes.level(&key) + 1).min(Self::MAX_BOX)
+        } else {
+            0
+        };
+        self.boxes.0.insert(key, level);
+
+        self.answered += 1;
+        self.correct += u32::from(correct);
+        let recommended = recommended.to_string().to_lowercase();
+        self.feedback = Some(if correct {
+            (true, format!("Correct, {recommended} on {situation}"))
+        } else {
+            (
+                false,
+                format!("Basic strategy: {recommended} on {situation}"),
+            )
+        });
+
+        self.cell = Self::pick(&self.cells, &self.boxes);
+        let table = self.hand.cards().iter().copied().chain([self.up_card]);
+        self.deck.discard(table);
+        (self.hand, self.up_card) = self.cell.deal(&mut self.deck);
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        use Constraint::{Length, Min};
+
+        let block = Block::bordered()
+            .title("Strategy Flashcards")
+            .title_bottom(Line::from("Esc) Quit").right_aligned());
+        let inner_area = block.inner(frame.area());
+        frame.render_widget(block, frame.area());
+
+        let [card_area, info_area] =
+            Layout::vertical([Min(Card::HEIGHT + 1), Length(5)]).areas(inner_area);
+        let [card_area] = Layout::vertical([Length(Card::HEIGHT + 1)])
+            .flex(Flex::Center)
+            .areas(card_area);
+        let [label_area, card_area] =
+            Layout::vertical([Length(1), Length(Card::HEIGHT)]).areas(card_area);
+        let columns = Layout::horizontal([Length(Card::WIDTH); 4])
+            .spacing(2)
+            .flex(Flex::Center);
+        let [you_label, _, _, dealer_label] = columns.areas(label_area);
+        let [first_area, second_area, _, up_area] = columns.areas(card_area);
+        frame.render_widget(Line::from("You").centered(), you_label);
+        frame.render_widget(Line::from("Dealer").centered(), dealer_label);
+        frame.render_widget(self.hand.cards()[0], first_area);
+        frame.render_widget(self.hand.cards()[1], second_area);
+        frame.render_widget(self.up_card, up_area);
+
+        let choices = self
+            .choices()
+            .iter()
+            .map(|(key, action)| format!("{key}) {action}"))
+            .collect::<Vec<_>>()
+            .join("    ");
+        let feedback = match &self.feedback {
+            Some((true, message)) => Line::from(message.as_str()).green(),
+            Some((false, message)) => Line::from(message.as_str()).red(),
+            None => Line::default(),
+        };
+        let mastered = self
+            .cells
+            .iter()
+            .filter(|cell| self.boxes.level(&cell.key()) == Self::MAX_BOX)
+            .count();
+        let lines = vec![
+            Line::from(choices),
+            feedback,
+            Line::default(),
+            Line::from(format!(
+                "Session: {}/{} correct",
+                self.correct, self.answered
+            )),
+            Line::from(format!("Mastered: {mastered}/{} hands", self.cells.len())),
+        ];
+        frame.render_widget(Paragraph::new(lines).centered(), info_area);
+    }
+}
+
+/// Quiz flashcards until the player leaves, returning how many were answered correctly
+/// and how many were answered.
+pub fn run(config: &Config) -> std::io::Result<(u32, u32)> {
+    let mut terminal = term::init()?;
+    let mut flashcards = Flashcards::new(Rules::default());
+
+    loop {
+        terminal.draw(|frame| {
+            flashcards.render(frame);
+            config.theme.apply(frame.buffer_mut());
+            config.plain().apply(frame.buffer_mut());
+        })?;
+
+        if let Event::Key(key) = term::read()? {
+            if suspend::is_suspend_key(&key) {
+                suspend::suspend(&mut terminal)?;
+                continue;
+            }
+            if !keyboard::acts_on(&key) {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                KeyCode::Char(c) => {
+                    let choice = flashcards.choices().into_iter().find(|(key, _)| *key == c);
+                    if let Some((_, action)) = choice {
+                        flashcards.answer(action);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+    term::restore()?;
+
+    flashcards.boxes.save()?;
+    Ok((flashcards.correct, flashcards.answered))
+}
@@ -0,0 +1,1430 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use tracing::{debug, info};
+
+use crate::{
+    config::Config,
+    counting::{self, Count},
+    events::GameEvent,
+    history::card_code,
+    jackpot::Jackpot,
+    rules::Rules,
+    scenario::Scenario,
+    side_bets::{Settled, Wager},
+    strategy::{self, HandKind},
+    widgets::*,
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    pub deck: Shoe,
+    /// the hand in play, or the last one played once a pair is split
+    pub player_hand: Hand<Player>,
+    pub dealer_hand: Hand<Dealer>,
+    pub state: GameState,
+    pub rules: Rules,
+    pub bankroll: u32,
+    /// the bet on the current hand, and the default for the next one
+    pub bet: u32,
+    /// whether the bet on the hand in play was doubled
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub doubled: bool,
+    /// hands split from the one dealt and played before the hand in play, waiting on the
+    /// dealer with it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub split_hands: Vec<SplitHand>,
+    /// cards split off a pair, each to be dealt a second card and played as a hand of its
+    /// own once the hand in play is done, the last split first
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub split_cards: Vec<Card>,
+    /// insurance on the current hand against a dealer blackjack
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub insurance: Insurance,
+    /// turn down insurance without asking
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auto_decline_insurance: bool,
+    /// take even money without asking
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auto_even_money: bool,
+    /// net result of the last settled hand
+    pub payout: i64,
+    /// side bets offered at the table, with the stake on each every hand
+    pub side_bets: Vec<Wager>,
+    /// the side bets on the hand in play, settled as soon as it's dealt
+    pub side_results: Vec<Settled>,
+    pub jackpot: Jackpot,
+    pub count: Count,
+    /// cards the player has seen since the shoe was shuffled
+    #[cfg_attr(feature = "serde", serde(default))]
+    seen: Vec<Card>,
+    /// only what the player has seen is used to tell what's left in the shoe
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub realistic_info: bool,
+    /// when the hole card is turned over on a hand the dealer doesn't play out
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hole_card: HoleCard,
+    /// share of tens among the cards unseen at the deal, when the dealer showed an ace and
+    /// insurance would have been offered
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub insurance_tens: Option<f64>,
+    /// the final count of the last shoe, set when the deck is reshuffled
+    pub shuffled_count: Option<Count>,
+    /// practice situation every deal is rigged to
+    pub scenario: Option<Scenario>,
+    /// what happened since the events were last taken
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<GameEvent>,
+}
+impl Game {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            deck: Shoe::for_table(&config.rules, config.shuffle.clone()),
+            player_hand: Hand::default(),
+            dealer_hand: Hand::default(),
+            state: GameState::Betting,
+            rules: config.rules,
+            bankroll: config.bankroll,
+            bet: config.bet_unit.min(config.bankroll),
+            doubled: false,
+            split_hands: Vec::new(),
+            split_cards: Vec::new(),
+            insurance: Insurance::Unoffered,
+            auto_decline_insurance: config.auto_decline_insurance,
+            auto_even_money: config.auto_even_money,
+            payout: 0,
+            side_bets: config.side_bets.clone(),
+            side_results: Vec::new(),
+            jackpot: Jackpot::default(),
+            count: Count::new(config.counting_system, config.rules.decks, 0),
+            seen: Vec::new(),
+            realistic_info: config.realistic_info,
+            hole_card: config.hole_card,
+            insurance_tens: None,
+            shuffled_count: None,
+            scenario: config.scenario.clone(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Take the events of everything that happened since the last call, for the
+    /// renderer, sounds, logs, and anything else following the game. Events not taken by
+    /// the time the next bet is placed are dropped, so a game nobody follows, like in a
+    /// simulation, doesn't pile them up.
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Count a card the player can see, starting a fresh count if the deck was reshuffled.
+    fn observe(&mut self, card: Card) {
+        self.check_shuffle();
+        self.count.observe(card);
+        self.seen.push(card);
+    }
+
+    /// Start a fresh count if the deck was reshuffled since the last card was counted.
+    fn check_shuffle(&mut self) {
+        if self.count.shoe != self.deck.shuffles() {
+            let count = Count::new(self.count.system, self.rules.decks, self.deck.shuffles());
+            self.shuffled_count = Some(std::mem::replace(&mut self.count, count));
+            self.seen.clear();
+            self.events.push(GameEvent::Shuffle {
+                shoe: self.deck.shuffles(),
+                riffles: self.rules.riffles,
+                burned: self.deck.burned(),
+            });
+        }
+    }
+
+    /// Tell of the cut card once it comes out, so the shoe is shuffled after the hand.
+    fn check_cut(&mut self) {
+        if self.deck.take_cut_card() {
+            debug!(remaining = self.deck.remaining(), "cut card came out");
+            self.events.push(GameEvent::ShuffleNeeded {
+                remaining: self.deck.remaining(),
+            });
+        }
+    }
+
+    fn observe_deal(&mut self) {
+        let cards = [
+            self.player_hand.cards()[0],
+            self.player_hand.cards()[1],
+            self.dealer_hand.up_card(),
+        ];
+        for card in cards {
+            self.observe(card);
+        }
+        if self.rules.double_exposure {
+            self.observe(self.dealer_hand.cards()[0]);
+        }
+    }
+
+    /// Whether the dealer's hole card is on the table face down.
+    pub fn hole_card_hidden(&self) -> bool {
+        !matches!(self.state, GameState::Betting)
+            && !self.rules.double_exposure
+            && !self.dealer_hand.is_revealed()
+    }
+
+    /// The cards the player hasn't seen. That's the ones left in the shoe, and the hole
+    /// card while it's face down so what's left doesn't give it away, or with realistic
+    /// information, every card of the shoe but the ones seen since it was shuffled.
+    pub fn unseen_cards(&self) -> Vec<Card> {
+        if self.realistic_info {
+            let mut unseen = self.deck.full();
+            for card in &self.seen {
+                if let Some(index) = unseen.iter().position(|unseen| unseen == card) {
+                    unseen.swap_remove(index);
+                }
+            }
+            return unseen;
+        }
+        let mut unseen = self.deck.undealt().to_vec();
+        if self.hole_card_hidden() {
+            unseen.push(self.dealer_hand.cards()[0]);
+        }
+        unseen
+    }
+
+    /// Cards of each rank the player hasn't seen.
+    pub fn unseen(&self) -> counting::Composition {
+        counting::Composition::of(&self.unseen_cards())
+    }
+
+    /// The main bets on the current hand and every hand split from it, each doubled if
+    /// it was.
+    pub fn stake(&self) -> u32 {
+        let split = self
+            .split_hands
+            .iter()
+            .map(|split| self.bet_on(split.doubled));
+        let waiting = self.bet * self.split_cards.len() as u32;
+        split.sum::<u32>() + waiting + self.bet_on(self.doubled)
+    }
+
+    /// The bet on one hand, doubled or not.
+    fn bet_on(&self, doubled: bool) -> u32 {
+        match doubled {
+            true => self.bet * 2,
+            false => self.bet,
+        }
+    }
+
+    /// Whether the dealt hand has been split into more.
+    pub fn is_split(&self) -> bool {
+        !self.split_hands.is_empty() || !self.split_cards.is_empty()
+    }
+
+    /// The hands the player has this round, played or still to play.
+    fn hand_count(&self) -> usize {
+        1 + self.split_hands.len() + self.split_cards.len()
+    }
+
+    /// Whether the bankroll covers insurance on the bet as well as the bet.
+    fn covers_insurance(&self) -> bool {
+        u64::from(self.bankroll) >= u64::from(self.bet) + u64::from(self.bet / 2)
+    }
+
+    /// Whether the bankroll covers another bet the size of the first, to double or split
+    /// on, besides the bets on every hand and any insurance taken.
+    fn covers_another_bet(&self) -> bool {
+        let insured = match self.insurance {
+            Insurance::Taken { stake } => stake,
+            _ => 0,
+        };
+        let staked = u64::from(self.stake()) + u64::from(insured);
+        u64::from(self.bankroll) >= staked + u64::from(self.bet)
+    }
+
+    /// Basic strategy advice for the player's hand against the dealer's up-card. Insurance
+    /// is always turned down, and a double the bankroll or a split hand doesn't allow, or
+    /// a surrender after splitting, is played as the chart's fallback.
+    pub fn hint(&self) -> (strategy::Action, strategy::Situation) {
+        let situation = strategy::Situation::new(&self.player_hand, self.dealer_hand.up_card());
+        if self.insurance == Insurance::Offered {
+            return (strategy::Action::Insurance(false), situation);
+        }
+        // a doubled hand is rescued from a stiff total against a strong up-card
+        if self.doubled {
+            let rescue = situation.total < 17 && situation.up >= 7;
+            let action = match rescue && self.is_legal(strategy::Action::Surrender) {
+                true => strategy::Action::Surrender,
+                false => strategy::Action::Stand,
+            };
+            return (action, situation);
+        }
+        let mut rules = self.rules;
+        rules.double &= self.covers_another_bet() && (!self.is_split() || rules.double_after_split);
+        rules.surrender &= !self.is_split();
+        rules.split &= self.hand_count() < SPLIT_HANDS && self.covers_another_bet();
+        // past the first two cards, the moves a table allows on any number are read from
+        // the chart as they would be on two
+        let mut dealt = situation;
+        if !dealt.first_two {
+            rules.double &= rules.double_any_cards;
+            rules.surrender &= rules.surrender_any_cards;
+            dealt.first_two = true;
+        }
+        (strategy::recommend(&dealt, &rules), situation)
+    }
+
+    /// The hand being played as a line of text to share, like `Player: A♠ 7♦ (soft 18)
+    /// vs Dealer 9, true count +2`.
+    pub fn summary(&self) -> String {
+        let (_, situation) = self.hint();
+        let cards: Vec<_> = self.player_hand.cards().iter().map(Card::short).collect();
+        let hand = match situation.kind {
+            HandKind::Hard => format!("hard {}", situation.total),
+            HandKind::Soft => format!("soft {}", situation.total),
+            HandKind::Pair => format!("pair of {}s", strategy::card_value_label(situation.total)),
+        };
+        let true_count = self.count.true_count(counting::decks_remaining(&self.deck));
+        format!(
+            "Player: {} ({hand}) vs Dealer {}, true count {true_count:+.0}",
+            cards.join(" "),
+            strategy::card_value_label(situation.up)
+        )
+    }
+
+    /// The hands split from the one in play, those played with their totals and then
+    /// the ones still to play, like `Split: 8♠ 3♦ 10♣ (21), 8♥ to play`, or `None` when
+    /// nothing was split.
+    pub fn split_summary(&self) -> Option<String> {
+        if !self.is_split() {
+            return None;
+        }
+        let played = self
+            .split_hands
+            .iter()
+            .map(|split| format!("{} ({})", split.hand.short(), split.hand.count_value()));
+        // the last card split off is played next
+        let waiting = self
+            .split_cards
+            .iter()
+            .rev()
+            .map(|card| format!("{} to play", card.short()));
+        let hands: Vec<_> = played.chain(waiting).collect();
+        Some(format!("Split: {}", hands.join(", ")))
+    }
+
+    /// Everything the player can do right now. Any bet from one chip up to the bankroll
+    /// can be placed, so betting is listed once, as the largest bet.
+    pub fn legal_actions(&self) -> Vec<strategy::Action> {
+        use strategy::Action;
+        match self.state {
+            GameState::Betting if self.bankroll > 0 => vec![Action::Bet(self.bankroll)],
+            GameState::PlayingHand => [
+                Action::Hit,
+                Action::Stand,
+                Action::Double,
+                Action::Split,
+                Action::Surrender,
+                Action::Insurance(true),
+                Action::Insurance(false),
+            ]
+            .into_iter()
+            .filter(|action| self.is_legal(*action))
+            .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether `action` can be taken right now, or why it can't.
+    pub fn check(&self, action: strategy::Action) -> Result<(), IllegalAction> {
+        use strategy::Action;
+        match (action, self.state) {
+            (Action::Bet(amount), GameState::Betting) if (1..=self.bankroll).contains(&amount) => {
+                Ok(())
+            }
+            (Action::Bet(_), GameState::Betting) => Err(IllegalAction::BetOutOfRange {
+                bankroll: self.bankroll,
+            }),
+            (Action::Bet(_), _) => Err(IllegalAction::HandInPlay),
+            (Action::Double, _) if !self.rules.double => Err(IllegalAction::NotOffered(action)),
+            (Action::Split, _) if !self.rules.split => Err(IllegalAction::NotOffered(action)),
+            (Action::Surrender, _) if !self.rules.surrender => {
+                Err(IllegalAction::NotOffered(action))
+            }
+            (_, GameState::Betting) => Err(IllegalAction::NoHand),
+            (_, GameState::HandScoreScreen(_)) => Err(IllegalAction::HandOver),
+            (_, GameState::PlayingHand) if self.is_dealer_turn() => Err(IllegalAction::DealerTurn),
+            (Action::Insurance(_), _) if self.insurance != Insurance::Offered => {
+                Err(IllegalAction::NoInsurance)
+            }
+            (Action::Insurance(true), _) if !self.covers_insurance() => {
+                Err(IllegalAction::Uncovered(action))
+            }
+            (Action::Insurance(_), _) => Ok(()),
+            _ if self.insurance == Insurance::Offered => Err(IllegalAction::InsuranceOffered),
+            // a doubled hand left open for surrender can only be held or surrendered
+            (Action::Hit | Action::Double | Action::Split, _) if self.doubled => {
+                Err(IllegalAction::Doubled(action))
+            }
+            (Action::Split, _) if self.player_hand.cards().len() != 2 => {
+                Err(IllegalAction::FirstTwoCards(action))
+            }
+            (Action::Double, _)
+                if self.player_hand.cards().len() != 2 && !self.rules.double_any_cards =>
+            {
+                Err(IllegalAction::FirstTwoCards(action))
+            }
+            (Action::Surrender, _)
+                if self.player_hand.cards().len() != 2 && !self.rules.surrender_any_cards =>
+            {
+                Err(IllegalAction::FirstTwoCards(action))
+            }
+            (Action::Surrender, _) if self.is_split() => Err(IllegalAction::AfterSplit(action)),
+            (Action::Double, _) if self.is_split() && !self.rules.double_after_split => {
+                Err(IllegalAction::AfterSplit(action))
+            }
+            (Action::Split, _) if !self.player_hand.is_pair() => Err(IllegalAction::NotAPair),
+            (Action::Split, _) if self.hand_count() >= SPLIT_HANDS => {
+                Err(IllegalAction::SplitLimit)
+            }
+            (Action::Double | Action::Split, _) if !self.covers_another_bet() => {
+                Err(IllegalAction::Uncovered(action))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `action` can be taken right now.
+    pub fn is_legal(&self, action: strategy::Action) -> bool {
+        self.check(action).is_ok()
+    }
+
+    /// Take `action`, if it's allowed right now.
+    pub fn play(&mut self, action: strategy::Action) -> Result<(), IllegalAction> {
+        use strategy::Action;
+        match action {
+            Action::Bet(bet) => self.place_bet(bet),
+            Action::Hit => self.hit(),
+            Action::Stand => self.hold(),
+            Action::Double => self.double(),
+            Action::Surrender => self.surrender(),
+            Action::Insurance(take) => self.insure(take),
+            Action::Split => self.split(),
+        }
+    }
+
+    /// Draw a card for the player. The dealer waits for the player to hold before drawing.
+    pub fn hit(&mut self) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Hit)?;
+        let card = self.player_hand.hit(&mut self.deck);
+        debug!(card = %card.short(), total = self.player_hand.count_value(), "player hit");
+        self.observe(card);
+        self.events.push(GameEvent::Hit {
+            card: card_code(&card),
+            player_total: self.player_hand.count_value(),
+        });
+        self.check_cut();
+        self.check_hand();
+        Ok(())
+    }
+
+    pub fn hold(&mut self) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Stand)?;
+        debug!(total = self.player_hand.count_value(), "player held");
+        self.events.push(GameEvent::Stand {
+            player_total: self.player_hand.count_value(),
+        });
+        self.player_hand.hold();
+        self.check_hand();
+        Ok(())
+    }
+
+    /// Double the bet and draw one last card. Where surrender is allowed after doubling,
+    /// the hand is left open until it's held or surrendered.
+    pub fn double(&mut self) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Double)?;
+        self.doubled = true;
+        let card = self.player_hand.hit(&mut self.deck);
+        debug!(card = %card.short(), total = self.player_hand.count_value(), "player doubled");
+        self.observe(card);
+        self.events.push(GameEvent::Double {
+            card: card_code(&card),
+            player_total: self.player_hand.count_value(),
+            bet: self.bet_on(true),
+        });
+        self.check_cut();
+        if !self.can_surrender_doubled() {
+            self.player_hand.hold();
+        }
+        self.check_hand();
+        Ok(())
+    }
+
+    /// Whether the table takes a surrender of the hand in play once it's doubled.
+    fn can_surrender_doubled(&self) -> bool {
+        self.rules.surrender && self.rules.surrender_any_cards && !self.is_split()
+    }
+
+    /// Split the pair into two hands, each on a bet the size of the first, played one
+    /// after the other. The first is dealt its second card now, and the other once the
+    /// first is done.
+    pub fn split(&mut self) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Split)?;
+        let [first, second] = [self.player_hand.cards()[0], self.player_hand.cards()[1]];
+        self.split_cards.push(second);
+        debug!(card = %first.short(), hands = self.hand_count(), "player split");
+        self.events.push(GameEvent::Split {
+            hands: self.hand_count() as u8,
+        });
+        self.play_split(first);
+        Ok(())
+    }
+
+    /// Play a hand of `card`, split off a pair, once it's dealt a second card. A split ace
+    /// gets no more than that card.
+    fn play_split(&mut self, card: Card) {
+        let drawn = self.deck.draw();
+        self.player_hand = Hand::new([card, drawn]);
+        self.doubled = false;
+        debug!(player = %self.player_hand.short(), "playing a split hand");
+        self.observe(drawn);
+        self.events.push(GameEvent::PlayHand {
+            player: self.player_hand.cards().iter().map(card_code).collect(),
+            player_total: self.player_hand.count_value(),
+        });
+        self.check_cut();
+        if card.rank() == Rank::Ace {
+            self.player_hand.hold();
+        }
+        self.check_hand();
+    }
+
+    /// Give up the hand for half the bet back.
+    pub fn surrender(&mut self) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Surrender)?;
+        debug!(total = self.player_hand.count_value(), "player surrendered");
+        self.events.push(GameEvent::Surrender);
+        self.player_hand.hold();
+        self.state = GameState::HandScoreScreen(HandResult::Surrender);
+        self.finish(HandResult::Surrender);
+        Ok(())
+    }
+
+    /// Take insurance, half the bet against a dealer blackjack, or turn it down, and let
+    /// the dealer peek.
+    pub fn insure(&mut self, take: bool) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Insurance(take))?;
+        let stake = self.bet / 2;
+        self.insurance = match take {
+            true => Insurance::Taken { stake },
+            false => Insurance::Declined,
+        };
+        debug!(take, stake, "player answered insurance");
+        self.events
+            .push(GameEvent::Insurance { taken: take, stake });
+        self.check_naturals();
+        Ok(())
+    }
+
+    /// The hand is being played and the player hasn't held yet.
+    pub fn is_player_turn(&self) -> bool {
+        matches!(self.state, GameState::PlayingHand) && self.player_hand.is_active()
+    }
+
+    /// The player has held and the dealer still has cards to play out.
+    pub fn is_dealer_turn(&self) -> bool {
+        matches!(self.state, GameState::PlayingHand) && !self.player_hand.is_active()
+    }
+
+    pub fn dealer_step(&mut self) {
+        if !self.is_dealer_turn() {
+            return;
+        }
+        // turning the hole card over is a step of its own, before any drawing
+        if self.reveal_hole_card() {
+            return;
+        }
+        match self
+            .dealer_hand
+            .do_dealer_action(&mut self.deck, self.rules.dealer_hits_soft_17)
+        {
+            Some(card) => {
+                debug!(card = %card.short(), total = self.dealer_hand.count_value(), "dealer hit");
+                self.observe(card);
+                self.events.push(GameEvent::DealerDraw {
+                    card: card_code(&card),
+                });
+                self.check_cut();
+            }
+            None => debug!(total = self.dealer_hand.count_value(), "dealer stood"),
+        }
+        self.check_hand();
+    }
+
+    /// Finish with the last hand and go back to taking bets.
+    pub fn new_hand(&mut self) {
+        if !matches!(self.state, GameState::HandScoreScreen(_)) {
+            return;
+        }
+        let split = self.split_hands.iter().flat_map(|split| split.hand.cards());
+        let table = split
+            .chain(self.player_hand.cards())
+            .chain(self.dealer_hand.cards());
+        self.deck.discard(table.copied());
+        if self.deck.needs_shuffle() {
+            self.deck.reshuffle();
+            self.check_shuffle();
+        }
+        self.bet = self.bet.min(self.bankroll);
+        self.state = GameState::Betting;
+        debug!(bankroll = self.bankroll, "taking bets");
+    }
+
+    /// Place the bet and deal the hand. The bet must be covered by the bankroll, and can
+    /// only be placed between hands.
+    pub fn place_bet(&mut self, bet: u32) -> Result<(), IllegalAction> {
+        self.check(strategy::Action::Bet(bet))?;
+        self.bet = bet;
+        self.doubled = false;
+        self.split_hands.clear();
+        self.split_cards.clear();
+        self.events.clear();
+        match &self.scenario {
+            Some(scenario) => {
+                (self.player_hand, self.dealer_hand) = scenario.deal(&mut self.deck);
+            }
+            None => {
+                self.player_hand = self.deck.new_hand::<Player>();
+                self.dealer_hand = self.deck.new_hand::<Dealer>();
+            }
+        }
+        self.state = GameState::PlayingHand;
+        debug!(
+            bet,
+            player = %self.player_hand.short(),
+            dealer_up = %self.dealer_hand.up_card().short(),
+            "dealt a hand"
+        );
+        self.observe_deal();
+        self.events.push(GameEvent::Bet {
+            amount: bet,
+            bankroll: self.bankroll,
+        });
+        self.events.push(GameEvent::Deal {
+            player: self.player_hand.cards().iter().map(card_code).collect(),
+            dealer_up: card_code(&self.dealer_hand.up_card()),
+            dealer_hole: self
+                .rules
+                .double_exposure
+                .then(|| card_code(&self.dealer_hand.cards()[0])),
+            player_total: self.player_hand.count_value(),
+        });
+        self.settle_side_bets();
+        self.check_cut();
+        // the odds are taken before the dealer peeks, when insurance is decided
+        let offered = self.dealer_hand.up_card().rank() == Rank::Ace && self.hole_card_hidden();
+        self.insurance_tens = offered.then(|| self.unseen().ten_density());
+        // insurance is half the bet, so a bet of one chip can't have any, and it has to be
+        // covered by what the side bets left of the bankroll
+        self.insurance = match offered && bet >= 2 && self.covers_insurance() {
+            true => Insurance::Offered,
+            false => Insurance::Unoffered,
+        };
+        // the dealer peeks once insurance is answered, which the player may always answer
+        // the same way
+        let answer = match self.player_hand.is_blackjack() {
+            true if self.auto_even_money => Some(true),
+            _ if self.auto_decline_insurance => Some(false),
+            _ => None,
+        };
+        match (self.insurance, answer) {
+            (Insurance::Offered, Some(take)) => self.insure(take)?,
+            (Insurance::Offered, None) => (),
+            _ => self.check_naturals(),
+        }
+        Ok(())
+    }
+
+    /// Settle the side bets staked on the cards just dealt. A bet sits out any hand its
+    /// stake can't be wagered on.
+    fn settle_side_bets(&mut self) {
+        self.side_results.clear();
+        for wager in self.side_bets.clone() {
+            if wager.stake == 0 {
+                continue;
+            }
+            if let Err(reason) = wager.bet.check_stake(wager.stake, self.bet, self.bankroll) {
+                debug!(side_bet = wager.bet.name(), reason, "side bet sat out");
+                continue;
+            }
+            let settled = Settled::new(
+                wager,
+                &self.player_hand,
+                &self.dealer_hand,
+                &mut self.jackpot,
+            );
+            self.bankroll = credit(self.bankroll, settled.payout);
+            debug!(
+                side_bet = wager.bet.name(),
+                payout = settled.payout,
+                "settled a side bet"
+            );
+            self.events.push(GameEvent::SideBet {
+                name: settled.name.clone(),
+                stake: settled.stake,
+                hand: settled.hand.clone(),
+                payout: settled.payout,
+                bankroll: self.bankroll,
+            });
+            self.side_results.push(settled);
+        }
+    }
+
+    /// Settle straight away when either hand is dealt a blackjack.
+    fn check_naturals(&mut self) {
+        let Some(hand_result) =
+            HandResult::natural(&self.player_hand, &self.dealer_hand, &self.rules)
+        else {
+            return;
+        };
+        debug!(result = hand_result.name(), "natural dealt");
+        self.player_hand.hold();
+        self.dealer_hand.hold();
+        self.state = GameState::HandScoreScreen(hand_result);
+        self.finish(hand_result);
+    }
+
+    fn check_hand(&mut self) {
+        // a bust hand is done, and once the hand in play is, the next split hand is played
+        if self.player_hand.is_bust() {
+            self.player_hand.hold();
+        }
+        if !self.player_hand.is_active() {
+            if let Some(card) = self.split_cards.pop() {
+                self.split_hands.push(SplitHand {
+                    hand: std::mem::take(&mut self.player_hand),
+                    doubled: self.doubled,
+                    result: None,
+                });
+                self.play_split(card);
+                return;
+            }
+        }
+        let player_hand = &self.player_hand;
+        let dealer_hand = &self.dealer_hand;
+        // the dealer has nothing to play for once every hand is bust
+        let all_bust =
+            player_hand.is_bust() && self.split_hands.iter().all(|split| split.hand.is_bust());
+        if all_bust
+            || dealer_hand.is_bust()
+            || (!player_hand.is_active() && !dealer_hand.is_active())
+        {
+            self.state =
+                GameState::HandScoreScreen(HandResult::of(player_hand, dealer_hand, &self.rules));
+        }
+
+        if let GameState::HandScoreScreen(hand_result) = self.state {
+            self.finish(hand_result);
+        }
+    }
+
+    /// Turn the hole card over, returning whether it was still face down.
+    fn reveal_hole_card(&mut self) -> bool {
+        if self.dealer_hand.is_revealed() {
+            return false;
+        }
+        self.dealer_hand.reveal();
+        if !self.rules.double_exposure {
+            self.observe(self.dealer_hand.cards()[0]);
+        }
+        self.events.push(GameEvent::Reveal {
+            card: card_code(&self.dealer_hand.cards()[0]),
+            dealer_total: self.dealer_hand.count_value(),
+        });
+        true
+    }
+
+    /// Show the hole card, if the dealer's turn hasn't already and the table turns it over
+    /// on this hand, and pay out.
+    fn finish(&mut self, hand_result: HandResult) {
+        self.dealer_hand.hold();
+        if self.hole_card.is_shown(hand_result) {
+            self.reveal_hole_card();
+        }
+        self.settle(hand_result);
+    }
+
+    fn settle(&mut self, hand_result: HandResult) {
+        let stake = self.stake();
+        self.payout = 0;
+        for split in &mut self.split_hands {
+            let result = HandResult::of(&split.hand, &self.dealer_hand, &self.rules);
+            let bet = match split.doubled {
+                true => self.bet * 2,
+                false => self.bet,
+            };
+            self.payout += result.pay(bet, &split.hand, &self.rules, &mut self.bankroll);
+            split.result = Some(result);
+        }
+        let bet = self.bet_on(self.doubled);
+        self.payout += hand_result.pay(bet, &self.player_hand, &self.rules, &mut self.bankroll);
+        if let Insurance::Taken { stake } = self.insurance {
+            // insurance pays 2 to 1
+            let insured = match self.dealer_hand.is_blackjack() {
+                true => 2 * i64::from(stake),
+                false => -i64::from(stake),
+            };
+            self.bankroll = credit(self.bankroll, insured);
+            self.payout += insured;
+        }
+        self.events.push(GameEvent::Settle {
+            result: hand_result.name().to_string(),
+            payout: self.payout,
+            bankroll: self.bankroll,
+        });
+        info!(
+            result = hand_result.name(),
+            bet = stake,
+            hands = self.hand_count(),
+            payout = self.payout,
+            bankroll = self.bankroll,
+            player = %self.player_hand.short(),
+            dealer = %self.dealer_hand.short(),
+            "settled the hand"
+        );
+    }
+}
+
+/// Most hands a pair can be split into.
+pub const SPLIT_HANDS: usize = 4;
+
+/// A hand split from a pair and played before the hand in play, waiting on the dealer to
+/// be settled.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitHand {
+    pub hand: Hand<Player>,
+    pub doubled: bool,
+    /// how the hand came out, once the round is settled
+    pub result: Option<HandResult>,
+}
+
+/// `bankroll` after `chips` are won, or lost if negative. Bets are always covered, so a
+/// bankroll never goes below nothing, but it's held there and at the most a bankroll can
+/// hold rather than wrapping around.
+pub fn credit(bankroll: u32, chips: i64) -> u32 {
+    let total = i64::from(bankroll).saturating_add(chips);
+    u32::try_from(total.max(0)).unwrap_or(u32::MAX)
+}
+
+/// Why the game turned down an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IllegalAction {
+    /// a move before the hand has been dealt
+    NoHand,
+    /// a move after the player held, while the dealer plays out
+    DealerTurn,
+    /// a move after the hand was settled
+    HandOver,
+    /// a move at a shared table while another seat is deciding
+    NotYourTurn,
+    /// a bet while a hand is being played
+    HandInPlay,
+    /// a bet of nothing, or of more than the bankroll
+    BetOutOfRange { bankroll: u32 },
+    /// a move this table doesn't deal, like surrender at most tables
+    NotOffered(strategy::Action),
+    /// a move before insurance is taken or turned down
+    InsuranceOffered,
+    /// insurance when it isn't offered
+    NoInsurance,
+    /// a double, split, or surrender after drawing, where the table only allows it on the
+    /// first two cards
+    FirstTwoCards(strategy::Action),
+    /// a double, split, or insurance the bankroll can't cover
+    Uncovered(strategy::Action),
+    /// a split of two cards that aren't a pair
+    NotAPair,
+    /// a split into more hands than a pair can be split into
+    SplitLimit,
+    /// a surrender, or a double where the table doesn't double after splitting, on a hand
+    /// split from a pair
+    AfterSplit(strategy::Action),
+    /// a hit, double, or split of a hand already doubled
+    Doubled(strategy::Action),
+}
+impl Display for IllegalAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IllegalAction::NoHand => write!(f, "no hand has been dealt yet"),
+            IllegalAction::DealerTurn => write!(f, "it's the dealer's turn"),
+            IllegalAction::HandOver => write!(f, "the hand is over"),
+            IllegalAction::NotYourTurn => write!(f, "it isn't your turn to play"),
+            IllegalAction::HandInPlay => write!(f, "bets are only taken between hands"),
+            IllegalAction::BetOutOfRange { bankroll } => {
+                write!(f, "bets are from 1 chip up to the bankroll of {bankroll}")
+            }
+            IllegalAction::NotOffered(action) => {
+                write!(f, "this table doesn't offer {}", action.name())
+            }
+            IllegalAction::InsuranceOffered => {
+                write!(f, "insurance has to be taken or turned down first")
+            }
+            IllegalAction::NoInsurance => {
+                write!(
+                    f,
+                    "insurance is only offered against a dealer ace, before anyone plays"
+                )
+            }
+            IllegalAction::FirstTwoCards(action) => {
+                write!(
+                    f,
+                    "{} is only allowed on the first two cards",
+                    action.name()
+                )
+            }
+            IllegalAction::Uncovered(action) => {
+                write!(f, "the bankroll can't cover the {}", action.name())
+            }
+            IllegalAction::NotAPair => write!(f, "only a pair can be split"),
+            IllegalAction::SplitLimit => {
+                write!(
+                    f,
+                    "a pair can be split into {SPLIT_HANDS} hands at the most"
+                )
+            }
+            IllegalAction::AfterSplit(action) => {
+                write!(f, "{} isn't allowed on a split hand", action.name())
+            }
+            IllegalAction::Doubled(action) => {
+                write!(f, "{} isn't allowed on a doubled hand", action.name())
+            }
+        }
+    }
+}
+impl std::error::Error for IllegalAction {}
+
+/// When the dealer turns the hole card over on a hand the dealer doesn't play out. The
+/// dealer peeks for blackjack on the deal, so a dealer blackjack is always turned over
+/// then, and a hand that never shows the hole card has already been checked for one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HoleCard {
+    /// at the end of every hand, even one the player busted
+    #[default]
+    Always,
+    /// only once the player stands, so never on a bust or a surrender
+    Stand,
+}
+impl HoleCard {
+    pub const ALL: [HoleCard; 2] = [HoleCard::Always, HoleCard::Stand];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HoleCard::Always => "always",
+            HoleCard::Stand => "stand",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|hole_card| hole_card.name() == name)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(HoleCard::name).join(", ");
+                format!("unknown hole card timing '{name}', expected one of {names}")
+            })
+    }
+
+    /// Whether the hole card is turned over when the hand ends in `result`.
+    fn is_shown(self, result: HandResult) -> bool {
+        match self {
+            HoleCard::Always => true,
+            HoleCard::Stand => !matches!(result, HandResult::Bust | HandResult::Surrender),
+        }
+    }
+}
+
+/// Insurance on the hand in play. It's offered when the dealer shows an ace with the hole
+/// card face down, for half the bet, and pays 2 to 1 if the dealer has blackjack. Taken
+/// on a blackjack, it's even money.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Insurance {
+    #[default]
+    Unoffered,
+    /// waiting on the player, before the dealer peeks
+    Offered,
+    Taken {
+        stake: u32,
+    },
+    Declined,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameState {
+    Betting,
+    PlayingHand,
+    HandScoreScreen(HandResult),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandResult {
+    Blackjack,
+    PlayerWin,
+    DealerWin,
+    Push,
+    Bust,
+    /// half the bet given up to leave the hand
+    Surrender,
+}
+impl HandResult {
+    /// The result once the player is bust or both hands are played out. Under double
+    /// exposure the dealer wins ties, and under Super Fun 21 a player's 21 or six cards
+    /// win outright.
+    pub fn of(player_hand: &Hand<Player>, dealer_hand: &Hand<Dealer>, rules: &Rules) -> Self {
+        if player_hand.is_bust() {
+            return HandResult::Bust;
+        }
+        if rules.super_fun && (player_hand.count_value() == 21 || player_hand.cards().len() >= 6) {
+            return HandResult::PlayerWin;
+        }
+        if dealer_hand.is_bust() {
+            return HandResult::PlayerWin;
+        }
+        match player_hand.count_value().cmp(&dealer_hand.count_value()) {
+            Ordering::Less => HandResult::DealerWin,
+            Ordering::Equal if rules.double_exposure => HandResult::DealerWin,
+            Ordering::Equal => HandResult::Push,
+            Ordering::Greater => HandResult::PlayerWin,
+        }
+    }
+
+    /// The result when either hand is dealt a blackjack, settled before anyone plays, or
+    /// `None` when neither is. Under double exposure the dealer wins a pair of them, and
+    /// under Super Fun 21 the player's always wins.
+    pub fn natural(
+        player_hand: &Hand<Player>,
+        dealer_hand: &Hand<Dealer>,
+        rules: &Rules,
+    ) -> Option<Self> {
+        match (player_hand.is_blackjack(), dealer_hand.is_blackjack()) {
+            (true, _) if rules.super_fun => Some(HandResult::Blackjack),
+            (true, true) if rules.double_exposure => Some(HandResult::DealerWin),
+            (true, true) => Some(HandResult::Push),
+            (true, false) => Some(HandResult::Blackjack),
+            (false, true) => Some(HandResult::DealerWin),
+            (false, false) => None,
+        }
+    }
+
+    /// Pay a bet on `player_hand` into `bankroll`, returning its [`payout`](Self::payout).
+    pub fn pay(
+        &self,
+        bet: u32,
+        player_hand: &Hand<Player>,
+        rules: &Rules,
+        bankroll: &mut u32,
+    ) -> i64 {
+        let payout = self.payout(bet, player_hand, rules);
+        *bankroll = credit(*bankroll, payout);
+        payout
+    }
+
+    /// Chips won, or lost if negative, on a bet on `player_hand`.
+    pub fn payout(&self, bet: u32, player_hand: &Hand<Player>, rules: &Rules) -> i64 {
+        let bet = i64::from(bet);
+        let diamonds = player_hand
+            .cards()
+            .iter()
+            .all(|card| matches!(card.suit(), Suit::Diamond));
+        let (win, pays_on) = match rules.super_fun && diamonds {
+            true => (2, 1),
+            false => rules.blackjack_pays,
+        };
+        match self {
+            HandResult::Blackjack => bet * i64::from(win) / i64::from(pays_on),
+            HandResult::PlayerWin => bet,
+            HandResult::Push => 0,
+            HandResult::DealerWin | HandResult::Bust => -bet,
+            // the house keeps the odd chip of an odd bet
+            HandResult::Surrender => -(bet + 1) / 2,
+        }
+    }
+
+    /// Name of the result in written logs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandResult::Blackjack => "blackjack",
+            HandResult::PlayerWin => "win",
+            HandResult::DealerWin => "loss",
+            HandResult::Push => "push",
+            HandResult::Bust => "bust",
+            HandResult::Surrender => "surrender",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{history::parse_card_code, shuffle::Shuffler, strategy::Action};
+
+    /// A hand of `cards`, written as in the hand history.
+    fn hand(cards: &str) -> Hand<Player> {
+        let cards: Vec<_> = cards.split(' ').filter_map(parse_card_code).collect();
+        let mut hand = Hand::new([cards[0], cards[1]]);
+        let mut draws = Shoe::for_table(&Rules::default(), Shuffler::Stacked(cards[2..].to_vec()));
+        for _ in 2..cards.len() {
+            hand.hit(&mut draws);
+        }
+        hand
+    }
+
+    /// A game under `rules` with 10 bet on a deck that deals the cards `first` names, two
+    /// to the player, then the dealer's hole card and up-card, then the draws.
+    fn dealt(first: &str, rules: Rules) -> Game {
+        dealt_at(
+            first,
+            Config {
+                rules,
+                ..Config::default()
+            },
+        )
+    }
+
+    /// Like [`dealt`], at the table `config` sets.
+    fn dealt_at(first: &str, config: Config) -> Game {
+        let config = Config {
+            shuffle: Shuffler::stacked(first),
+            ..config
+        };
+        let mut game = Game::new(&config);
+        game.place_bet(10).unwrap();
+        game
+    }
+
+    fn play_out(game: &mut Game) {
+        while game.is_dealer_turn() {
+            game.dealer_step();
+        }
+    }
+
+    #[test]
+    fn pays_each_result() {
+        let rules = Rules::default();
+        let twenty = hand("KS QH");
+        assert_eq!(HandResult::Blackjack.payout(10, &hand("AS KS"), &rules), 15);
+        assert_eq!(HandResult::PlayerWin.payout(10, &twenty, &rules), 10);
+        assert_eq!(HandResult::Push.payout(10, &twenty, &rules), 0);
+        assert_eq!(HandResult::DealerWin.payout(10, &twenty, &rules), -10);
+        assert_eq!(HandResult::Bust.payout(10, &hand("KS QH 5C"), &rules), -10);
+        // the house keeps the odd chip
+        assert_eq!(HandResult::Surrender.payout(5, &twenty, &rules), -3);
+
+        let six_to_five = Rules {
+            blackjack_pays: (6, 5),
+            ..rules
+        };
+        assert_eq!(
+            HandResult::Blackjack.payout(10, &hand("AS KS"), &six_to_five),
+            12
+        );
+        let super_fun = Rules::preset("super-fun-21").unwrap();
+        assert_eq!(
+            HandResult::Blackjack.payout(10, &hand("AD KD"), &super_fun),
+            20
+        );
+        assert_eq!(
+            HandResult::Blackjack.payout(10, &hand("AS KS"), &super_fun),
+            10
+        );
+    }
+
+    #[test]
+    fn decides_naturals_and_ties_by_the_rules() {
+        let rules = Rules::default();
+        let exposure = Rules::preset("double-exposure").unwrap();
+        let super_fun = Rules::preset("super-fun-21").unwrap();
+        let blackjack = hand("AS KS");
+        let nineteen = hand("9S KC");
+        let dealer = |cards: &str| {
+            let player = hand(cards);
+            Hand::<Dealer>::new([player.cards()[0], player.cards()[1]])
+        };
+        let dealer_blackjack = dealer("AH QH");
+        let natural = HandResult::natural;
+
+        assert_eq!(
+            natural(&blackjack, &dealer_blackjack, &rules),
+            Some(HandResult::Push)
+        );
+        assert_eq!(
+            natural(&blackjack, &dealer_blackjack, &exposure),
+            Some(HandResult::DealerWin)
+        );
+        assert_eq!(
+            natural(&blackjack, &dealer_blackjack, &super_fun),
+            Some(HandResult::Blackjack)
+        );
+        assert_eq!(
+            natural(&nineteen, &dealer_blackjack, &rules),
+            Some(HandResult::DealerWin)
+        );
+        assert_eq!(natural(&nineteen, &dealer("9H QH"), &rules), None);
+
+        let eighteen = hand("9S 9C");
+        let dealer_18 = dealer("9H 9D");
+        let six_cards = hand("2S 3S 2C 3C 2H 3H");
+        assert_eq!(
+            HandResult::of(&eighteen, &dealer_18, &rules),
+            HandResult::Push
+        );
+        assert_eq!(
+            HandResult::of(&eighteen, &dealer_18, &exposure),
+            HandResult::DealerWin
+        );
+        assert_eq!(
+            HandResult::of(&six_cards, &dealer_18, &super_fun),
+            HandResult::PlayerWin
+        );
+    }
+
+    #[test]
+    fn settles_naturals_as_they_are_dealt() {
+        let game = dealt("AS KS 9H 7H", Rules::default());
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::Blackjack)
+        ));
+        assert_eq!((game.payout, game.bankroll), (15, 1015));
+
+        let game = dealt("9S 7S AH KH", Rules::default());
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::DealerWin)
+        ));
+        assert_eq!((game.payout, game.bankroll), (-10, 990));
+    }
+
+    #[test]
+    fn doubles_for_one_card_on_twice_the_bet() {
+        let mut game = dealt("5S 6S 9H 7H 10D 8C", Rules::default());
+        assert_eq!(game.play(Action::Double), Ok(()));
+        assert_eq!(game.player_hand.count_value(), 21);
+        assert_eq!(game.stake(), 20);
+        play_out(&mut game);
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::PlayerWin)
+        ));
+        assert_eq!((game.payout, game.bankroll), (20, 1020));
+
+        let mut game = dealt("5S 6S 9H 7H 2D", Rules::default());
+        game.hit().unwrap();
+        assert_eq!(
+            game.play(Action::Double),
+            Err(IllegalAction::FirstTwoCards(Action::Double))
+        );
+    }
+
+    #[test]
+    fn splits_pairs_into_hands_played_in_turn() {
+        let mut game = dealt("8S 8H 9H 10H 3D 10C 10S", Rules::default());
+        assert_eq!(game.play(Action::Split), Ok(()));
+        assert_eq!(game.player_hand.short(), "8♠ 3♦");
+        assert_eq!(game.split_cards.len(), 1);
+        assert_eq!(game.stake(), 20);
+        assert_eq!(
+            game.play(Action::Double),
+            Err(IllegalAction::AfterSplit(Action::Double))
+        );
+        game.hit().unwrap();
+        game.hold().unwrap();
+        // the second hand is dealt its card once the first is done
+        assert_eq!(game.player_hand.short(), "8♥ 10♠");
+        assert!(game.is_player_turn());
+        game.hold().unwrap();
+        play_out(&mut game);
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::DealerWin)
+        ));
+        assert_eq!(game.split_hands[0].result, Some(HandResult::PlayerWin));
+        assert_eq!((game.payout, game.bankroll), (0, 1000));
+
+        // split aces get a card each, and 21 on them is no blackjack
+        let mut game = dealt("AS AH 9H 7H KD 5C 2D", Rules::default());
+        game.split().unwrap();
+        assert!(game.is_dealer_turn());
+        play_out(&mut game);
+        assert_eq!(game.split_hands[0].result, Some(HandResult::PlayerWin));
+        assert_eq!((game.payout, game.bankroll), (0, 1000));
+    }
+
+    #[test]
+    fn splits_only_what_the_table_and_bankroll_allow() {
+        let mut game = dealt("8S 9H 9D 10H", Rules::default());
+        assert_eq!(game.play(Action::Split), Err(IllegalAction::NotAPair));
+
+        let rules = Rules {
+            surrender: true,
+            ..Rules::default()
+        };
+        let mut game = dealt("10S KH 9H 10H QD JC KS", rules);
+        for _ in 1..SPLIT_HANDS {
+            game.split().unwrap();
+        }
+        assert_eq!(game.play(Action::Split), Err(IllegalAction::SplitLimit));
+        assert_eq!(
+            game.play(Action::Surrender),
+            Err(IllegalAction::AfterSplit(Action::Surrender))
+        );
+
+        let config = Config {
+            bankroll: 15,
+            ..Config::default()
+        };
+        let mut game = dealt_at("8S 8H 9H 10H", config);
+        assert_eq!(
+            game.play(Action::Split),
+            Err(IllegalAction::Uncovered(Action::Split))
+        );
+    }
+
+    #[test]
+    fn the_dealer_sits_out_once_every_split_hand_busts() {
+        let mut game = dealt("8S 8H 9H 7H 5D KC 6D QC", Rules::default());
+        game.split().unwrap();
+        game.hit().unwrap();
+        assert!(game.is_player_turn());
+        game.hit().unwrap();
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::Bust)
+        ));
+        assert_eq!(game.dealer_hand.cards().len(), 2);
+        assert_eq!((game.payout, game.bankroll), (-20, 980));
+    }
+
+    #[test]
+    fn surrenders_only_where_the_table_allows_it() {
+        let mut game = dealt("10S 6S 9H 10H", Rules::default());
+        assert_eq!(
+            game.play(Action::Surrender),
+            Err(IllegalAction::NotOffered(Action::Surrender))
+        );
+
+        let rules = Rules {
+            surrender: true,
+            ..Rules::default()
+        };
+        let mut game = dealt("10S 6S 9H 10H", rules);
+        assert_eq!(game.play(Action::Surrender), Ok(()));
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::Surrender)
+        ));
+        assert_eq!((game.payout, game.bankroll), (-5, 995));
+    }
+
+    #[test]
+    fn super_fun_doubles_and_surrenders_on_any_number_of_cards() {
+        let mut game = dealt("2S 3S 9H 10H 4D 5C", Rules::default());
+        game.hit().unwrap();
+        assert_eq!(
+            game.play(Action::Double),
+            Err(IllegalAction::FirstTwoCards(Action::Double))
+        );
+
+        let super_fun = Rules {
+            burn: 0,
+            ..Rules::preset("super-fun-21").unwrap()
+        };
+        let mut game = dealt("2S 3S 9H 10H 4D 5C", super_fun);
+        game.hit().unwrap();
+        assert_eq!(game.play(Action::Double), Ok(()));
+        // the doubled 14 waits to be held or rescued
+        assert!(game.is_player_turn());
+        assert_eq!(
+            game.play(Action::Hit),
+            Err(IllegalAction::Doubled(Action::Hit))
+        );
+        assert_eq!(game.hint().0, Action::Surrender);
+        assert_eq!(game.play(Action::Surrender), Ok(()));
+        // half of everything bet on the hand
+        assert_eq!((game.payout, game.bankroll), (-10, 990));
+
+        let mut game = dealt("10S 2S 9H 10H 4D", super_fun);
+        game.hit().unwrap();
+        assert_eq!(game.play(Action::Surrender), Ok(()));
+        assert_eq!((game.payout, game.bankroll), (-5, 995));
+    }
+
+    #[test]
+    fn insurance_is_answered_before_anything_else() {
+        let mut game = dealt("10S 9S KH AH", Rules::default());
+        assert_eq!(game.insurance, Insurance::Offered);
+        assert_eq!(game.play(Action::Hit), Err(IllegalAction::InsuranceOffered));
+        assert_eq!(game.play(Action::Insurance(true)), Ok(()));
+        // the bet is lost and the insurance pays 2 to 1 on half of it
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::DealerWin)
+        ));
+        assert_eq!((game.payout, game.bankroll), (0, 1000));
+
+        let mut game = dealt("10S 9S 7H AH", Rules::default());
+        assert_eq!(game.play(Action::Insurance(true)), Ok(()));
+        assert_eq!(game.play(Action::Stand), Ok(()));
+        play_out(&mut game);
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::PlayerWin)
+        ));
+        assert_eq!((game.payout, game.bankroll), (5, 1005));
+
+        let mut game = dealt("10S 9S 7H 9H", Rules::default());
+        assert_eq!(
+            game.play(Action::Insurance(true)),
+            Err(IllegalAction::NoInsurance)
+        );
+    }
+
+    #[test]
+    fn insurance_has_to_be_covered_after_the_side_bets() {
+        let config = Config {
+            bankroll: 20,
+            side_bets: vec![Wager {
+                bet: &crate::side_bets::LuckyLadies,
+                stake: 10,
+            }],
+            ..Config::default()
+        };
+        let mut game = dealt_at("10S 6S 7H AH", config);
+        // the side bet lost 10, which leaves the bet and nothing for insurance
+        assert_eq!(game.bankroll, 10);
+        assert_eq!(game.insurance, Insurance::Unoffered);
+        assert_eq!(
+            game.play(Action::Insurance(true)),
+            Err(IllegalAction::NoInsurance)
+        );
+        game.hold().unwrap();
+        play_out(&mut game);
+        assert!(matches!(
+            game.state,
+            GameState::HandScoreScreen(HandResult::DealerWin)
+        ));
+        assert_eq!(game.bankroll, 0);
+    }
+
+    #[test]
+    fn bankrolls_never_wrap_around() {
+        assert_eq!(credit(10, -15), 0);
+        assert_eq!(credit(u32::MAX, 5), u32::MAX);
+        assert_eq!(credit(10, -4), 6);
+    }
+
+    #[test]
+    fn parses_hole_card_timings() {
+        assert_eq!(HoleCard::parse("stand"), Ok(HoleCard::Stand));
+        assert!(HoleCard::parse("never").is_err());
+    }
+}
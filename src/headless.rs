@@ -0,0 +1,131 @@
+use std::io::{self, Write};
+
+use crate::{
+    bot::{self, BotAction, BotEvent, Heard, Json, Voice},
+    config::Config,
+    game::Game,
+    stats::GameStats,
+};
+
+/// Play one game over plain pipes until `quit`, the end of input, the hand limit, or the
+/// bankroll is gone, returning its results. Commands are read a line at a time from stdin:
+///
+/// ```text
+/// bet 10
+/// no insurance
+/// hit
+/// stand
+/// double
+/// split
+/// quit
+/// ```
+///
+/// The game is written to stdout as a line for each change, either as the same JSON
+/// events as the bot API or as words and `key=value` pairs:
+///
+/// ```text
+/// betting bankroll=1000 bet=10 legal=bet
+/// playing bankroll=1000 bet=10 player=10H,6S total=16 soft=false dealer=9C legal=hit,stand,double
+/// result loss payout=-10 bankroll=990 player=10H,6S total=16 dealer=8D,9C dealer_total=17
+/// error it isn't your turn to play
+/// ```
+///
+/// The result of a split round lists the hands played before the last one, like
+/// `split=8S,3D/8H,10C`, ahead of the dealer's cards.
+pub fn run(config: &Config, json: bool) -> io::Result<GameStats> {
+    let reader = io::stdin().lock();
+    let writer = io::stdout().lock();
+    let (_, stats) = match json {
+        true => bot::play(config, reader, &mut Json(writer))?,
+        false => bot::play(config, reader, &mut Text(writer))?,
+    };
+    Ok(stats)
+}
+
+/// Speaks in words and `key=value` pairs, a line for each change.
+struct Text<W>(W);
+impl<W: Write> Voice for Text<W> {
+    fn hear(&mut self, _game: &mut Game, line: &str) -> io::Result<Heard> {
+        Ok(match line.trim().is_empty() {
+            true => Heard::Handled,
+            false => parse(line).map_or_else(Heard::Refused, Heard::Action),
+        })
+    }
+
+    fn send(&mut self, _game: &Game, event: &BotEvent) -> io::Result<()> {
+        send_text(&mut self.0, event)
+    }
+}
+
+fn parse(line: &str) -> Result<BotAction, String> {
+    let line = line.trim().to_lowercase();
+    let words: Vec<_> = line.split_whitespace().collect();
+    match words[..] {
+        ["bet", amount] => amount
+            .parse()
+            .map(|amount| BotAction::Bet { amount })
+            .map_err(|_| format!("expected a number of chips, found '{amount}'")),
+        ["bet"] => Err("expected a bet like 'bet 10'".to_string()),
+        ["hit" | "h"] => Ok(BotAction::Hit),
+        ["stand" | "hold" | "s"] => Ok(BotAction::Stand),
+        ["double" | "d"] => Ok(BotAction::Double),
+        ["split" | "p"] => Ok(BotAction::Split),
+        ["surrender" | "r"] => Ok(BotAction::Surrender),
+        ["insurance" | "insure"] | ["even", "money"] => Ok(BotAction::Insurance { take: true }),
+        ["no", "insurance"] | ["decline"] => Ok(BotAction::Insurance { take: false }),
+        ["quit" | "q"] => Ok(BotAction::Quit),
+        _ => Err(format!(
+            "unknown command '{line}', expected bet <chips>, hit, stand, double, split, \
+             surrender, insurance, no insurance, or quit"
+        )),
+    }
+}
+
+fn send_text(writer: &mut impl Write, event: &BotEvent) -> io::Result<()> {
+    match event {
+        BotEvent::State(state) => {
+            write!(
+                writer,
+                "{} bankroll={} bet={}",
+                state.phase, state.bankroll, state.bet
+            )?;
+            if let (Some(total), Some(dealer)) = (state.player_total, &state.dealer_up) {
+                write!(
+                    writer,
+                    " player={} total={total} soft={} dealer={dealer}",
+                    state.player.join(","),
+                    state.soft
+                )?;
+            }
+            writeln!(writer, " legal={}", state.legal.join(","))?;
+        }
+        BotEvent::Result {
+            result,
+            payout,
+            bankroll,
+            player,
+            split,
+            dealer,
+            player_total,
+            dealer_total,
+        } => {
+            write!(
+                writer,
+                "result {result} payout={payout} bankroll={bankroll} player={} \
+                 total={player_total}",
+                player.join(",")
+            )?;
+            if !split.is_empty() {
+                let hands: Vec<_> = split.iter().map(|hand| hand.join(",")).collect();
+                write!(writer, " split={}", hands.join("/"))?;
+            }
+            writeln!(
+                writer,
+                " dealer={} dealer_total={dealer_total}",
+                dealer.join(",")
+            )?
+        }
+        BotEvent::Error { message } => writeln!(writer, "error {message}")?,
+    }
+    writer.flush()
+}
@@ -0,0 +1,416 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    counting,
+    game::{Game, GameState, HandResult, Insurance},
+    paths, report,
+    strategy::Action,
+    widgets::{Card, Rank, Suit},
+};
+
+/// One finished round, as written to the hand history.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Round {
+    /// when the session the round was played in started
+    pub session_ms: u128,
+    pub started_ms: u128,
+    pub finished_ms: u128,
+    pub rules: String,
+    /// name of the preset the rules match, or `custom`, empty in rounds written before it
+    /// was recorded
+    pub preset: String,
+    /// the bet on every hand, doubled where it was
+    pub bet: u32,
+    pub running_count: i32,
+    pub true_count: f64,
+    pub player: Vec<String>,
+    /// hands split from the one dealt and played before `player`, empty without a split
+    pub split: Vec<Vec<String>>,
+    pub dealer: Vec<String>,
+    pub actions: Vec<String>,
+    pub player_total: u8,
+    pub dealer_total: u8,
+    pub result: String,
+    pub payout: i64,
+    pub bankroll: u32,
+}
+/// Every finished round, appended to `history.jsonl` in the data directory as one JSON
+/// object per line:
+///
+/// ```text
+/// {"version":1,"session_ms":1760000000000,"started_ms":1760000001000,
+///  "finished_ms":1760000004200,"rules":"6 decks, S17, 3:2, 75% dealt, burn 1",
+///  "preset":"vegas","bet":10,"running_count":2,"true_count":0.4,
+///  "player":["10H","6S","4C"],"dealer":["KD","7C"],"actions":["hit","stand"],
+///  "player_total":20,"dealer_total":17,"result":"win","payout":10,"bankroll":1010}
+/// ```
+///
+/// Times are milliseconds since the Unix epoch and cards are written rank then suit (`S`,
+/// `C`, `D`, or `H`). The count is the one the bet was placed on, and `result` is one of
+/// `blackjack`, `win`, `loss`, `push`, `bust`, or `surrender`. `actions` are `hit`,
+/// `stand`, `double`, `split`, `surrender`, `insurance`, or `no insurance`. A split
+/// round keeps the hands played before the last one in `split`, and its `result` and
+/// totals are the last hand's, while `bet` and `payout` are over every hand, with any
+/// insurance. `preset` names the rules' preset, or `custom`, and the full `rules` keep
+/// results under different tables apart.
+///
+/// The history is kept in builds with the serde feature. Without it rounds are only
+/// remembered for the session.
+#[derive(Debug)]
+pub struct History {
+    session_ms: u128,
+    /// when the player first saw the hand being played
+    started_ms: Option<u128>,
+    /// running and true count while betting
+    count: (i32, f64),
+    /// the settled hand on screen has been written
+    written: bool,
+    /// rounds played this session
+    pub rounds: Vec<Round>,
+}
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            session_ms: now_ms(),
+            started_ms: None,
+            count: (0, 0.0),
+            written: false,
+            rounds: Vec::new(),
+        }
+    }
+}
+impl History {
+    #[cfg(feature = "serde")]
+    const FILE: &str = "history.jsonl";
+    #[cfg(feature = "serde")]
+    const VERSION: u32 = 1;
+
+    #[cfg(feature = "serde")]
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// Every round in the saved history, skipping lines that can't be read.
+    #[cfg(feature = "serde")]
+    pub fn load() -> io::Result<Vec<Round>> {
+        let Some(path) = Self::path() else {
+            return Ok(Vec::new());
+        };
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// No history is kept without serde.
+    #[cfg(not(feature = "serde"))]
+    pub fn load() -> io::Result<Vec<Round>> {
+        Ok(Vec::new())
+    }
+
+    /// Write this session's rounds to a CSV file in the data directory.
+    pub fn export(&self) -> Result<PathBuf, String> {
+        let dir = paths::data_dir().ok_or("there's no data directory to export to")?;
+        fs::create_dir_all(&dir)
+            .map_err(|err| format!("couldn't create {}: {err}", dir.display()))?;
+        let path = dir.join(format!("session-{}.csv", self.session_ms));
+        report::write_rounds(&path, report::Format::Csv, &self.rounds)?;
+        Ok(path)
+    }
+
+    /// Follow the game, writing each round once it is settled.
+    pub fn update(&mut self, game: &Game) -> io::Result<()> {
+        match game.state {
+            GameState::Betting => {
+                let decks = counting::decks_remaining(&game.deck);
+                self.count = (game.count.running, game.count.true_count(decks));
+                self.started_ms = None;
+                self.written = false;
+            }
+            GameState::PlayingHand => {
+                self.started_ms.get_or_insert_with(now_ms);
+            }
+            GameState::HandScoreScreen(result) if !self.written => {
+                self.written = true;
+                self.append(game, result)?;
+            }
+            GameState::HandScoreScreen(_) => (),
+        }
+        Ok(())
+    }
+
+    fn append(&mut self, game: &Game, result: HandResult) -> io::Result<()> {
+        let finished_ms = now_ms();
+        let cards = |cards: &[Card]| cards.iter().map(card_code).collect();
+        let round = Round {
+            session_ms: self.session_ms,
+            started_ms: self.started_ms.unwrap_or(finished_ms),
+            finished_ms,
+            rules: game.rules.to_string(),
+            preset: game.rules.name().to_string(),
+            bet: game.stake(),
+            running_count: self.count.0,
+            // to the hundredth, as it's shown
+            true_count: (self.count.1 * 100.0).round() / 100.0,
+            player: cards(game.player_hand.cards()),
+            split: game
+                .split_hands
+                .iter()
+                .map(|split| cards(split.hand.cards()))
+                .collect(),
+            dealer: cards(game.dealer_hand.cards()),
+            actions: actions(game, result)
+                .iter()
+                .map(|action| action.to_string().to_lowercase())
+                .collect(),
+            player_total: game.player_hand.count_value(),
+            dealer_total: game.dealer_hand.count_value(),
+            result: result.name().to_string(),
+            payout: game.payout,
+            bankroll: game.bankroll,
+        };
+        #[cfg(feature = "serde")]
+        Self::write(&round)?;
+        self.rounds.push(round);
+        Ok(())
+    }
+
+    /// Add `round` to the end of the saved history.
+    #[cfg(feature = "serde")]
+    fn write(round: &Round) -> io::Result<()> {
+        use std::{fs::OpenOptions, io::Write};
+
+        /// A line of the history, which starts with the version of its layout.
+        #[derive(serde::Serialize)]
+        struct Line<'a> {
+            version: u32,
+            #[serde(flatten)]
+            round: &'a Round,
+        }
+
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let line = Line {
+            version: History::VERSION,
+            round,
+        };
+        let mut line = serde_json::to_string(&line)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+/// The bankroll before the first round and after each one.
+pub fn bankrolls(rounds: &[Round]) -> Vec<i64> {
+    let start = rounds
+        .first()
+        .map(|round| i64::from(round.bankroll) - round.payout);
+    start
+        .into_iter()
+        .chain(rounds.iter().map(|round| i64::from(round.bankroll)))
+        .collect()
+}
+
+/// Every set of rules rounds were played under, in the order they were first played.
+pub fn rule_sets<'a>(rounds: impl IntoIterator<Item = &'a Round>) -> Vec<&'a str> {
+    let mut rule_sets = Vec::new();
+    for round in rounds {
+        if !rule_sets.contains(&round.rules.as_str()) {
+            rule_sets.push(round.rules.as_str());
+        }
+    }
+    rule_sets
+}
+
+/// Net chips won before the first round and after each one.
+pub fn net<'a>(rounds: impl Iterator<Item = &'a Round>) -> Vec<i64> {
+    std::iter::once(0)
+        .chain(rounds.scan(0, |net, round| {
+            *net += round.payout;
+            Some(*net)
+        }))
+        .collect()
+}
+
+/// The player's decisions, worked out from the cards they ended with. A split comes
+/// first, once for each hand it made, and then the moves on each hand in the order they
+/// were played.
+pub fn actions(game: &Game, result: HandResult) -> Vec<Action> {
+    let mut actions = match game.insurance {
+        Insurance::Taken { .. } => vec![Action::Insurance(true)],
+        Insurance::Declined => vec![Action::Insurance(false)],
+        Insurance::Unoffered | Insurance::Offered => Vec::new(),
+    };
+    let naturals = game.player_hand.is_blackjack() || game.dealer_hand.is_blackjack();
+    if naturals && !game.is_split() {
+        return actions;
+    }
+    if matches!(result, HandResult::Surrender) {
+        actions.push(Action::Surrender);
+        return actions;
+    }
+    actions.extend(vec![Action::Split; game.split_hands.len()]);
+    let split = game
+        .split_hands
+        .iter()
+        .map(|split| (&split.hand, split.doubled));
+    for (hand, doubled) in split.chain([(&game.player_hand, game.doubled)]) {
+        // split aces are dealt a card each and nothing more
+        if game.is_split() && hand.cards()[0].rank() == Rank::Ace {
+            continue;
+        }
+        let drawn = hand.cards().len().saturating_sub(2);
+        match doubled {
+            true => actions.push(Action::Double),
+            false => actions.extend(vec![Action::Hit; drawn]),
+        }
+        if !hand.is_bust() && !doubled {
+            actions.push(Action::Stand);
+        }
+    }
+    actions
+}
+
+/// A card written as rank then suit, like `10H`.
+pub fn card_code(card: &Card) -> String {
+    let suit = match card.suit() {
+        Suit::Spade => 'S',
+        Suit::Club => 'C',
+        Suit::Diamond => 'D',
+        Suit::Heart => 'H',
+    };
+    format!("{}{suit}", card.rank().get_rank())
+}
+
+/// The card of a code like `10H` or `QS`, as written by [`card_code`].
+pub fn parse_card_code(code: &str) -> Option<Card> {
+    let suit = code.chars().last()?;
+    let rank = &code[..code.len() - suit.len_utf8()];
+    let suit = match suit {
+        'S' => Suit::Spade,
+        'C' => Suit::Club,
+        'D' => Suit::Diamond,
+        'H' => Suit::Heart,
+        _ => return None,
+    };
+    let rank = Rank::ALL.into_iter().find(|r| r.get_rank() == rank)?;
+    Some(Card::new(rank, suit))
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(payout: i64, bankroll: u32) -> Round {
+        Round {
+            session_ms: 1_760_000_000_000,
+            rules: "6 decks, S17, 3:2".to_string(),
+            bet: 10,
+            player: vec!["10H".to_string(), "9S".to_string()],
+            dealer: vec!["KD".to_string(), "7C".to_string()],
+            actions: vec!["stand".to_string()],
+            result: "win".to_string(),
+            payout,
+            bankroll,
+            ..Round::default()
+        }
+    }
+
+    #[test]
+    fn card_codes_read_back() {
+        for rank in Rank::ALL {
+            for suit in [Suit::Spade, Suit::Club, Suit::Diamond, Suit::Heart] {
+                let card = Card::new(rank, suit);
+                assert_eq!(parse_card_code(&card_code(&card)), Some(card));
+            }
+        }
+        assert_eq!(parse_card_code("1X"), None);
+        assert_eq!(parse_card_code(""), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rounds_read_back_from_their_lines() {
+        let round = Round {
+            rules: "a \"quoted\" rule\n".to_string(),
+            true_count: -1.25,
+            ..round(10, 1010)
+        };
+        let line = serde_json::to_string(&round).unwrap();
+        assert_eq!(serde_json::from_str::<Round>(&line).unwrap(), round);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reads_lines_from_before_presets() {
+        let line = r#"{"version":1,"session_ms":1,"started_ms":2,"finished_ms":3,
+            "rules":"1 deck","bet":5,"running_count":-2,"true_count":-0.5,
+            "player":["AS","KS"],"dealer":["9H","7D"],"actions":[],"player_total":21,
+            "dealer_total":16,"result":"blackjack","payout":7,"bankroll":1007}"#;
+        let round: Round = serde_json::from_str(line).unwrap();
+        assert_eq!(round.preset, "");
+        assert_eq!(round.payout, 7);
+        assert_eq!(round.player, ["AS", "KS"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn settled_hands_are_saved_and_read_back() {
+        use crate::{config::Config, shuffle::Shuffler};
+
+        paths::use_test_dir();
+        let config = Config {
+            shuffle: Shuffler::stacked("10S 9S 8H 9H"),
+            ..Config::default()
+        };
+        let mut game = Game::new(&config);
+        let mut history = History::default();
+        history.update(&game).unwrap();
+        game.place_bet(10).unwrap();
+        history.update(&game).unwrap();
+        game.hold().unwrap();
+        while game.is_dealer_turn() {
+            game.dealer_step();
+        }
+        history.update(&game).unwrap();
+        history.update(&game).unwrap();
+
+        let [round] = history.rounds.as_slice() else {
+            panic!("expected one round, found {:?}", history.rounds);
+        };
+        assert_eq!(round.actions, ["stand"]);
+        assert_eq!((round.result.as_str(), round.payout), ("win", 10));
+        assert!(History::load().unwrap().contains(round));
+    }
+
+    #[test]
+    fn follows_the_bankroll_and_net() {
+        let rounds = [round(10, 1010), round(-20, 990)];
+        assert_eq!(bankrolls(&rounds), [1000, 1010, 990]);
+        assert_eq!(net(rounds.iter()), [0, 10, -10]);
+    }
+}
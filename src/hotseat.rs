@@ -0,0 +1,243 @@
+use ratatui::{
+    layout::Flex,
+    prelude::{Color, Constraint, Frame, Layout, Line, Style, Stylize},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    config::Config,
+    keyboard,
+    money::Money,
+    strategy::Action,
+    suspend,
+    table::{Phase, Seat, Table},
+    term::{self, Event, KeyCode},
+    widgets::{Card, FaceDownCard},
+};
+
+/// How many players can sit at a hotseat table, or boxes one player can play.
+pub const PLAYERS: std::ops::RangeInclusive<u8> = 2..=4;
+
+/// A table of players sharing the keyboard, or of boxes one player plays.
+#[derive(Debug)]
+struct Hotseat {
+    table: Table,
+    /// how chip amounts are written
+    money: Money,
+}
+impl Hotseat {
+    /// Handle a key press, returning false when the players quit.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if matches!(code, KeyCode::Char('q') | KeyCode::Esc) {
+            return false;
+        }
+        let table = &mut self.table;
+        let turn = table.turn;
+        let unit = table.bet_unit;
+        match table.phase {
+            Phase::Betting => {
+                let available = table.available(turn);
+                let seat = &mut table.seats[turn];
+                match code {
+                    KeyCode::Up | KeyCode::Char('+' | '=') => {
+                        seat.bet = (seat.bet + unit).min(available)
+                    }
+                    KeyCode::Down | KeyCode::Char('-') => {
+                        seat.bet = seat.bet.saturating_sub(unit).max(unit.min(available))
+                    }
+                    KeyCode::Char('s') => {
+                        let _ = table.place_bet(turn, 0);
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => {
+                        let bet = seat.bet;
+                        let _ = table.place_bet(turn, bet);
+                    }
+                    _ => (),
+                }
+            }
+            Phase::Playing => match code {
+                KeyCode::Char('1') => {
+                    let _ = table.act(turn, Action::Hit);
+                }
+                KeyCode::Char('2') => {
+                    let _ = table.act(turn, Action::Stand);
+                }
+                _ => (),
+            },
+            Phase::Dealer => (),
+            Phase::Results => {
+                if table.is_over() {
+                    return false;
+                }
+                table.new_round();
+            }
+        }
+        true
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        use Constraint::{Fill, Length};
+        let table = &self.table;
+
+        let [title_area, dealer_area, seats_area, status_area] =
+            Layout::vertical([Length(2), Length(Card::HEIGHT + 3), Fill(1), Length(1)])
+                .areas(frame.area());
+        let (title, players) = match table.shared_bankroll {
+            true => (
+                format!("Blackjack · {} boxes", table.seats.len()),
+                format!("Bankroll: {} ", self.money.amount(table.seats[0].bankroll)),
+            ),
+            false => (
+                "Blackjack · Hotseat".to_string(),
+                format!("{} players ", table.seats.len()),
+            ),
+        };
+        frame.render_widget(
+            Block::bordered()
+                .title(title)
+                .title(Line::from(players).right_aligned()),
+            title_area,
+        );
+
+        let dealer_block = Block::bordered().title("Dealer");
+        let [cards_area, value_area] = Layout::vertical([Length(Card::HEIGHT), Length(1)])
+            .areas(dealer_block.inner(dealer_area));
+        frame.render_widget(dealer_block, dealer_area);
+        let columns = Layout::horizontal(vec![Length(Card::WIDTH); table.dealer.cards().len()])
+            .spacing(2)
+            .split(cards_area);
+        // under double exposure there's no hole card to hide
+        let face_up = table.dealer.is_revealed() || table.rules.double_exposure;
+        for (index, (card, area)) in table.dealer.cards().iter().zip(columns.iter()).enumerate() {
+            if index == 0 && !face_up {
+                frame.render_widget(FaceDownCard, *area);
+            } else {
+                frame.render_widget(*card, *area);
+            }
+        }
+        if face_up {
+            frame.render_widget(
+                Line::from(format!("Value: {}", table.dealer.count_value())),
+                value_area,
+            );
+        }
+
+        let seat_areas = Layout::horizontal(vec![Fill(1); table.seats.len()])
+            .flex(Flex::Center)
+            .split(seats_area);
+        for (index, (seat, area)) in table.seats.iter().zip(seat_areas.iter()).enumerate() {
+            let turn =
+                index == table.turn && matches!(table.phase, Phase::Betting | Phase::Playing);
+            let mut block = Block::bordered().title(if turn {
+                format!("▶ {}", seat.name)
+            } else {
+                seat.name.clone()
+            });
+            if turn {
+                block = block.border_style(Style::new().fg(Color::Yellow)).bold();
+            }
+            let mut lines = vec![
+                Line::from(format!("Bankroll: {}", self.money.amount(seat.bankroll))),
+                Line::from(if seat.bet > 0 {
+                    format!("Bet: {}", self.money.amount(seat.bet))
+                } else {
+                    "Sitting out".to_string()
+                }),
+                Line::default(),
+            ];
+            if !seat.hand.cards().is_empty() {
+                let cards: Vec<_> = seat.hand.cards().iter().map(Card::short).collect();
+                lines.push(Line::from(cards.join(" ")));
+                lines.push(Line::from(format!("Value: {}", seat.hand.count_value())));
+            }
+            if let Some((result, payout)) = seat.result {
+                let line = Line::from(format!("{} {}", result.name(), self.money.signed(payout)));
+                lines.push(if payout > 0 {
+                    line.green()
+                } else if payout < 0 {
+                    line.red()
+                } else {
+                    line.yellow()
+                });
+            }
+            lines.push(Line::default());
+            lines.push(Line::from(format!(
+                "Won {}/{} hands",
+                seat.stats.wins, seat.stats.hands
+            )));
+            lines.push(Line::from(format!("Net: {:+}", seat.stats.net)));
+            frame.render_widget(Paragraph::new(lines).block(block), *area);
+        }
+
+        let name = &table.seats[table.turn].name;
+        let status = match table.phase {
+            Phase::Betting => {
+                format!(" {name} to bet: Up/Down) Bet    Enter) Place    s) Sit out    q) Quit")
+            }
+            Phase::Playing => format!(" {name} to play: 1) Hit    2) Hold    q) Quit"),
+            Phase::Dealer => " Dealer plays".to_string(),
+            Phase::Results if table.is_over() => " Everyone is out of chips. Any) Quit".to_string(),
+            Phase::Results => " Any) Next round    q) Quit".to_string(),
+        };
+        frame.render_widget(Line::from(status), status_area);
+    }
+}
+
+/// Play a hotseat game for 2 to 4 players, returning the seats as they were left.
+pub fn run(config: &Config, players: u8) -> Result<Vec<Seat>, Box<dyn std::error::Error>> {
+    if !PLAYERS.contains(&players) {
+        return Err(format!("expected 2 to 4 players, found {players}").into());
+    }
+    let names = (1..=players).map(|number| format!("Player {number}"));
+    play(config, Table::new(config, names))
+}
+
+/// Play 2 to 4 boxes at once, each bet and played in turn from one bankroll.
+pub fn run_boxes(config: &Config, boxes: u8) -> Result<Vec<Seat>, Box<dyn std::error::Error>> {
+    if !PLAYERS.contains(&boxes) {
+        return Err(format!("expected 2 to 4 boxes, found {boxes}").into());
+    }
+    play(config, Table::boxes(config, boxes))
+}
+
+fn play(config: &Config, table: Table) -> Result<Vec<Seat>, Box<dyn std::error::Error>> {
+    let mut hotseat = Hotseat {
+        table,
+        money: config.money(),
+    };
+    let (theme, plain) = (config.theme, config.plain());
+    let mut terminal = term::init()?;
+
+    let result = loop {
+        let drawn = terminal.draw(|frame| {
+            hotseat.render(frame);
+            theme.apply(frame.buffer_mut());
+            plain.apply(frame.buffer_mut());
+        });
+        if let Err(err) = drawn {
+            break Err(err);
+        }
+        if hotseat.table.phase == Phase::Dealer {
+            std::thread::sleep(config.dealer_pace());
+            hotseat.table.dealer_step();
+            continue;
+        }
+        match term::read() {
+            Ok(Event::Key(key)) if suspend::is_suspend_key(&key) => {
+                if let Err(err) = suspend::suspend(&mut terminal) {
+                    break Err(err);
+                }
+            }
+            Ok(Event::Key(key)) if keyboard::acts_on(&key) => {
+                if !hotseat.handle_key(key.code) {
+                    break Ok(());
+                }
+            }
+            Ok(_) => (),
+            Err(err) => break Err(err),
+        }
+    };
+    term::restore()?;
+    result?;
+    Ok(hotseat.table.seats)
+}
@@ -0,0 +1,97 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::{config::*, paths};
+
+/// The progressive jackpot, fed a share of every side bet and saved between sessions so
+/// it keeps growing until someone hits it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Jackpot {
+    /// chips the jackpot pays
+    pub pool: u64,
+}
+impl Default for Jackpot {
+    fn default() -> Self {
+        Self { pool: Self::SEED }
+    }
+}
+impl Jackpot {
+    const FILE: &str = "jackpot.toml";
+    /// what the house puts in a fresh jackpot
+    const SEED: u64 = 1000;
+    /// percent of every side bet that goes to the jackpot
+    const SHARE: u64 = 10;
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// The saved jackpot, or a fresh one if none has been saved.
+    pub fn load() -> Self {
+        let Some(Ok(Some(entries))) = Self::path().map(|path| read_key_values(&path)) else {
+            return Self::default();
+        };
+        let pool = entries
+            .into_iter()
+            .find(|(_, key, _)| key == "pool")
+            .and_then(|(_, _, value)| parse_number(&value).ok());
+        Self {
+            pool: pool.unwrap_or(Self::SEED),
+        }
+    }
+
+    /// Save through a temporary file, so a crash while writing can't lose the jackpot.
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, format!("pool = {}\n", self.pool))?;
+        fs::rename(temp, path)
+    }
+
+    /// Save the jackpot, if it changed since the `last` save.
+    pub fn sync(&self, last: &mut Option<Self>) -> io::Result<()> {
+        if *last != Some(*self) {
+            self.save()?;
+            *last = Some(*self);
+        }
+        Ok(())
+    }
+
+    /// Put the jackpot's share of a side bet of `stake` into it, rounded up to a chip.
+    pub fn feed(&mut self, stake: u32) {
+        self.pool += (u64::from(stake) * Self::SHARE).div_ceil(100);
+    }
+
+    /// Start the jackpot again from the seed, once it's been paid out.
+    pub fn reset(&mut self) {
+        self.pool = Self::SEED;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_pool_between_sessions() {
+        paths::use_test_dir();
+        let mut jackpot = Jackpot::default();
+        jackpot.feed(5);
+        assert_eq!(jackpot.pool, Jackpot::SEED + 1);
+        jackpot.feed(25);
+        assert_eq!(jackpot.pool, Jackpot::SEED + 4);
+
+        let mut last = None;
+        jackpot.sync(&mut last).unwrap();
+        assert_eq!(last, Some(jackpot));
+        assert_eq!(Jackpot::load(), jackpot);
+
+        jackpot.reset();
+        assert_eq!(jackpot, Jackpot::default());
+    }
+}
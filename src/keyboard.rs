@@ -0,0 +1,12 @@
+use crate::term::{self, KeyEvent, KeyEventKind};
+
+/// Whether to act on `key`: its release where the terminal reports releases, so a key
+/// held down acts once, and its press everywhere else. Keys held with Ctrl, Alt, or the
+/// like are never game keys, so Alt+H or a stray escape sequence doesn't hit.
+pub fn acts_on(key: &KeyEvent) -> bool {
+    let kind = match term::reports_releases() {
+        true => KeyEventKind::Release,
+        false => KeyEventKind::Press,
+    };
+    key.kind == kind && !key.ctrl && !key.alt
+}
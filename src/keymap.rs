@@ -0,0 +1,103 @@
+use crate::strategy::Action;
+
+/// The keys that make the moves of a hand, the number keys from 1 in the order of
+/// [`Action::MOVES`] unless the config file maps them to others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    keys: [char; 7],
+}
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            keys: ['1', '2', '3', '4', '5', '6', '7'],
+        }
+    }
+}
+impl Keymap {
+    /// Names of the moves in the config file, in the order of [`Action::MOVES`].
+    const NAMES: [&str; 7] = [
+        "hit",
+        "stand",
+        "double",
+        "surrender",
+        "insurance",
+        "no_insurance",
+        "split",
+    ];
+
+    /// Parse moves mapped to keys, like `hit:j, stand:k`. Moves left out keep their
+    /// number key, and no two moves can share a key.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        for entry in value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let Some((name, key)) = entry.split_once(':') else {
+                return Err(format!("expected `move:key`, found '{entry}'"));
+            };
+            let index = Self::NAMES
+                .iter()
+                .position(|move_name| *move_name == name.trim())
+                .ok_or_else(|| {
+                    format!(
+                        "unknown move '{}', expected one of {}",
+                        name.trim(),
+                        Self::NAMES.join(", ")
+                    )
+                })?;
+            let mut chars = key.trim().chars();
+            keymap.keys[index] = match (chars.next(), chars.next()) {
+                (Some(key), None) => key.to_ascii_lowercase(),
+                _ => return Err(format!("expected one key for {name}, found '{key}'")),
+            };
+        }
+        for (index, key) in keymap.keys.iter().enumerate() {
+            if let Some(other) = keymap.keys[..index].iter().position(|other| other == key) {
+                return Err(format!(
+                    "'{key}' is the key for both {} and {}",
+                    Self::NAMES[other],
+                    Self::NAMES[index]
+                ));
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// The move `key` makes.
+    pub fn action(&self, key: char) -> Option<Action> {
+        let index = self.keys.iter().position(|mapped| *mapped == key)?;
+        Some(Action::MOVES[index])
+    }
+
+    /// The key that makes `action`, one of [`Action::MOVES`].
+    pub fn key(&self, action: Action) -> char {
+        let index = Action::MOVES
+            .iter()
+            .position(|mapped| *mapped == action)
+            .unwrap_or_default();
+        self.keys[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_moves_to_keys() {
+        let keymap = Keymap::parse("hit:J, stand: k").unwrap();
+        assert_eq!(keymap.action('j'), Some(Action::Hit));
+        assert_eq!(keymap.action('1'), None);
+        assert_eq!(keymap.key(Action::Stand), 'k');
+        assert_eq!(keymap.key(Action::Split), '7');
+        assert_eq!(Keymap::parse(""), Ok(Keymap::default()));
+
+        let err = Keymap::parse("hit:2").unwrap_err();
+        assert_eq!(err, "'2' is the key for both hit and stand");
+        assert!(Keymap::parse("fold:f").is_err());
+        assert!(Keymap::parse("hit:jk").is_err());
+        assert!(Keymap::parse("hit").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+pub mod analysis;
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod autosave;
+pub mod betting;
+#[cfg(any(feature = "serde", feature = "tui"))]
+pub mod bot;
+pub mod challenge;
+pub mod chatter;
+#[cfg(all(feature = "serde", feature = "tui"))]
+pub mod client;
+pub mod clipboard;
+pub mod config;
+pub mod counting;
+#[cfg(feature = "tui")]
+pub mod drill;
+pub mod events;
+pub mod explain;
+pub mod fairness;
+#[cfg(feature = "tui")]
+pub mod flashcards;
+pub mod game;
+#[cfg(feature = "serde")]
+pub mod headless;
+pub mod history;
+#[cfg(feature = "tui")]
+pub mod hotseat;
+pub mod jackpot;
+#[cfg(feature = "tui")]
+pub mod keyboard;
+pub mod keymap;
+pub mod limits;
+pub mod logging;
+#[cfg(feature = "serde")]
+pub mod metrics;
+pub mod money;
+pub mod narrate;
+pub mod paths;
+pub mod plain;
+#[cfg(feature = "serde")]
+pub mod protocol;
+pub mod record;
+pub mod report;
+pub mod rewind;
+pub mod rules;
+pub mod scenario;
+#[cfg(feature = "serde")]
+pub mod server;
+pub mod sessions;
+pub mod shuffle;
+pub mod side_bets;
+pub mod simulate;
+pub mod sound;
+#[cfg(feature = "tui")]
+pub mod spoken;
+pub mod stats;
+pub mod strategy;
+pub mod strategy_file;
+#[cfg(feature = "tui")]
+pub mod suspend;
+pub mod table;
+#[cfg(feature = "tui")]
+pub mod term;
+pub mod theme;
+pub mod trainer;
+#[cfg(feature = "web")]
+pub mod web;
+pub mod widgets;
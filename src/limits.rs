@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// A session limit set in the config, or in the profile's
+/// [file](Config::PROFILE_FILE) so each player keeps their own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Loss,
+    Win,
+    Time,
+}
+impl Limit {
+    pub fn message(self, config: &Config) -> String {
+        match self {
+            Limit::Loss => format!("You've lost {} chips this session.", config.loss_limit),
+            Limit::Win => format!("You've won {} chips this session.", config.win_goal),
+            Limit::Time => format!(
+                "You've played for {} minutes.",
+                config.time_limit.as_secs() / 60
+            ),
+        }
+    }
+}
+
+/// A break forced by a limit, which can't be dismissed until it's over.
+#[derive(Clone, Copy, Debug)]
+pub struct Break {
+    pub limit: Limit,
+    pub until: Instant,
+}
+impl Break {
+    /// Time left before the break can be dismissed.
+    pub fn remaining(&self) -> Duration {
+        self.until.saturating_duration_since(Instant::now())
+    }
+}
+
+/// The limits reached this session. Each one interrupts play once, between hands.
+#[derive(Debug)]
+pub struct Limits {
+    started: Instant,
+    reached: Vec<Limit>,
+}
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            reached: Vec::new(),
+        }
+    }
+}
+impl Limits {
+    /// The first limit newly reached with the session's net result at `net`.
+    pub fn check(&mut self, config: &Config, net: i64) -> Option<Limit> {
+        let loss = config.loss_limit > 0 && -net >= i64::from(config.loss_limit);
+        let win = config.win_goal > 0 && net >= i64::from(config.win_goal);
+        let time = !config.time_limit.is_zero() && self.started.elapsed() >= config.time_limit;
+        let limit = [(Limit::Loss, loss), (Limit::Win, win), (Limit::Time, time)]
+            .into_iter()
+            .find(|(limit, reached)| *reached && !self.reached.contains(limit))?
+            .0;
+        self.reached.push(limit);
+        Some(limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_limit_interrupts_once() {
+        let config = Config {
+            loss_limit: 100,
+            win_goal: 50,
+            ..Config::default()
+        };
+        let mut limits = Limits::default();
+        assert_eq!(limits.check(&config, -99), None);
+        assert_eq!(limits.check(&config, -100), Some(Limit::Loss));
+        assert_eq!(limits.check(&config, -150), None);
+        assert_eq!(limits.check(&config, 50), Some(Limit::Win));
+        assert_eq!(limits.check(&config, 80), None);
+    }
+
+    #[test]
+    fn time_runs_from_the_start_of_the_session() {
+        let config = Config {
+            time_limit: Duration::from_secs(60 * 60),
+            ..Config::default()
+        };
+        let mut limits = Limits::default();
+        assert_eq!(limits.check(&config, 0), None);
+        limits.started -= config.time_limit;
+        assert_eq!(limits.check(&config, 0), Some(Limit::Time));
+        assert_eq!(limits.check(&config, 0), None);
+    }
+
+    #[test]
+    fn zero_is_no_limit() {
+        let mut limits = Limits {
+            started: Instant::now() - Duration::from_secs(24 * 60 * 60),
+            reached: Vec::new(),
+        };
+        assert_eq!(limits.check(&Config::default(), -1_000_000), None);
+        assert_eq!(limits.check(&Config::default(), 1_000_000), None);
+    }
+}
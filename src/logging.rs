@@ -0,0 +1,44 @@
+use std::{
+    fs::{self, OpenOptions},
+    path::Path,
+    sync::Mutex,
+};
+
+use tracing_subscriber::EnvFilter;
+
+use crate::paths;
+
+/// Name of the log written to the log directory when only `RUST_LOG` is set.
+const FILE: &str = "blackjack-tui.log";
+
+/// Log what the engine does to `path`, or to the log directory when only `RUST_LOG` is
+/// set, filtered by `RUST_LOG` and everything from the game by default. Nothing is logged
+/// without either, and never to the terminal, which the game is drawn on.
+pub fn init(path: Option<&Path>) -> Result<(), String> {
+    let filter = std::env::var("RUST_LOG").ok();
+    let path = match (path, &filter) {
+        (Some(path), _) => path.to_path_buf(),
+        (None, Some(_)) => paths::log_dir()
+            .ok_or("no log directory to log to, use --log-file")?
+            .join(FILE),
+        (None, None) => return Ok(()),
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+    let filter = match filter {
+        Some(filter) => EnvFilter::try_new(filter).map_err(|err| format!("RUST_LOG: {err}"))?,
+        None => EnvFilter::new("blackjack_tui=debug"),
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .init();
+    Ok(())
+}
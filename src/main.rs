@@ -1,168 +1,410 @@
-use std::{cell::RefCell, cmp::Ordering, rc::Rc};
-
-use ratatui::{
-    crossterm::{
-        event::{self, Event, KeyCode, KeyEventKind},
-        style::Stylize,
-    },
-    layout::Flex,
-    prelude::{Constraint, Frame, Layout, Line},
-    widgets::{Block, Clear, List, ListItem},
+#[cfg(feature = "serde")]
+use std::net::{Ipv4Addr, SocketAddr};
+use std::{
+    io::{Stderr, Write},
+    path::{Path, PathBuf},
 };
 
-mod widgets;
-use widgets::*;
+mod cli;
+mod ssh;
+
+use ratatui::Terminal;
+
+use blackjack_tui::{
+    app, betting, challenge, config, drill, flashcards, history, hotseat, logging, money::Money,
+    paths, record::Recorder, report, scenario, sessions, shuffle, simulate, spoken, stats,
+    table::Seat, term, widgets::Shoe,
+};
+#[cfg(feature = "serde")]
+use blackjack_tui::{bot, client, headless, server};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    run_as_tui()?;
-    Ok(())
-}
+    let mut args = cli::Args::parse()?;
+    // a guest's files go when they leave
+    let _guest = match args.command {
+        cli::Command::Ssh => ssh::Login::read()?.apply(&mut args),
+        _ => None,
+    };
+    if let Some(dir) = args.data_dir.clone() {
+        paths::set_data_dir(dir);
+    }
+    if let Some(name) = &args.profile {
+        paths::set_profile(name)?;
+    }
+    logging::init(args.log_file.as_deref())?;
+    let config_file = args
+        .config
+        .clone()
+        .or_else(|| config::env_var("BLACKJACK_CONFIG").map(PathBuf::from));
+    let config_path = config_file.clone().or_else(paths::config_file);
+    let config = load_config(&args, config_file)?;
+
+    // a game played to a hand limit always ends with its summary
+    let summarize = args.summary || config.hand_limit.is_some();
+    let money = config.money();
 
-fn run_as_tui() -> std::io::Result<()> {
-    let mut terminal = ratatui::init();
-    let mut game_state = GameState::PlayingHand;
-    let mut deck = Deck::new();
-    let player_hand = Rc::new(RefCell::new(deck.new_hand::<Player>()));
-    let dealer_hand = Rc::new(RefCell::new(deck.new_hand::<Dealer>()));
-    let deck = Rc::new(RefCell::new(deck));
-
-    loop {
-        let _player_hand = player_hand.clone();
-        let _dealer_hand = dealer_hand.clone();
-        let deck = deck.clone();
-        terminal.draw(move |frame: &mut Frame| {
-            use Constraint::{Fill, Length, Min};
-
-            let vertical = Layout::vertical([Length(2), Min(0)]);
-            let [title_area, main_area] = vertical.areas(frame.area());
-            let horizontal = Layout::horizontal([Fill(1); 2]);
-            let [left_area, right_area] = horizontal.areas(main_area);
-
-            frame.render_widget(Block::bordered().title("Blackjack"), title_area);
-            frame.render_widget(&*_player_hand.borrow(), left_area);
-            frame.render_widget(&*_dealer_hand.borrow(), right_area);
-
-            match game_state {
-                GameState::PlayingHand => (),
-                GameState::HandScoreScreen(hand_result) => {
-                    let player_hand = _player_hand.borrow();
-                    let dealer_hand = _dealer_hand.borrow();
-
-                    let frame_area = frame.area();
-                    let block = Block::bordered()
-                        .title("Hand Result")
-                        .title_bottom(Line::from("Any) New Hand").left_aligned())
-                        .title_bottom(Line::from("q) Quit").right_aligned());
-                    let vertical =
-                        Layout::vertical([Constraint::Percentage(20)]).flex(Flex::Center);
-                    let horizontal =
-                        Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::Center);
-                    let [area] = vertical.areas(frame_area);
-                    let [area] = horizontal.areas(area);
-
-                    frame.render_widget(Clear, area);
-
-                    let list_items: [ListItem; 2] = [
-                        Line::from(
-                            match hand_result {
-                                HandResult::PlayerWin => format!("{hand_result:?}").green(),
-                                HandResult::DealerWin => format!("{hand_result:?}").red(),
-                                HandResult::Push => format!("{hand_result:?}").yellow(),
-                                HandResult::Bust => format!("{hand_result:?}").red(),
-                            }
-                            .to_string(),
-                        )
-                        .into(),
-                        Line::from(format!(
-                            "You: {} Dealer: {}",
-                            player_hand.count_value(),
-                            dealer_hand.count_value()
-                        ))
-                        .into(),
-                    ];
-
-                    frame.render_widget(List::new(list_items).block(block), area);
+    if args.command == cli::Command::Drill {
+        let session = drill::run(&config)?;
+        if summarize {
+            println!("Count drill: {}.", session.summary());
+        }
+    } else if args.command == cli::Command::Flashcards {
+        let (correct, answered) = flashcards::run(&config)?;
+        if summarize {
+            println!("Answered {answered} flashcards, {correct} correctly.");
+        }
+    } else if matches!(
+        args.command,
+        cli::Command::Bot | cli::Command::Host | cli::Command::Join
+    ) {
+        #[cfg(not(feature = "serde"))]
+        return Err("network play needs a build with the serde feature".into());
+        #[cfg(feature = "serde")]
+        match args.command {
+            cli::Command::Bot => match &args.socket {
+                Some(path) => bot::listen_unix(&config, path)?,
+                None => bot::listen_tcp(&config, args.port.unwrap_or(bot::PORT))?,
+            },
+            cli::Command::Host => {
+                let addr = args.metrics_addr.unwrap_or(Ipv4Addr::LOCALHOST.into());
+                server::host(
+                    &config,
+                    args.port.unwrap_or(server::PORT),
+                    args.metrics_port.map(|port| SocketAddr::new(addr, port)),
+                )?
+            }
+            _ => {
+                let name = args
+                    .name
+                    .or_else(|| std::env::var("USER").ok())
+                    .unwrap_or_else(|| "Player".to_string());
+                // a table hosted on this machine, when none is given
+                let address = args
+                    .address
+                    .unwrap_or_else(|| format!("127.0.0.1:{}", args.port.unwrap_or(server::PORT)));
+                client::join(&config, &address, &name, args.spectate)?;
+            }
+        }
+    } else if args.command == cli::Command::Stats {
+        match args.stats_action {
+            Some(cli::StatsAction::Export) => {
+                let format = match (&args.format, &args.output) {
+                    (Some(format), _) => report::Format::parse(format)?,
+                    (None, Some(path)) => report::Format::from_path(path)?,
+                    (None, None) => report::Format::Csv,
+                };
+                let path = args
+                    .output
+                    .unwrap_or_else(|| format!("hands.{}", format.extension()).into());
+                let mut rounds = history::History::load()?;
+                // just the hands dealt under the table rules asked for
+                if args.rules.is_some() {
+                    let rules = config.rules.to_string();
+                    rounds.retain(|round| round.rules == rules);
                 }
+                report::write_rounds(&path, format, &rounds)?;
+                println!("Wrote {} hands to {}.", rounds.len(), path.display());
+            }
+            Some(cli::StatsAction::Reset) => {
+                stats::GameStats::reset()?;
+                println!("Lifetime statistics reset.");
             }
-        })?;
-
-        if let Event::Key(key) = event::read()? {
-            let mut player_hand = player_hand.borrow_mut();
-            let mut dealer_hand = dealer_hand.borrow_mut();
-            let mut deck = deck.borrow_mut();
-            if matches!(key.kind, KeyEventKind::Release) {
-                match game_state {
-                    GameState::PlayingHand => match key.code {
-                        KeyCode::Char(c) => match c {
-                            '1' => {
-                                player_hand.hit(&mut deck);
-                                dealer_hand.do_dealer_action(&mut deck);
-                                check_hand(&player_hand, &mut dealer_hand, &mut game_state);
-                            }
-                            '2' => {
-                                player_hand.hold();
-                                while dealer_hand.is_active() && !dealer_hand.is_bust() {
-                                    dealer_hand.do_dealer_action(&mut deck);
-                                    check_hand(&player_hand, &mut dealer_hand, &mut game_state);
-                                }
-                                check_hand(&player_hand, &mut dealer_hand, &mut game_state);
-                            }
-                            'q' => break,
-                            _ => (),
-                        },
-                        KeyCode::Esc => break,
-                        _ => (),
-                    },
-                    GameState::HandScoreScreen(_) => match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        _ => {
-                            *player_hand = deck.new_hand::<Player>();
-                            *dealer_hand = deck.new_hand::<Dealer>();
-                            game_state = GameState::PlayingHand;
-                        }
-                    },
+            None => print_stats(&config.money(), args.json)?,
+        }
+    } else if args.command == cli::Command::Shoe {
+        let shoe = Shoe::for_table(&config.rules, config.shuffle.clone());
+        let path = args.output.unwrap_or_else(|| "shoe.txt".into());
+        shuffle::write_shoe(&path, shoe.shuffled())?;
+        println!("Wrote the shoe to {}.", path.display());
+    } else if args.command == cli::Command::Simulate {
+        let strategy =
+            simulate::Strategy::parse(args.strategy.as_deref().unwrap_or("basic"), &config)?;
+        if let Some(count) = args.sessions {
+            if args.output.is_some() || !args.sweeps.is_empty() {
+                return Err("--output and --sweep can't be used with --sessions".into());
+            }
+            let plan = sessions::SessionPlan {
+                sessions: count,
+                hours: args.session_hours.unwrap_or(sessions::SessionPlan::HOURS),
+                hands_per_hour: args
+                    .hands_per_hour
+                    .unwrap_or(sessions::SessionPlan::HANDS_PER_HOUR),
+            };
+            let betting = match args.betting.as_deref() {
+                None => Vec::new(),
+                Some("all") => betting::BettingSystem::ALL.to_vec(),
+                Some(names) => names
+                    .split(',')
+                    .map(|name| betting::BettingSystem::parse(name.trim()))
+                    .collect::<Result<_, _>>()?,
+            };
+            sessions::run(&config, plan, &strategy, &betting)?;
+            return Ok(());
+        }
+        let output = simulate::Output {
+            path: args.output,
+            per_hand: args.per_hand,
+        };
+        simulate::run(
+            &config,
+            args.hands.unwrap_or(1_000_000),
+            strategy,
+            &args.sweeps,
+            output,
+        )?;
+    } else if args.no_tui {
+        #[cfg(not(feature = "serde"))]
+        return Err("--no-tui needs a build with the serde feature".into());
+        #[cfg(feature = "serde")]
+        {
+            let json = match args.format.as_deref() {
+                None | Some("text") => false,
+                Some("json") => true,
+                Some(format) => {
+                    return Err(format!("unknown format '{format}', expected text or json").into())
                 }
+            };
+            let stats = headless::run(&config, json)?;
+            if summarize {
+                let session = stats::SessionSummary {
+                    stats,
+                    graded: None,
+                };
+                // JSON lines on stdout would be broken up by the summary
+                print_summary(&session.paragraph(&money), json);
+            }
+        }
+    } else if let Some(players) = args.players {
+        let seats = hotseat::run(&config, players)?;
+        if summarize {
+            println!("{}", seat_summaries(&seats, &money));
+        }
+    } else if let Some(boxes) = args.boxes {
+        let seats = hotseat::run_boxes(&config, boxes)?;
+        if summarize {
+            println!("{}", seat_summaries(&seats, &money));
+        }
+    } else if args.screen_reader {
+        let session = spoken::run(&config)?;
+        if summarize {
+            println!("{}", session.paragraph(&money));
+        }
+    } else {
+        let app = app::App::new(&config);
+        // nobody could dismiss the notice a reload shows during a demo
+        let app = match config_path.filter(|_| !config.autoplay) {
+            Some(path) => {
+                app.watch_config(path, |path| load_config(&args, Some(path.to_path_buf())))
             }
+            None => app,
+        };
+        #[cfg(not(feature = "serde"))]
+        if args.json_events.is_some() {
+            return Err("--json-events needs a build with the serde feature".into());
+        }
+        #[cfg(feature = "serde")]
+        let app = match &args.json_events {
+            Some(path) => app.with_events(blackjack_tui::events::EventLog::open(path)?),
+            None => app,
+        };
+        let events_on_stdout = args.json_events.as_deref() == Some(std::path::Path::new("-"));
+        let session = if events_on_stdout {
+            // stdout carries the events, so the game is drawn on stderr
+            let mut terminal = init_on_stderr()?;
+            let result = run_app(app, &mut terminal, args.record.as_deref());
+            restore_stderr()?;
+            result?
+        } else {
+            let mut terminal = term::init()?;
+            let result = run_app(app, &mut terminal, args.record.as_deref());
+            term::restore()?;
+            result?
+        };
+        if summarize {
+            print_summary(&session.paragraph(&money), events_on_stdout);
         }
     }
-    ratatui::restore();
     Ok(())
 }
 
-fn check_hand(
-    player_hand: &Hand<Player>,
-    dealer_hand: &mut Hand<Dealer>,
-    game_state: &mut GameState,
-) {
-    if !player_hand.is_bust() && !player_hand.is_active() && !dealer_hand.is_active() {
-        let player_value = player_hand.count_value();
-        let dealer_value = dealer_hand.count_value();
-        *game_state = GameState::HandScoreScreen(match player_value.cmp(&dealer_value) {
-            Ordering::Less => HandResult::DealerWin,
-            Ordering::Equal => HandResult::Push,
-            Ordering::Greater => HandResult::PlayerWin,
-        });
-    } else if player_hand.is_bust() {
-        *game_state = GameState::HandScoreScreen(HandResult::Bust);
-    } else if dealer_hand.is_bust() {
-        *game_state = GameState::HandScoreScreen(HandResult::PlayerWin);
-    }
-
-    if matches!(game_state, GameState::HandScoreScreen(_)) {
-        dealer_hand.reveal();
+/// The settings from the config `file`, or the default one, overridden by the profile,
+/// the environment, and the flags in `args`, in that order. A watched config file is
+/// loaded through here again each time it changes.
+fn load_config(args: &cli::Args, file: Option<PathBuf>) -> Result<config::Config, String> {
+    let mut config = config::Config::load(file)?;
+    config.apply_profile()?;
+    config.apply_env()?;
+    config.ascii |= args.ascii;
+    config.no_color |= args.no_color;
+    config.autoplay |= args.autoplay;
+    config.reduced_motion |= args.reduced_motion;
+    config.mute |= args.mute;
+    config.trainer |= args.trainer;
+    config.cheat_mode |= args.cheat;
+    config.show_count |= args.show_count;
+    config.count_quiz |= args.count_quiz;
+    // the quiz only makes sense while the count is hidden
+    config.show_count &= !config.count_quiz;
+    if let Some(name) = &args.counting_system {
+        config.counting_system = config::parse_counting_system(name)?;
+    }
+    if let Some(rules) = &args.rules {
+        config.rules = config::parse_rules(rules)?;
+    }
+    if let Some(penetration) = &args.penetration {
+        config.rules.set("penetration", penetration)?;
+    }
+    if let Some(shuffle) = &args.shuffle {
+        config.shuffle = shuffle::Shuffler::parse(shuffle)?;
+    }
+    if let Some(scenario) = &args.scenario {
+        config.scenario = Some(scenario::Scenario::parse(scenario)?);
+    }
+    if let Some(name) = &args.challenge {
+        if config.autoplay {
+            return Err("a challenge has to be played, not autoplayed".to_string());
+        }
+        let challenge = challenge::Challenge::parse(name)?;
+        challenge.apply(&mut config);
+        config.challenge = Some(challenge);
+    }
+    // a stacked shoe is dealt with as many decks as it holds
+    if let shuffle::Shuffler::Stacked(cards) = &config.shuffle {
+        let decks = (cards.len() / 52).to_string();
+        config.rules.set("decks", &decks)?;
+    }
+
+    if args.hands == Some(0) {
+        return Err("--hands expects at least one hand".to_string());
+    }
+    config.hand_limit = args.hands;
+    if let Some(pace) = args.drill_pace {
+        config.drill_pace = std::time::Duration::from_millis(pace);
+    }
+    if let Some(pace) = args.autoplay_pace {
+        config.autoplay_pace = std::time::Duration::from_millis(pace);
+    }
+    if let Some(secs) = args.turn_timer {
+        config.turn_timer = std::time::Duration::from_secs(secs);
+    }
+    Ok(config)
+}
+
+/// Print how a session went once the terminal is restored, on stderr when stdout
+/// carries the game's JSON.
+fn print_summary(summary: &str, on_stderr: bool) {
+    match on_stderr {
+        true => eprintln!("{summary}"),
+        false => println!("{summary}"),
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum GameState {
-    PlayingHand,
-    HandScoreScreen(HandResult),
+/// How each seat at a shared table did, one sentence after another.
+fn seat_summaries(seats: &[Seat], money: &Money) -> String {
+    let seats: Vec<_> = seats
+        .iter()
+        .map(|seat| format!("{}: {}", seat.name, seat.stats.summary(money)))
+        .collect();
+    seats.join(" ")
 }
 
-#[derive(Clone, Copy, Debug)]
-enum HandResult {
-    PlayerWin,
-    DealerWin,
-    Push,
-    Bust,
+/// Print the saved lifetime statistics and those of the last session in the history.
+fn print_stats(money: &Money, json: bool) -> std::io::Result<()> {
+    let lifetime = stats::GameStats::load().map_err(std::io::Error::other)?;
+    let session = stats::GameStats::last_session(&history::History::load()?);
+    if json {
+        #[cfg(not(feature = "serde"))]
+        return Err(std::io::Error::other(
+            "--json needs a build with the serde feature",
+        ));
+        #[cfg(feature = "serde")]
+        {
+            #[derive(serde::Serialize)]
+            struct Stats<'a> {
+                profile: Option<&'a str>,
+                lifetime: stats::StatsReport<'a>,
+                last_session: Option<stats::StatsReport<'a>>,
+            }
+            let stats = Stats {
+                profile: paths::profile(),
+                lifetime: lifetime.report(),
+                last_session: session.as_ref().map(stats::GameStats::report),
+            };
+            println!("{}", serde_json::to_string(&stats)?);
+            return Ok(());
+        }
+    }
+    if let Some(name) = paths::profile() {
+        println!("Profile: {name}\n");
+    }
+    println!("Lifetime\n  Sessions: {}", lifetime.sessions);
+    for line in lifetime.describe(money) {
+        println!("  {line}");
+    }
+    if let Some(session) = session {
+        println!("\nLast session");
+        for line in session.describe(money) {
+            println!("  {line}");
+        }
+    }
+    Ok(())
+}
+
+/// Play `app` on `terminal`, recording it to the cast file at `record` if given.
+fn run_app<W: Write>(
+    app: app::App,
+    terminal: &mut Terminal<term::Backend<W>>,
+    record: Option<&Path>,
+) -> std::io::Result<stats::SessionSummary> {
+    let Some(path) = record else {
+        return app.run(terminal);
+    };
+    let recorder = Recorder::create(path, terminal.size()?, terminal.backend_mut())?;
+    app.run(&mut Terminal::new(term::Backend::new(recorder))?)
+}
+
+/// Set up the terminal as `ratatui::init` does, but drawing on stderr.
+fn init_on_stderr() -> std::io::Result<Terminal<term::Backend<Stderr>>> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_stderr();
+        hook(info);
+    }));
+    term::enter(&mut std::io::stderr())?;
+    Terminal::new(term::Backend::new(std::io::stderr()))
+}
+
+fn restore_stderr() -> std::io::Result<()> {
+    term::leave(&mut std::io::stderr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_override_the_config_file_on_every_load() {
+        let dir = std::env::temp_dir().join(format!("blackjack-tui-main-{}", std::process::id()));
+        paths::set_data_dir(dir.clone());
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.toml");
+        std::fs::write(&file, "mute = false\nvolume = 20\n").unwrap();
+        let args = cli::Args {
+            mute: true,
+            autoplay_pace: Some(100),
+            ..cli::Args::default()
+        };
+
+        let config = load_config(&args, Some(file.clone())).unwrap();
+        assert!(config.mute);
+        assert_eq!(config.volume, 20);
+
+        std::fs::write(&file, "autoplay_pace_ms = 900\ntheme = felt\n").unwrap();
+        let mut live = config.clone();
+        live.reload(load_config(&args, Some(file)).unwrap());
+        assert!(live.mute);
+        assert_eq!(live.autoplay_pace, std::time::Duration::from_millis(100));
+        assert_eq!(live.theme, blackjack_tui::theme::Theme::Felt);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,260 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Write as _},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::table::Table;
+
+/// How far back rounds a minute are averaged over.
+const RATE_WINDOW: Duration = Duration::from_secs(300);
+/// How long a scraper has to send its whole request, and to read the answer.
+const REQUEST_TIME: Duration = Duration::from_secs(5);
+/// Most bytes of a request read, headers and all.
+const REQUEST_LIMIT: u64 = 8192;
+
+/// Counts kept by a hosted table for monitoring, served as Prometheus metrics:
+///
+/// ```text
+/// # HELP blackjack_rounds_total Rounds dealt.
+/// # TYPE blackjack_rounds_total counter
+/// blackjack_rounds_total 212
+/// ```
+#[derive(Debug)]
+pub struct Metrics {
+    started: Instant,
+    rounds: u64,
+    /// player hands dealt and settled
+    hands: u64,
+    /// chips bet on those hands
+    wagered: u64,
+    /// chips players won
+    paid: u64,
+    /// chips players lost
+    collected: u64,
+    /// when each round in the last [`RATE_WINDOW`] was settled
+    recent: VecDeque<Instant>,
+}
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            rounds: 0,
+            hands: 0,
+            wagered: 0,
+            paid: 0,
+            collected: 0,
+            recent: VecDeque::new(),
+        }
+    }
+}
+impl Metrics {
+    /// Count the round `table` has just settled.
+    pub fn record_round(&mut self, table: &Table) {
+        self.rounds += 1;
+        for seat in &table.seats {
+            let Some((_, payout)) = seat.result else {
+                continue;
+            };
+            self.hands += 1;
+            self.wagered += u64::from(seat.bet);
+            match payout >= 0 {
+                true => self.paid += payout.unsigned_abs(),
+                false => self.collected += payout.unsigned_abs(),
+            }
+        }
+        self.recent.push_back(Instant::now());
+    }
+
+    /// Rounds settled a minute over the last five minutes, or since the table opened if
+    /// that's sooner.
+    fn rounds_per_minute(&mut self) -> f64 {
+        while self
+            .recent
+            .front()
+            .is_some_and(|at| at.elapsed() > RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+        let window = self.started.elapsed().min(RATE_WINDOW).as_secs_f64();
+        match window > 0.0 {
+            true => self.recent.len() as f64 * 60.0 / window,
+            false => 0.0,
+        }
+    }
+
+    /// The metrics in the Prometheus text format, with `players` seated and `spectators`
+    /// watching.
+    pub fn render(&mut self, players: usize, spectators: usize) -> String {
+        let mut text = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: &dyn Display| {
+            let _ = writeln!(text, "# HELP blackjack_{name} {help}");
+            let _ = writeln!(text, "# TYPE blackjack_{name} {kind}");
+            let _ = writeln!(text, "blackjack_{name} {value}");
+        };
+        metric("rounds_total", "counter", "Rounds dealt.", &self.rounds);
+        metric("hands_total", "counter", "Player hands dealt.", &self.hands);
+        let rate = self.rounds_per_minute();
+        metric(
+            "rounds_per_minute",
+            "gauge",
+            "Rounds dealt a minute over the last five minutes.",
+            &format!("{rate:.2}"),
+        );
+        metric(
+            "players",
+            "gauge",
+            "Players connected with a seat.",
+            &players,
+        );
+        metric(
+            "spectators",
+            "gauge",
+            "Connections watching without a seat.",
+            &spectators,
+        );
+        metric(
+            "wagered_chips_total",
+            "counter",
+            "Chips bet on hands dealt.",
+            &self.wagered,
+        );
+        metric(
+            "paid_chips_total",
+            "counter",
+            "Chips paid out to players on winning hands.",
+            &self.paid,
+        );
+        metric(
+            "collected_chips_total",
+            "counter",
+            "Chips the house took from players on losing hands.",
+            &self.collected,
+        );
+        text
+    }
+}
+
+/// Answer HTTP requests for `/metrics` on `listener` with what `scrape` returns, each on
+/// a thread of its own so a slow scraper holds up no one else.
+pub fn serve(listener: TcpListener, scrape: impl Fn() -> Option<String> + Clone + Send + 'static) {
+    for stream in listener.incoming().flatten() {
+        let scrape = scrape.clone();
+        thread::spawn(move || {
+            let _ = answer(&stream, scrape);
+        });
+    }
+}
+
+/// Answer one request, for the metrics `scrape` returns, or with a 503 if it can't.
+fn answer(stream: &TcpStream, scrape: impl Fn() -> Option<String>) -> io::Result<()> {
+    stream.set_write_timeout(Some(REQUEST_TIME))?;
+    let path = request_path(stream)?;
+    if path != "/metrics" {
+        return respond(stream, "404 Not Found", "Metrics are at /metrics.\n");
+    }
+    match scrape() {
+        Some(text) => respond(stream, "200 OK", &text),
+        None => respond(stream, "503 Service Unavailable", "The table is closed.\n"),
+    }
+}
+
+/// The path asked for in a request like `GET /metrics HTTP/1.1`, read up to the blank
+/// line ending its headers, which have to come within [`REQUEST_TIME`] and
+/// [`REQUEST_LIMIT`].
+fn request_path(stream: &TcpStream) -> io::Result<String> {
+    let deadline = Deadline {
+        stream,
+        at: Instant::now() + REQUEST_TIME,
+    };
+    let mut lines = BufReader::new(deadline).take(REQUEST_LIMIT).lines();
+    let request = lines.next().transpose()?.unwrap_or_default();
+    let mut ended = false;
+    for line in lines {
+        if line?.is_empty() {
+            ended = true;
+            break;
+        }
+    }
+    if !ended {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the request's headers never ended",
+        ));
+    }
+    let path = request.split_whitespace().nth(1).unwrap_or_default();
+    // a query string changes nothing
+    Ok(path.split('?').next().unwrap_or_default().to_string())
+}
+
+/// Reads from a connection that has to be done by `at`, however slowly it trickles in.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    at: Instant,
+}
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.at.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+fn respond(mut stream: &TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What [`request_path`] makes of `request`.
+    fn path_of(request: &[u8]) -> io::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let mut client = TcpStream::connect(listener.local_addr()?)?;
+        let (stream, _) = listener.accept()?;
+        client.write_all(request)?;
+        client.shutdown(std::net::Shutdown::Write)?;
+        request_path(&stream)
+    }
+
+    #[test]
+    fn reads_the_path_asked_for() {
+        let path = path_of(b"GET /metrics?x=1 HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+        assert_eq!(path, "/metrics");
+    }
+
+    #[test]
+    fn refuses_requests_too_long_or_cut_short() {
+        let mut long = b"GET /metrics HTTP/1.1\r\nX: ".to_vec();
+        long.resize(REQUEST_LIMIT as usize * 2, b'a');
+        long.extend(b"\r\n\r\n");
+        assert!(path_of(&long).is_err());
+        assert!(path_of(b"GET /metrics HTTP/1.1\r\n").is_err());
+    }
+
+    #[test]
+    fn renders_gauges_of_who_is_connected() {
+        let mut metrics = Metrics::default();
+        let text = metrics.render(2, 1);
+        assert!(text.contains("blackjack_players 2\n"));
+        assert!(text.contains("blackjack_spectators 1\n"));
+        assert!(text.contains("blackjack_rounds_total 0\n"));
+    }
+}
@@ -0,0 +1,136 @@
+/// What chip amounts are shown as.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Currency {
+    /// just the number
+    #[default]
+    Plain,
+    /// the number followed by `units`
+    Units,
+    /// the number after a symbol, like `$`
+    Symbol(String),
+}
+impl Currency {
+    /// Parse `none` for plain numbers, `units`, or any other text as a symbol.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "" => Err("expected none, units, or a currency symbol".to_string()),
+            "none" => Ok(Currency::Plain),
+            "units" => Ok(Currency::Units),
+            symbol => Ok(Currency::Symbol(symbol.to_string())),
+        }
+    }
+}
+
+/// How chip amounts are written on screen: grouped in thousands, with the currency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Money {
+    pub currency: Currency,
+    /// between groups of thousands, if they're grouped
+    pub separator: Option<char>,
+}
+impl Default for Money {
+    fn default() -> Self {
+        Self {
+            currency: Currency::Plain,
+            separator: locale_separator(),
+        }
+    }
+}
+impl Money {
+    /// An amount of chips, like `$1,250` or `-40 units`.
+    pub fn amount(&self, chips: impl Into<i64>) -> String {
+        let chips = chips.into();
+        let sign = if chips < 0 { "-" } else { "" };
+        self.with_sign(sign, chips.unsigned_abs())
+    }
+
+    /// An amount won or lost, always signed, like `+$1,250`.
+    pub fn signed(&self, chips: impl Into<i64>) -> String {
+        let chips = chips.into();
+        let sign = if chips < 0 { "-" } else { "+" };
+        self.with_sign(sign, chips.unsigned_abs())
+    }
+
+    fn with_sign(&self, sign: &str, chips: u64) -> String {
+        let digits = chips.to_string();
+        let mut number = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            let left = digits.len() - index;
+            if index > 0 && left.is_multiple_of(3) {
+                number.extend(self.separator);
+            }
+            number.push(digit);
+        }
+        match &self.currency {
+            Currency::Plain => format!("{sign}{number}"),
+            Currency::Units => format!("{sign}{number} units"),
+            Currency::Symbol(symbol) => format!("{sign}{symbol}{number}"),
+        }
+    }
+}
+
+/// Parse the thousands separator: `none`, `locale`, `space`, or a single character.
+pub fn parse_separator(value: &str) -> Result<Option<char>, String> {
+    match value {
+        "none" => Ok(None),
+        "locale" => Ok(locale_separator()),
+        "space" => Ok(Some(' ')),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(separator), None) => Ok(Some(separator)),
+                _ => Err(format!(
+                    "expected none, locale, space, or one character, found '{value}'"
+                )),
+            }
+        }
+    }
+}
+
+/// The thousands separator of the locale numbers are formatted in, going by the
+/// language in `LC_ALL`, `LC_NUMERIC`, or `LANG`, and a comma when none is set.
+fn locale_separator() -> Option<char> {
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default();
+    let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+    match language {
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" => Some('.'),
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" => Some(' '),
+        _ => Some(','),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_amounts_in_the_currency() {
+        let dollars = Money {
+            currency: Currency::Symbol("$".to_string()),
+            separator: Some(','),
+        };
+        assert_eq!(dollars.amount(1250), "$1,250");
+        assert_eq!(dollars.signed(1_000_000), "+$1,000,000");
+        assert_eq!(dollars.amount(-40), "-$40");
+        let units = Money {
+            currency: Currency::Units,
+            separator: None,
+        };
+        assert_eq!(units.signed(-2500), "-2500 units");
+    }
+
+    #[test]
+    fn parses_currencies_and_separators() {
+        assert_eq!(Currency::parse("none"), Ok(Currency::Plain));
+        assert_eq!(Currency::parse("€"), Ok(Currency::Symbol("€".to_string())));
+        assert!(Currency::parse("").is_err());
+        assert_eq!(parse_separator("space"), Ok(Some(' ')));
+        assert_eq!(parse_separator("'"), Ok(Some('\'')));
+        assert_eq!(parse_separator("none"), Ok(None));
+        assert!(parse_separator("..").is_err());
+    }
+}
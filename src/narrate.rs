@@ -0,0 +1,234 @@
+use crate::{game::*, widgets::*};
+
+/// Turns game state changes into plain sentences for screen readers.
+#[derive(Debug, Default)]
+pub struct Narrator {
+    player_seen: usize,
+    /// hands the player had, once a pair is split
+    hands_seen: usize,
+    /// split hands already played out
+    split_seen: usize,
+    dealer_seen: usize,
+    dealer_revealed: bool,
+    insurance_told: bool,
+    result_announced: bool,
+}
+impl Narrator {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Describe everything that happened since the last call.
+    pub fn narrate(&mut self, game: &Game) -> Vec<String> {
+        let mut lines = Vec::new();
+        let player_hand = &game.player_hand;
+        let dealer_hand = &game.dealer_hand;
+        if matches!(game.state, GameState::Betting) {
+            return lines;
+        }
+
+        if self.player_seen == 0 {
+            let [first, second] = [player_hand.cards()[0], player_hand.cards()[1]];
+            lines.push(format!(
+                "You are dealt {} and {}, total {}.",
+                card_name(first),
+                card_name(second),
+                number_words(player_hand.count_value())
+            ));
+            self.player_seen = 2;
+            self.hands_seen = 1;
+        }
+        if !self.insurance_told {
+            match game.insurance {
+                Insurance::Taken { stake } => {
+                    lines.push(format!("You took insurance for {stake}."))
+                }
+                Insurance::Declined => lines.push("You turned down insurance.".to_string()),
+                Insurance::Unoffered | Insurance::Offered => (),
+            }
+            self.insurance_told = !matches!(game.insurance, Insurance::Offered);
+        }
+        let hands = 1 + game.split_hands.len() + game.split_cards.len();
+        if hands > self.hands_seen {
+            lines.push(format!(
+                "You split into {} hands.",
+                number_words(hands as u8)
+            ));
+        }
+        // each split hand is dealt its second card as it comes into play
+        if hands > self.hands_seen || game.split_hands.len() > self.split_seen {
+            for split in &game.split_hands[self.split_seen..] {
+                lines.push(format!(
+                    "That hand ends on {}.",
+                    number_words(split.hand.count_value())
+                ));
+            }
+            let [first, second] = [player_hand.cards()[0], player_hand.cards()[1]];
+            lines.push(format!(
+                "Your next hand is {} and {}, total {}.",
+                card_name(first),
+                card_name(second),
+                number_words(player_hand.count_value())
+            ));
+            self.player_seen = 2;
+            self.hands_seen = hands;
+            self.split_seen = game.split_hands.len();
+        }
+        for card in &player_hand.cards()[self.player_seen..] {
+            lines.push(match game.doubled {
+                true => format!(
+                    "You doubled to {} and drew {}, total {}.",
+                    game.bet * 2,
+                    card_name(*card),
+                    number_words(player_hand.count_value())
+                ),
+                false => format!(
+                    "You drew {}, total {}.",
+                    card_name(*card),
+                    number_words(player_hand.count_value())
+                ),
+            });
+        }
+        self.player_seen = player_hand.cards().len();
+
+        for settled in game.side_results.iter().filter(|_| self.dealer_seen == 0) {
+            lines.push(match &settled.hand {
+                Some(hand) => format!("{} pays {} for a {hand}.", settled.name, settled.payout),
+                None => format!("{} loses {}.", settled.name, settled.stake),
+            });
+        }
+        if self.dealer_seen == 0 && game.rules.double_exposure {
+            // both cards are dealt face up, so there's nothing left to reveal
+            lines.push(format!(
+                "Dealer shows {} and {}, total {}.",
+                card_name(dealer_hand.cards()[0]),
+                card_name(dealer_hand.up_card()),
+                number_words(dealer_hand.count_value())
+            ));
+            self.dealer_seen = 2;
+            self.dealer_revealed = true;
+        }
+        if self.dealer_seen == 0 {
+            lines.push(format!(
+                "Dealer shows {}.",
+                card_name(dealer_hand.up_card())
+            ));
+            self.dealer_seen = 2;
+        }
+        if dealer_hand.is_revealed() && !self.dealer_revealed {
+            // the hole card is turned over before the dealer draws
+            let [hole, up] = [dealer_hand.cards()[0], dealer_hand.up_card()];
+            lines.push(format!(
+                "Dealer reveals {}, dealer total {}.",
+                card_name(hole),
+                number_words(Hand::<Dealer>::new([hole, up]).count_value())
+            ));
+            self.dealer_revealed = true;
+        }
+        for card in &dealer_hand.cards()[self.dealer_seen..] {
+            lines.push(format!("Dealer draws {}.", card_name(*card)));
+        }
+        self.dealer_seen = dealer_hand.cards().len();
+
+        if let GameState::HandScoreScreen(hand_result) = game.state {
+            if !self.result_announced {
+                for split in &game.split_hands {
+                    let result = split.result.map_or("", |result| result.name());
+                    lines.push(format!(
+                        "Your hand of {} is a {result}.",
+                        number_words(split.hand.count_value())
+                    ));
+                }
+                lines.push(result_sentence(game, hand_result));
+                lines.push(match game.payout {
+                    0 => format!("Your bet is returned, bankroll {}.", game.bankroll),
+                    won if won > 0 => format!("You win {won}, bankroll {}.", game.bankroll),
+                    lost => format!("You lose {}, bankroll {}.", -lost, game.bankroll),
+                });
+                self.result_announced = true;
+            }
+        }
+
+        lines
+    }
+}
+
+fn result_sentence(game: &Game, hand_result: HandResult) -> String {
+    let you = number_words(game.player_hand.count_value());
+    let dealer = number_words(game.dealer_hand.count_value());
+    match hand_result {
+        HandResult::Blackjack => "Blackjack!".to_string(),
+        HandResult::PlayerWin if game.dealer_hand.is_bust() => {
+            format!("Dealer busts with {dealer}, you win.")
+        }
+        HandResult::PlayerWin => format!("You win, {you} to {dealer}."),
+        HandResult::DealerWin => format!("Dealer wins, {dealer} to {you}."),
+        HandResult::Push => format!("Push, both have {you}."),
+        HandResult::Bust => format!("You bust with {you}."),
+        HandResult::Surrender => format!("You surrender on {you}."),
+    }
+}
+
+pub fn card_name(card: Card) -> String {
+    let rank = match card.rank() {
+        Rank::Two => "two",
+        Rank::Three => "three",
+        Rank::Four => "four",
+        Rank::Five => "five",
+        Rank::Six => "six",
+        Rank::Seven => "seven",
+        Rank::Eight => "eight",
+        Rank::Nine => "nine",
+        Rank::Ten => "ten",
+        Rank::Jack => "jack",
+        Rank::Queen => "queen",
+        Rank::King => "king",
+        Rank::Ace => "ace",
+    };
+    let suit = match card.suit() {
+        Suit::Spade => "spades",
+        Suit::Club => "clubs",
+        Suit::Diamond => "diamonds",
+        Suit::Heart => "hearts",
+    };
+    format!("the {rank} of {suit}")
+}
+
+pub fn number_words(num: u8) -> String {
+    const ONES: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    match num {
+        0..=19 => ONES[num as usize].to_string(),
+        20..=99 if num.is_multiple_of(10) => TENS[(num / 10) as usize].to_string(),
+        20..=99 => format!(
+            "{}-{}",
+            TENS[(num / 10) as usize],
+            ONES[(num % 10) as usize]
+        ),
+        _ => num.to_string(),
+    }
+}
@@ -0,0 +1,146 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use crate::config::env_var;
+
+/// Name of the game's directory in each of the platform's places for files.
+const APP: &str = "blackjack-tui";
+
+/// The directory given with `--data-dir`, which holds everything for a portable install.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// The profile given with `--profile`, whose statistics and saves are kept apart.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Keep every file the game reads and writes in `dir`, before anything looks for one.
+pub fn set_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+/// Keep every file tests write in a directory of the test run's own, well away from the
+/// player's.
+#[cfg(test)]
+pub fn use_test_dir() {
+    let dir = std::env::temp_dir().join(format!("{APP}-test-{}", std::process::id()));
+    set_data_dir(dir);
+}
+
+/// Keep statistics, history, and saves under the profile `name`, before anything looks
+/// for them. Settings and logs are shared between profiles.
+pub fn set_profile(name: &str) -> Result<(), String> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || !valid {
+        return Err(format!(
+            "a profile name is letters, digits, - and _, found '{name}'"
+        ));
+    }
+    let _ = PROFILE.set(name.to_string());
+    Ok(())
+}
+
+/// The profile given with `--profile`, if any.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+/// A directory given with `--data-dir` or `BLACKJACK_DATA_DIR`, in that order, which
+/// takes the place of all of the platform's.
+fn portable_dir() -> Option<PathBuf> {
+    DATA_DIR
+        .get()
+        .cloned()
+        .or_else(|| env_var("BLACKJACK_DATA_DIR").map(PathBuf::from))
+}
+
+/// The config file read when none is given.
+pub fn config_file() -> Option<PathBuf> {
+    let dir = portable_dir().or_else(|| platform::config_dir().map(|dir| dir.join(APP)))?;
+    Some(dir.join("config.toml"))
+}
+
+/// Directory for saved statistics, sessions, and other files the game writes, the
+/// profile's own when there is one.
+pub fn data_dir() -> Option<PathBuf> {
+    let dir = portable_dir().or_else(|| platform::data_dir().map(|dir| dir.join(APP)))?;
+    match profile() {
+        Some(name) => Some(dir.join("profiles").join(name)),
+        None => Some(dir),
+    }
+}
+
+/// Directory the log is written to when no log file is given.
+pub fn log_dir() -> Option<PathBuf> {
+    portable_dir().or_else(|| platform::log_dir().map(|dir| dir.join(APP)))
+}
+
+#[cfg(not(windows))]
+fn home() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+/// The XDG base directories on Linux and the other Unixes.
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use std::path::PathBuf;
+
+    use super::home;
+
+    /// The directory in the XDG variable `name`, or `fallback` under the home directory.
+    fn xdg(name: &str, fallback: &str) -> Option<PathBuf> {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(fallback)))
+    }
+
+    pub fn config_dir() -> Option<PathBuf> {
+        xdg("XDG_CONFIG_HOME", ".config")
+    }
+
+    pub fn data_dir() -> Option<PathBuf> {
+        xdg("XDG_DATA_HOME", ".local/share")
+    }
+
+    pub fn log_dir() -> Option<PathBuf> {
+        xdg("XDG_STATE_HOME", ".local/state")
+    }
+}
+
+/// `~/Library` on macOS.
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::PathBuf;
+
+    use super::home;
+
+    pub fn config_dir() -> Option<PathBuf> {
+        home().map(|home| home.join("Library/Application Support"))
+    }
+
+    pub fn data_dir() -> Option<PathBuf> {
+        config_dir()
+    }
+
+    pub fn log_dir() -> Option<PathBuf> {
+        home().map(|home| home.join("Library/Logs"))
+    }
+}
+
+/// `AppData` on Windows: roaming for settings and saves, local for logs.
+#[cfg(windows)]
+mod platform {
+    use std::path::PathBuf;
+
+    pub fn config_dir() -> Option<PathBuf> {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    }
+
+    pub fn data_dir() -> Option<PathBuf> {
+        config_dir()
+    }
+
+    pub fn log_dir() -> Option<PathBuf> {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    }
+}
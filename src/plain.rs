@@ -0,0 +1,67 @@
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+/// How frames are drawn for terminals that can't show everything: without colors, or
+/// with ASCII in place of suits, box drawing, and other symbols.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Plain {
+    pub no_color: bool,
+    pub ascii: bool,
+}
+impl Plain {
+    /// Rework a frame once everything is drawn on it.
+    pub fn apply(self, buf: &mut Buffer) {
+        if !self.no_color && !self.ascii {
+            return;
+        }
+        for cell in buf.content.iter_mut() {
+            if self.no_color {
+                // a background marked something out, like a highlight, so keep it marked
+                if cell.bg != Color::Reset {
+                    cell.modifier.insert(Modifier::REVERSED);
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+            if self.ascii && !cell.symbol().is_ascii() {
+                let mut chars = cell.symbol().chars();
+                let symbol = match (chars.next(), chars.next()) {
+                    (Some(symbol), None) => ascii(symbol),
+                    _ => '?',
+                };
+                cell.set_char(symbol);
+            }
+        }
+    }
+}
+
+/// The closest ASCII character to `symbol`.
+fn ascii(symbol: char) -> char {
+    match symbol {
+        '♠' => 'S',
+        '♥' => 'H',
+        '♦' => 'D',
+        '♣' => 'C',
+        '─' | '━' | '═' => '-',
+        '│' | '┃' | '║' => '|',
+        '·' | '•' | '…' => '.',
+        '“' | '”' => '"',
+        '‘' | '’' => '\'',
+        '▶' | '→' => '>',
+        '←' => '<',
+        '↑' => '^',
+        '↓' => 'v',
+        '±' => '+',
+        '×' => 'x',
+        // corners, tees, and crossings of box drawing
+        '\u{2500}'..='\u{257f}' => '+',
+        // an empty braille dot pattern is a blank
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28ff}' => '.',
+        // block elements and geometric shapes, like bars and gauges
+        '\u{2580}'..='\u{25ff}' => '#',
+        _ => '?',
+    }
+}
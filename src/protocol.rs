@@ -0,0 +1,293 @@
+use std::io::{self, Write};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    fairness::Commitment,
+    history::card_code,
+    table::{self, Table},
+};
+
+/// Version of the protocol, sent to each client when it is seated.
+pub const VERSION: u32 = 1;
+
+/// What a client sends to the host, one JSON object per line:
+///
+/// ```text
+/// {"type":"join","name":"Ada","token":null}
+/// {"type":"watch"}
+/// {"type":"bet","amount":20}
+/// {"type":"hit"}
+/// {"type":"stand"}
+/// {"type":"chat","text":"good luck"}
+/// ```
+///
+/// A bet of 0 sits the hand out. Bets and actions are only accepted on the seat's turn.
+/// After a dropped connection, joining with the token from the welcome takes the same
+/// seat back. Watching instead of joining follows the table without a seat.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join {
+        name: String,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    Watch,
+    Bet {
+        amount: u32,
+    },
+    Hit,
+    Stand,
+    Chat {
+        text: String,
+    },
+}
+
+/// What the host sends its clients, one JSON object per line:
+///
+/// ```text
+/// {"type":"welcome","version":1,"seat":0,"token":"5f0c2a9e81d3b7a4"}
+/// {"type":"state","rules":"6 decks, S17, 3:2, 75% dealt","phase":"playing","turn":0,
+///  "dealer":[null,"KD"],"dealer_total":null,"seats":[{"seat":0,"name":"Ada",
+///  "bankroll":1000,"bet":20,"cards":["10H","6S"],"total":16,"result":null,"payout":0}],
+///  "spectators":1,"time_left_ms":30000}
+/// {"type":"chat","from":"Ada","seat":0,"text":"good luck"}
+/// {"type":"timed_out","seat":1}
+/// {"type":"shuffle","shoe":1,"hash":"3b7e…"}
+/// {"type":"reveal","shoe":1,"salt":"9d04…","cards":["QS","4H",…]}
+/// {"type":"error","message":"it isn't your turn to bet"}
+/// ```
+///
+/// The whole table is sent again after every change, and chat goes to every client with
+/// `seat` left `null` for spectators. A seat that doesn't decide within `time_left_ms`
+/// sits out the hand or stands, and everyone is told it timed out. Cards are written rank then suit, as
+/// in the hand history, and the dealer's hole card is `null` until it is turned over.
+///
+/// Before any card of a shoe is shown, the host sends the hash of its order, and once the
+/// shoe is reshuffled it reveals the salt and cards that were hashed (see
+/// [`crate::fairness::hash`]), so clients can check the shoe wasn't changed while it was dealt.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome {
+        version: u32,
+        seat: usize,
+        token: String,
+    },
+    State(TableState),
+    Chat {
+        from: String,
+        seat: Option<usize>,
+        text: String,
+    },
+    TimedOut {
+        seat: usize,
+    },
+    Shuffle {
+        shoe: u32,
+        hash: String,
+    },
+    Reveal {
+        shoe: u32,
+        salt: String,
+        cards: Vec<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    /// Publish the hash of a shoe about to be dealt.
+    pub fn shuffle(commitment: &Commitment) -> Self {
+        ServerMessage::Shuffle {
+            shoe: commitment.shoe,
+            hash: commitment.hash(),
+        }
+    }
+
+    /// Show the order of a shoe that has been dealt.
+    pub fn reveal(commitment: Commitment) -> Self {
+        ServerMessage::Reveal {
+            shoe: commitment.shoe,
+            salt: commitment.salt,
+            cards: commitment.cards,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Betting,
+    Playing,
+    Dealer,
+    Results,
+}
+impl From<table::Phase> for Phase {
+    fn from(phase: table::Phase) -> Self {
+        match phase {
+            table::Phase::Betting => Phase::Betting,
+            table::Phase::Playing => Phase::Playing,
+            table::Phase::Dealer => Phase::Dealer,
+            table::Phase::Results => Phase::Results,
+        }
+    }
+}
+
+/// The table as every player may see it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableState {
+    pub rules: String,
+    pub phase: Phase,
+    /// the seat whose turn it is to bet or play
+    pub turn: Option<usize>,
+    pub dealer: Vec<Option<String>>,
+    pub dealer_total: Option<u8>,
+    pub seats: Vec<SeatState>,
+    #[serde(default)]
+    pub spectators: usize,
+    /// time the seat deciding has left, if there is a limit
+    #[serde(default)]
+    pub time_left_ms: Option<u64>,
+}
+impl TableState {
+    pub fn of(table: &Table, spectators: usize) -> Self {
+        let revealed = table.dealer.is_revealed() || table.rules.double_exposure;
+        let dealer = table
+            .dealer
+            .cards()
+            .iter()
+            .enumerate()
+            .map(|(index, card)| (index > 0 || revealed).then(|| card_code(card)))
+            .collect();
+        let turn = matches!(table.phase, table::Phase::Betting | table::Phase::Playing);
+        Self {
+            rules: table.rules.to_string(),
+            phase: table.phase.into(),
+            turn: turn.then_some(table.turn),
+            dealer,
+            dealer_total: revealed.then(|| table.dealer.count_value()),
+            seats: table
+                .players()
+                .map(|(index, seat)| SeatState {
+                    seat: index,
+                    name: seat.name.clone(),
+                    bankroll: seat.bankroll,
+                    bet: seat.bet,
+                    cards: seat.hand.cards().iter().map(card_code).collect(),
+                    total: (!seat.hand.cards().is_empty()).then(|| seat.hand.count_value()),
+                    result: seat.result.map(|(result, _)| result.name().to_string()),
+                    payout: seat.result.map_or(0, |(_, payout)| payout),
+                })
+                .collect(),
+            spectators,
+            time_left_ms: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatState {
+    pub seat: usize,
+    pub name: String,
+    pub bankroll: u32,
+    pub bet: u32,
+    pub cards: Vec<String>,
+    pub total: Option<u8>,
+    /// `blackjack`, `win`, `loss`, `push`, or `bust` once the hand is settled
+    pub result: Option<String>,
+    pub payout: i64,
+}
+
+/// Write a message as one line.
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    writer.write_all(line(message)?.as_bytes())?;
+    writer.flush()
+}
+
+/// A message as the line [`send`] writes, ending in a newline.
+pub fn line<T: Serialize>(message: &T) -> io::Result<String> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Read a message from one line.
+pub fn parse<T: DeserializeOwned>(line: &str) -> Result<T, String> {
+    serde_json::from_str(line).map_err(|err| format!("bad message: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn reads_what_clients_send() {
+        assert_eq!(
+            parse::<ClientMessage>(r#"{"type":"join","name":"Ada"}"#),
+            Ok(ClientMessage::Join {
+                name: "Ada".to_string(),
+                token: None
+            })
+        );
+        assert_eq!(
+            parse::<ClientMessage>(r#"{"type":"bet","amount":20}"#),
+            Ok(ClientMessage::Bet { amount: 20 })
+        );
+        assert_eq!(
+            parse::<ClientMessage>(r#"{"type":"hit"}"#),
+            Ok(ClientMessage::Hit)
+        );
+        let err = parse::<ClientMessage>(r#"{"type":"split"}"#).unwrap_err();
+        assert!(err.starts_with("bad message: "), "{err}");
+    }
+
+    #[test]
+    fn messages_read_back_from_their_lines() {
+        let messages = [
+            ServerMessage::Welcome {
+                version: VERSION,
+                seat: 1,
+                token: "5f0c".to_string(),
+            },
+            ServerMessage::Chat {
+                from: "Bo".to_string(),
+                seat: None,
+                text: "a \"quoted\" line\n".to_string(),
+            },
+            ServerMessage::TimedOut { seat: 0 },
+        ];
+        for message in messages {
+            let line = line(&message).unwrap();
+            assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+            assert_eq!(parse::<ServerMessage>(&line), Ok(message));
+        }
+        let line = line(&ServerMessage::TimedOut { seat: 2 }).unwrap();
+        assert_eq!(line, "{\"type\":\"timed_out\",\"seat\":2}\n");
+    }
+
+    #[test]
+    fn the_hole_card_stays_hidden_until_turned_over() {
+        let mut table = Table::new(&Config::default(), ["Ada".to_string()]);
+        let state = TableState::of(&table, 2);
+        assert_eq!(state.phase, Phase::Betting);
+        assert_eq!((state.turn, state.spectators), (Some(0), 2));
+        assert!(state.dealer.is_empty());
+
+        table.place_bet(0, 10).unwrap();
+        let state = TableState::of(&table, 0);
+        match state.phase {
+            // a natural turns it over straight away
+            Phase::Results => assert!(state.dealer.iter().all(Option::is_some)),
+            _ => {
+                assert_eq!(state.dealer[0], None);
+                assert!(state.dealer[1].is_some());
+                assert_eq!(state.dealer_total, None);
+            }
+        }
+        assert_eq!(state.seats[0].cards.len(), 2);
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::layout::Size;
+
+use crate::report::json_string;
+
+/// Passes everything drawn through to the terminal and records it, frame by frame, as an
+/// asciinema cast that `asciinema play` can replay or the web player can share:
+///
+/// ```text
+/// {"version": 2, "width": 120, "height": 40, "timestamp": 1791000000}
+/// [0.004210, "o", "\u001b[1;1H┌Blackjack…"]
+/// [1.250871, "o", "\u001b[12;40H╭─────────╮…"]
+/// ```
+pub struct Recorder<W: Write> {
+    terminal: W,
+    cast: BufWriter<File>,
+    started: Instant,
+    /// output of the frame being drawn, sent as one event when it's flushed
+    frame: Vec<u8>,
+}
+impl<W: Write> Recorder<W> {
+    /// Record what's written to `terminal`, of `size`, to a new cast file at `path`.
+    pub fn create(path: &Path, size: Size, terminal: W) -> io::Result<Self> {
+        let mut cast = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        writeln!(
+            cast,
+            r#"{{"version": 2, "width": {}, "height": {}, "timestamp": {timestamp}}}"#,
+            size.width, size.height
+        )?;
+        Ok(Self {
+            terminal,
+            cast,
+            started: Instant::now(),
+            frame: Vec::new(),
+        })
+    }
+}
+impl<W: Write> Write for Recorder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.terminal.write(buf)?;
+        self.frame.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.terminal.flush()?;
+        // a character split across writes waits for the rest of it
+        let complete = match std::str::from_utf8(&self.frame) {
+            Ok(text) => text.len(),
+            Err(err) => err.valid_up_to(),
+        };
+        if complete == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.frame[..complete]);
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(self.cast, "[{time:.6}, \"o\", {}]", json_string(&text))?;
+        self.frame.drain(..complete);
+        self.cast.flush()
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{game::*, history::Round, simulate::Results};
+
+/// File formats simulation results can be written in, picked by file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format '{name}', expected csv or json")),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Ok(Format::Csv),
+            Some("json") => Ok(Format::Json),
+            _ => Err(format!(
+                "can't tell the format of '{}', expected a .csv or .json file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// One simulated configuration and how it did.
+#[derive(Debug)]
+pub struct Summary<'a> {
+    pub rules: &'a str,
+    pub strategy: &'a str,
+    pub results: &'a Results,
+}
+impl Summary<'_> {
+    const FIELDS: [&'static str; 11] = [
+        "rules",
+        "strategy",
+        "hands",
+        "wagered",
+        "net",
+        "house_edge",
+        "win_rate",
+        "push_rate",
+        "loss_rate",
+        "hands_per_hour",
+        "elapsed_secs",
+    ];
+
+    fn values(&self) -> [Value; 11] {
+        let results = self.results;
+        [
+            Value::Text(self.rules.to_string()),
+            Value::Text(self.strategy.to_string()),
+            Value::Number(results.hands.to_string()),
+            Value::Number(results.wagered.to_string()),
+            Value::Number(results.net.to_string()),
+            Value::Number(format!("{:.4}", results.house_edge())),
+            Value::Number(format!("{:.4}", results.rate(results.wins))),
+            Value::Number(format!("{:.4}", results.rate(results.pushes))),
+            Value::Number(format!("{:.4}", results.rate(results.losses))),
+            Value::Number(format!("{:.0}", results.hands_per_hour())),
+            Value::Number(format!("{:.3}", results.elapsed_secs)),
+        ]
+    }
+}
+
+/// Write a row for each configuration to `path`.
+pub fn write_summaries(path: &Path, summaries: &[Summary]) -> Result<(), String> {
+    let format = Format::from_path(path)?;
+    let mut table = Table::create(path, format, &Summary::FIELDS)?;
+    for summary in summaries {
+        table.row(&summary.values())?;
+    }
+    table.finish()
+}
+
+/// Every hand of a simulation, written as it is played.
+pub struct HandLog {
+    table: Table,
+    hands: u64,
+}
+impl HandLog {
+    const FIELDS: [&str; 6] = ["hand", "bet", "player", "dealer", "result", "payout"];
+
+    /// The hand log kept next to a summary file, like `results-hands.csv`.
+    pub fn path_for(summary: &Path) -> PathBuf {
+        let stem = summary
+            .file_stem()
+            .map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+        let extension = summary.extension().map_or(String::new(), |extension| {
+            extension.to_string_lossy().into_owned()
+        });
+        summary.with_file_name(format!("{stem}-hands.{extension}"))
+    }
+
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let format = Format::from_path(path)?;
+        Ok(Self {
+            table: Table::create(path, format, &Self::FIELDS)?,
+            hands: 0,
+        })
+    }
+
+    /// Record the hand the game just settled.
+    pub fn record(&mut self, game: &Game) -> Result<(), String> {
+        let GameState::HandScoreScreen(result) = game.state else {
+            return Ok(());
+        };
+        self.hands += 1;
+        self.table.row(&[
+            Value::Number(self.hands.to_string()),
+            Value::Number(game.stake().to_string()),
+            Value::Number(game.player_hand.count_value().to_string()),
+            Value::Number(game.dealer_hand.count_value().to_string()),
+            Value::Text(result.name().to_string()),
+            Value::Number(game.payout.to_string()),
+        ])
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        self.table.finish()
+    }
+}
+
+/// Write every round from the hand history, one row each.
+pub fn write_rounds(path: &Path, format: Format, rounds: &[Round]) -> Result<(), String> {
+    const FIELDS: [&str; 17] = [
+        "session_ms",
+        "started_ms",
+        "finished_ms",
+        "rules",
+        "preset",
+        "bet",
+        "running_count",
+        "true_count",
+        "player",
+        "split",
+        "dealer",
+        "actions",
+        "player_total",
+        "dealer_total",
+        "result",
+        "payout",
+        "bankroll",
+    ];
+    let mut table = Table::create(path, format, &FIELDS)?;
+    for round in rounds {
+        table.row(&[
+            Value::Number(round.session_ms.to_string()),
+            Value::Number(round.started_ms.to_string()),
+            Value::Number(round.finished_ms.to_string()),
+            Value::Text(round.rules.clone()),
+            Value::Text(round.preset.clone()),
+            Value::Number(round.bet.to_string()),
+            Value::Number(round.running_count.to_string()),
+            Value::Number(format!("{:.2}", round.true_count)),
+            Value::Text(round.player.join(" ")),
+            Value::Text(split_hands(&round.split)),
+            Value::Text(round.dealer.join(" ")),
+            Value::Text(round.actions.join(" ")),
+            Value::Number(round.player_total.to_string()),
+            Value::Number(round.dealer_total.to_string()),
+            Value::Text(round.result.clone()),
+            Value::Number(round.payout.to_string()),
+            Value::Number(round.bankroll.to_string()),
+        ])?;
+    }
+    table.finish()
+}
+
+/// Hands split from the one dealt, like `8S 3D / 8H 10C`.
+fn split_hands(hands: &[Vec<String>]) -> String {
+    let hands: Vec<_> = hands.iter().map(|hand| hand.join(" ")).collect();
+    hands.join(" / ")
+}
+
+enum Value {
+    Number(String),
+    Text(String),
+}
+
+/// Rows written as CSV, or as a JSON array of objects.
+struct Table {
+    path: PathBuf,
+    format: Format,
+    fields: &'static [&'static str],
+    writer: BufWriter<File>,
+    rows: u64,
+}
+impl Table {
+    fn create(
+        path: &Path,
+        format: Format,
+        fields: &'static [&'static str],
+    ) -> Result<Self, String> {
+        let mut table = Self {
+            path: path.to_path_buf(),
+            format,
+            fields,
+            writer: BufWriter::new(File::create(path).map_err(|err| table_error(path, err))?),
+            rows: 0,
+        };
+        let header = match format {
+            Format::Csv => format!("{}\n", fields.join(",")),
+            Format::Json => "[".to_string(),
+        };
+        table.write(&header)?;
+        Ok(table)
+    }
+
+    fn row(&mut self, values: &[Value]) -> Result<(), String> {
+        let line = match self.format {
+            Format::Csv => {
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Number(number) => number.clone(),
+                        Value::Text(text) if text.contains([',', '"', '\n']) => {
+                            format!("\"{}\"", text.replace('"', "\"\""))
+                        }
+                        Value::Text(text) => text.clone(),
+                    })
+                    .collect();
+                format!("{}\n", values.join(","))
+            }
+            Format::Json => {
+                let fields: Vec<_> = std::iter::zip(self.fields, values)
+                    .map(|(field, value)| match value {
+                        Value::Number(number) => format!("\"{field}\":{number}"),
+                        Value::Text(text) => format!("\"{field}\":{}", json_string(text)),
+                    })
+                    .collect();
+                let separator = if self.rows == 0 { "" } else { "," };
+                format!("{separator}\n  {{{}}}", fields.join(","))
+            }
+        };
+        self.rows += 1;
+        self.write(&line)
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        if self.format == Format::Json {
+            self.write("\n]\n")?;
+        }
+        self.writer
+            .flush()
+            .map_err(|err| table_error(&self.path, err))
+    }
+
+    fn write(&mut self, text: &str) -> Result<(), String> {
+        self.writer
+            .write_all(text.as_bytes())
+            .map_err(|err| table_error(&self.path, err))
+    }
+}
+
+fn table_error(path: &Path, err: io::Error) -> String {
+    format!("couldn't write {}: {err}", path.display())
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+
+    fn test_file(name: &str) -> PathBuf {
+        paths::use_test_dir();
+        let dir = paths::data_dir().unwrap();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn writes_summaries_as_csv_or_json() {
+        let results = Results {
+            hands: 4,
+            wins: 1,
+            wagered: 40,
+            net: -4,
+            elapsed_secs: 2.0,
+            ..Results::default()
+        };
+        let summary = || Summary {
+            rules: "6 decks, S17, 3:2",
+            strategy: "basic",
+            results: &results,
+        };
+
+        let csv = test_file("summary.csv");
+        write_summaries(&csv, &[summary()]).unwrap();
+        let written = std::fs::read_to_string(&csv).unwrap();
+        let lines: Vec<_> = written.lines().collect();
+        assert_eq!(lines[0], Summary::FIELDS.join(","));
+        assert_eq!(
+            lines[1],
+            "\"6 decks, S17, 3:2\",basic,4,40,-4,10.0000,25.0000,0.0000,0.0000,7200,2.000"
+        );
+
+        let json = test_file("summary.json");
+        write_summaries(&json, &[summary(), summary()]).unwrap();
+        let written = std::fs::read_to_string(&json).unwrap();
+        assert!(written.starts_with("[\n  {\"rules\":\"6 decks, S17, 3:2\",\"strategy\""));
+        assert!(written.ends_with("}\n]\n"));
+        assert_eq!(written.matches("\"hands\":4,").count(), 2);
+
+        let err = write_summaries(Path::new("summary.txt"), &[]).unwrap_err();
+        assert!(
+            err.starts_with("can't tell the format of 'summary.txt'"),
+            "{err}"
+        );
+        assert_eq!(
+            HandLog::path_for(Path::new("out/results.csv")),
+            Path::new("out/results-hands.csv")
+        );
+        assert_eq!(json_string("a \"b\"\n"), r#""a \"b\"\u000a""#);
+    }
+
+    #[test]
+    fn writes_a_row_for_every_round() {
+        let round = Round {
+            rules: "1 deck, S17, 3:2".to_string(),
+            preset: "single-deck".to_string(),
+            bet: 20,
+            player: vec!["8S".to_string(), "3D".to_string(), "10C".to_string()],
+            split: vec![vec!["8H".to_string(), "9C".to_string()]],
+            dealer: vec!["10H".to_string(), "7C".to_string()],
+            actions: vec![
+                "split".to_string(),
+                "stand".to_string(),
+                "stand".to_string(),
+            ],
+            player_total: 21,
+            dealer_total: 17,
+            result: "win".to_string(),
+            payout: 20,
+            bankroll: 1020,
+            ..Round::default()
+        };
+        let csv = test_file("rounds.csv");
+        write_rounds(&csv, Format::Csv, &[round]).unwrap();
+        let written = std::fs::read_to_string(&csv).unwrap();
+        let [header, row] = written.lines().collect::<Vec<_>>()[..] else {
+            panic!("expected a header and one row, found {written}");
+        };
+        assert!(header.starts_with("session_ms,started_ms,finished_ms,rules,"));
+        assert_eq!(
+            row,
+            "0,0,0,\"1 deck, S17, 3:2\",single-deck,20,0,0.00,8S 3D 10C,8H 9C,10H 7C,\
+             split stand stand,21,17,win,20,1020"
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    prelude::{Buffer, Line, Rect, Style, Stylize},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::events::{card_text, GameEvent};
+
+/// The events of the round just played, stepped through one at a time to see how it
+/// unfolded.
+#[derive(Clone, Debug)]
+pub struct Rewind {
+    events: Vec<GameEvent>,
+    /// the last event shown
+    step: usize,
+}
+impl Rewind {
+    /// Start at the end of the round's `events`, or `None` if there's nothing to rewind.
+    pub fn new(events: Vec<GameEvent>) -> Option<Self> {
+        let step = events.len().checked_sub(1)?;
+        Some(Self { events, step })
+    }
+
+    pub fn back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub fn forward(&mut self) {
+        self.step = (self.step + 1).min(self.events.len() - 1);
+    }
+
+    pub fn first(&mut self) {
+        self.step = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.step = self.events.len() - 1;
+    }
+
+    /// The table as it stood after the event shown: the player's cards and total, and the
+    /// dealer's cards, hole card first, with the total once it's turned over.
+    fn table(&self) -> (Vec<String>, u8, Vec<String>, Option<u8>) {
+        let (mut player, mut player_total) = (Vec::new(), 0);
+        let (mut dealer, mut dealer_total) = (Vec::new(), None);
+        for event in &self.events[..=self.step] {
+            match event {
+                GameEvent::Deal {
+                    player: cards,
+                    dealer_up,
+                    dealer_hole,
+                    player_total: total,
+                } => {
+                    player = cards.iter().map(|card| card_text(card)).collect();
+                    player_total = *total;
+                    let hole = dealer_hole.as_deref().map_or("??".to_string(), card_text);
+                    dealer = vec![hole, card_text(dealer_up)];
+                }
+                GameEvent::Hit {
+                    card,
+                    player_total: total,
+                } => {
+                    player.push(card_text(card));
+                    player_total = *total;
+                }
+                GameEvent::Double {
+                    card,
+                    player_total: total,
+                    ..
+                } => {
+                    player.push(card_text(card));
+                    player_total = *total;
+                }
+                GameEvent::PlayHand {
+                    player: cards,
+                    player_total: total,
+                } => {
+                    player = cards.iter().map(|card| card_text(card)).collect();
+                    player_total = *total;
+                }
+                GameEvent::DealerDraw { card } => dealer.push(card_text(card)),
+                GameEvent::Reveal {
+                    card,
+                    dealer_total: total,
+                } => {
+                    if let Some(hole) = dealer.first_mut() {
+                        *hole = card_text(card);
+                    }
+                    dealer_total = Some(*total);
+                }
+                GameEvent::Bet { .. }
+                | GameEvent::SideBet { .. }
+                | GameEvent::Stand { .. }
+                | GameEvent::Split { .. }
+                | GameEvent::Surrender
+                | GameEvent::Insurance { .. }
+                | GameEvent::Settle { .. }
+                | GameEvent::ShuffleNeeded { .. }
+                | GameEvent::Shuffle { .. }
+                | GameEvent::DealerSays { .. }
+                | GameEvent::ConfigReloaded { .. } => (),
+            }
+        }
+        (player, player_total, dealer, dealer_total)
+    }
+}
+impl Widget for &Rewind {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (player, player_total, dealer, dealer_total) = self.table();
+        let dealer_total = dealer_total.map_or(String::new(), |total| format!(" ({total})"));
+        let mut lines = vec![
+            Line::from(format!("Player: {} ({player_total})", player.join(" "))),
+            Line::from(format!("Dealer: {}{dealer_total}", dealer.join(" "))),
+            Line::default(),
+        ];
+        lines.extend(self.events.iter().enumerate().map(|(step, event)| {
+            let line = Line::from(describe(event));
+            match step.cmp(&self.step) {
+                std::cmp::Ordering::Less => line,
+                std::cmp::Ordering::Equal => line.bold().cyan(),
+                std::cmp::Ordering::Greater => line.dark_gray(),
+            }
+        }));
+        lines.push(Line::default());
+        lines.push(Line::from(
+            "←/→) Step    Home/End) First/last    Esc) Close",
+        ));
+
+        let block = Block::bordered()
+            .title(format!(
+                "Rewind · step {} of {}",
+                self.step + 1,
+                self.events.len()
+            ))
+            .border_style(Style::new().cyan());
+        let vertical = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)]);
+        let horizontal = Layout::horizontal([Constraint::Length(52)]);
+        let [area] = vertical.flex(Flex::Center).areas(area);
+        let [area] = horizontal.flex(Flex::Center).areas(area);
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// One event as a line of the rewind.
+fn describe(event: &GameEvent) -> String {
+    match event {
+        GameEvent::Bet { amount, .. } => format!("Bet {amount}"),
+        GameEvent::Deal {
+            player,
+            dealer_up,
+            dealer_hole,
+            player_total,
+        } => {
+            let player: Vec<_> = player.iter().map(|card| card_text(card)).collect();
+            let dealer = match dealer_hole {
+                Some(hole) => format!("{} {}", card_text(hole), card_text(dealer_up)),
+                None => card_text(dealer_up),
+            };
+            format!(
+                "Dealt {} ({player_total}) against {dealer}",
+                player.join(" ")
+            )
+        }
+        GameEvent::Hit { card, player_total } => {
+            format!("Hit, drew {} ({player_total})", card_text(card))
+        }
+        GameEvent::Stand { player_total } => format!("Stood on {player_total}"),
+        GameEvent::Double {
+            card,
+            player_total,
+            bet,
+        } => format!(
+            "Doubled to {bet}, drew {} ({player_total})",
+            card_text(card)
+        ),
+        GameEvent::Split { hands } => format!("Split, into {hands} hands"),
+        GameEvent::PlayHand {
+            player,
+            player_total,
+        } => {
+            let player: Vec<_> = player.iter().map(|card| card_text(card)).collect();
+            format!(
+                "Played the split hand {} ({player_total})",
+                player.join(" ")
+            )
+        }
+        GameEvent::Surrender => "Surrendered".to_string(),
+        GameEvent::Insurance { taken: true, stake } => format!("Took insurance for {stake}"),
+        GameEvent::Insurance { taken: false, .. } => "Turned down insurance".to_string(),
+        GameEvent::DealerDraw { card } => format!("Dealer drew {}", card_text(card)),
+        GameEvent::Reveal { card, dealer_total } => {
+            format!("Dealer turned over {} ({dealer_total})", card_text(card))
+        }
+        GameEvent::SideBet {
+            name, hand, payout, ..
+        } => match hand {
+            Some(hand) => format!("{name} won on a {hand}, {payout:+}"),
+            None => format!("{name} lost, {payout:+}"),
+        },
+        GameEvent::Settle { result, payout, .. } => format!("Settled as a {result}, {payout:+}"),
+        GameEvent::ShuffleNeeded { .. } => "Cut card out, shuffling after this hand".to_string(),
+        GameEvent::Shuffle {
+            shoe,
+            riffles,
+            burned,
+        } => {
+            let riffles = match riffles {
+                0 => String::new(),
+                1 => " in 1 riffle".to_string(),
+                riffles => format!(" in {riffles} riffles"),
+            };
+            let burned = match burned {
+                0 => String::new(),
+                burned => format!(", burned {burned}"),
+            };
+            format!("Shuffled shoe {shoe}{riffles}{burned}")
+        }
+        GameEvent::DealerSays { line } => format!("Dealer: “{line}”"),
+        GameEvent::ConfigReloaded { error: None } => "Reloaded the config".to_string(),
+        GameEvent::ConfigReloaded { error: Some(error) } => {
+            format!("Couldn't reload the config: {error}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, game::Game, shuffle::Shuffler};
+
+    #[test]
+    fn steps_through_the_round_as_it_was_played() {
+        let config = Config {
+            shuffle: Shuffler::stacked("10S 6S 9H 10H 5D"),
+            ..Config::default()
+        };
+        let mut game = Game::new(&config);
+        game.take_events();
+        game.place_bet(10).unwrap();
+        game.hit().unwrap();
+        if game.is_player_turn() {
+            game.hold().unwrap();
+        }
+        while game.is_dealer_turn() {
+            game.dealer_step();
+        }
+        assert!(Rewind::new(Vec::new()).is_none());
+        let mut rewind = Rewind::new(game.take_events()).unwrap();
+
+        let (player, player_total, dealer, dealer_total) = rewind.table();
+        assert_eq!((player.len(), player_total), (3, 21));
+        assert_eq!(
+            (dealer.join(" "), dealer_total),
+            ("9♥ 10♥".to_string(), Some(19))
+        );
+
+        rewind.first();
+        rewind.back();
+        assert_eq!(describe(&rewind.events[rewind.step]), "Bet 10");
+        rewind.forward();
+        let (player, player_total, dealer, dealer_total) = rewind.table();
+        assert_eq!((player.join(" "), player_total), ("10♠ 6♠".to_string(), 16));
+        // the hole card stays hidden until it's turned over
+        assert_eq!(
+            (dealer.join(" "), dealer_total),
+            ("?? 10♥".to_string(), None)
+        );
+
+        rewind.last();
+        rewind.forward();
+        assert_eq!(rewind.step, rewind.events.len() - 1);
+    }
+}
@@ -0,0 +1,358 @@
+use std::fmt::Display;
+
+use crate::config::parse_bool;
+
+/// Table rules that decide which plays are available to the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Rules {
+    pub decks: u8,
+    /// percent of the shoe dealt before it is reshuffled
+    pub penetration: u8,
+    /// cards burned face down after every shuffle
+    pub burn: u8,
+    /// riffles in a shuffle, or zero for a shuffle that leaves the cards perfectly mixed
+    pub riffles: u8,
+    /// what a natural pays, as a ratio
+    pub blackjack_pays: (u32, u32),
+    pub dealer_hits_soft_17: bool,
+    /// doubling on any first two cards
+    pub double: bool,
+    /// doubling on any number of cards, not just the first two
+    pub double_any_cards: bool,
+    /// splitting a pair into two hands, and those again up to four, with a single card
+    /// on each split ace
+    pub split: bool,
+    /// doubling on the first two cards of a hand split from a pair
+    pub double_after_split: bool,
+    /// late surrender, of half the bet on the first two cards once the dealer has checked
+    /// for blackjack
+    pub surrender: bool,
+    /// surrender after hitting or doubling too, of half of everything bet on the hand
+    pub surrender_any_cards: bool,
+    /// both dealer cards are dealt face up, and in return the dealer wins ties
+    pub double_exposure: bool,
+    /// Super Fun 21: the player's blackjacks and 21s always win, six cards without busting
+    /// win, and a blackjack in diamonds pays 2:1. It's dealt with doubling on any number
+    /// of cards and surrender after hitting or doubling.
+    pub super_fun: bool,
+}
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            decks: 1,
+            penetration: 100,
+            burn: 0,
+            riffles: 0,
+            blackjack_pays: (3, 2),
+            dealer_hits_soft_17: false,
+            double: true,
+            double_any_cards: false,
+            split: true,
+            double_after_split: false,
+            surrender: false,
+            surrender_any_cards: false,
+            double_exposure: false,
+            super_fun: false,
+        }
+    }
+}
+impl Rules {
+    /// Named tables that can be picked with `--rules`.
+    pub const PRESETS: [(&str, Rules); 5] = [
+        (
+            "single-deck",
+            Rules {
+                decks: 1,
+                penetration: 100,
+                burn: 0,
+                riffles: 0,
+                blackjack_pays: (3, 2),
+                dealer_hits_soft_17: false,
+                double: true,
+                double_any_cards: false,
+                split: true,
+                double_after_split: false,
+                surrender: false,
+                surrender_any_cards: false,
+                double_exposure: false,
+                super_fun: false,
+            },
+        ),
+        (
+            "vegas",
+            Rules {
+                decks: 6,
+                penetration: 75,
+                burn: 1,
+                riffles: 0,
+                blackjack_pays: (3, 2),
+                dealer_hits_soft_17: false,
+                double: true,
+                double_any_cards: false,
+                split: true,
+                double_after_split: true,
+                surrender: true,
+                surrender_any_cards: false,
+                double_exposure: false,
+                super_fun: false,
+            },
+        ),
+        (
+            "downtown",
+            Rules {
+                decks: 2,
+                penetration: 75,
+                burn: 1,
+                riffles: 0,
+                blackjack_pays: (3, 2),
+                dealer_hits_soft_17: true,
+                double: true,
+                double_any_cards: false,
+                split: true,
+                double_after_split: true,
+                surrender: false,
+                surrender_any_cards: false,
+                double_exposure: false,
+                super_fun: false,
+            },
+        ),
+        (
+            "double-exposure",
+            Rules {
+                decks: 6,
+                penetration: 75,
+                burn: 1,
+                riffles: 0,
+                blackjack_pays: (1, 1),
+                dealer_hits_soft_17: false,
+                double: true,
+                double_any_cards: false,
+                split: true,
+                double_after_split: false,
+                surrender: false,
+                surrender_any_cards: false,
+                double_exposure: true,
+                super_fun: false,
+            },
+        ),
+        (
+            "super-fun-21",
+            Rules {
+                decks: 6,
+                penetration: 75,
+                burn: 1,
+                riffles: 0,
+                blackjack_pays: (1, 1),
+                dealer_hits_soft_17: true,
+                double: true,
+                double_any_cards: true,
+                split: true,
+                double_after_split: true,
+                surrender: true,
+                surrender_any_cards: true,
+                double_exposure: false,
+                super_fun: true,
+            },
+        ),
+    ];
+
+    /// Name of the preset these rules match, if any.
+    pub fn name(&self) -> &'static str {
+        Self::PRESETS
+            .iter()
+            .find(|(_, rules)| rules == self)
+            .map_or("custom", |(name, _)| name)
+    }
+
+    pub fn preset(name: &str) -> Option<Rules> {
+        Self::PRESETS
+            .iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+            .map(|(_, rules)| *rules)
+    }
+
+    /// Change one rule, as in `decks = 6`, `soft17 = hit`, `payout = 6:5`,
+    /// `penetration = 75`, `burn = 1`, `riffles = 7`, `double = false`,
+    /// `double_any_cards = true`, `split = false`, `double_after_split = true`,
+    /// `surrender = true`, `surrender_any_cards = true`, `double_exposure = true`, or
+    /// `super_fun = true`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "decks" => {
+                self.decks = value
+                    .parse()
+                    .ok()
+                    .filter(|decks| (1..=8).contains(decks))
+                    .ok_or_else(|| format!("expected 1 to 8 decks, found '{value}'"))?
+            }
+            "penetration" => {
+                self.penetration = value
+                    .trim_end_matches('%')
+                    .parse()
+                    .ok()
+                    .filter(|penetration| (10..=100).contains(penetration))
+                    .ok_or_else(|| format!("expected 10 to 100 percent, found '{value}'"))?
+            }
+            "burn" => {
+                self.burn = value
+                    .parse()
+                    .ok()
+                    .filter(|burn| *burn <= 5)
+                    .ok_or_else(|| format!("expected 0 to 5 cards, found '{value}'"))?
+            }
+            "riffles" => {
+                self.riffles = value
+                    .parse()
+                    .ok()
+                    .filter(|riffles| *riffles <= 20)
+                    .ok_or_else(|| format!("expected 0 to 20 riffles, found '{value}'"))?
+            }
+            "soft17" => {
+                self.dealer_hits_soft_17 = match value.to_lowercase().as_str() {
+                    "hit" | "h17" => true,
+                    "stand" | "s17" => false,
+                    _ => return Err(format!("expected hit or stand, found '{value}'")),
+                }
+            }
+            "payout" => {
+                self.blackjack_pays = value
+                    .split_once(':')
+                    .and_then(|(win, bet)| Some((win.parse().ok()?, bet.parse().ok()?)))
+                    .filter(|(_, bet)| *bet > 0)
+                    .ok_or_else(|| format!("expected a payout like 3:2, found '{value}'"))?
+            }
+            "double" => self.double = parse_bool(value)?,
+            "double_any_cards" => self.double_any_cards = parse_bool(value)?,
+            "split" => self.split = parse_bool(value)?,
+            "double_after_split" => self.double_after_split = parse_bool(value)?,
+            "surrender" => self.surrender = parse_bool(value)?,
+            "surrender_any_cards" => self.surrender_any_cards = parse_bool(value)?,
+            "double_exposure" => {
+                self.double_exposure = parse_bool(value)?;
+                // the variant pays blackjacks even money, unless a payout is set after it
+                if self.double_exposure {
+                    self.blackjack_pays = (1, 1);
+                }
+            }
+            "super_fun" => {
+                self.super_fun = parse_bool(value)?;
+                // blackjacks out of diamonds pay even money, and doubling and surrender are
+                // liberal, unless they're set after it
+                if self.super_fun {
+                    self.blackjack_pays = (1, 1);
+                    self.double_any_cards = true;
+                    self.surrender = true;
+                    self.surrender_any_cards = true;
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "unknown rule '{key}', expected decks, penetration, burn, riffles, soft17, \
+                     payout, double, double_any_cards, split, double_after_split, surrender, \
+                     surrender_any_cards, double_exposure, or super_fun"
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (win, bet) = self.blackjack_pays;
+        let soft_17 = if self.dealer_hits_soft_17 {
+            "H17"
+        } else {
+            "S17"
+        };
+        match self.decks {
+            1 => write!(f, "1 deck, {soft_17}, {win}:{bet}")?,
+            decks => write!(f, "{decks} decks, {soft_17}, {win}:{bet}")?,
+        }
+        if self.penetration < 100 {
+            write!(f, ", {}% dealt", self.penetration)?;
+        }
+        if self.burn > 0 {
+            write!(f, ", burn {}", self.burn)?;
+        }
+        match (self.double, self.double_any_cards) {
+            (false, _) => write!(f, ", no doubling")?,
+            (true, true) => write!(f, ", double on any cards")?,
+            (true, false) => (),
+        }
+        match (self.split, self.double && self.double_after_split) {
+            (false, _) => write!(f, ", no splitting")?,
+            (true, true) => write!(f, ", double after split")?,
+            (true, false) => (),
+        }
+        match (self.surrender, self.surrender_any_cards) {
+            (false, _) => (),
+            (true, true) => write!(f, ", surrender any time")?,
+            (true, false) => write!(f, ", surrender")?,
+        }
+        if self.double_exposure {
+            write!(f, ", double exposure")?;
+        }
+        if self.super_fun {
+            write!(f, ", Super Fun 21")?;
+        }
+        match self.riffles {
+            0 => (),
+            1 => write!(f, ", 1 riffle")?,
+            riffles => write!(f, ", {riffles} riffles")?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_rules_from_their_values() {
+        let mut rules = Rules::default();
+        rules.set("decks", "6").unwrap();
+        rules.set("soft17", "H17").unwrap();
+        rules.set("payout", "6:5").unwrap();
+        rules.set("penetration", "75%").unwrap();
+        rules.set("surrender", "true").unwrap();
+        assert_eq!(rules.to_string(), "6 decks, H17, 6:5, 75% dealt, surrender");
+
+        for (key, value) in [
+            ("decks", "9"),
+            ("penetration", "5"),
+            ("burn", "6"),
+            ("soft17", "maybe"),
+            ("payout", "3:0"),
+            ("double", "yes"),
+            ("split", "yes"),
+        ] {
+            assert!(rules.set(key, value).is_err(), "{key} = {value}");
+        }
+    }
+
+    #[test]
+    fn variants_pay_even_money_unless_told_otherwise() {
+        let mut rules = Rules::default();
+        rules.set("double_exposure", "true").unwrap();
+        assert_eq!(rules.blackjack_pays, (1, 1));
+        rules.set("payout", "3:2").unwrap();
+        assert_eq!(rules.blackjack_pays, (3, 2));
+    }
+
+    #[test]
+    fn presets_are_found_by_their_names() {
+        for (name, rules) in Rules::PRESETS {
+            assert_eq!(rules.name(), name);
+            assert_eq!(Rules::preset(&name.to_uppercase()), Some(rules));
+        }
+        assert_eq!(Rules::preset("atlantic-city"), None);
+        let custom = Rules {
+            burn: 3,
+            ..Rules::default()
+        };
+        assert_eq!(custom.name(), "custom");
+    }
+}
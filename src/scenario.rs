@@ -0,0 +1,223 @@
+use std::fmt::Display;
+
+use rand::prelude::{thread_rng, SliceRandom};
+
+use crate::{strategy::HandKind, widgets::*};
+
+/// Scenarios that can be picked by name instead of written out.
+pub const PRESETS: [(&str, &str); 5] = [
+    ("pairs-vs-strong", "pairs vs 9-A"),
+    ("soft-18", "soft 18 vs any"),
+    ("soft-doubles", "soft 13-18 vs 3-6"),
+    ("stiffs", "hard 12-16 vs 2-A"),
+    ("doubles", "hard 9-11 vs any"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PlayerSpec {
+    Pairs(Vec<u8>),
+    Soft(Vec<u8>),
+    Hard(Vec<u8>),
+    Cards([u8; 2]),
+}
+
+/// A practice situation the deal is rigged to, such as `soft 18 vs any` or `8,8 vs 9-A`.
+///
+/// Card values are 2 through 10, with `A` for aces. The dealer's hole card is always left
+/// to the deck.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Scenario {
+    definition: String,
+    player: PlayerSpec,
+    /// dealer up-card values to pick from
+    dealer: Vec<u8>,
+}
+impl Scenario {
+    /// Parse a scenario definition, or the name of a preset.
+    pub fn parse(definition: &str) -> Result<Self, String> {
+        let definition = PRESETS
+            .iter()
+            .find(|(name, _)| *name == definition)
+            .map_or(definition, |(_, preset)| preset)
+            .trim()
+            .to_string();
+        let lowercase = definition.to_lowercase();
+        let invalid =
+            || format!("invalid scenario '{definition}', expected something like 'soft 18 vs 9-A'");
+
+        let (player, dealer) = lowercase.split_once(" vs ").ok_or_else(invalid)?;
+        let player = match player.trim().split_once(' ') {
+            None if player.trim() == "pairs" => PlayerSpec::Pairs((2..=11).collect()),
+            Some(("pairs" | "pair", values)) => PlayerSpec::Pairs(parse_values(values)?),
+            Some(("soft", totals)) => {
+                let totals = parse_values(totals)?;
+                if totals.iter().any(|total| !(13..=21).contains(total)) {
+                    return Err(format!("soft totals must be 13 to 21 in '{definition}'"));
+                }
+                PlayerSpec::Soft(totals)
+            }
+            Some(("hard", totals)) => {
+                let totals = parse_values(totals)?;
+                if totals.iter().any(|total| !(5..=19).contains(total)) {
+                    return Err(format!("hard totals must be 5 to 19 in '{definition}'"));
+                }
+                PlayerSpec::Hard(totals)
+            }
+            None => match parse_values(player)?[..] {
+                [first, second] => PlayerSpec::Cards([first, second]),
+                _ => return Err(invalid()),
+            },
+            Some(_) => return Err(invalid()),
+        };
+        let dealer = match dealer.trim() {
+            "any" => (2..=11).collect(),
+            dealer => parse_values(dealer)?,
+        };
+        if dealer.iter().any(|value| !(2..=11).contains(value)) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            definition,
+            player,
+            dealer,
+        })
+    }
+
+    /// Card values for the player's two cards and the dealer's up-card.
+    pub fn pick(&self) -> ([u8; 2], u8) {
+        let pick = |values: &[u8]| *values.choose(&mut thread_rng()).unwrap_or(&10);
+
+        let player = match &self.player {
+            PlayerSpec::Pairs(values) => hand_values(HandKind::Pair, pick(values)),
+            PlayerSpec::Soft(totals) => hand_values(HandKind::Soft, pick(totals)),
+            PlayerSpec::Hard(totals) => hand_values(HandKind::Hard, pick(totals)),
+            PlayerSpec::Cards(cards) => *cards,
+        };
+        (player, pick(&self.dealer))
+    }
+
+    /// Deal the player and dealer hands for a fresh pick of this scenario.
+    pub fn deal(&self, deck: &mut Shoe) -> (Hand<Player>, Hand<Dealer>) {
+        let ([first, second], up) = self.pick();
+        let player = Hand::new([draw_value(deck, first), draw_value(deck, second)]);
+        // the hole card comes first and is left to chance
+        let dealer = Hand::new([deck.draw(), draw_value(deck, up)]);
+        (player, dealer)
+    }
+}
+impl Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.definition)
+    }
+}
+impl TryFrom<String> for Scenario {
+    type Error = String;
+
+    fn try_from(definition: String) -> Result<Self, Self::Error> {
+        Scenario::parse(&definition)
+    }
+}
+impl From<Scenario> for String {
+    fn from(scenario: Scenario) -> Self {
+        scenario.definition
+    }
+}
+
+/// Card values for a two-card hand of the given kind, where `total` is the value of one
+/// card for pairs. Hard hands are made without aces or pairs, where the total allows.
+pub fn hand_values(kind: HandKind, total: u8) -> [u8; 2] {
+    match kind {
+        HandKind::Pair => [total, total],
+        HandKind::Soft => [11, total - 11],
+        HandKind::Hard => {
+            let splits: Vec<u8> = (2..=10)
+                .filter(|first| total > *first && total != first * 2)
+                .filter(|first| (2..=10).contains(&(total - first)))
+                .collect();
+            let first = *splits.choose(&mut thread_rng()).unwrap_or(&2);
+            [first, total - first]
+        }
+    }
+}
+
+/// Parse card values like `8`, `A`, `9-A`, or `2,3,4`.
+fn parse_values(values: &str) -> Result<Vec<u8>, String> {
+    let value = |value: &str| match value.trim() {
+        "a" => Ok(11),
+        "j" | "q" | "k" => Ok(10),
+        value => value
+            .parse::<u8>()
+            .map_err(|_| format!("expected a card value, found '{value}'")),
+    };
+
+    let mut parsed = Vec::new();
+    for part in values.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => parsed.extend(value(from)?..=value(to)?),
+            None => parsed.push(value(part)?),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Take a card of the given value from the shoe, shuffling the discards in if none are
+/// left.
+pub fn draw_value(deck: &mut Shoe, value: u8) -> Card {
+    deck.take(|card| card.rank().get_value() == value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Situation;
+
+    #[test]
+    fn deals_the_hands_it_describes() {
+        let scenario = Scenario::parse("8,8 vs 9-A").unwrap();
+        assert_eq!(scenario.player, PlayerSpec::Cards([8, 8]));
+        assert_eq!(scenario.dealer, [9, 10, 11]);
+        let mut deck = Shoe::new(1);
+        for _ in 0..20 {
+            let (player, dealer) = scenario.deal(&mut deck);
+            let situation = Situation::new(&player, dealer.up_card());
+            assert_eq!((situation.kind, situation.total), (HandKind::Pair, 8));
+            assert!((9..=11).contains(&situation.up), "{situation}");
+            deck.discard(player.cards().iter().chain(dealer.cards()).copied());
+        }
+
+        let soft = Scenario::parse("soft-18").unwrap();
+        assert_eq!(soft.to_string(), "soft 18 vs any");
+        for _ in 0..20 {
+            let ([first, second], _) = soft.pick();
+            assert_eq!((first, second), (11, 7));
+        }
+        // hard hands aren't dealt as pairs, which are read from their own rows
+        for _ in 0..20 {
+            let [first, second] = hand_values(HandKind::Hard, 16);
+            assert!(first != second && first + second == 16);
+        }
+    }
+
+    #[test]
+    fn refuses_hands_that_cannot_be_dealt() {
+        for definition in [
+            "soft 18",
+            "hard 4 vs 6",
+            "soft 22 vs 6",
+            "8 vs 9",
+            "8,8 vs 1",
+        ] {
+            assert!(Scenario::parse(definition).is_err(), "{definition}");
+        }
+        assert_eq!(
+            Scenario::parse("A,x vs 6").unwrap_err(),
+            "expected a card value, found 'x'"
+        );
+    }
+}
@@ -0,0 +1,521 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::Config,
+    fairness::Commitment,
+    metrics::{self, Metrics},
+    protocol::{self, ClientMessage, ServerMessage, TableState},
+    strategy::Action,
+    table::{Phase, Table},
+};
+
+pub const PORT: u16 = 7777;
+/// Most seats at the table, taken or kept for players who dropped.
+const SEATS: usize = 7;
+/// Longest chat message passed on, in characters.
+const CHAT_LENGTH: usize = 200;
+/// Longest name a player can sit down under, in characters.
+const NAME_LENGTH: usize = 24;
+/// How long the results stay up before bets are taken again.
+const RESULTS_PAUSE: Duration = Duration::from_secs(3);
+/// Longest line a client may send, in bytes. A client sending more is hung up on.
+const MAX_LINE: u64 = 4096;
+/// Messages waiting to be written to a client before it's hung up on as too far behind.
+const OUTBOX: usize = 64;
+/// How long a write to a client may block before it's hung up on.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum Event {
+    Connected(u64, Client),
+    Message(u64, Result<ClientMessage, String>),
+    Disconnected(u64),
+    /// a request for the metrics, answered on the sender
+    Scrape(Sender<String>),
+}
+
+#[derive(Debug)]
+struct Client {
+    /// lines waiting for the client's writer thread, so a slow client never holds up the
+    /// table
+    outbox: SyncSender<Arc<str>>,
+    /// the connection, to hang up on a client that can't keep up
+    stream: TcpStream,
+    seat: Option<usize>,
+    /// following the table without a seat
+    watching: bool,
+}
+impl Client {
+    /// Queue `line` to be written, hanging up if the client is too far behind to take
+    /// it. Its reader then sees the connection close and it's dropped.
+    fn post(&self, line: &Arc<str>) {
+        if self.outbox.try_send(Arc::clone(line)).is_err() {
+            let _ = self.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// A dealer for players connected over TCP, all sharing one table.
+#[derive(Debug)]
+struct Server {
+    table: Table,
+    clients: HashMap<u64, Client>,
+    /// the seat each player can take back after a dropped connection
+    tokens: HashMap<String, usize>,
+    /// how long a seat has to decide, zero for no limit
+    turn_timer: Duration,
+    /// the phase, seat, and cards in hand of the decision being timed
+    decision: Option<(Phase, usize, usize)>,
+    /// when the seat deciding runs out of time
+    turn_ends: Option<Instant>,
+    /// the order of the shoe being dealt, revealed once it is reshuffled
+    commitment: Commitment,
+    metrics: Metrics,
+    /// the round in the results has been counted in the metrics
+    counted: bool,
+}
+impl Server {
+    /// An empty table dealt by `config`.
+    fn new(config: &Config) -> Self {
+        let table = Table::new(config, []);
+        let commitment = Commitment::new(&table.deck);
+        Self {
+            table,
+            clients: HashMap::new(),
+            tokens: HashMap::new(),
+            turn_timer: config.turn_timer,
+            decision: None,
+            turn_ends: None,
+            commitment,
+            metrics: Metrics::default(),
+            counted: false,
+        }
+    }
+
+    /// Handle a client event, returning whether the table changed.
+    fn handle(&mut self, event: Event) -> bool {
+        match event {
+            Event::Connected(id, client) => {
+                self.clients.insert(id, client);
+                self.send(id, &ServerMessage::shuffle(&self.commitment));
+                let state = self.state();
+                self.send(id, &state);
+                false
+            }
+            Event::Message(id, Err(message)) => {
+                self.send(id, &ServerMessage::Error { message });
+                false
+            }
+            Event::Message(id, Ok(message)) => {
+                let chat = matches!(message, ClientMessage::Chat { .. });
+                match self.apply(id, message) {
+                    Ok(()) => !chat,
+                    Err(message) => {
+                        self.send(id, &ServerMessage::Error { message });
+                        false
+                    }
+                }
+            }
+            Event::Disconnected(id) => {
+                let Some(client) = self.clients.remove(&id) else {
+                    return false;
+                };
+                let Some(seat) = client.seat else {
+                    return client.watching;
+                };
+                println!("{} left.", self.table.seats[seat].name);
+                self.table.leave(seat);
+                true
+            }
+            Event::Scrape(reply) => {
+                let players = self.clients.values().filter(|client| client.seat.is_some());
+                let spectators = self.clients.values().filter(|client| client.watching);
+                let text = self.metrics.render(players.count(), spectators.count());
+                let _ = reply.send(text);
+                false
+            }
+        }
+    }
+
+    fn apply(&mut self, id: u64, message: ClientMessage) -> Result<(), String> {
+        let seat = self.clients.get(&id).and_then(|client| client.seat);
+        match (message, seat) {
+            (ClientMessage::Join { .. } | ClientMessage::Watch, Some(_)) => {
+                Err("you already have a seat".to_string())
+            }
+            (ClientMessage::Chat { text }, seat) => {
+                let text = printable(&text, CHAT_LENGTH);
+                if text.is_empty() {
+                    return Err("nothing to say".to_string());
+                }
+                let from = seat.map_or("Spectator".to_string(), |seat| {
+                    self.table.seats[seat].name.clone()
+                });
+                println!("{from}: {text}");
+                self.tell_everyone(&ServerMessage::Chat { from, seat, text });
+                Ok(())
+            }
+            (ClientMessage::Watch, None) => {
+                if let Some(client) = self.clients.get_mut(&id) {
+                    client.watching = true;
+                }
+                Ok(())
+            }
+            (ClientMessage::Join { name, token }, None) => {
+                let rejoin = token
+                    .as_ref()
+                    .and_then(|token| self.tokens.get(token))
+                    .copied()
+                    .filter(|&seat| self.table.seats[seat].left);
+                let (seat, token) = match (rejoin, token) {
+                    (Some(seat), Some(token)) => {
+                        self.table.rejoin(seat);
+                        println!("{} rejoined.", self.table.seats[seat].name);
+                        (seat, token)
+                    }
+                    _ => {
+                        let name = printable(&name, NAME_LENGTH);
+                        if name.is_empty() {
+                            return Err("sit down under a name".to_string());
+                        }
+                        let seat = self.table.join(name.clone(), SEATS)?;
+                        println!("{name} joined.");
+                        // whoever left the seat can't take it back now
+                        self.tokens.retain(|_, held| *held != seat);
+                        let token = format!("{:016x}", rand::random::<u64>());
+                        self.tokens.insert(token.clone(), seat);
+                        (seat, token)
+                    }
+                };
+                if let Some(client) = self.clients.get_mut(&id) {
+                    client.seat = Some(seat);
+                    client.watching = false;
+                }
+                let welcome = ServerMessage::Welcome {
+                    version: protocol::VERSION,
+                    seat,
+                    token,
+                };
+                self.send(id, &welcome);
+                Ok(())
+            }
+            (_, None) => Err("join the table first".to_string()),
+            (ClientMessage::Bet { amount }, Some(seat)) => self.table.place_bet(seat, amount),
+            (ClientMessage::Hit, Some(seat)) => self
+                .table
+                .act(seat, Action::Hit)
+                .map_err(|err| err.to_string()),
+            (ClientMessage::Stand, Some(seat)) => self
+                .table
+                .act(seat, Action::Stand)
+                .map_err(|err| err.to_string()),
+        }
+    }
+
+    /// Send to one client. A client that can't be written to is dropped when its reader
+    /// sees the connection close.
+    fn send(&self, id: u64, message: &ServerMessage) {
+        if let (Some(client), Ok(line)) = (self.clients.get(&id), protocol::line(message)) {
+            client.post(&line.into());
+        }
+    }
+
+    /// Send to every client, writing the message out once for them all.
+    fn tell_everyone(&self, message: &ServerMessage) {
+        let Ok(line) = protocol::line(message) else {
+            return;
+        };
+        let line = line.into();
+        for client in self.clients.values() {
+            client.post(&line);
+        }
+    }
+
+    fn state(&self) -> ServerMessage {
+        let spectators = self.clients.values().filter(|client| client.watching);
+        let mut state = TableState::of(&self.table, spectators.count());
+        state.time_left_ms = self
+            .turn_ends
+            .map(|ends| ends.saturating_duration_since(Instant::now()).as_millis() as u64);
+        ServerMessage::State(state)
+    }
+
+    /// Start the clock again whenever a new decision is waiting.
+    fn time_turn(&mut self) {
+        let table = &self.table;
+        let decision = matches!(table.phase, Phase::Betting | Phase::Playing).then(|| {
+            let cards = table.seats[table.turn].hand.cards().len();
+            (table.phase, table.turn, cards)
+        });
+        if decision != self.decision {
+            self.decision = decision;
+            self.turn_ends = decision
+                .filter(|_| !self.turn_timer.is_zero())
+                .map(|_| Instant::now() + self.turn_timer);
+        }
+    }
+
+    /// Sit out or stand for a seat that took too long to decide.
+    fn time_out(&mut self) {
+        self.turn_ends = None;
+        let seat = self.table.turn;
+        println!("{} ran out of time.", self.table.seats[seat].name);
+        match self.table.phase {
+            Phase::Betting => {
+                let _ = self.table.place_bet(seat, 0);
+            }
+            Phase::Playing => {
+                let _ = self.table.act(seat, Action::Stand);
+            }
+            Phase::Dealer | Phase::Results => return,
+        }
+        self.tell_everyone(&ServerMessage::TimedOut { seat });
+    }
+
+    /// Count a round in the metrics once its results are in.
+    fn count_round(&mut self) {
+        let settled = self.table.phase == Phase::Results && !self.table.dealer.cards().is_empty();
+        if settled && !self.counted {
+            self.metrics.record_round(&self.table);
+        }
+        self.counted = settled;
+    }
+
+    /// Reveal the last shoe and publish the hash of the new one once the deck has been
+    /// reshuffled, before anyone sees a card from it.
+    fn check_shuffle(&mut self) {
+        if self.table.deck.shuffles() == self.commitment.shoe {
+            return;
+        }
+        let commitment = Commitment::new(&self.table.deck);
+        let shuffle = ServerMessage::shuffle(&commitment);
+        let reveal = ServerMessage::reveal(std::mem::replace(&mut self.commitment, commitment));
+        self.tell_everyone(&reveal);
+        self.tell_everyone(&shuffle);
+    }
+
+    fn broadcast(&self) {
+        self.tell_everyone(&self.state());
+    }
+}
+
+/// `text` without control characters, so nothing a client sends can move the host's
+/// cursor or clear its screen, trimmed and cut to `length` characters.
+fn printable(text: &str, length: usize) -> String {
+    let text: String = text.chars().filter(|c| !c.is_control()).collect();
+    text.trim().chars().take(length).collect()
+}
+
+/// Accept connections, passing each one and its messages on as events. Each client has
+/// a thread reading from it and another writing to it.
+fn accept(listener: TcpListener, events: Sender<Event>) {
+    for (stream, id) in listener.incoming().flatten().zip(0..) {
+        let (Ok(reader), Ok(writer)) = (stream.try_clone(), stream.try_clone()) else {
+            continue;
+        };
+        let _ = stream.set_nodelay(true);
+        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+        let (outbox, lines) = mpsc::sync_channel(OUTBOX);
+        thread::spawn(move || write_out(writer, lines));
+        let client = Client {
+            outbox,
+            stream,
+            seat: None,
+            watching: false,
+        };
+        if events.send(Event::Connected(id, client)).is_err() {
+            return;
+        }
+        let events = events.clone();
+        thread::spawn(move || read_in(id, reader, &events));
+    }
+}
+
+/// Pass on each line client `id` sends, until it hangs up or sends a line longer than
+/// [`MAX_LINE`].
+fn read_in(id: u64, stream: TcpStream, events: &Sender<Event>) {
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match (&mut reader).take(MAX_LINE).read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if !line.ends_with('\n') && line.len() as u64 == MAX_LINE => {
+                let _ = events.send(Event::Message(id, Err("message too long".to_string())));
+                break;
+            }
+            Ok(_) => (),
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        if events
+            .send(Event::Message(id, protocol::parse(&line)))
+            .is_err()
+        {
+            return;
+        }
+    }
+    // the writer hangs up once it has written what's queued, like why it was dropped
+    let _ = events.send(Event::Disconnected(id));
+}
+
+/// Write each line queued for a client, until the server drops it or a write fails,
+/// which hangs up so its reader sees the connection close.
+fn write_out(mut stream: TcpStream, lines: Receiver<Arc<str>>) {
+    for line in lines {
+        if stream.write_all(line.as_bytes()).is_err() {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+/// Ask the table for its metrics on each request to the metrics endpoint.
+fn serve_metrics(listener: TcpListener, events: Sender<Event>) {
+    metrics::serve(listener, move || {
+        let (reply, text) = mpsc::channel();
+        events.send(Event::Scrape(reply)).ok()?;
+        text.recv().ok()
+    });
+}
+
+/// Deal a table to players connecting on `port`, until the process is stopped, serving
+/// Prometheus metrics over HTTP at `metrics` if given.
+pub fn host(config: &Config, port: u16, metrics: Option<SocketAddr>) -> io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    println!("Hosting a table on port {port} with {}.", config.rules);
+    let (events, received) = mpsc::channel();
+    if let Some(metrics) = metrics {
+        let listener = TcpListener::bind(metrics)?;
+        println!("Serving metrics at http://{metrics}/metrics.");
+        let events = events.clone();
+        thread::spawn(move || serve_metrics(listener, events));
+    }
+    thread::spawn(move || accept(listener, events));
+
+    let mut server = Server::new(config);
+    // when the dealer draws next, or the next round starts
+    let mut step_at = None;
+    loop {
+        let wait = step_at
+            .into_iter()
+            .chain(server.turn_ends)
+            .min()
+            .map_or(Duration::from_secs(60), |at: Instant| {
+                at.saturating_duration_since(Instant::now())
+            });
+        let mut changed = match received.recv_timeout(wait) {
+            Ok(event) => server.handle(event),
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        if server.turn_ends.is_some_and(|at| at <= Instant::now()) {
+            server.time_out();
+            changed = true;
+        }
+        if step_at.is_some_and(|at| at <= Instant::now()) {
+            step_at = None;
+            match server.table.phase {
+                Phase::Dealer => server.table.dealer_step(),
+                Phase::Results => server.table.new_round(),
+                Phase::Betting | Phase::Playing => (),
+            }
+            changed = true;
+        }
+        if step_at.is_none() {
+            step_at = match server.table.phase {
+                Phase::Dealer => Some(Instant::now() + config.dealer_pace()),
+                // nothing was dealt, so there are no results to show
+                Phase::Results if server.table.dealer.cards().is_empty() => {
+                    (!server.table.is_over()).then(Instant::now)
+                }
+                Phase::Results if !server.table.is_over() => Some(Instant::now() + RESULTS_PAUSE),
+                Phase::Betting | Phase::Playing | Phase::Results => None,
+            };
+        }
+        server.time_turn();
+        server.count_round();
+        server.check_shuffle();
+        if changed {
+            server.broadcast();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_control_characters_out() {
+        assert_eq!(printable(" \x1b[2Jhi\u{7}\n", 10), "[2Jhi");
+        assert_eq!(printable("abcdef", 3), "abc");
+        assert_eq!(printable("\r\n\t", 3), "");
+    }
+
+    #[test]
+    fn seats_players_under_printable_names() {
+        let mut server = Server::new(&Config::default());
+        let join = |name: &str| ClientMessage::Join {
+            name: name.to_string(),
+            token: None,
+        };
+        assert!(server.apply(0, join("\x1b\x07 ")).is_err());
+        assert!(server.apply(0, join(&"x".repeat(100))).is_ok());
+        assert_eq!(server.table.seats[0].name, "x".repeat(NAME_LENGTH));
+    }
+
+    #[test]
+    fn caps_the_seats_and_gives_left_ones_away() {
+        let mut server = Server::new(&Config::default());
+        let join = |name: &str| ClientMessage::Join {
+            name: name.to_string(),
+            token: None,
+        };
+        for seat in 0..SEATS {
+            server.apply(seat as u64, join("Ada")).unwrap();
+        }
+        assert_eq!(
+            server.apply(9, join("Bo")),
+            Err("the table is full".to_string())
+        );
+
+        server.table.leave(3);
+        server.apply(9, join("Bo")).unwrap();
+        assert_eq!(server.table.seats.len(), SEATS);
+        assert_eq!(server.table.seats[3].name, "Bo");
+        assert_eq!(server.tokens.len(), SEATS);
+        assert_eq!(server.tokens.values().filter(|&&seat| seat == 3).count(), 1);
+    }
+
+    #[test]
+    fn hangs_up_on_lines_too_long() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let (events, received) = mpsc::channel();
+        let reader = thread::spawn(move || read_in(3, stream, &events));
+        client.write_all(b"{\"type\":\"hit\"}\n").unwrap();
+        client.write_all(&[b'x'; MAX_LINE as usize + 1]).unwrap();
+        reader.join().unwrap();
+        let events: Vec<_> = received.into_iter().collect();
+        assert!(matches!(
+            events[..],
+            [
+                Event::Message(3, Ok(ClientMessage::Hit)),
+                Event::Message(3, Err(_)),
+                Event::Disconnected(3)
+            ]
+        ));
+    }
+}
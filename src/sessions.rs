@@ -0,0 +1,251 @@
+use std::io::Write;
+
+use crate::{
+    betting::{BettingSystem, Progression},
+    config::Config,
+    counting,
+    simulate::{self, Strategy},
+};
+
+/// Length and pace of each simulated playing session.
+#[derive(Clone, Copy, Debug)]
+pub struct SessionPlan {
+    pub sessions: u32,
+    pub hours: f64,
+    /// hands dealt in an hour at a real table
+    pub hands_per_hour: u32,
+}
+impl SessionPlan {
+    pub const HOURS: f64 = 4.0;
+    pub const HANDS_PER_HOUR: u32 = 80;
+
+    fn hands(&self) -> u32 {
+        (self.hours * f64::from(self.hands_per_hour)).round() as u32
+    }
+}
+
+/// How one session ended.
+#[derive(Clone, Copy, Debug)]
+struct Session {
+    bankroll: u32,
+    /// the bankroll fell below the smallest bet
+    ruined: bool,
+}
+
+/// Play a session from the configured bankroll. Without a betting system, bets follow the
+/// strategy's ramp or the count's spread.
+fn play_session(
+    config: &Config,
+    plan: &SessionPlan,
+    strategy: &Strategy,
+    betting: Option<BettingSystem>,
+) -> Session {
+    let mut game = crate::game::Game::new(config);
+    let spread = config.bet_spread();
+    let mut progression = betting.map(|system| Progression::new(system, spread));
+    for _ in 0..plan.hands() {
+        if game.bankroll < config.bet_unit {
+            return Session {
+                bankroll: game.bankroll,
+                ruined: true,
+            };
+        }
+        let decks_remaining = counting::decks_remaining(&game.deck);
+        let bet = match (&progression, strategy.ramp_units(&game)) {
+            (Some(progression), _) => progression.bet(game.bankroll, &game.count, decks_remaining),
+            (None, Some(units)) => units * config.bet_unit,
+            (None, None) => spread.suggest(&game.count, decks_remaining),
+        }
+        .min(game.bankroll);
+        simulate::play_hand(&mut game, strategy, bet);
+        if let Some(progression) = progression.as_mut() {
+            progression.record(game.payout);
+        }
+        game.new_hand();
+    }
+    Session {
+        bankroll: game.bankroll,
+        ruined: game.bankroll < config.bet_unit,
+    }
+}
+
+/// Final bankrolls over many sessions.
+#[derive(Debug)]
+struct Distribution {
+    /// sorted, lowest first
+    sessions: Vec<Session>,
+    start: u32,
+}
+impl Distribution {
+    const PERCENTILES: [u32; 5] = [5, 25, 50, 75, 95];
+
+    fn new(mut sessions: Vec<Session>, start: u32) -> Self {
+        sessions.sort_by_key(|session| session.bankroll);
+        Self { sessions, start }
+    }
+
+    fn count(&self) -> f64 {
+        self.sessions.len().max(1) as f64
+    }
+
+    /// Share of sessions that went broke, in percent.
+    fn risk_of_ruin(&self) -> f64 {
+        let ruined = self
+            .sessions
+            .iter()
+            .filter(|session| session.ruined)
+            .count();
+        ruined as f64 / self.count() * 100.0
+    }
+
+    fn results(&self) -> impl Iterator<Item = f64> + '_ {
+        self.sessions
+            .iter()
+            .map(|session| f64::from(session.bankroll) - f64::from(self.start))
+    }
+
+    fn mean(&self) -> f64 {
+        self.results().sum::<f64>() / self.count()
+    }
+
+    fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        let variance = self
+            .results()
+            .map(|result| (result - mean).powi(2))
+            .sum::<f64>()
+            / self.count();
+        variance.sqrt()
+    }
+
+    fn percentile(&self, percentile: u32) -> u32 {
+        let index = (f64::from(percentile) / 100.0 * (self.count() - 1.0)).round() as usize;
+        self.sessions
+            .get(index)
+            .map_or(0, |session| session.bankroll)
+    }
+}
+
+pub fn run(
+    config: &Config,
+    plan: SessionPlan,
+    strategy: &Strategy,
+    betting: &[BettingSystem],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdout = std::io::stdout();
+    writeln!(
+        stdout,
+        "Simulating {} sessions of {} hours ({} hands), bankroll {}, bet unit {}\n",
+        plan.sessions,
+        plan.hours,
+        plan.hands(),
+        config.bankroll,
+        config.bet_unit
+    )?;
+    let play = |betting| {
+        let sessions = (0..plan.sessions)
+            .map(|_| play_session(config, &plan, strategy, betting))
+            .collect();
+        Distribution::new(sessions, config.bankroll)
+    };
+
+    if !betting.is_empty() {
+        write!(
+            stdout,
+            "{:<11} {:>7} {:>9} {:>8}",
+            "Betting", "Ruin", "Mean", "Std dev"
+        )?;
+        for percentile in Distribution::PERCENTILES {
+            write!(stdout, " {:>7}", format!("{percentile}%"))?;
+        }
+        writeln!(stdout)?;
+        for system in betting {
+            let distribution = play(Some(*system));
+            write!(
+                stdout,
+                "{:<11} {:>6.2}% {:>+9.1} {:>8.1}",
+                system.name(),
+                distribution.risk_of_ruin(),
+                distribution.mean(),
+                distribution.std_dev()
+            )?;
+            for percentile in Distribution::PERCENTILES {
+                write!(stdout, " {:>7}", distribution.percentile(percentile))?;
+            }
+            writeln!(stdout)?;
+        }
+        return Ok(());
+    }
+
+    let distribution = play(None);
+    writeln!(
+        stdout,
+        "Risk of ruin:        {:.2}%",
+        distribution.risk_of_ruin()
+    )?;
+    writeln!(
+        stdout,
+        "Expected per hour:   {:+.2}",
+        distribution.mean() / plan.hours.max(f64::EPSILON)
+    )?;
+    writeln!(
+        stdout,
+        "Session result:      {:+.1} ± {:.1}",
+        distribution.mean(),
+        distribution.std_dev()
+    )?;
+    writeln!(stdout, "Final bankroll percentiles:")?;
+    for percentile in Distribution::PERCENTILES {
+        writeln!(
+            stdout,
+            "  {percentile:>2}%  {}",
+            distribution.percentile(percentile)
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(bankroll: u32, ruined: bool) -> Session {
+        Session { bankroll, ruined }
+    }
+
+    #[test]
+    fn summarizes_the_final_bankrolls() {
+        let distribution = Distribution::new(
+            vec![
+                session(150, false),
+                session(0, true),
+                session(100, false),
+                session(50, false),
+            ],
+            100,
+        );
+        assert_eq!(distribution.risk_of_ruin(), 25.0);
+        assert_eq!(distribution.mean(), -25.0);
+        assert!((distribution.std_dev() - 55.9).abs() < 0.1);
+        assert_eq!(distribution.percentile(5), 0);
+        assert_eq!(distribution.percentile(50), 100);
+        assert_eq!(distribution.percentile(95), 150);
+    }
+
+    #[test]
+    fn a_bankroll_below_the_smallest_bet_is_ruined() {
+        let config = Config {
+            bankroll: 5,
+            bet_unit: 10,
+            ..Config::default()
+        };
+        let plan = SessionPlan {
+            sessions: 1,
+            hours: SessionPlan::HOURS,
+            hands_per_hour: SessionPlan::HANDS_PER_HOUR,
+        };
+        let session = play_session(&config, &plan, &Strategy::Basic, Some(BettingSystem::Flat));
+        assert!(session.ruined);
+        assert_eq!(session.bankroll, 5);
+    }
+}
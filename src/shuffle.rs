@@ -0,0 +1,187 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::{ChaCha12Rng, ChaCha20Rng};
+
+use crate::{
+    history::{card_code, parse_card_code},
+    widgets::Card,
+};
+
+/// Where the randomness every shoe is shuffled with comes from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shuffler {
+    /// ChaCha keyed afresh from the operating system's secure random numbers for every
+    /// shuffle, for real play
+    #[default]
+    Secure,
+    /// ChaCha from a seed, so every shoe is shuffled the same way each time
+    Seeded(u64),
+    /// these numbers over and over, each picking which of the cards not yet placed goes
+    /// next, for tests that need to know how the cards fall
+    Scripted(Vec<u64>),
+    /// the cards of a shoe file in the order they're drawn for the first shoe, and secure
+    /// shuffles after it
+    Stacked(Vec<Card>),
+}
+impl Shuffler {
+    /// Parse `secure`, `seed:N`, `scripted:N,N,…`, or `file:PATH` of a shoe file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let expected = || {
+            format!(
+                "unknown shuffle '{text}', expected secure, seed:N, scripted:N,N,…, or file:PATH"
+            )
+        };
+        match text.split_once(':') {
+            None if text == "secure" => Ok(Shuffler::Secure),
+            Some(("seed", seed)) => seed
+                .trim()
+                .parse()
+                .map(Shuffler::Seeded)
+                .map_err(|_| expected()),
+            Some(("scripted", values)) => values
+                .split(',')
+                .map(|value| value.trim().parse())
+                .collect::<Result<_, _>>()
+                .map(Shuffler::Scripted)
+                .map_err(|_| expected()),
+            Some(("file", path)) => read_shoe(Path::new(path.trim())).map(Shuffler::Stacked),
+            _ => Err(expected()),
+        }
+    }
+
+    /// The generator for a shoe's `shuffle`th shuffle. Seeded shoes start each shuffle
+    /// from the seed plus its number. Scripted shoes have none, as their script places
+    /// the cards itself with [`play_script`].
+    pub fn rng(&self, shuffle: u32) -> Option<Box<dyn RngCore>> {
+        match self {
+            Shuffler::Secure | Shuffler::Stacked(_) => Some(Box::new(ChaCha20Rng::from_entropy())),
+            Shuffler::Seeded(seed) => Some(Box::new(ChaCha12Rng::seed_from_u64(
+                seed.wrapping_add(u64::from(shuffle)),
+            ))),
+            Shuffler::Scripted(_) => None,
+        }
+    }
+
+    /// A deck that deals the cards `first` names, written as in the hand history, and
+    /// then the rest of the deck, for tests that deal a known hand.
+    #[cfg(test)]
+    pub(crate) fn stacked(first: &str) -> Self {
+        let first: Vec<_> = first.split(' ').filter_map(parse_card_code).collect();
+        let mut rest = crate::widgets::Shoe::new(1).full();
+        rest.retain(|card| !first.contains(card));
+        Shuffler::Stacked([first, rest].concat())
+    }
+}
+
+/// Shuffle `cards` the way Fisher and Yates do, from the last card down, with each value
+/// of the script (over and over) taken modulo the cards left to pick from, so any
+/// number at all picks a card.
+pub fn play_script(cards: &mut [Card], values: &[u64]) {
+    if values.is_empty() {
+        return;
+    }
+    let mut script = values.iter().cycle();
+    for i in (1..cards.len()).rev() {
+        let value = script.next().copied().unwrap_or_default();
+        // the remainder is at most `i`, so it fits back in a usize
+        let j = (value % (i as u64 + 1)) as usize;
+        cards.swap(i, j);
+    }
+}
+
+/// Write a shoe file of `cards`, in the order they're drawn, to deal again with
+/// `--shuffle file:PATH`.
+pub fn write_shoe<'a>(
+    path: &Path,
+    cards: impl IntoIterator<Item = &'a Card>,
+) -> Result<(), String> {
+    let codes: Vec<_> = cards.into_iter().map(card_code).collect();
+    let decks = match codes.len() / 52 {
+        1 => "1 deck".to_string(),
+        decks => format!("{decks} decks"),
+    };
+    let text = format!(
+        "# blackjack-tui shoe of {decks}, in the order the cards are drawn\n{}\n",
+        codes.join(" ")
+    );
+    fs::write(path, text).map_err(|err| format!("couldn't write {}: {err}", path.display()))
+}
+
+/// Read a shoe file: card codes like `10H` or `QS` in the order they're drawn, separated
+/// by spaces, commas, or lines, with `#` starting a comment. It has to hold whole decks.
+pub fn read_shoe(path: &Path) -> Result<Vec<Card>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("couldn't read {}: {err}", path.display()))?;
+    let mut cards = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        for code in line.split([' ', ',', '\t']).filter(|code| !code.is_empty()) {
+            let card = parse_card_code(code).ok_or_else(|| {
+                format!("'{code}' in {} isn't a card like 10H or QS", path.display())
+            })?;
+            cards.push(card);
+        }
+    }
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for card in &cards {
+        *counts.entry(card_code(card)).or_default() += 1;
+    }
+    let decks = cards.len() / 52;
+    if decks == 0 || counts.len() != 52 || counts.values().any(|&n| n != decks) {
+        return Err(format!(
+            "the shoe in {} isn't made of whole decks",
+            path.display()
+        ));
+    }
+    Ok(cards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rules::Rules,
+        widgets::{Rank, Shoe, Suit},
+    };
+
+    #[test]
+    fn parses_shufflers() {
+        assert_eq!(Shuffler::parse("secure"), Ok(Shuffler::Secure));
+        assert_eq!(Shuffler::parse("seed: 42"), Ok(Shuffler::Seeded(42)));
+        assert_eq!(
+            Shuffler::parse("scripted:1, 2,3"),
+            Ok(Shuffler::Scripted(vec![1, 2, 3]))
+        );
+        assert!(Shuffler::parse("seed:x").is_err());
+        assert!(Shuffler::parse("lucky").is_err());
+    }
+
+    #[test]
+    fn scripts_pick_any_card_with_any_number() {
+        // values this big once sent the shuffle looking for a number in range forever
+        let shuffler = Shuffler::Scripted(vec![u64::MAX]);
+        let first = Shoe::for_table(&Rules::default(), shuffler.clone());
+        let again = Shoe::for_table(&Rules::default(), shuffler);
+        assert_eq!(first.undealt(), again.undealt());
+        assert_eq!(first.undealt().len(), first.full().len());
+    }
+
+    #[test]
+    fn scripts_of_zeros_rotate_the_cards() {
+        let mut cards = [Rank::Two, Rank::Three, Rank::Four, Rank::Five]
+            .map(|rank| Card::new(rank, Suit::Spade));
+        play_script(&mut cards, &[0]);
+        let ranks = cards.map(|card| card.rank());
+        assert_eq!(ranks, [Rank::Three, Rank::Four, Rank::Five, Rank::Two]);
+    }
+
+    #[test]
+    fn seeds_shuffle_the_same_each_time() {
+        let shuffler = Shuffler::Seeded(7);
+        let first = Shoe::for_table(&Rules::default(), shuffler.clone());
+        let again = Shoe::for_table(&Rules::default(), shuffler);
+        assert_eq!(first.undealt(), again.undealt());
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt::Debug;
+
+use crate::{
+    jackpot::Jackpot,
+    widgets::{Dealer, Hand, Player, Rank, Suit},
+};
+
+/// A wager on the cards dealt, made next to the main bet and settled as soon as they're
+/// dealt. Bets are offered at the table by listing them in the `side_bets` setting.
+pub trait SideBet: Debug + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Key that turns the bet on and off on the bet screen.
+    fn key(&self) -> char;
+
+    /// The bet can win the progressive jackpot, which is shown while it's offered.
+    fn is_progressive(&self) -> bool {
+        false
+    }
+
+    /// Why `stake` can't be wagered on top of `staked` chips already bet from `bankroll`,
+    /// if it can't. The bet sits out any hand it can't be wagered on.
+    fn check_stake(&self, stake: u32, staked: u32, bankroll: u32) -> Result<(), String> {
+        match staked.checked_add(stake) {
+            Some(total) if total <= bankroll => Ok(()),
+            _ => Err(format!(
+                "the bankroll doesn't cover {} as well as the bet",
+                self.name()
+            )),
+        }
+    }
+
+    /// The winning hand the cards just dealt make, if any.
+    fn evaluate(&self, player_hand: &Hand<Player>, dealer_hand: &Hand<Dealer>) -> Option<Win>;
+
+    /// Chips won, or lost if negative, on `stake`, with `jackpot` chips in the progressive
+    /// jackpot.
+    fn payout(&self, stake: u32, win: Option<Win>, jackpot: u64) -> i64 {
+        match win.map(|win| win.pays) {
+            Some(Pays::ToOne(pays)) => i64::from(stake) * i64::from(pays),
+            Some(Pays::Jackpot) => jackpot as i64,
+            None => -i64::from(stake),
+        }
+    }
+}
+
+/// A winning side bet hand and what it pays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Win {
+    pub hand: &'static str,
+    pub pays: Pays,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pays {
+    /// this many times the stake
+    ToOne(u32),
+    /// the whole progressive jackpot
+    Jackpot,
+}
+
+/// Lucky Ladies, which pays when the player's first two cards make 20, and the most for a
+/// pair of queens of hearts.
+#[derive(Debug)]
+pub struct LuckyLadies;
+impl SideBet for LuckyLadies {
+    fn name(&self) -> &'static str {
+        "Lucky Ladies"
+    }
+
+    fn key(&self) -> char {
+        'l'
+    }
+
+    fn evaluate(&self, player_hand: &Hand<Player>, dealer_hand: &Hand<Dealer>) -> Option<Win> {
+        let [first, second] = player_hand.cards().get(..2)?.try_into().ok()?;
+        if Hand::<Player>::new([first, second]).count_value() != 20 {
+            return None;
+        }
+        let queens_of_hearts = [first, second]
+            .iter()
+            .all(|card| matches!((card.rank(), card.suit()), (Rank::Queen, Suit::Heart)));
+        let (hand, pays) = match (first == second, first.suit() == second.suit()) {
+            _ if queens_of_hearts && dealer_hand.is_blackjack() => {
+                ("queens of hearts against a blackjack", 1000)
+            }
+            _ if queens_of_hearts => ("queen of hearts pair", 200),
+            (true, _) => ("matched 20", 25),
+            (false, true) => ("suited 20", 10),
+            (false, false) => ("20", 4),
+        };
+        Some(Win {
+            hand,
+            pays: Pays::ToOne(pays),
+        })
+    }
+}
+
+/// The progressive, which pays on a blackjack, and the whole jackpot for the ace and jack
+/// of spades against a dealer blackjack.
+#[derive(Debug)]
+pub struct Progressive;
+impl SideBet for Progressive {
+    fn name(&self) -> &'static str {
+        "Progressive"
+    }
+
+    fn key(&self) -> char {
+        'j'
+    }
+
+    fn is_progressive(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, player_hand: &Hand<Player>, dealer_hand: &Hand<Dealer>) -> Option<Win> {
+        let [first, second] = player_hand.cards().get(..2)?.try_into().ok()?;
+        if !Hand::<Player>::new([first, second]).is_blackjack() {
+            return None;
+        }
+        let suited = first.suit() == second.suit();
+        let spades = suited && matches!(first.suit(), Suit::Spade);
+        let ace_jack = [first, second]
+            .iter()
+            .any(|card| matches!(card.rank(), Rank::Jack));
+        let (hand, pays) = match (spades && ace_jack, suited) {
+            _ if spades && ace_jack && dealer_hand.is_blackjack() => {
+                ("ace and jack of spades against a blackjack", Pays::Jackpot)
+            }
+            (true, _) => ("ace and jack of spades", Pays::ToOne(100)),
+            (false, true) => ("suited blackjack", Pays::ToOne(25)),
+            (false, false) => ("blackjack", Pays::ToOne(5)),
+        };
+        Some(Win { hand, pays })
+    }
+}
+
+pub const SIDE_BETS: [&dyn SideBet; 2] = [&LuckyLadies, &Progressive];
+
+/// Find a side bet by name, ignoring case and punctuation.
+pub fn side_bet_by_name(name: &str) -> Option<&'static dyn SideBet> {
+    let normalize = |name: &str| {
+        name.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_lowercase()
+    };
+    SIDE_BETS
+        .into_iter()
+        .find(|bet| normalize(bet.name()) == normalize(name))
+}
+
+/// Parse the side bets offered at the table and their stakes, like `lucky-ladies:5`, with
+/// a stake of zero or none for a bet that starts off, or `none` for no side bets.
+pub fn parse_side_bets(value: &str) -> Result<Vec<Wager>, String> {
+    if value == "none" {
+        return Ok(Vec::new());
+    }
+    value
+        .split(',')
+        .map(|entry| {
+            let (name, stake) = entry.split_once(':').unwrap_or((entry, "0"));
+            let bet = side_bet_by_name(name.trim()).ok_or_else(|| {
+                let names = SIDE_BETS.map(|bet| bet.name()).join(", ");
+                format!(
+                    "unknown side bet '{}', expected one of {names}",
+                    name.trim()
+                )
+            })?;
+            let stake = stake
+                .trim()
+                .parse()
+                .map_err(|_| format!("expected a stake in chips, found '{}'", stake.trim()))?;
+            Ok(Wager { bet, stake })
+        })
+        .collect()
+}
+
+/// A side bet offered at the table and the chips staked on it each hand, zero while it's
+/// turned off.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wager {
+    #[cfg_attr(feature = "serde", serde(with = "bet_name"))]
+    pub bet: &'static dyn SideBet,
+    pub stake: u32,
+}
+
+/// How a side bet came out on the hand just dealt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settled {
+    pub name: String,
+    pub stake: u32,
+    /// the winning hand, if the bet won
+    pub hand: Option<String>,
+    pub payout: i64,
+}
+impl Settled {
+    /// Settle `wager` on the cards just dealt, after feeding its share to the `jackpot`.
+    pub fn new(
+        wager: Wager,
+        player_hand: &Hand<Player>,
+        dealer_hand: &Hand<Dealer>,
+        jackpot: &mut Jackpot,
+    ) -> Self {
+        jackpot.feed(wager.stake);
+        let win = wager.bet.evaluate(player_hand, dealer_hand);
+        let payout = wager.bet.payout(wager.stake, win, jackpot.pool);
+        if win.is_some_and(|win| win.pays == Pays::Jackpot) {
+            jackpot.reset();
+        }
+        Self {
+            name: wager.bet.name().to_string(),
+            stake: wager.stake,
+            hand: win.map(|win| win.hand.to_string()),
+            payout,
+        }
+    }
+}
+
+/// Side bets are saved by name.
+#[cfg(feature = "serde")]
+mod bet_name {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::SideBet;
+
+    pub fn serialize<S: Serializer>(
+        bet: &&'static dyn SideBet,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(bet.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<&'static dyn SideBet, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        super::side_bet_by_name(&name)
+            .ok_or_else(|| D::Error::custom(format!("unknown side bet '{name}'")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::parse_card_code;
+
+    fn cards(codes: &str) -> [crate::widgets::Card; 2] {
+        let cards: Vec<_> = codes.split(' ').filter_map(parse_card_code).collect();
+        [cards[0], cards[1]]
+    }
+
+    fn pays(bet: &dyn SideBet, player: &str, dealer: &str) -> Option<Pays> {
+        let win = bet.evaluate(&Hand::new(cards(player)), &Hand::new(cards(dealer)));
+        win.map(|win| win.pays)
+    }
+
+    #[test]
+    fn lucky_ladies_pays_on_twenties() {
+        let bet = &LuckyLadies;
+        assert_eq!(pays(bet, "QH QH", "KS AS"), Some(Pays::ToOne(1000)));
+        assert_eq!(pays(bet, "QH QH", "KS 9S"), Some(Pays::ToOne(200)));
+        assert_eq!(pays(bet, "KS KS", "KS 9S"), Some(Pays::ToOne(25)));
+        assert_eq!(pays(bet, "KS QS", "KS 9S"), Some(Pays::ToOne(10)));
+        assert_eq!(pays(bet, "KS AH", "KS 9S"), None);
+        assert_eq!(pays(bet, "9S AH", "KS 9S"), Some(Pays::ToOne(4)));
+    }
+
+    #[test]
+    fn the_progressive_pays_on_blackjacks() {
+        let bet = &Progressive;
+        assert_eq!(pays(bet, "AS JS", "KH AH"), Some(Pays::Jackpot));
+        assert_eq!(pays(bet, "JS AS", "KH 9H"), Some(Pays::ToOne(100)));
+        assert_eq!(pays(bet, "AH KH", "KS 9S"), Some(Pays::ToOne(25)));
+        assert_eq!(pays(bet, "AH KS", "KS 9S"), Some(Pays::ToOne(5)));
+        assert_eq!(pays(bet, "KH KS", "KS 9S"), None);
+    }
+
+    #[test]
+    fn settling_feeds_and_pays_the_jackpot() {
+        let mut jackpot = Jackpot::default();
+        let wager = Wager {
+            bet: &Progressive,
+            stake: 5,
+        };
+        let lost = Settled::new(
+            wager,
+            &Hand::new(cards("KH KS")),
+            &Hand::new(cards("KS 9S")),
+            &mut jackpot,
+        );
+        assert_eq!(lost.payout, -5);
+        // a tenth of the stake, rounded up to a chip
+        assert_eq!(jackpot.pool, 1001);
+
+        let hit = Settled::new(
+            wager,
+            &Hand::new(cards("AS JS")),
+            &Hand::new(cards("KH AH")),
+            &mut jackpot,
+        );
+        assert_eq!(hit.payout, 1002);
+        assert_eq!(jackpot, Jackpot::default());
+    }
+
+    #[test]
+    fn parses_the_bets_offered() {
+        let wagers = parse_side_bets("lucky-ladies:5, Progressive").unwrap();
+        let wagers: Vec<_> = wagers.iter().map(|w| (w.bet.name(), w.stake)).collect();
+        assert_eq!(wagers, [("Lucky Ladies", 5), ("Progressive", 0)]);
+        assert!(parse_side_bets("none").unwrap().is_empty());
+        assert!(parse_side_bets("perfect-pairs:5").is_err());
+        assert!(parse_side_bets("lucky-ladies:lots").is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use std::{io::Write, path::PathBuf, time::Instant};
+
+use crate::{
+    betting::BetSpread,
+    config::Config,
+    counting,
+    game::*,
+    report::{self, HandLog, Summary},
+    rules::Rules,
+    strategy::{self, Action},
+    strategy_file::StrategyFile,
+};
+
+/// Chips in one betting unit, enough for 3:2 and 6:5 blackjacks to pay out exactly.
+const UNIT: u32 = 10;
+
+/// How the simulated player decides to hit or hold.
+#[derive(Clone, Debug)]
+pub enum Strategy {
+    Basic,
+    /// hit below 17, like the dealer
+    Dealer,
+    /// hold on anything that could bust
+    NeverBust,
+    /// basic strategy with index plays, betting the count's spread in units
+    Counting {
+        spread: u32,
+    },
+    /// a chart loaded from a file
+    File(Box<StrategyFile>),
+}
+impl Strategy {
+    /// Parse the name of a built-in strategy, or the path of a `.csv` chart. Counting
+    /// bets the configured spread.
+    pub fn parse(name: &str, config: &Config) -> Result<Self, String> {
+        if name.ends_with(".csv") {
+            return StrategyFile::load(name.as_ref()).map(|file| Strategy::File(Box::new(file)));
+        }
+        let built_in = [
+            Strategy::Basic,
+            Strategy::Dealer,
+            Strategy::NeverBust,
+            Strategy::Counting {
+                spread: config.bet_spread,
+            },
+        ];
+        built_in
+            .clone()
+            .into_iter()
+            .find(|strategy| strategy.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names = built_in.map(|strategy| strategy.name().to_string());
+                format!(
+                    "unknown strategy '{name}', expected one of {} or a .csv chart",
+                    names.join(", ")
+                )
+            })
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Strategy::Basic => "basic",
+            Strategy::Dealer => "dealer",
+            Strategy::NeverBust => "never-bust",
+            Strategy::Counting { .. } => "counting",
+            Strategy::File(file) => &file.name,
+        }
+    }
+
+    fn decide(&self, game: &Game) -> Action {
+        let value = game.player_hand.count_value();
+        match self {
+            Strategy::Basic => game.hint().0,
+            Strategy::Dealer if value < 17 => Action::Hit,
+            Strategy::NeverBust if value < 12 => Action::Hit,
+            Strategy::Dealer | Strategy::NeverBust => Action::Stand,
+            Strategy::Counting { .. } => {
+                let (action, situation) = game.hint();
+                let true_count = game.count.true_count(counting::decks_remaining(&game.deck));
+                strategy::deviation(&situation, true_count).unwrap_or(action)
+            }
+            Strategy::File(file) => file.recommend(&game.hint().1, &game.rules),
+        }
+    }
+
+    /// Units to bet on the next hand, when the strategy has its own bet ramp.
+    pub fn ramp_units(&self, game: &Game) -> Option<u32> {
+        match self {
+            Strategy::Counting { spread } => {
+                let spread = BetSpread {
+                    unit: 1,
+                    spread: *spread,
+                };
+                Some(spread.units(&game.count, counting::decks_remaining(&game.deck)))
+            }
+            Strategy::File(file) => file.units(&game.count, &game.deck),
+            _ => None,
+        }
+    }
+}
+
+/// Totals of a simulation run.
+#[derive(Debug, Default)]
+pub struct Results {
+    pub hands: u64,
+    pub wins: u64,
+    pub pushes: u64,
+    pub losses: u64,
+    /// chips bet over every hand
+    pub wagered: u64,
+    /// net chips won
+    pub net: i64,
+    /// each hand's result in bets, squared and summed
+    pub squared: f64,
+    pub elapsed_secs: f64,
+}
+impl Results {
+    /// The house's share of every chip bet, in percent.
+    pub fn house_edge(&self) -> f64 {
+        -(self.net as f64) / self.wagered.max(1) as f64 * 100.0
+    }
+
+    /// How far one hand's result strays from the edge, as a standard deviation in bets.
+    pub fn std_dev(&self) -> f64 {
+        let mean = self.house_edge() / 100.0;
+        (self.squared / self.hands.max(1) as f64 - mean * mean)
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// Share of hands, in percent.
+    pub fn rate(&self, count: u64) -> f64 {
+        count as f64 / self.hands.max(1) as f64 * 100.0
+    }
+
+    pub fn hands_per_hour(&self) -> f64 {
+        self.hands as f64 / self.elapsed_secs.max(f64::EPSILON) * 3600.0
+    }
+}
+
+/// Bet and play out one hand. The bet must be covered by the bankroll.
+pub fn play_hand(game: &mut Game, strategy: &Strategy, bet: u32) {
+    if game.place_bet(bet).is_err() {
+        return;
+    }
+    while matches!(game.state, GameState::PlayingHand) {
+        if game.is_dealer_turn() {
+            game.dealer_step();
+            continue;
+        }
+        // a move the hand doesn't allow, like a double the bankroll can't cover, is
+        // played as basic strategy would
+        if game.play(strategy.decide(game)).is_err() {
+            let _ = game.play(game.hint().0);
+        }
+    }
+}
+
+/// Play `hands` hands, as fast as the engine can deal them, logging each one if asked.
+pub fn simulate(
+    config: &Config,
+    hands: u64,
+    strategy: &Strategy,
+    mut hand_log: Option<&mut HandLog>,
+) -> Result<Results, String> {
+    let mut game = Game::new(config);
+    let mut results = Results::default();
+    let started = Instant::now();
+
+    for _ in 0..hands {
+        let bet = strategy.ramp_units(&game).unwrap_or(1) * UNIT;
+        // enough to split into every hand there can be and double each, or to insure
+        game.bankroll = bet * 2 * SPLIT_HANDS as u32;
+        play_hand(&mut game, strategy, bet);
+
+        results.hands += 1;
+        results.wagered += u64::from(bet);
+        results.net += game.payout;
+        results.squared += (game.payout as f64 / f64::from(bet)).powi(2);
+        match game.payout {
+            1.. => results.wins += 1,
+            0 => results.pushes += 1,
+            _ => results.losses += 1,
+        }
+        if let Some(hand_log) = hand_log.as_mut() {
+            hand_log.record(&game)?;
+        }
+        game.new_hand();
+    }
+    results.elapsed_secs = started.elapsed().as_secs_f64();
+    Ok(results)
+}
+
+/// Where to write results besides the terminal.
+#[derive(Debug, Default)]
+pub struct Output {
+    pub path: Option<PathBuf>,
+    /// also write every hand next to the summary
+    pub per_hand: bool,
+}
+
+/// Every combination of the swept rule values, applied on top of `base`. Each sweep is
+/// written `KEY=VALUES`, like `decks=1-8` or `payout=3:2,6:5`.
+pub fn sweep_rules(base: Rules, sweeps: &[String]) -> Result<Vec<Rules>, String> {
+    let mut combinations = vec![base];
+    for sweep in sweeps {
+        let (key, listed) = sweep
+            .split_once('=')
+            .ok_or_else(|| format!("expected a sweep like 'decks=1-8', found '{sweep}'"))?;
+        let mut values: Vec<String> = Vec::new();
+        for value in listed.split(',').map(str::trim) {
+            match value
+                .split_once('-')
+                .map(|(from, to)| (from.parse(), to.parse()))
+            {
+                Some((Ok(from), Ok(to))) => values.extend((from..=to).map(|n: u8| n.to_string())),
+                _ => values.push(value.to_string()),
+            }
+        }
+        if values.is_empty() || values.iter().any(String::is_empty) {
+            return Err(format!("no values to sweep in '{sweep}'"));
+        }
+
+        let mut next = Vec::new();
+        for rules in &combinations {
+            for value in &values {
+                let mut rules = *rules;
+                rules.set(key.trim(), value)?;
+                next.push(rules);
+            }
+        }
+        combinations = next;
+    }
+    Ok(combinations)
+}
+
+pub fn run(
+    config: &Config,
+    hands: u64,
+    strategy: Strategy,
+    sweeps: &[String],
+    output: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(path) = &output.path {
+        report::Format::from_path(path)?;
+    }
+    let mut stdout = std::io::stdout();
+    if !sweeps.is_empty() {
+        if output.per_hand {
+            return Err("--per-hand can't be used with --sweep".into());
+        }
+        return run_sweep(config, hands, &strategy, sweeps, output.path, &mut stdout);
+    }
+
+    writeln!(
+        stdout,
+        "Simulating {hands} hands, {} rules, {} strategy",
+        config.rules.name(),
+        strategy.name()
+    )?;
+
+    let mut hand_log = match (&output.path, output.per_hand) {
+        (Some(path), true) => Some(HandLog::create(&HandLog::path_for(path))?),
+        _ => None,
+    };
+    let results = simulate(config, hands, &strategy, hand_log.as_mut())?;
+    if let Some(hand_log) = hand_log {
+        hand_log.finish()?;
+    }
+
+    write!(stdout, "House edge:     {:.2}%", results.house_edge())?;
+    if results.house_edge() < 0.0 {
+        write!(stdout, " (the player has the edge)")?;
+    }
+    writeln!(stdout)?;
+    writeln!(
+        stdout,
+        "Win/push/loss:  {:.2}% / {:.2}% / {:.2}%",
+        results.rate(results.wins),
+        results.rate(results.pushes),
+        results.rate(results.losses)
+    )?;
+    writeln!(
+        stdout,
+        "Hands per hour: {:.0} ({:.2}s elapsed)",
+        results.hands_per_hour(),
+        results.elapsed_secs
+    )?;
+
+    if let Some(path) = &output.path {
+        let summary = Summary {
+            rules: config.rules.name(),
+            strategy: strategy.name(),
+            results: &results,
+        };
+        report::write_summaries(path, &[summary])?;
+    }
+    Ok(())
+}
+
+/// Simulate each combination of rules and compare their house edges to the first.
+fn run_sweep(
+    config: &Config,
+    hands: u64,
+    strategy: &Strategy,
+    sweeps: &[String],
+    path: Option<PathBuf>,
+    stdout: &mut impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let combinations = sweep_rules(config.rules, sweeps)?;
+    writeln!(
+        stdout,
+        "Simulating {hands} hands for each of {} rule sets, {} strategy\n",
+        combinations.len(),
+        strategy.name()
+    )?;
+    writeln!(
+        stdout,
+        "{:<24} {:>10} {:>9}",
+        "Rules", "House edge", "Change"
+    )?;
+
+    let mut runs: Vec<(String, Results)> = Vec::new();
+    for rules in combinations {
+        let config = Config {
+            rules,
+            ..config.clone()
+        };
+        let results = simulate(&config, hands, strategy, None)?;
+        let change = match runs.first() {
+            Some((_, base)) => format!("{:+.2}%", results.house_edge() - base.house_edge()),
+            None => "base".to_string(),
+        };
+        writeln!(
+            stdout,
+            "{:<24} {:>9.2}% {change:>9}",
+            rules.to_string(),
+            results.house_edge()
+        )?;
+        runs.push((rules.to_string(), results));
+    }
+
+    if let Some(path) = path {
+        let summaries: Vec<_> = runs
+            .iter()
+            .map(|(rules, results)| Summary {
+                rules,
+                strategy: strategy.name(),
+                results,
+            })
+            .collect();
+        report::write_summaries(&path, &summaries)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shuffle::Shuffler;
+
+    #[test]
+    fn plays_every_hand_it_is_asked_to() {
+        let config = Config {
+            shuffle: Shuffler::Seeded(7),
+            ..Config::default()
+        };
+        let results = simulate(&config, 500, &Strategy::Basic, None).unwrap();
+        assert_eq!(results.hands, 500);
+        assert_eq!(results.wins + results.pushes + results.losses, 500);
+        assert_eq!(results.wagered, 500 * u64::from(UNIT));
+        // the same seed deals the same hands
+        let again = simulate(&config, 500, &Strategy::Basic, None).unwrap();
+        assert_eq!((again.net, again.wins), (results.net, results.wins));
+
+        let results = Results {
+            hands: 4,
+            wins: 1,
+            wagered: 40,
+            net: -4,
+            ..Results::default()
+        };
+        assert_eq!(results.house_edge(), 10.0);
+        assert_eq!(results.rate(results.wins), 25.0);
+    }
+
+    #[test]
+    fn finds_strategies_by_name() {
+        let config = Config::default();
+        let strategy = Strategy::parse("Never-Bust", &config).unwrap();
+        assert_eq!(strategy.name(), "never-bust");
+        assert!(matches!(
+            Strategy::parse("counting", &config),
+            Ok(Strategy::Counting { spread }) if spread == config.bet_spread
+        ));
+        let err = Strategy::parse("martingale", &config).unwrap_err();
+        assert!(err.starts_with("unknown strategy 'martingale'"), "{err}");
+    }
+
+    #[test]
+    fn sweeps_every_combination_of_rules() {
+        let sweeps = ["decks=1-2".to_string(), "payout=3:2, 6:5".to_string()];
+        let combinations = sweep_rules(Rules::default(), &sweeps).unwrap();
+        let names: Vec<_> = combinations.iter().map(Rules::to_string).collect();
+        assert_eq!(
+            names,
+            [
+                "1 deck, S17, 3:2",
+                "1 deck, S17, 6:5",
+                "2 decks, S17, 3:2",
+                "2 decks, S17, 6:5",
+            ]
+        );
+
+        let err = sweep_rules(Rules::default(), &["decks".to_string()]).unwrap_err();
+        assert_eq!(err, "expected a sweep like 'decks=1-8', found 'decks'");
+        assert!(sweep_rules(Rules::default(), &["decks=".to_string()]).is_err());
+        assert!(sweep_rules(Rules::default(), &["decks=1-9".to_string()]).is_err());
+    }
+
+    #[test]
+    fn results_stray_by_their_standard_deviation() {
+        // two wins and two losses of one bet each
+        let even = Results {
+            hands: 4,
+            wagered: 40,
+            squared: 4.0,
+            ..Results::default()
+        };
+        assert_eq!(even.std_dev(), 1.0);
+        assert_eq!(Results::default().std_dev(), 0.0);
+    }
+}
@@ -0,0 +1,197 @@
+use std::io::{self, IsTerminal, Write};
+
+use crate::{
+    config::Config,
+    events::{GameEvent, Subscriber},
+};
+
+/// A moment in the game with a sound effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    /// a card flicked onto the table
+    Deal,
+    /// chips clinking as a win is paid
+    Payout,
+    /// a soft chime for a natural
+    Blackjack,
+    /// a riffle as the shoe is reshuffled
+    Shuffle,
+    /// a nudge when a network table is waiting on you
+    Turn,
+}
+impl Sound {
+    pub const ALL: [Sound; 5] = [
+        Sound::Deal,
+        Sound::Payout,
+        Sound::Blackjack,
+        Sound::Shuffle,
+        Sound::Turn,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sound::Deal => "deal",
+            Sound::Payout => "payout",
+            Sound::Blackjack => "blackjack",
+            Sound::Shuffle => "shuffle",
+            Sound::Turn => "turn",
+        }
+    }
+
+    /// Parse a comma separated list of sound names, or `none` for an empty one.
+    pub fn parse_list(value: &str) -> Result<Vec<Sound>, String> {
+        if value.trim() == "none" {
+            return Ok(Vec::new());
+        }
+        value
+            .split(',')
+            .map(|name| {
+                let name = name.trim();
+                Sound::ALL
+                    .into_iter()
+                    .find(|sound| sound.name() == name)
+                    .ok_or_else(|| {
+                        let names = Sound::ALL.map(Sound::name).join(", ");
+                        format!("unknown sound '{name}', expected none or some of {names}")
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Plays sound effects for a game's events. Nothing is played when muted, without an
+/// audio device, or in a build without the `audio` feature; the terminal bell rings
+/// instead for the sounds chosen to ring it.
+pub struct Sounds {
+    output: Option<output::Output>,
+    /// sounds that ring the terminal bell when there is no audio to play
+    bell: Vec<Sound>,
+}
+impl Sounds {
+    pub fn new(config: &Config) -> Self {
+        let output = match config.mute || config.volume == 0 {
+            true => None,
+            false => output::Output::open(f32::from(config.volume) / 100.0),
+        };
+        Self {
+            output,
+            bell: config.bell.clone(),
+        }
+    }
+
+    pub fn play(&self, sound: Sound) {
+        match &self.output {
+            Some(output) => output.play(sound),
+            None if self.bell.contains(&sound) => ring_bell(),
+            None => (),
+        }
+    }
+}
+
+impl Subscriber for Sounds {
+    fn notify(&mut self, event: &GameEvent) -> io::Result<()> {
+        match event {
+            // two cards each, the dealer's hole card among them
+            GameEvent::Deal { .. } => (0..4).for_each(|_| self.play(Sound::Deal)),
+            GameEvent::Hit { .. }
+            | GameEvent::Double { .. }
+            | GameEvent::PlayHand { .. }
+            | GameEvent::DealerDraw { .. } => self.play(Sound::Deal),
+            GameEvent::Settle { result, .. } if result == "blackjack" => {
+                self.play(Sound::Blackjack)
+            }
+            GameEvent::Settle { payout, .. } if *payout > 0 => self.play(Sound::Payout),
+            GameEvent::Shuffle { .. } => self.play(Sound::Shuffle),
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Ring the terminal bell on whichever of stdout and stderr the game is drawn on.
+fn ring_bell() {
+    let _ = match io::stdout().is_terminal() {
+        true => io::stdout()
+            .write_all(b"\x07")
+            .and_then(|()| io::stdout().flush()),
+        false => io::stderr().write_all(b"\x07"),
+    };
+}
+
+#[cfg(feature = "audio")]
+mod output {
+    use std::time::Duration;
+
+    use rodio::{source::SineWave, OutputStream, Sink, Source};
+
+    use super::Sound;
+
+    /// The default audio device. The stream has to be kept open for the sink to play.
+    pub struct Output {
+        _stream: OutputStream,
+        sink: Sink,
+    }
+    impl Output {
+        /// Open the default device at `volume`, from 0 to 1.
+        pub fn open(volume: f32) -> Option<Self> {
+            let (stream, handle) = OutputStream::try_default().ok()?;
+            let sink = Sink::try_new(&handle).ok()?;
+            sink.set_volume(volume);
+            Some(Self {
+                _stream: stream,
+                sink,
+            })
+        }
+
+        /// Queue a sound after any still playing.
+        pub fn play(&self, sound: Sound) {
+            match sound {
+                Sound::Deal => self.sink.append(tone(2400.0, 25)),
+                Sound::Payout => {
+                    let clink = tone(3100.0, 60).mix(tone(4300.0, 60).delay(ms(30)));
+                    self.sink.append(clink);
+                }
+                Sound::Blackjack => {
+                    self.sink.append(tone(660.0, 150));
+                    self.sink.append(tone(880.0, 250));
+                }
+                Sound::Shuffle => {
+                    for _ in 0..12 {
+                        self.sink.append(tone(1800.0, 12).delay(ms(18)));
+                    }
+                }
+                Sound::Turn => {
+                    self.sink.append(tone(990.0, 80));
+                    self.sink.append(tone(990.0, 80).delay(ms(60)));
+                }
+            }
+        }
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// A quiet sine tone, faded in so it doesn't click.
+    fn tone(freq: f32, length_ms: u64) -> impl Source<Item = f32> + Send {
+        SineWave::new(freq)
+            .take_duration(ms(length_ms))
+            .fade_in(ms(5))
+            .amplify(0.2)
+    }
+}
+
+#[cfg(not(feature = "audio"))]
+mod output {
+    use super::Sound;
+
+    /// Without the `audio` feature there is no device to play on.
+    pub struct Output;
+    impl Output {
+        pub fn open(_volume: f32) -> Option<Self> {
+            None
+        }
+
+        pub fn play(&self, _sound: Sound) {}
+    }
+}
@@ -0,0 +1,291 @@
+use std::io::{self, Write};
+
+use crate::{
+    autosave::Autosave,
+    bot::{self, BotAction, BotEvent, Heard, Voice},
+    config::Config,
+    counting::{self, Count},
+    drill::{CountQuiz, DrillStats},
+    game::{Game, GameState, Insurance},
+    history::History,
+    narrate::Narrator,
+    stats::{SessionStats, SessionSummary},
+    strategy::Action,
+    trainer::Trainer,
+};
+
+/// Play one game in plain sentences for a screen reader, a line of stdin for each answer,
+/// until `quit`, the end of input, the hand limit, or the bankroll is gone.
+pub fn run(config: &Config) -> io::Result<SessionSummary> {
+    let mut spoken = Spoken {
+        config: config.clone(),
+        writer: io::stdout(),
+        narrator: Narrator::default(),
+        trainer: config.trainer.then(Trainer::new),
+        restore: Autosave::load(),
+        quiz: None,
+        quiz_stats: DrillStats::default(),
+        saved: false,
+        session: SessionStats::default(),
+        history: History::default(),
+        prompted: false,
+    };
+    if let Some(scenario) = &config.scenario {
+        writeln!(
+            spoken.writer,
+            "Practicing {scenario}, every deal is rigged."
+        )?;
+    }
+    bot::play(config, io::stdin().lock(), &mut spoken)?;
+    spoken.finish()
+}
+
+/// Speaks the game for a screen reader, and answers the questions a player at the table
+/// would ask.
+struct Spoken<W> {
+    config: Config,
+    writer: W,
+    narrator: Narrator,
+    trainer: Option<Trainer>,
+    /// a save left by a session that ended unexpectedly, until the player says whether
+    /// to restore it
+    restore: Option<Autosave>,
+    /// the count quiz for the shoe just shuffled away, until the player answers it
+    quiz: Option<CountQuiz>,
+    quiz_stats: DrillStats,
+    /// whether the hand on the table has been autosaved since it was settled
+    saved: bool,
+    session: SessionStats,
+    history: History,
+    /// whether the last thing written is a prompt, still waiting for its answer
+    prompted: bool,
+}
+impl<W: Write> Spoken<W> {
+    /// Ask what's next: whether to restore, the quiz, or the next move in `game`.
+    fn prompt(&mut self, game: &Game) -> io::Result<()> {
+        if let Some(save) = &self.restore {
+            write!(
+                self.writer,
+                "The last session ended unexpectedly with a bankroll of {}. Restore it? (y/n) ",
+                save.game.bankroll
+            )?;
+        } else if self.quiz.is_some() {
+            write!(
+                self.writer,
+                "The deck was shuffled. What was the running count? "
+            )?;
+        } else {
+            match game.state {
+                GameState::Betting => write!(
+                    self.writer,
+                    "Bankroll {}. Bet how much? Enter bets {}. ",
+                    game.bankroll, game.bet
+                )?,
+                _ if game.insurance == Insurance::Offered => {
+                    match game.player_hand.is_blackjack() {
+                        true => write!(self.writer, "Even money? (y/n) ")?,
+                        false => write!(self.writer, "Insurance for {}? (y/n) ", game.bet / 2)?,
+                    }
+                }
+                _ => {
+                    let mut moves = vec!["Hit", "hold"];
+                    if game.is_legal(Action::Double) {
+                        moves.push("double");
+                    }
+                    if game.is_legal(Action::Split) {
+                        moves.push("split");
+                    }
+                    if game.is_legal(Action::Surrender) {
+                        moves.push("surrender");
+                    }
+                    write!(self.writer, "{}, or hint? ", moves.join(", "))?
+                }
+            }
+        }
+        self.prompted = true;
+        self.writer.flush()
+    }
+
+    /// Answer one of the player's questions about `game`, returning whether `input` was
+    /// one.
+    fn answer(&mut self, game: &Game, input: &str) -> io::Result<bool> {
+        match (game.state, input) {
+            (_, "mistakes") if self.trainer.is_some() => {
+                let worst = self
+                    .trainer
+                    .as_ref()
+                    .map(|t| t.mistakes.worst(5))
+                    .unwrap_or_default();
+                if worst.is_empty() {
+                    writeln!(self.writer, "No mistakes recorded yet.")?;
+                }
+                for (cell, mistakes) in worst {
+                    writeln!(self.writer, "{cell}: {mistakes} mistakes.")?;
+                }
+            }
+            (_, "stats") => {
+                let stats = &self.session.stats;
+                writeln!(
+                    self.writer,
+                    "{} hands this session, won {:.1}%, pushed {:.1}%, lost {:.1}%, net {:+}.",
+                    stats.hands,
+                    stats.rate(stats.wins),
+                    stats.rate(stats.pushes),
+                    stats.rate(stats.losses),
+                    stats.net
+                )?;
+            }
+            (GameState::PlayingHand, "hint") => {
+                let (action, situation) = game.hint();
+                writeln!(self.writer, "Basic strategy says {action} on {situation}.")?;
+            }
+            (_, "count") if !self.config.count_quiz => {
+                let decks = counting::decks_remaining(&game.deck);
+                let count = game.count;
+                write!(
+                    self.writer,
+                    "{} running count {:+}",
+                    count.system.name(),
+                    count.running
+                )?;
+                if count.system.is_balanced() {
+                    write!(self.writer, ", true count {:+.1}", count.true_count(decks))?;
+                }
+                writeln!(self.writer, ", {decks:.1} decks remaining.")?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Say how the session went, and save what it leaves behind.
+    fn finish(mut self) -> io::Result<SessionSummary> {
+        if self.prompted {
+            // end the prompt's line
+            writeln!(self.writer)?;
+        }
+        if let Some(trainer) = &self.trainer {
+            writeln!(self.writer, "Session: {}.", trainer.summary())?;
+            trainer.mistakes.save()?;
+        }
+        Autosave::clear()?;
+        self.session.stats.save_session()?;
+        self.quiz_stats.save_session(DrillStats::QUIZ_FILE)?;
+        Ok(SessionSummary {
+            stats: self.session.stats,
+            graded: self
+                .trainer
+                .map(|trainer| (trainer.correct, trainer.decisions)),
+        })
+    }
+}
+impl<W: Write> Voice for Spoken<W> {
+    fn hear(&mut self, game: &mut Game, line: &str) -> io::Result<Heard> {
+        self.prompted = false;
+        let input = line.trim().to_lowercase();
+        if let Some(save) = self.restore.take() {
+            if input == "y" {
+                save.restore(game);
+            }
+            self.prompt(game)?;
+            return Ok(Heard::Handled);
+        }
+        if let Some(mut quiz) = self.quiz.take() {
+            quiz.input = input;
+            match quiz.answer(&mut self.quiz_stats) {
+                Some((_, message)) => writeln!(self.writer, "{message}.")?,
+                None => writeln!(self.writer, "That isn't a number, skipping the quiz.")?,
+            }
+            self.prompt(game)?;
+            return Ok(Heard::Handled);
+        }
+        if matches!(input.as_str(), "q" | "quit") {
+            return Ok(Heard::Action(BotAction::Quit));
+        }
+        if self.answer(game, &input)? {
+            self.prompt(game)?;
+            return Ok(Heard::Handled);
+        }
+
+        let action = match input.as_str() {
+            _ if game.insurance == Insurance::Offered => match input.as_str() {
+                "y" | "yes" => Some(Action::Insurance(true)),
+                "n" | "no" => Some(Action::Insurance(false)),
+                _ => None,
+            },
+            "1" | "h" | "hit" => Some(Action::Hit),
+            "2" | "s" | "hold" | "stand" => Some(Action::Stand),
+            "3" | "d" | "double" => Some(Action::Double),
+            "4" | "r" | "surrender" => Some(Action::Surrender),
+            "7" | "p" | "split" => Some(Action::Split),
+            _ => None,
+        };
+        match (game.state, action) {
+            (GameState::Betting, _) if input.is_empty() => {
+                return Ok(Heard::Action(BotAction::Bet { amount: game.bet }));
+            }
+            (GameState::Betting, _) => {
+                return Ok(match input.parse() {
+                    Ok(amount) => Heard::Action(BotAction::Bet { amount }),
+                    Err(_) => {
+                        Heard::Refused(format!("expected a number of chips, found '{input}'"))
+                    }
+                });
+            }
+            (GameState::PlayingHand, Some(action)) => {
+                if let Some(trainer) = self.trainer.as_mut().filter(|_| game.is_legal(action)) {
+                    if !trainer.grade(game, action) {
+                        writeln!(
+                            self.writer,
+                            "Mistake. {}.",
+                            trainer.mistake.as_deref().unwrap_or_default()
+                        )?;
+                    }
+                }
+                return Ok(Heard::Action(match action {
+                    Action::Hit => BotAction::Hit,
+                    Action::Double => BotAction::Double,
+                    Action::Split => BotAction::Split,
+                    Action::Surrender => BotAction::Surrender,
+                    Action::Insurance(take) => BotAction::Insurance { take },
+                    _ => BotAction::Stand,
+                }));
+            }
+            (GameState::PlayingHand, None) if game.insurance == Insurance::Offered => {
+                writeln!(self.writer, "Type y or n, hint, count, stats, or quit.")?
+            }
+            _ => writeln!(
+                self.writer,
+                "Type hit, hold, double, split, surrender, hint, count, stats, mistakes, or \
+                 quit."
+            )?,
+        }
+        self.prompt(game)?;
+        Ok(Heard::Handled)
+    }
+
+    fn send(&mut self, game: &Game, event: &BotEvent) -> io::Result<()> {
+        for line in self.narrator.narrate(game) {
+            writeln!(self.writer, "{line}")?;
+        }
+        Autosave::sync(game, &mut self.saved)?;
+        self.session.update(game);
+        self.history.update(game)?;
+        match event {
+            BotEvent::State(state) if state.legal.is_empty() => {
+                writeln!(self.writer, "You're out of chips.")?;
+            }
+            BotEvent::State(_) => self.prompt(game)?,
+            // the narrator has said how it went
+            BotEvent::Result { .. } => self.narrator.reset(),
+            BotEvent::Error { message } => writeln!(self.writer, "Not allowed, {message}.")?,
+        }
+        Ok(())
+    }
+
+    fn shuffled(&mut self, count: Count) {
+        if self.config.count_quiz {
+            self.quiz = Some(CountQuiz::new(count.running));
+        }
+    }
+}
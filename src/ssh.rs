@@ -0,0 +1,151 @@
+use std::{fs, io::IsTerminal};
+
+use blackjack_tui::paths;
+use sha2::{Digest, Sha256};
+
+use crate::cli::{Args, Command};
+
+/// What a player logging in over SSH asked for, read from the command they gave `ssh`.
+///
+/// The game is served over SSH by the host's own sshd, running `blackjack-tui ssh` for
+/// every login to an account set aside for it, like `play`. There's no SSH server built
+/// in, so sshd does the logging in:
+///
+/// ```text
+/// Match User play
+///     ForceCommand blackjack-tui ssh
+///     ExposeAuthInfo yes
+///     PermitTTY yes
+///     AllowTcpForwarding no
+///     X11Forwarding no
+/// ```
+///
+/// Each login gets a session of its own, or a seat at the table the host runs with
+/// `blackjack-tui host`, or a view of it:
+///
+/// ```text
+/// ssh play@host                  a game of your own
+/// ssh -t play@host table alice   a seat at the shared table as alice
+/// ssh -t play@host watch         watch the shared table
+/// ```
+///
+/// Everyone logs in to the same account, so a game of your own is kept under a profile
+/// for the key you logged in with, which sshd tells of with `ExposeAuthInfo`. Nobody can
+/// pick someone else's. Logins without a key, like with a password, play as guests under
+/// a profile of their own that's gone when they leave.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Login {
+    /// a game of their own
+    Play,
+    /// a seat at the table hosted on this machine
+    Table(Option<String>),
+    Watch,
+}
+impl Login {
+    /// Read the login from `SSH_ORIGINAL_COMMAND`, which nobody but sshd sets.
+    pub fn read() -> Result<Self, String> {
+        if !std::io::stdout().is_terminal() {
+            return Err("the game needs a terminal, connect with ssh -t".to_string());
+        }
+        let command = std::env::var("SSH_ORIGINAL_COMMAND").unwrap_or_default();
+        let words: Vec<_> = command.split_whitespace().collect();
+        let name = |name: &str| Some(name.to_string());
+        match words[..] {
+            [] | ["play"] => Ok(Login::Play),
+            ["table"] => Ok(Login::Table(None)),
+            ["table", player] => Ok(Login::Table(name(player))),
+            ["watch"] => Ok(Login::Watch),
+            _ => Err(format!(
+                "unknown command '{command}', expected play, table NAME, or watch"
+            )),
+        }
+    }
+
+    /// Set `args` up to play what was asked for, in place of the `ssh` command. A guest
+    /// is given a profile of their own, removed when the returned [`Guest`] is dropped.
+    pub fn apply(self, args: &mut Args) -> Option<Guest> {
+        match self {
+            Login::Play => {
+                args.command = Command::Play;
+                match key_fingerprint() {
+                    Some(fingerprint) => args.profile = Some(format!("key-{fingerprint}")),
+                    None => return Some(Guest::enter(args)),
+                }
+            }
+            Login::Table(name) => {
+                args.command = Command::Join;
+                args.name = name.or_else(|| Some("Guest".to_string()));
+            }
+            Login::Watch => {
+                args.command = Command::Join;
+                args.spectate = true;
+            }
+        }
+        None
+    }
+}
+
+/// The start of the SHA-256 of the public key the player logged in with, from the
+/// `publickey` line of the file sshd names in `SSH_USER_AUTH`.
+fn key_fingerprint() -> Option<String> {
+    let auth = fs::read_to_string(std::env::var_os("SSH_USER_AUTH")?).ok()?;
+    fingerprint(&auth)
+}
+
+/// The fingerprint of the first public key in `auth`, lines like
+/// `publickey ssh-ed25519 AAAA…` as sshd writes them.
+fn fingerprint(auth: &str) -> Option<String> {
+    let key = auth.lines().find_map(|line| {
+        let mut words = line.split_whitespace();
+        (words.next() == Some("publickey")).then(|| words.nth(1))?
+    })?;
+    let digest = Sha256::digest(key);
+    Some(
+        digest[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect(),
+    )
+}
+
+/// A player without a key, keeping their files under a profile of their own while they
+/// play.
+#[derive(Debug)]
+pub struct Guest {
+    profile: String,
+}
+impl Guest {
+    /// Give the guest a profile no one else has, which keeps the host's settings like
+    /// every profile does.
+    fn enter(args: &mut Args) -> Self {
+        let profile = format!("guest-{}", std::process::id());
+        args.profile = Some(profile.clone());
+        Guest { profile }
+    }
+}
+impl Drop for Guest {
+    fn drop(&mut self) {
+        // only ever the guest's own profile, never the data directory it's in
+        if paths::profile() != Some(self.profile.as_str()) {
+            return;
+        }
+        if let Some(dir) = paths::data_dir() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_the_key_logged_in_with() {
+        let auth = "password\npublickey ssh-ed25519 AAAAC3Nz\npublickey ssh-rsa AAAAB3\n";
+        let first = fingerprint(auth).unwrap();
+        assert_eq!(first.len(), 16);
+        assert_eq!(fingerprint("publickey ssh-ed25519 AAAAC3Nz"), Some(first));
+        assert_ne!(fingerprint("publickey ssh-rsa AAAAB3"), fingerprint(auth));
+        assert_eq!(fingerprint("keyboard-interactive\n"), None);
+    }
+}
@@ -0,0 +1,456 @@
+use std::{fs, io, path::PathBuf, time::Instant};
+
+use crate::{
+    game::{Game, GameState, HandResult},
+    history::{self, Round},
+    money::Money,
+    paths,
+    strategy::Action,
+};
+
+/// Results of played hands, for one session or over every session.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GameStats {
+    pub sessions: u32,
+    pub hands: u64,
+    pub wins: u64,
+    pub pushes: u64,
+    pub losses: u64,
+    pub blackjacks: u64,
+    pub busts: u64,
+    pub hits: u64,
+    pub stands: u64,
+    pub doubles: u64,
+    /// doubles in rounds that came out ahead
+    pub doubles_won: u64,
+    pub splits: u64,
+    /// splits in rounds that came out ahead
+    pub splits_won: u64,
+    /// chips bet over every hand
+    pub wagered: u64,
+    /// net chips won
+    pub net: i64,
+    /// each hand's result in bets, summed, and squared and summed, for how widely
+    /// results spread without keeping every hand
+    pub result_sum: f64,
+    pub result_squares: f64,
+    /// session limits reached, each of which called for a break
+    pub limits_hit: u64,
+    /// time played, in seconds
+    pub seconds: u64,
+}
+impl GameStats {
+    const FILE: &str = "game_stats.json";
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// The saved lifetime totals, or none at all if nothing has been saved.
+    #[cfg(feature = "serde")]
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(format!("couldn't read {}: {err}", path.display())),
+        };
+        serde_json::from_str(&contents).map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    /// No totals are kept without serde.
+    #[cfg(not(feature = "serde"))]
+    pub fn load() -> Result<Self, String> {
+        Ok(Self::default())
+    }
+
+    /// Save through a temporary file, so a crash while writing can't lose the totals.
+    #[cfg(feature = "serde")]
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(temp, path)
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn save(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Forget every saved result.
+    pub fn reset() -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count a settled hand and the actions taken on it.
+    pub fn record(&mut self, result: HandResult, bet: u32, payout: i64, actions: &[Action]) {
+        self.hands += 1;
+        match result {
+            HandResult::Blackjack => {
+                self.wins += 1;
+                self.blackjacks += 1;
+            }
+            HandResult::PlayerWin => self.wins += 1,
+            HandResult::Push => self.pushes += 1,
+            HandResult::DealerWin | HandResult::Surrender => self.losses += 1,
+            HandResult::Bust => {
+                self.losses += 1;
+                self.busts += 1;
+            }
+        }
+        for action in actions {
+            match action {
+                Action::Hit => self.hits += 1,
+                Action::Stand => self.stands += 1,
+                Action::Double => {
+                    self.doubles += 1;
+                    self.doubles_won += u64::from(payout > 0);
+                }
+                Action::Split => {
+                    self.splits += 1;
+                    self.splits_won += u64::from(payout > 0);
+                }
+                _ => (),
+            }
+        }
+        self.wagered += u64::from(bet);
+        self.net += payout;
+        let result = payout as f64 / f64::from(bet.max(1));
+        self.result_sum += result;
+        self.result_squares += result * result;
+    }
+
+    /// The average result of a hand and its standard deviation, in bets, once there are
+    /// two hands to go on.
+    pub fn result_spread(&self) -> Option<(f64, f64)> {
+        if self.hands < 2 {
+            return None;
+        }
+        let hands = self.hands as f64;
+        let mean = self.result_sum / hands;
+        let variance = (self.result_squares - hands * mean * mean) / (hands - 1.0);
+        Some((mean, variance.max(0.0).sqrt()))
+    }
+
+    /// The 95% confidence interval of the win rate, in percent, as its margin either side.
+    pub fn win_rate_margin(&self) -> f64 {
+        let rate = self.rate(self.wins) / 100.0;
+        1.96 * (rate * (1.0 - rate) / self.hands.max(1) as f64).sqrt() * 100.0
+    }
+
+    /// Totals of `rounds` from the hand history. The history doesn't tell of breaks, so
+    /// limits hit aren't counted, and time played is each session's first hand to its last.
+    pub fn from_rounds<'a>(rounds: impl IntoIterator<Item = &'a Round>) -> Self {
+        let mut stats = GameStats::default();
+        // each session's first start and last finish
+        let mut sessions: Vec<(u128, u128, u128)> = Vec::new();
+        for round in rounds {
+            match sessions.iter_mut().find(|(id, ..)| *id == round.session_ms) {
+                Some((_, _, finished)) => *finished = round.finished_ms,
+                None => sessions.push((round.session_ms, round.started_ms, round.finished_ms)),
+            }
+            let result = match round.result.as_str() {
+                "blackjack" => HandResult::Blackjack,
+                "win" => HandResult::PlayerWin,
+                "push" => HandResult::Push,
+                "bust" => HandResult::Bust,
+                "surrender" => HandResult::Surrender,
+                _ => HandResult::DealerWin,
+            };
+            let actions: Vec<_> = round
+                .actions
+                .iter()
+                .map(|action| match action.as_str() {
+                    "hit" => Action::Hit,
+                    "double" => Action::Double,
+                    "split" => Action::Split,
+                    "surrender" => Action::Surrender,
+                    "insurance" => Action::Insurance(true),
+                    "no insurance" => Action::Insurance(false),
+                    _ => Action::Stand,
+                })
+                .collect();
+            stats.record(result, round.bet, round.payout, &actions);
+        }
+        stats.sessions = sessions.len() as u32;
+        stats.seconds = sessions
+            .iter()
+            .map(|(_, started, finished)| (finished.saturating_sub(*started) / 1000) as u64)
+            .sum();
+        stats
+    }
+
+    /// Share of hands, in percent.
+    pub fn rate(&self, count: u64) -> f64 {
+        count as f64 / self.hands.max(1) as f64 * 100.0
+    }
+
+    /// Hands played an hour, once there's been a minute of play to go on.
+    pub fn hands_per_hour(&self) -> Option<f64> {
+        (self.seconds >= 60).then(|| self.hands as f64 * 3600.0 / self.seconds as f64)
+    }
+
+    /// These totals with a session's results added, as another session if it played.
+    pub fn with_session(&self, session: &GameStats) -> GameStats {
+        GameStats {
+            sessions: self.sessions + u32::from(session.hands > 0),
+            hands: self.hands + session.hands,
+            wins: self.wins + session.wins,
+            pushes: self.pushes + session.pushes,
+            losses: self.losses + session.losses,
+            blackjacks: self.blackjacks + session.blackjacks,
+            busts: self.busts + session.busts,
+            hits: self.hits + session.hits,
+            stands: self.stands + session.stands,
+            doubles: self.doubles + session.doubles,
+            doubles_won: self.doubles_won + session.doubles_won,
+            splits: self.splits + session.splits,
+            splits_won: self.splits_won + session.splits_won,
+            wagered: self.wagered + session.wagered,
+            net: self.net + session.net,
+            result_sum: self.result_sum + session.result_sum,
+            result_squares: self.result_squares + session.result_squares,
+            limits_hit: self.limits_hit + session.limits_hit,
+            seconds: self.seconds + session.seconds,
+        }
+    }
+
+    /// These totals as lines of text, for printing outside the UI.
+    pub fn describe(&self, money: &Money) -> Vec<String> {
+        let mut lines = vec![
+            format!("Hands: {}", self.hands),
+            format!(
+                "Won {:.1}%, pushed {:.1}%, lost {:.1}%",
+                self.rate(self.wins),
+                self.rate(self.pushes),
+                self.rate(self.losses)
+            ),
+            format!("Blackjacks: {}, busts: {}", self.blackjacks, self.busts),
+            format!(
+                "Doubles: {}, {:.1}% won; splits: {}, {:.1}% won",
+                self.doubles,
+                won(self.doubles_won, self.doubles),
+                self.splits,
+                won(self.splits_won, self.splits)
+            ),
+            format!(
+                "Net: {} on {} wagered",
+                money.signed(self.net),
+                money.amount(self.wagered as i64)
+            ),
+            match self.hands_per_hour() {
+                Some(hourly) => format!("Time: {}, {hourly:.0} hands/hour", clock(self.seconds)),
+                None => format!("Time: {}", clock(self.seconds)),
+            },
+        ];
+        if let Some((mean, std_dev)) = self.result_spread() {
+            lines.push(format!("Per hand: {mean:+.2} bets, SD {std_dev:.2}"));
+        }
+        if self.limits_hit > 0 {
+            lines.push(format!("Limits reached: {}", self.limits_hit));
+        }
+        lines
+    }
+
+    /// These totals with the rates worked out, as `stats --json` prints them.
+    #[cfg(feature = "serde")]
+    pub fn report(&self) -> StatsReport<'_> {
+        let spread = self.result_spread();
+        // to the hundredth, as they're shown
+        let percent = |count| (self.rate(count) * 100.0).round() / 100.0;
+        StatsReport {
+            totals: self,
+            win_rate: percent(self.wins),
+            push_rate: percent(self.pushes),
+            loss_rate: percent(self.losses),
+            mean_result: spread.map(|(mean, _)| mean),
+            result_sd: spread.map(|(_, std_dev)| std_dev),
+        }
+    }
+
+    /// A sentence telling how these results went, for printing after a session.
+    pub fn summary(&self, money: &Money) -> String {
+        format!(
+            "Played {} {} in {}: won {}, pushed {}, and lost {}, for {} on {} wagered.",
+            self.hands,
+            match self.hands {
+                1 => "hand",
+                _ => "hands",
+            },
+            clock(self.seconds),
+            self.wins,
+            self.pushes,
+            self.losses,
+            money.signed(self.net),
+            money.amount(self.wagered as i64)
+        )
+    }
+
+    /// Totals of the most recent session in the hand history, if there's any history.
+    pub fn last_session(rounds: &[Round]) -> Option<Self> {
+        let session = rounds.iter().map(|round| round.session_ms).max()?;
+        Some(GameStats::from_rounds(
+            rounds.iter().filter(|round| round.session_ms == session),
+        ))
+    }
+
+    /// Add a session's results to the saved lifetime totals. Totals that can't be read
+    /// are left as they are, rather than saved over.
+    pub fn save_session(&self) -> io::Result<()> {
+        if self.hands == 0 {
+            return Ok(());
+        }
+        GameStats::load()
+            .map_err(io::Error::other)?
+            .with_session(self)
+            .save()
+    }
+}
+
+/// Totals with their rates in percent, and how much a hand won or lost on average and
+/// how widely that spread, in bets.
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize)]
+pub struct StatsReport<'a> {
+    #[serde(flatten)]
+    pub totals: &'a GameStats,
+    pub win_rate: f64,
+    pub push_rate: f64,
+    pub loss_rate: f64,
+    pub mean_result: Option<f64>,
+    pub result_sd: Option<f64>,
+}
+
+/// How a session went, told in a paragraph once it's over.
+#[derive(Clone, Debug, Default)]
+pub struct SessionSummary {
+    pub stats: GameStats,
+    /// decisions that matched basic strategy and decisions graded, when the trainer was on
+    pub graded: Option<(u32, u32)>,
+}
+impl SessionSummary {
+    pub fn paragraph(&self, money: &Money) -> String {
+        let mut paragraph = self.stats.summary(money);
+        if let Some((correct, decisions)) = self.graded {
+            paragraph.push_str(&format!(
+                " {correct} of {decisions} decisions matched basic strategy, {:.0}%.",
+                f64::from(correct) / f64::from(decisions.max(1)) * 100.0
+            ));
+        }
+        paragraph
+    }
+}
+
+/// This session's results, counting each hand once as it is settled.
+#[derive(Debug)]
+pub struct SessionStats {
+    pub stats: GameStats,
+    /// the settled hand on screen has been counted
+    counted: bool,
+    started: Instant,
+}
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self {
+            stats: GameStats::default(),
+            counted: false,
+            started: Instant::now(),
+        }
+    }
+}
+impl SessionStats {
+    /// Seconds since the session started.
+    pub fn elapsed(&self) -> u64 {
+        self.started.elapsed().as_secs()
+    }
+
+    pub fn update(&mut self, game: &Game) {
+        self.stats.seconds = self.elapsed();
+        match game.state {
+            GameState::HandScoreScreen(result) if !self.counted => {
+                let actions = history::actions(game, result);
+                self.stats
+                    .record(result, game.stake(), game.payout, &actions);
+                self.counted = true;
+            }
+            GameState::HandScoreScreen(_) => (),
+            _ => self.counted = false,
+        }
+    }
+}
+
+/// Share of `count` that were `won`, in percent.
+fn won(won: u64, count: u64) -> f64 {
+    won as f64 / count.max(1) as f64 * 100.0
+}
+
+/// A length of time played in hours and minutes, like `1:05`.
+pub fn clock(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 3600, seconds / 60 % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_doubles_and_splits_apart_from_hits() {
+        let mut stats = GameStats::default();
+        stats.record(
+            HandResult::PlayerWin,
+            20,
+            20,
+            &[Action::Hit, Action::Double],
+        );
+        stats.record(
+            HandResult::DealerWin,
+            20,
+            -20,
+            &[Action::Split, Action::Stand, Action::Double],
+        );
+        assert_eq!((stats.hits, stats.stands), (1, 1));
+        assert_eq!((stats.doubles, stats.doubles_won), (2, 1));
+        assert_eq!((stats.splits, stats.splits_won), (1, 0));
+        assert_eq!((stats.wins, stats.losses), (1, 1));
+        assert_eq!(stats.net, 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn adds_sessions_to_the_saved_totals() {
+        paths::use_test_dir();
+        GameStats::reset().unwrap();
+        let mut session = GameStats::default();
+        session.record(HandResult::Blackjack, 10, 15, &[]);
+        session.save_session().unwrap();
+        session.save_session().unwrap();
+        let lifetime = GameStats::load().unwrap();
+        assert_eq!((lifetime.sessions, lifetime.hands), (2, 2));
+        assert_eq!((lifetime.blackjacks, lifetime.net), (2, 30));
+
+        // totals that can't be read are reported, and not saved over
+        let path = GameStats::path().unwrap();
+        fs::write(&path, "hands = 12\n").unwrap();
+        let err = GameStats::load().unwrap_err();
+        assert!(err.starts_with(&path.display().to_string()), "{err}");
+        assert!(session.save_session().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hands = 12\n");
+        GameStats::reset().unwrap();
+    }
+}
@@ -0,0 +1,574 @@
+use std::fmt::Display;
+
+use crate::{rules::Rules, widgets::*};
+
+/// Something the player can do at the table. [`Game::legal_actions`] tells which can be
+/// done right now.
+///
+/// [`Game::legal_actions`]: crate::game::Game::legal_actions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+    /// take or turn down insurance against a dealer ace
+    Insurance(bool),
+    /// bet this many chips on the next hand
+    Bet(u32),
+}
+impl Action {
+    /// The moves of a hand, in the order of the number keys that make them, from 1.
+    pub const MOVES: [Action; 7] = [
+        Action::Hit,
+        Action::Stand,
+        Action::Double,
+        Action::Surrender,
+        Action::Insurance(true),
+        Action::Insurance(false),
+        Action::Split,
+    ];
+
+    /// The move number key `key` makes.
+    pub fn of_key(key: char) -> Option<Action> {
+        let index = key.to_digit(10)?.checked_sub(1)?;
+        Self::MOVES.get(index as usize).copied()
+    }
+
+    /// Name of the action in bot and headless output, without its amount.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Hit => "hit",
+            Action::Stand => "stand",
+            Action::Double => "double",
+            Action::Split => "split",
+            Action::Surrender => "surrender",
+            Action::Insurance(_) => "insurance",
+            Action::Bet(_) => "bet",
+        }
+    }
+}
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Insurance(true) => write!(f, "Insurance"),
+            Action::Insurance(false) => write!(f, "No insurance"),
+            Action::Bet(amount) => write!(f, "Bet {amount}"),
+            // defer to debug impl
+            action => write!(f, "{:?}", action),
+        }
+    }
+}
+
+/// A cell of the basic strategy chart, before it is resolved against the table rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Play {
+    Hit,
+    Stand,
+    /// double, otherwise hit
+    DoubleHit,
+    /// double, otherwise stand
+    DoubleStand,
+    Split,
+    /// split if doubling after split is allowed, otherwise hit
+    SplitHit,
+    /// surrender, otherwise hit
+    SurrenderHit,
+    /// surrender, otherwise stand
+    SurrenderStand,
+    /// surrender, otherwise split
+    SurrenderSplit,
+}
+impl Play {
+    /// Short chart notation for the cell.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Play::Hit => "H",
+            Play::Stand => "S",
+            Play::DoubleHit => "Dh",
+            Play::DoubleStand => "Ds",
+            Play::Split => "P",
+            Play::SplitHit => "Ph",
+            Play::SurrenderHit => "Rh",
+            Play::SurrenderStand => "Rs",
+            Play::SurrenderSplit => "Rp",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Play> {
+        [
+            Play::Hit,
+            Play::Stand,
+            Play::DoubleHit,
+            Play::DoubleStand,
+            Play::Split,
+            Play::SplitHit,
+            Play::SurrenderHit,
+            Play::SurrenderStand,
+            Play::SurrenderSplit,
+        ]
+        .into_iter()
+        .find(|play| play.code().eq_ignore_ascii_case(code))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HandKind {
+    Hard,
+    Soft,
+    Pair,
+}
+
+/// The player's hand against the dealer's up-card, as a row and column of the chart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Situation {
+    pub kind: HandKind,
+    /// hand total, or the value of one card for pairs
+    pub total: u8,
+    /// dealer up-card value, aces are 11
+    pub up: u8,
+    /// doubling and surrender are only possible on the first two cards
+    pub first_two: bool,
+}
+impl Situation {
+    /// Two cards of the same value are read from the pair rows, by the value of one card.
+    pub fn new(hand: &Hand<Player>, up_card: Card) -> Self {
+        let first_two = hand.cards().len() == 2;
+        let (kind, total) = if hand.is_pair() {
+            (HandKind::Pair, hand.cards()[0].rank().get_value())
+        } else if hand.is_soft() {
+            (HandKind::Soft, hand.count_value())
+        } else {
+            (HandKind::Hard, hand.count_value())
+        };
+        Self {
+            kind,
+            total,
+            up: up_card.rank().get_value(),
+            first_two,
+        }
+    }
+
+    /// The same hand read as a plain total, used when a pair isn't split.
+    fn unpaired(&self) -> Self {
+        let (kind, total) = match (self.kind, self.total) {
+            (HandKind::Pair, 11) => (HandKind::Soft, 12),
+            (HandKind::Pair, value) => (HandKind::Hard, value * 2),
+            (kind, total) => (kind, total),
+        };
+        Self {
+            kind,
+            total,
+            ..*self
+        }
+    }
+}
+impl Display for Situation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let up = card_value_label(self.up);
+        match self.kind {
+            HandKind::Hard => write!(f, "hard {} vs {up}", self.total),
+            HandKind::Soft => write!(f, "soft {} vs {up}", self.total),
+            HandKind::Pair => write!(f, "pair of {}s vs {up}", card_value_label(self.total)),
+        }
+    }
+}
+
+pub fn card_value_label(value: u8) -> String {
+    match value {
+        11 => "A".to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// A row of the printed chart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChartRow {
+    pub kind: HandKind,
+    pub total: u8,
+}
+impl ChartRow {
+    /// Every row of the chart, top to bottom.
+    pub fn all() -> impl Iterator<Item = ChartRow> {
+        let hard = (8..=17).map(|total| ChartRow {
+            kind: HandKind::Hard,
+            total,
+        });
+        let soft = (13..=20).map(|total| ChartRow {
+            kind: HandKind::Soft,
+            total,
+        });
+        let pairs = (2..=11).map(|total| ChartRow {
+            kind: HandKind::Pair,
+            total,
+        });
+        hard.chain(soft).chain(pairs)
+    }
+
+    /// Whether the situation is read from this row.
+    pub fn contains(&self, situation: &Situation) -> bool {
+        match (self.kind, self.total) {
+            (HandKind::Hard, 8) => situation.kind == HandKind::Hard && situation.total <= 8,
+            (HandKind::Hard, 17) => situation.kind == HandKind::Hard && situation.total >= 17,
+            (kind, total) => situation.kind == kind && situation.total == total,
+        }
+    }
+
+    /// The row a situation is read from.
+    pub fn of(situation: &Situation) -> Option<ChartRow> {
+        ChartRow::all().find(|row| row.contains(situation))
+    }
+
+    /// Name of one cell of this row, like `soft A,7 vs 9`.
+    pub fn cell_name(&self, up: u8) -> String {
+        let kind = match self.kind {
+            HandKind::Hard => "hard",
+            HandKind::Soft => "soft",
+            HandKind::Pair => "pair",
+        };
+        format!("{kind} {} vs {}", self.label(), card_value_label(up))
+    }
+
+    pub fn label(&self) -> String {
+        match (self.kind, self.total) {
+            (HandKind::Hard, 8) => "5-8".to_string(),
+            (HandKind::Hard, 17) => "17+".to_string(),
+            (HandKind::Hard, total) => total.to_string(),
+            (HandKind::Soft, total) => format!("A,{}", total - 11),
+            (HandKind::Pair, value) => {
+                let value = card_value_label(value);
+                format!("{value},{value}")
+            }
+        }
+    }
+}
+
+/// The recommended action for a situation under the given rules.
+pub fn recommend(situation: &Situation, rules: &Rules) -> Action {
+    recommend_from(situation, rules, &|kind, total, up| {
+        chart_play(kind, total, up, rules)
+    })
+}
+
+/// Like [`recommend`], reading the cells from another chart.
+pub fn recommend_from(
+    situation: &Situation,
+    rules: &Rules,
+    chart: &dyn Fn(HandKind, u8, u8) -> Play,
+) -> Action {
+    let play = chart(situation.kind, situation.total, situation.up);
+    let can_double = rules.double && situation.first_two;
+    let can_surrender = rules.surrender && situation.first_two;
+    let can_split = rules.split && situation.kind == HandKind::Pair;
+    let doubles_after = rules.double && rules.double_after_split;
+    match play {
+        Play::Hit => Action::Hit,
+        Play::Stand => Action::Stand,
+        Play::DoubleHit if can_double => Action::Double,
+        Play::DoubleHit => Action::Hit,
+        Play::DoubleStand if can_double => Action::Double,
+        Play::DoubleStand => Action::Stand,
+        Play::SurrenderHit if can_surrender => Action::Surrender,
+        Play::SurrenderHit => Action::Hit,
+        Play::SurrenderStand if can_surrender => Action::Surrender,
+        Play::SurrenderStand => Action::Stand,
+        Play::Split | Play::SplitHit if can_split && doubles_after => Action::Split,
+        Play::Split if can_split => Action::Split,
+        Play::SurrenderSplit if can_surrender => Action::Surrender,
+        Play::SurrenderSplit if can_split => Action::Split,
+        // a pair that can't be split, and one only worth splitting to double after, is
+        // played as its total
+        Play::Split | Play::SplitHit | Play::SurrenderSplit => {
+            recommend_from(&situation.unpaired(), rules, chart)
+        }
+    }
+}
+
+/// Index plays for hitting and standing: hard total, dealer up-card, and the true count
+/// at or above which the player stands. Below it, the player hits.
+pub const INDEX_PLAYS: [(u8, u8, i32); 10] = [
+    (16, 10, 0),
+    (15, 10, 4),
+    (16, 9, 5),
+    (12, 2, 3),
+    (12, 3, 2),
+    (12, 4, 0),
+    (12, 5, -2),
+    (12, 6, -1),
+    (13, 2, -1),
+    (13, 3, -2),
+];
+
+/// The play the count calls for instead of basic strategy, if any.
+pub fn deviation(situation: &Situation, true_count: f64) -> Option<Action> {
+    if situation.kind != HandKind::Hard {
+        return None;
+    }
+    let (_, _, index) = INDEX_PLAYS
+        .iter()
+        .find(|(total, up, _)| *total == situation.total && *up == situation.up)?;
+    Some(if true_count >= f64::from(*index) {
+        Action::Stand
+    } else {
+        Action::Hit
+    })
+}
+
+/// Look up a single chart cell. `total` is the card value for pairs, and `up` is the
+/// dealer's up-card value with aces as 11. The chart is picked by the number of decks
+/// and whether the dealer hits soft 17.
+pub fn chart_play(kind: HandKind, total: u8, up: u8, rules: &Rules) -> Play {
+    use Play::*;
+
+    let column = usize::from(up.clamp(2, 11) - 2);
+    if rules.dealer_hits_soft_17 {
+        match (kind, total, up) {
+            (HandKind::Hard, 11, 11) => return DoubleHit,
+            (HandKind::Hard, 15, 11) => return SurrenderHit,
+            (HandKind::Hard, 17, 11) => return SurrenderStand,
+            (HandKind::Soft, 18, 2) => return DoubleStand,
+            (HandKind::Soft, 19, 6) => return DoubleStand,
+            (HandKind::Pair, 8, 11) => return SurrenderSplit,
+            _ => (),
+        }
+    }
+    if let Some(play) = few_decks_play(kind, total, up, rules.decks) {
+        return play;
+    }
+
+    match kind {
+        HandKind::Hard => match total {
+            ..=8 => Hit,
+            9..=16 => HARD[usize::from(total - 9)][column],
+            17.. => Stand,
+        },
+        HandKind::Soft => match total {
+            ..=12 => Hit,
+            13..=19 => SOFT[usize::from(total - 13)][column],
+            20.. => Stand,
+        },
+        HandKind::Pair => PAIRS[usize::from(total.clamp(2, 11) - 2)][column],
+    }
+}
+
+/// The cells where a single or double deck game is played differently from a shoe.
+/// Fewer decks leave each card dealt making more of a difference, which mostly favors
+/// doubling and splitting.
+fn few_decks_play(kind: HandKind, total: u8, up: u8, decks: u8) -> Option<Play> {
+    use HandKind::{Hard, Pair, Soft};
+
+    let play = match (decks, kind, total, up) {
+        (1, Hard, 8, 5 | 6) => Dh,
+        (1 | 2, Hard, 9, 2) => Dh,
+        (1 | 2, Hard, 11, 11) => Dh,
+        (1, Hard, 16, 9) => H,
+        (1, Soft, 13 | 14, 4) => Dh,
+        (1, Soft, 17, 2) => Dh,
+        (1, Soft, 18, 11) => S,
+        (1, Soft, 19, 6) => Ds,
+        (1 | 2, Pair, 2 | 3, 2 | 3) => P,
+        (1, Pair, 3, 8) => Ph,
+        (1, Pair, 4, 4) => Ph,
+        (1 | 2, Pair, 6, 2) => P,
+        (1 | 2, Pair, 6, 7) => Ph,
+        (1 | 2, Pair, 7, 8) => Ph,
+        (1, Pair, 7, 10) => Play::SurrenderStand,
+        _ => return None,
+    };
+    Some(play)
+}
+
+use Play::{
+    DoubleHit as Dh, DoubleStand as Ds, Hit as H, Split as P, SplitHit as Ph, Stand as S,
+    SurrenderHit as Rh,
+};
+
+// four or more decks, dealer stands on soft 17, columns are dealer 2 through ace
+
+/// hard 9 through 16
+pub const HARD: [[Play; 10]; 8] = [
+    [H, Dh, Dh, Dh, Dh, H, H, H, H, H],
+    [Dh, Dh, Dh, Dh, Dh, Dh, Dh, Dh, H, H],
+    [Dh, Dh, Dh, Dh, Dh, Dh, Dh, Dh, Dh, H],
+    [H, H, S, S, S, H, H, H, H, H],
+    [S, S, S, S, S, H, H, H, H, H],
+    [S, S, S, S, S, H, H, H, H, H],
+    [S, S, S, S, S, H, H, H, Rh, H],
+    [S, S, S, S, S, H, H, Rh, Rh, Rh],
+];
+
+/// soft 13 (A-2) through soft 19 (A-8)
+pub const SOFT: [[Play; 10]; 7] = [
+    [H, H, H, Dh, Dh, H, H, H, H, H],
+    [H, H, H, Dh, Dh, H, H, H, H, H],
+    [H, H, Dh, Dh, Dh, H, H, H, H, H],
+    [H, H, Dh, Dh, Dh, H, H, H, H, H],
+    [H, Dh, Dh, Dh, Dh, H, H, H, H, H],
+    [S, Ds, Ds, Ds, Ds, S, S, H, H, H],
+    [S, S, S, S, S, S, S, S, S, S],
+];
+
+/// pairs of 2s through aces
+pub const PAIRS: [[Play; 10]; 10] = [
+    [Ph, Ph, P, P, P, P, H, H, H, H],
+    [Ph, Ph, P, P, P, P, H, H, H, H],
+    [H, H, H, Ph, Ph, H, H, H, H, H],
+    [Dh, Dh, Dh, Dh, Dh, Dh, Dh, Dh, H, H],
+    [Ph, P, P, P, P, H, H, H, H, H],
+    [P, P, P, P, P, P, H, H, H, H],
+    [P, P, P, P, P, P, P, P, P, P],
+    [P, P, P, P, P, S, P, P, S, S],
+    [S, S, S, S, S, S, S, S, S, S],
+    [P, P, P, P, P, P, P, P, P, P],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::parse_card_code;
+
+    fn situation(kind: HandKind, total: u8, up: u8) -> Situation {
+        Situation {
+            kind,
+            total,
+            up,
+            first_two: true,
+        }
+    }
+
+    #[test]
+    fn plays_the_chart_within_the_rules() {
+        let rules = Rules::default();
+        let no_double = Rules {
+            double: false,
+            ..rules
+        };
+        let surrender = Rules {
+            surrender: true,
+            ..rules
+        };
+        let eleven = situation(HandKind::Hard, 11, 6);
+        assert_eq!(recommend(&eleven, &rules), Action::Double);
+        assert_eq!(recommend(&eleven, &no_double), Action::Hit);
+        let drawn = Situation {
+            first_two: false,
+            ..eleven
+        };
+        assert_eq!(recommend(&drawn, &rules), Action::Hit);
+
+        let sixteen = situation(HandKind::Hard, 16, 10);
+        assert_eq!(recommend(&sixteen, &rules), Action::Hit);
+        assert_eq!(recommend(&sixteen, &surrender), Action::Surrender);
+        assert_eq!(
+            recommend(&situation(HandKind::Soft, 18, 3), &rules),
+            Action::Double
+        );
+        let eights = situation(HandKind::Pair, 8, 10);
+        assert_eq!(recommend(&eights, &surrender), Action::Split);
+        // a pair of eights that can't be split is a hard 16
+        let no_split = Rules {
+            split: false,
+            ..surrender
+        };
+        assert_eq!(recommend(&eights, &no_split), Action::Surrender);
+        // and aces are a soft 12
+        let aces = situation(HandKind::Pair, 11, 6);
+        assert_eq!(recommend(&aces, &rules), Action::Split);
+        assert_eq!(recommend(&aces, &no_split), Action::Hit);
+        // in a shoe, twos against a 2 are only split to double after
+        let twos = situation(HandKind::Pair, 2, 2);
+        let shoe = Rules { decks: 6, ..rules };
+        let das = Rules {
+            double_after_split: true,
+            ..shoe
+        };
+        assert_eq!(recommend(&twos, &shoe), Action::Hit);
+        assert_eq!(recommend(&twos, &das), Action::Split);
+    }
+
+    #[test]
+    fn hitting_soft_17_changes_a_few_cells() {
+        let s17 = Rules {
+            decks: 6,
+            ..Rules::default()
+        };
+        let h17 = Rules {
+            dealer_hits_soft_17: true,
+            ..s17
+        };
+        assert_eq!(chart_play(HandKind::Hard, 11, 11, &s17), Play::Hit);
+        assert_eq!(chart_play(HandKind::Hard, 11, 11, &h17), Play::DoubleHit);
+        assert_eq!(chart_play(HandKind::Soft, 19, 6, &s17), Play::Stand);
+        assert_eq!(chart_play(HandKind::Soft, 19, 6, &h17), Play::DoubleStand);
+    }
+
+    #[test]
+    fn the_number_of_decks_picks_the_chart() {
+        let single = Rules::default();
+        let double = Rules { decks: 2, ..single };
+        let shoe = Rules { decks: 6, ..single };
+        assert_eq!(chart_play(HandKind::Hard, 11, 11, &single), Play::DoubleHit);
+        assert_eq!(chart_play(HandKind::Hard, 11, 11, &double), Play::DoubleHit);
+        assert_eq!(chart_play(HandKind::Hard, 11, 11, &shoe), Play::Hit);
+        assert_eq!(chart_play(HandKind::Soft, 18, 11, &single), Play::Stand);
+        assert_eq!(chart_play(HandKind::Soft, 18, 11, &shoe), Play::Hit);
+        assert_eq!(chart_play(HandKind::Pair, 6, 7, &double), Play::SplitHit);
+        assert_eq!(chart_play(HandKind::Pair, 6, 7, &shoe), Play::Hit);
+    }
+
+    #[test]
+    fn reads_pairs_from_the_pair_rows() {
+        let up = parse_card_code("6S").unwrap();
+        let hand = |codes: [&str; 2]| Hand::new(codes.map(|code| parse_card_code(code).unwrap()));
+        let situation = Situation::new(&hand(["8S", "8H"]), up);
+        assert_eq!((situation.kind, situation.total), (HandKind::Pair, 8));
+        let situation = Situation::new(&hand(["AS", "AH"]), up);
+        assert_eq!((situation.kind, situation.total), (HandKind::Pair, 11));
+        // a king and a ten are worth the same
+        let situation = Situation::new(&hand(["KS", "10H"]), up);
+        assert_eq!((situation.kind, situation.total), (HandKind::Pair, 10));
+        let situation = Situation::new(&hand(["AS", "8H"]), up);
+        assert_eq!((situation.kind, situation.total), (HandKind::Soft, 19));
+    }
+
+    #[test]
+    fn the_count_moves_index_plays() {
+        let sixteen = situation(HandKind::Hard, 16, 10);
+        assert_eq!(deviation(&sixteen, 0.0), Some(Action::Stand));
+        assert_eq!(deviation(&sixteen, -0.5), Some(Action::Hit));
+        let twelve = situation(HandKind::Hard, 12, 2);
+        assert_eq!(deviation(&twelve, 2.9), Some(Action::Hit));
+        assert_eq!(deviation(&twelve, 3.0), Some(Action::Stand));
+        assert_eq!(deviation(&situation(HandKind::Soft, 16, 10), 5.0), None);
+        assert_eq!(deviation(&situation(HandKind::Hard, 14, 10), 5.0), None);
+    }
+
+    #[test]
+    fn reads_keys_and_chart_codes() {
+        assert_eq!(Action::of_key('1'), Some(Action::Hit));
+        assert_eq!(Action::of_key('6'), Some(Action::Insurance(false)));
+        assert_eq!(Action::of_key('0'), None);
+        assert_eq!(Action::of_key('7'), Some(Action::Split));
+        assert_eq!(Action::of_key('8'), None);
+        assert_eq!(Play::from_code("rh"), Some(Play::SurrenderHit));
+        assert_eq!(Play::from_code("X"), None);
+    }
+
+    #[test]
+    fn every_situation_has_a_chart_row() {
+        for kind in [HandKind::Hard, HandKind::Soft] {
+            let totals = match kind {
+                HandKind::Hard => 4..=21,
+                _ => 13..=20,
+            };
+            for total in totals {
+                let row = ChartRow::of(&situation(kind, total, 2)).unwrap();
+                assert_eq!(row.kind, kind);
+            }
+        }
+        let row = ChartRow::of(&situation(HandKind::Hard, 5, 11)).unwrap();
+        assert_eq!(row.cell_name(11), "hard 5-8 vs A");
+    }
+}
@@ -0,0 +1,200 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{
+    counting::{self, Count},
+    rules::Rules,
+    strategy::{self, HandKind, Play, Situation},
+    widgets::Shoe,
+};
+
+/// A home-made strategy for the simulator, read from a CSV chart like:
+///
+/// ```text
+/// # row, then the play against dealer 2 through ace
+/// ,2,3,4,5,6,7,8,9,10,A
+/// hard 16,S,S,S,S,S,H,H,H,H,H
+/// soft 18,S,Ds,Ds,Ds,Ds,S,S,H,H,H
+/// pair 8,P,P,P,P,P,P,P,P,P,P
+/// # optional bet units at true count 0 or less, 1, 2, and so on
+/// ramp,1,1,2,4,8
+/// ```
+///
+/// Cells use the codes of the printed chart, and rows that aren't given are played by
+/// basic strategy.
+#[derive(Clone, Debug)]
+pub struct StrategyFile {
+    pub name: String,
+    rows: HashMap<(HandKind, u8), [Play; 10]>,
+    /// bet units by true count, the last applying to every count above it
+    ramp: Option<Vec<u32>>,
+}
+impl StrategyFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("couldn't read {}: {err}", path.display()))?;
+        let name = path
+            .file_stem()
+            .map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+        Self::parse(name, &contents).map_err(|err| format!("{}:{err}", path.display()))
+    }
+
+    fn parse(name: String, contents: &str) -> Result<Self, String> {
+        let mut file = Self {
+            name,
+            rows: HashMap::new(),
+            ramp: None,
+        };
+        for (number, line) in contents.lines().enumerate() {
+            let number = number + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut fields = line.split(',').map(str::trim);
+            let row = fields.next().unwrap_or_default().to_lowercase();
+            // blank lines and the header row
+            if row.is_empty() {
+                continue;
+            }
+
+            if row == "ramp" {
+                let ramp = fields
+                    .map(|units| {
+                        units
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|units| *units > 0)
+                            .ok_or_else(|| format!("{number}: expected bet units, found '{units}'"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if ramp.is_empty() {
+                    return Err(format!("{number}: the ramp needs at least one bet"));
+                }
+                file.ramp = Some(ramp);
+                continue;
+            }
+
+            let key = parse_row(&row).ok_or_else(|| {
+                format!("{number}: unknown row '{row}', expected something like 'hard 16'")
+            })?;
+            let plays = fields
+                .map(|code| {
+                    Play::from_code(code)
+                        .ok_or_else(|| format!("{number}: unknown play '{code}' in '{row}'"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let plays: [Play; 10] = plays.try_into().map_err(|plays: Vec<_>| {
+                format!(
+                    "{number}: '{row}' has {} plays, expected one for each up-card 2 to A",
+                    plays.len()
+                )
+            })?;
+            if file.rows.insert(key, plays).is_some() {
+                return Err(format!("{number}: '{row}' is given twice"));
+            }
+        }
+        Ok(file)
+    }
+
+    /// The chart cell for a hand, falling back to basic strategy.
+    fn play(&self, kind: HandKind, total: u8, up: u8, rules: &Rules) -> Play {
+        match self.rows.get(&(kind, total)) {
+            Some(plays) => plays[usize::from(up.clamp(2, 11) - 2)],
+            None => strategy::chart_play(kind, total, up, rules),
+        }
+    }
+
+    pub fn recommend(&self, situation: &Situation, rules: &Rules) -> strategy::Action {
+        strategy::recommend_from(situation, rules, &|kind, total, up| {
+            self.play(kind, total, up, rules)
+        })
+    }
+
+    /// Bet units for the count, if the file has a ramp.
+    pub fn units(&self, count: &Count, deck: &Shoe) -> Option<u32> {
+        let ramp = self.ramp.as_ref()?;
+        let count = if count.system.is_balanced() {
+            count.true_count(counting::decks_remaining(deck)).floor() as i32
+        } else {
+            count.running
+        };
+        let index = usize::try_from(count.max(0)).unwrap_or_default();
+        Some(ramp[index.min(ramp.len() - 1)])
+    }
+}
+
+/// Parse a row name like `hard 16`, `soft 18`, or `pair A`.
+fn parse_row(row: &str) -> Option<(HandKind, u8)> {
+    let (kind, total) = row.split_once(' ')?;
+    let total = match total.trim() {
+        "a" => 11,
+        total => total.parse().ok()?,
+    };
+    match kind {
+        "hard" if (4..=21).contains(&total) => Some((HandKind::Hard, total)),
+        "soft" if (12..=21).contains(&total) => Some((HandKind::Soft, total)),
+        "pair" if (2..=11).contains(&total) => Some((HandKind::Pair, total)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{counting::HiLo, strategy::Action};
+
+    const CHART: &str = "\
+# row, then the play against dealer 2 through ace
+,2,3,4,5,6,7,8,9,10,A
+hard 16,S,S,S,S,S,H,H,H,S,H
+pair 10,P,P,P,P,P,P,P,P,P,P
+ramp,1,1,2,4
+";
+
+    #[test]
+    fn plays_its_rows_and_basic_strategy_for_the_rest() {
+        let file = StrategyFile::parse("tens".to_string(), CHART).unwrap();
+        let rules = Rules::default();
+        let situation = |kind, total, up| Situation {
+            kind,
+            total,
+            up,
+            first_two: true,
+        };
+        assert_eq!(
+            file.recommend(&situation(HandKind::Hard, 16, 10), &rules),
+            Action::Stand
+        );
+        assert_eq!(
+            file.recommend(&situation(HandKind::Pair, 10, 6), &rules),
+            Action::Split
+        );
+        assert_eq!(
+            file.recommend(&situation(HandKind::Hard, 11, 6), &rules),
+            Action::Double
+        );
+
+        let deck = Shoe::new(1);
+        let count = |running| Count {
+            running,
+            ..Count::new(&HiLo, 1, 0)
+        };
+        assert_eq!(file.units(&count(-3), &deck), Some(1));
+        assert_eq!(file.units(&count(2), &deck), Some(2));
+        assert_eq!(file.units(&count(20), &deck), Some(4));
+    }
+
+    #[test]
+    fn points_at_the_line_it_cannot_read() {
+        let parse = |contents| StrategyFile::parse(String::new(), contents).unwrap_err();
+        assert_eq!(
+            parse("hard 16,S,S\n"),
+            "1: 'hard 16' has 2 plays, expected one for each up-card 2 to A"
+        );
+        assert_eq!(
+            parse("\nhard 3,S"),
+            "2: unknown row 'hard 3', expected something like 'hard 16'"
+        );
+        assert_eq!(parse("soft 18,S,X"), "1: unknown play 'X' in 'soft 18'");
+        assert_eq!(parse("ramp,1,0"), "1: expected bet units, found '0'");
+        let twice = format!("{CHART}hard 16,S,S,S,S,S,H,H,H,H,H");
+        assert_eq!(parse(&twice), "6: 'hard 16' is given twice");
+    }
+}
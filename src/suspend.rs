@@ -0,0 +1,37 @@
+use std::io::{self, Write};
+
+use ratatui::{prelude::Backend, Terminal};
+
+use crate::term::{self, KeyCode, KeyEvent, KeyEventKind};
+
+/// Whether `key` is Ctrl+Z, which reaches the game as a key in raw mode instead of
+/// stopping it.
+pub fn is_suspend_key(key: &KeyEvent) -> bool {
+    key.kind == KeyEventKind::Press && key.code == KeyCode::Char('z') && key.ctrl
+}
+
+/// Give the terminal back to the shell and stop, as Ctrl+Z does outside raw mode. Once
+/// resumed with `fg` the game takes the terminal back and draws everything again.
+pub fn suspend(terminal: &mut Terminal<impl Backend + Write>) -> io::Result<()> {
+    term::leave(terminal.backend_mut())?;
+    terminal.show_cursor()?;
+
+    stop();
+
+    term::enter(terminal.backend_mut())?;
+    terminal.hide_cursor()?;
+    terminal.clear()
+}
+
+/// Stop until the shell sends SIGCONT.
+#[cfg(unix)]
+fn stop() {
+    // SAFETY: raising a signal at ourselves has no memory safety requirements
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+}
+
+/// Without job control there is nothing to stop for.
+#[cfg(not(unix))]
+fn stop() {}
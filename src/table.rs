@@ -0,0 +1,447 @@
+use tracing::{debug, info};
+
+use crate::{
+    config::Config,
+    game::{HandResult, IllegalAction},
+    rules::Rules,
+    stats::GameStats,
+    strategy::Action,
+    widgets::{Dealer, Hand, Player, Shoe},
+};
+
+/// One player at a shared table.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Seat {
+    pub name: String,
+    pub bankroll: u32,
+    /// the bet on the current hand, 0 when sitting it out
+    pub bet: u32,
+    pub hand: Hand<Player>,
+    pub actions: Vec<Action>,
+    /// the result and payout, once the hand is settled
+    pub result: Option<(HandResult, i64)>,
+    pub stats: GameStats,
+    /// the player has left, and the seat is skipped from now on
+    pub left: bool,
+}
+impl Seat {
+    fn new(name: String, bankroll: u32, bet_unit: u32) -> Self {
+        Self {
+            name,
+            bankroll,
+            bet: bet_unit.min(bankroll),
+            hand: Hand::default(),
+            actions: Vec::new(),
+            result: None,
+            stats: GameStats::default(),
+            left: false,
+        }
+    }
+
+    /// The seat can bet on the next hand.
+    pub fn can_bet(&self) -> bool {
+        !self.left && self.bankroll > 0
+    }
+
+    fn is_playing(&self) -> bool {
+        self.is_live() && self.hand.is_active()
+    }
+
+    /// Still in the hand when the dealer plays.
+    fn is_live(&self) -> bool {
+        self.bet > 0 && self.result.is_none()
+    }
+
+    fn settle(&mut self, result: HandResult, rules: &Rules) {
+        let payout = result.pay(self.bet, &self.hand, rules, &mut self.bankroll);
+        info!(
+            seat = %self.name,
+            result = result.name(),
+            bet = self.bet,
+            payout,
+            bankroll = self.bankroll,
+            cards = %self.hand.short(),
+            "settled a seat"
+        );
+        self.result = Some((result, payout));
+        self.stats.record(result, self.bet, payout, &self.actions);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    Betting,
+    Playing,
+    Dealer,
+    Results,
+}
+
+/// Several players taking turns against one dealer hand from a shared shoe. Seats bet
+/// and then play in order, and the dealer plays once every seat is done.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
+    pub rules: Rules,
+    pub bet_unit: u32,
+    pub deck: Shoe,
+    pub dealer: Hand<Dealer>,
+    pub seats: Vec<Seat>,
+    pub phase: Phase,
+    /// the seat whose turn it is to bet or play
+    pub turn: usize,
+    /// bankroll each new seat starts with
+    bankroll: u32,
+    /// the seats are boxes one player plays from a single bankroll
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shared_bankroll: bool,
+}
+impl Table {
+    pub fn new(config: &Config, names: impl IntoIterator<Item = String>) -> Self {
+        let mut table = Self {
+            rules: config.rules,
+            bet_unit: config.bet_unit,
+            deck: Shoe::for_table(&config.rules, config.shuffle.clone()),
+            dealer: Hand::default(),
+            seats: names
+                .into_iter()
+                .map(|name| Seat::new(name, config.bankroll, config.bet_unit))
+                .collect(),
+            phase: Phase::Betting,
+            turn: 0,
+            bankroll: config.bankroll,
+            shared_bankroll: false,
+        };
+        table.new_round();
+        table
+    }
+
+    /// A table of `boxes` seats played by one player, bet and played in turn from one
+    /// bankroll.
+    pub fn boxes(config: &Config, boxes: u8) -> Self {
+        let names = (1..=boxes).map(|number| format!("Box {number}"));
+        let mut table = Self::new(config, names);
+        table.shared_bankroll = true;
+        table
+    }
+
+    /// Seat a new player at one of at most `max` seats, who waits for the next hand if
+    /// one is being played or betting has gone past their seat. A seat someone left is
+    /// only given away once every seat is taken, and never during a hand it's still in,
+    /// so a dropped player can usually [rejoin](Table::rejoin).
+    pub fn join(&mut self, name: String, max: usize) -> Result<usize, String> {
+        let index = match self.seats.len() < max {
+            true => self.seats.len(),
+            false => self
+                .seats
+                .iter()
+                .position(|seat| seat.left && seat.bet == 0)
+                .ok_or_else(|| "the table is full".to_string())?,
+        };
+        let mut seat = Seat::new(name, self.bankroll, self.bet_unit);
+        if self.phase != Phase::Betting || index < self.turn {
+            seat.bet = 0;
+        }
+        match self.seats.get_mut(index) {
+            Some(left) => *left = seat,
+            None => self.seats.push(seat),
+        }
+        Ok(index)
+    }
+
+    /// Take a player out of the game, standing on their hand if they're playing one.
+    pub fn leave(&mut self, seat: usize) {
+        self.seats[seat].left = true;
+        match self.phase {
+            Phase::Betting => {
+                self.seats[seat].bet = 0;
+                if self.turn == seat {
+                    self.next_bettor(seat + 1);
+                }
+            }
+            Phase::Playing => {
+                self.seats[seat].hand.hold();
+                if self.turn == seat {
+                    self.next_player(seat + 1);
+                }
+            }
+            Phase::Dealer | Phase::Results => (),
+        }
+    }
+
+    /// Bring back a player who left, with the bankroll they left with. They wait for
+    /// the next hand if betting on this one has gone past their seat.
+    pub fn rejoin(&mut self, seat: usize) {
+        let waits = self.phase != Phase::Betting || seat < self.turn;
+        let seat = &mut self.seats[seat];
+        seat.left = false;
+        seat.bet = if waits {
+            0
+        } else {
+            self.bet_unit.min(seat.bankroll)
+        };
+    }
+
+    /// The seats still at the table.
+    pub fn players(&self) -> impl Iterator<Item = (usize, &Seat)> {
+        self.seats.iter().enumerate().filter(|(_, seat)| !seat.left)
+    }
+
+    /// Nobody at the table has chips left to bet.
+    pub fn is_over(&self) -> bool {
+        !self.seats.iter().any(Seat::can_bet)
+    }
+
+    /// Take bets again, starting from the first seat with chips.
+    pub fn new_round(&mut self) {
+        let hands = self.seats.iter().flat_map(|seat| seat.hand.cards());
+        self.deck.discard(hands.chain(self.dealer.cards()).copied());
+        if self.deck.needs_shuffle() {
+            self.deck.reshuffle();
+        }
+        for seat in &mut self.seats {
+            seat.bet = if seat.can_bet() {
+                seat.bet.max(self.bet_unit).min(seat.bankroll)
+            } else {
+                0
+            };
+            seat.hand = Hand::default();
+            seat.actions.clear();
+            seat.result = None;
+        }
+        self.dealer = Hand::default();
+        self.phase = Phase::Betting;
+        debug!(players = self.players().count(), "taking bets");
+        self.turn = 0;
+        self.next_bettor(0);
+    }
+
+    /// Chips a seat can bet. Boxes sharing a bankroll bet what the boxes before them left.
+    pub fn available(&self, seat: usize) -> u32 {
+        let staked: u32 = match self.shared_bankroll {
+            true => self.seats[..seat].iter().map(|seat| seat.bet).sum(),
+            false => 0,
+        };
+        self.seats[seat].bankroll.saturating_sub(staked)
+    }
+
+    /// Place the bet for the seat whose turn it is, or sit the hand out with a bet of 0.
+    pub fn place_bet(&mut self, seat: usize, bet: u32) -> Result<(), String> {
+        if self.phase != Phase::Betting || seat != self.turn {
+            return Err("it isn't your turn to bet".to_string());
+        }
+        if bet > self.available(seat) {
+            return Err(format!(
+                "a bet of {bet} is more than your bankroll of {}",
+                self.available(seat)
+            ));
+        }
+        self.seats[seat].bet = bet;
+        debug!(seat, bet, "placed a bet");
+        self.next_bettor(seat + 1);
+        Ok(())
+    }
+
+    /// Hit or stand for the seat whose turn it is. The table deals no other moves.
+    pub fn act(&mut self, seat: usize, action: Action) -> Result<(), IllegalAction> {
+        if self.phase != Phase::Playing || seat != self.turn {
+            return Err(IllegalAction::NotYourTurn);
+        }
+        let player = &mut self.seats[seat];
+        match action {
+            Action::Hit => {
+                player.hand.hit(&mut self.deck);
+            }
+            Action::Stand => player.hand.hold(),
+            Action::Bet(_) => return Err(IllegalAction::HandInPlay),
+            action => return Err(IllegalAction::NotOffered(action)),
+        }
+        player.actions.push(action);
+        debug!(seat = %player.name, %action, "seat played");
+        if player.hand.is_bust() {
+            self.settle(seat, HandResult::Bust);
+        }
+        if !self.seats[seat].is_playing() {
+            self.next_player(self.turn + 1);
+        }
+        Ok(())
+    }
+
+    /// Settle a seat's hand, and every box's bankroll along with it when they share one.
+    fn settle(&mut self, seat: usize, result: HandResult) {
+        self.seats[seat].settle(result, &self.rules);
+        if self.shared_bankroll {
+            let bankroll = self.seats[seat].bankroll;
+            for seat in &mut self.seats {
+                seat.bankroll = bankroll;
+            }
+        }
+    }
+
+    /// Seats with a bet on the hand being played.
+    fn betting_seats(&self) -> Vec<usize> {
+        (0..self.seats.len())
+            .filter(|&seat| self.seats[seat].bet > 0)
+            .collect()
+    }
+
+    /// Draw one dealer card, settling every live seat once the dealer is done.
+    pub fn dealer_step(&mut self) {
+        if self.phase != Phase::Dealer {
+            return;
+        }
+        self.dealer.reveal();
+        if self
+            .dealer
+            .do_dealer_action(&mut self.deck, self.rules.dealer_hits_soft_17)
+            .is_some()
+            && !self.dealer.is_bust()
+        {
+            return;
+        }
+        self.dealer.hold();
+        self.dealer.reveal();
+        debug!(dealer = %self.dealer.short(), total = self.dealer.count_value(), "dealer done");
+        for seat in self.betting_seats() {
+            if self.seats[seat].is_live() {
+                let result = HandResult::of(&self.seats[seat].hand, &self.dealer, &self.rules);
+                self.settle(seat, result);
+            }
+        }
+        self.phase = Phase::Results;
+    }
+
+    /// Move to the next seat from `from` that can bet, or deal when every bet is in.
+    fn next_bettor(&mut self, from: usize) {
+        match (from..self.seats.len()).find(|&seat| self.seats[seat].can_bet()) {
+            Some(seat) => self.turn = seat,
+            None if self.seats.iter().any(|seat| seat.bet > 0) => self.deal(),
+            None => self.phase = Phase::Results,
+        }
+    }
+
+    fn deal(&mut self) {
+        for seat in self.seats.iter_mut().filter(|seat| seat.bet > 0) {
+            seat.hand = self.deck.new_hand();
+        }
+        self.dealer = self.deck.new_hand();
+        debug!(dealer_up = %self.dealer.up_card().short(), "dealt the table");
+
+        let dealer_blackjack = self.dealer.is_blackjack();
+        if dealer_blackjack {
+            self.dealer.reveal();
+        }
+        for seat in self.betting_seats() {
+            let hand = &self.seats[seat].hand;
+            if let Some(result) = HandResult::natural(hand, &self.dealer, &self.rules) {
+                self.seats[seat].hand.hold();
+                self.settle(seat, result);
+            }
+        }
+        if dealer_blackjack {
+            self.phase = Phase::Results;
+            return;
+        }
+        self.phase = Phase::Playing;
+        self.next_player(0);
+    }
+
+    /// Move to the next seat from `from` still playing, or to the dealer.
+    fn next_player(&mut self, from: usize) {
+        match (from..self.seats.len()).find(|&seat| self.seats[seat].is_playing()) {
+            Some(seat) => self.turn = seat,
+            None if self.seats.iter().any(Seat::is_live) => self.phase = Phase::Dealer,
+            None => {
+                self.dealer.reveal();
+                self.phase = Phase::Results;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shuffle::Shuffler;
+
+    /// A table of two seats betting 10 each from a deck that deals the cards `first`
+    /// names, two to each seat and then two to the dealer.
+    fn dealt(first: &str) -> Table {
+        let mut config = Config {
+            shuffle: Shuffler::stacked(first),
+            ..Config::default()
+        };
+        config.rules.set("decks", "1").unwrap();
+        let mut table = Table::new(&config, ["Ada".to_string(), "Bo".to_string()]);
+        table.place_bet(0, 10).unwrap();
+        table.place_bet(1, 10).unwrap();
+        table
+    }
+
+    #[test]
+    fn settles_naturals_as_they_are_dealt() {
+        let table = dealt("AS KS 10S 6S 9S 7S");
+        assert_eq!(table.seats[0].result, Some((HandResult::Blackjack, 15)));
+        assert_eq!(table.seats[0].bankroll, 1015);
+        assert_eq!(table.phase, Phase::Playing);
+        assert_eq!(table.turn, 1);
+
+        let table = dealt("AS KS 10S 6S AH QH");
+        assert_eq!(table.seats[0].result, Some((HandResult::Push, 0)));
+        assert_eq!(table.seats[1].result, Some((HandResult::DealerWin, -10)));
+        assert_eq!(table.phase, Phase::Results);
+    }
+
+    #[test]
+    fn refuses_moves_the_table_doesnt_deal() {
+        let mut table = dealt("10S 6S 9S 7S 9H 7H");
+        assert_eq!(
+            table.act(0, Action::Double),
+            Err(IllegalAction::NotOffered(Action::Double))
+        );
+        assert_eq!(table.act(1, Action::Hit), Err(IllegalAction::NotYourTurn));
+        assert_eq!(table.act(0, Action::Stand), Ok(()));
+        assert_eq!(table.act(1, Action::Stand), Ok(()));
+        assert_eq!(table.phase, Phase::Dealer);
+    }
+
+    #[test]
+    fn settles_every_seat_once_the_dealer_stands() {
+        let mut table = dealt("10S 9S 10H 6H 9D 8D");
+        table.act(0, Action::Stand).unwrap();
+        table.act(1, Action::Stand).unwrap();
+        while table.phase == Phase::Dealer {
+            table.dealer_step();
+        }
+        assert_eq!(table.phase, Phase::Results);
+        assert_eq!(table.seats[0].result, Some((HandResult::PlayerWin, 10)));
+        assert_eq!(table.seats[1].result, Some((HandResult::DealerWin, -10)));
+        assert_eq!(table.seats[0].bankroll, 1010);
+        assert_eq!(table.seats[1].bankroll, 990);
+    }
+
+    #[test]
+    fn gives_away_a_seat_only_once_its_hand_is_over() {
+        let mut table = dealt("10S 9S 10H 6H 9D 8D");
+        table.leave(0);
+        assert_eq!(
+            table.join("Cy".to_string(), 2),
+            Err("the table is full".to_string())
+        );
+        assert_eq!(table.join("Cy".to_string(), 3), Ok(2));
+        assert_eq!(table.seats[2].bet, 0);
+
+        table.act(1, Action::Stand).unwrap();
+        while table.phase == Phase::Dealer {
+            table.dealer_step();
+        }
+        table.new_round();
+        assert_eq!(table.turn, 1);
+        assert_eq!(table.join("Di".to_string(), 3), Ok(0));
+        assert_eq!(table.seats[0].name, "Di");
+        assert!(!table.seats[0].left);
+        assert_eq!(table.seats[0].bet, 0);
+        assert_eq!(table.seats.len(), 3);
+    }
+}
@@ -0,0 +1,734 @@
+use std::io::{self, Stdout, Write};
+
+use ratatui::Terminal;
+
+#[cfg(not(any(feature = "crossterm", feature = "termion", feature = "termwiz")))]
+compile_error!("the tui feature needs a terminal backend: enable crossterm, termion, or termwiz");
+
+pub use backend::{poll, read, report_focus, reports_releases, Backend};
+
+/// The game's terminal, drawn on stdout.
+pub type Tui = Terminal<Backend<Stdout>>;
+
+/// A key as the game sees it, whichever backend read it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    BackTab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    /// a key the game has no use for, like F1 or Page Up
+    Other,
+}
+
+/// Whether a key went down, is held, or came back up. Only terminals speaking the kitty
+/// keyboard protocol, and Windows, tell anything but presses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyEventKind {
+    Press,
+    Repeat,
+    Release,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub kind: KeyEventKind,
+    /// held with Ctrl
+    pub ctrl: bool,
+    /// held with Alt, Super, or any other modifier but Shift and Ctrl
+    pub alt: bool,
+}
+
+impl From<KeyCode> for KeyEvent {
+    /// `code` pressed on its own.
+    fn from(code: KeyCode) -> Self {
+        Self {
+            code,
+            kind: KeyEventKind::Press,
+            ctrl: false,
+            alt: false,
+        }
+    }
+}
+
+/// What the terminal reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Key(KeyEvent),
+    /// text pasted in one go, where the terminal keeps pastes apart from keys
+    Paste(String),
+    Resize,
+    FocusGained,
+    FocusLost,
+    /// anything else, like the mouse
+    Other,
+}
+
+/// Set up the terminal as `ratatui::init` does, with key releases reported where the
+/// terminal supports it and pasted text kept apart from keys.
+pub fn init() -> io::Result<Tui> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = leave(&mut io::stdout());
+        hook(info);
+    }));
+    enter(&mut io::stdout())?;
+    Terminal::new(Backend::new(io::stdout()))
+}
+
+/// Undo [`init`].
+pub fn restore() -> io::Result<()> {
+    leave(&mut io::stdout())
+}
+
+/// Put the terminal in raw mode and switch `writer` to the alternate screen, with the
+/// keyboard set up as [`init`] does.
+pub fn enter(writer: &mut impl Write) -> io::Result<()> {
+    backend::enter(writer)
+}
+
+/// Undo [`enter`].
+pub fn leave(writer: &mut impl Write) -> io::Result<()> {
+    backend::leave(writer)
+}
+
+/// crossterm, the default, on every platform.
+#[cfg(all(
+    feature = "crossterm",
+    not(any(feature = "termion", feature = "termwiz"))
+))]
+mod backend {
+    use std::{
+        io::{self, Write},
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    use ratatui::crossterm::{
+        event::{
+            self, DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste,
+            EnableFocusChange, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+            PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        },
+        execute, terminal,
+    };
+
+    use super::{Event, KeyCode, KeyEvent};
+
+    pub type Backend<W> = ratatui::backend::CrosstermBackend<W>;
+
+    /// The terminal speaks the kitty keyboard protocol and reports key releases.
+    static ENHANCED: AtomicBool = AtomicBool::new(false);
+
+    pub fn enter(writer: &mut impl Write) -> io::Result<()> {
+        terminal::enable_raw_mode()?;
+        execute!(writer, terminal::EnterAlternateScreen)?;
+        enhance(writer)
+    }
+
+    pub fn leave(writer: &mut impl Write) -> io::Result<()> {
+        release(writer)?;
+        terminal::disable_raw_mode()?;
+        execute!(writer, terminal::LeaveAlternateScreen)
+    }
+
+    /// Turn on bracketed paste on `writer`, so pasted text arrives as one paste rather
+    /// than a burst of keys, and opt into the kitty keyboard protocol if the terminal
+    /// supports it, for presses and releases told apart and modifiers on every key. Raw
+    /// mode has to be on.
+    fn enhance(writer: &mut impl Write) -> io::Result<()> {
+        execute!(writer, EnableBracketedPaste)?;
+        // terminals that don't answer the query at all are as good as a no
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+            execute!(writer, PushKeyboardEnhancementFlags(flags))?;
+            ENHANCED.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Undo [`enhance`].
+    fn release(writer: &mut impl Write) -> io::Result<()> {
+        execute!(writer, DisableBracketedPaste)?;
+        if ENHANCED.swap(false, Ordering::Relaxed) {
+            execute!(writer, PopKeyboardEnhancementFlags)?;
+        }
+        Ok(())
+    }
+
+    /// Whether keys are reported as they come back up as well as when they go down.
+    pub fn reports_releases() -> bool {
+        cfg!(windows) || ENHANCED.load(Ordering::Relaxed)
+    }
+
+    /// Have the terminal report when it gains and loses focus, or stop it.
+    pub fn report_focus(writer: &mut impl Write, on: bool) -> io::Result<()> {
+        match on {
+            true => execute!(writer, EnableFocusChange),
+            false => execute!(writer, DisableFocusChange),
+        }
+    }
+
+    /// Whether an event comes in before `timeout`, ready to [`read`].
+    pub fn poll(timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    /// The next event, waiting for one if none has come in.
+    pub fn read() -> io::Result<Event> {
+        Ok(match event::read()? {
+            event::Event::Key(key) => Event::Key(KeyEvent {
+                code: match key.code {
+                    event::KeyCode::Char(c) => KeyCode::Char(c),
+                    event::KeyCode::Enter => KeyCode::Enter,
+                    event::KeyCode::Esc => KeyCode::Esc,
+                    event::KeyCode::Backspace => KeyCode::Backspace,
+                    event::KeyCode::Tab => KeyCode::Tab,
+                    event::KeyCode::BackTab => KeyCode::BackTab,
+                    event::KeyCode::Up => KeyCode::Up,
+                    event::KeyCode::Down => KeyCode::Down,
+                    event::KeyCode::Left => KeyCode::Left,
+                    event::KeyCode::Right => KeyCode::Right,
+                    event::KeyCode::Home => KeyCode::Home,
+                    event::KeyCode::End => KeyCode::End,
+                    _ => KeyCode::Other,
+                },
+                kind: match key.kind {
+                    KeyEventKind::Press => super::KeyEventKind::Press,
+                    KeyEventKind::Repeat => super::KeyEventKind::Repeat,
+                    KeyEventKind::Release => super::KeyEventKind::Release,
+                },
+                ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+                alt: key.modifiers.intersects(
+                    KeyModifiers::ALT
+                        | KeyModifiers::SUPER
+                        | KeyModifiers::HYPER
+                        | KeyModifiers::META,
+                ),
+            }),
+            event::Event::Paste(text) => Event::Paste(text),
+            event::Event::Resize(..) => Event::Resize,
+            event::Event::FocusGained => Event::FocusGained,
+            event::Event::FocusLost => Event::FocusLost,
+            event::Event::Mouse(_) => Event::Other,
+        })
+    }
+}
+
+/// termion, for Unix terminals crossterm gets wrong. It reads `/dev/tty` itself, so input
+/// comes from the terminal even when stdin doesn't. Termion has no bracketed paste, focus
+/// reports, or kitty keyboard protocol: pastes arrive as keys, the game doesn't pause when
+/// the terminal loses focus, and keys act when pressed.
+#[cfg(feature = "termion")]
+mod backend {
+    use std::{
+        fs::File,
+        io::{self, Write},
+        sync::{
+            mpsc::{self, Receiver, RecvTimeoutError},
+            Mutex, OnceLock,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use ratatui::termion::{
+        self,
+        event::{self, Key},
+        input::TermRead,
+        raw::{IntoRawMode, RawTerminal},
+        screen::{ToAlternateScreen, ToMainScreen},
+    };
+
+    use super::{Event, KeyCode, KeyEvent, KeyEventKind};
+
+    pub type Backend<W> = ratatui::backend::TermionBackend<W>;
+
+    /// The terminal while it's in raw mode, which puts it back as it was when dropped.
+    static RAW: Mutex<Option<RawTerminal<File>>> = Mutex::new(None);
+
+    /// Events read from the terminal, by a thread of their own since termion's reads
+    /// block.
+    static EVENTS: OnceLock<Mutex<Events>> = OnceLock::new();
+
+    /// Termion has no event for a resize, so the size is checked this often instead.
+    const RESIZE_CHECK: Duration = Duration::from_millis(100);
+
+    struct Events {
+        receiver: Receiver<io::Result<event::Event>>,
+        /// the event found by the last poll, for the next read
+        waiting: Option<Event>,
+        /// columns and rows when last looked at
+        size: (u16, u16),
+    }
+    impl Events {
+        fn start() -> io::Result<Mutex<Self>> {
+            let tty = termion::get_tty()?;
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                for event in tty.events() {
+                    if sender.send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+            Ok(Mutex::new(Self {
+                receiver,
+                waiting: None,
+                size: termion::terminal_size().unwrap_or_default(),
+            }))
+        }
+
+        fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+            let deadline = Instant::now() + timeout;
+            loop {
+                if self.waiting.is_some() {
+                    return Ok(true);
+                }
+                let size = termion::terminal_size().unwrap_or(self.size);
+                if size != self.size {
+                    self.size = size;
+                    self.waiting = Some(Event::Resize);
+                    return Ok(true);
+                }
+                let left = deadline.saturating_duration_since(Instant::now());
+                match self.receiver.recv_timeout(left.min(RESIZE_CHECK)) {
+                    Ok(event) => self.waiting = Some(convert(event?)),
+                    Err(RecvTimeoutError::Timeout) if left.is_zero() => return Ok(false),
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Err(io::ErrorKind::UnexpectedEof.into())
+                    }
+                }
+            }
+        }
+    }
+
+    fn events() -> io::Result<std::sync::MutexGuard<'static, Events>> {
+        let events = match EVENTS.get() {
+            Some(events) => events,
+            None => {
+                let started = Events::start()?;
+                EVENTS.get_or_init(|| started)
+            }
+        };
+        Ok(events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    fn raw() -> std::sync::MutexGuard<'static, Option<RawTerminal<File>>> {
+        RAW.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn enter(writer: &mut impl Write) -> io::Result<()> {
+        *raw() = Some(termion::get_tty()?.into_raw_mode()?);
+        write!(writer, "{ToAlternateScreen}")?;
+        writer.flush()
+    }
+
+    pub fn leave(writer: &mut impl Write) -> io::Result<()> {
+        raw().take();
+        write!(writer, "{ToMainScreen}")?;
+        writer.flush()
+    }
+
+    pub fn reports_releases() -> bool {
+        false
+    }
+
+    pub fn report_focus(_writer: &mut impl Write, _on: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn poll(timeout: Duration) -> io::Result<bool> {
+        events()?.poll(timeout)
+    }
+
+    /// The next event, waiting for one if none has come in.
+    pub fn read() -> io::Result<Event> {
+        let mut events = events()?;
+        while !events.poll(Duration::from_secs(1))? {}
+        Ok(events.waiting.take().unwrap_or(Event::Other))
+    }
+
+    fn convert(event: event::Event) -> Event {
+        let event::Event::Key(key) = event else {
+            return Event::Other;
+        };
+        let (code, ctrl, alt) = match key {
+            Key::Char('\n' | '\r') => (KeyCode::Enter, false, false),
+            Key::Char('\t') => (KeyCode::Tab, false, false),
+            Key::Char(c) => (KeyCode::Char(c), false, false),
+            Key::Ctrl(c) => (KeyCode::Char(c), true, false),
+            Key::Alt(c) => (KeyCode::Char(c), false, true),
+            Key::Esc => (KeyCode::Esc, false, false),
+            Key::Backspace => (KeyCode::Backspace, false, false),
+            Key::BackTab => (KeyCode::BackTab, false, false),
+            Key::Up | Key::ShiftUp => (KeyCode::Up, false, false),
+            Key::Down | Key::ShiftDown => (KeyCode::Down, false, false),
+            Key::Left | Key::ShiftLeft => (KeyCode::Left, false, false),
+            Key::Right | Key::ShiftRight => (KeyCode::Right, false, false),
+            Key::CtrlUp => (KeyCode::Up, true, false),
+            Key::CtrlDown => (KeyCode::Down, true, false),
+            Key::CtrlLeft => (KeyCode::Left, true, false),
+            Key::CtrlRight => (KeyCode::Right, true, false),
+            Key::AltUp => (KeyCode::Up, false, true),
+            Key::AltDown => (KeyCode::Down, false, true),
+            Key::AltLeft => (KeyCode::Left, false, true),
+            Key::AltRight => (KeyCode::Right, false, true),
+            Key::Home => (KeyCode::Home, false, false),
+            Key::CtrlHome => (KeyCode::Home, true, false),
+            Key::End => (KeyCode::End, false, false),
+            Key::CtrlEnd => (KeyCode::End, true, false),
+            _ => (KeyCode::Other, false, false),
+        };
+        Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ctrl,
+            alt,
+        })
+    }
+}
+
+/// termwiz, for terminals crossterm gets wrong on Unix and Windows alike. Termwiz sets the
+/// terminal up and reads it, from `/dev/tty` or the Windows console, and frames are
+/// written as the escape sequences termwiz spells out. There are no focus reports or
+/// kitty keyboard protocol: the game doesn't pause when the terminal loses focus, and
+/// keys act when pressed.
+#[cfg(all(feature = "termwiz", not(feature = "termion")))]
+mod backend {
+    use std::{
+        io::{self, Write},
+        sync::{Mutex, MutexGuard},
+        time::Duration,
+    };
+
+    use ratatui::{
+        backend::{ClearType, WindowSize},
+        buffer::Cell,
+        layout::{Position, Size},
+        style::{Color, Modifier},
+    };
+    use termwiz::{
+        caps::Capabilities,
+        cell::{Blink, Intensity, Underline},
+        color::ColorSpec,
+        escape::{
+            csi::{
+                Cursor, DecPrivateMode, DecPrivateModeCode, Edit, EraseInDisplay, EraseInLine,
+                Mode, Sgr,
+            },
+            OneBased, CSI,
+        },
+        input::{self, InputEvent, Modifiers},
+        terminal::{SystemTerminal, Terminal},
+    };
+
+    use super::{Event, KeyCode, KeyEvent, KeyEventKind};
+
+    /// The terminal while it's set up for the game, which puts it back as it was when
+    /// dropped.
+    static TERMINAL: Mutex<Option<SystemTerminal>> = Mutex::new(None);
+
+    fn terminal() -> MutexGuard<'static, Option<SystemTerminal>> {
+        TERMINAL
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn error(err: termwiz::Error) -> io::Error {
+        io::Error::other(err.to_string())
+    }
+
+    /// The terminal when it's set up, or an error for reading one that isn't.
+    fn with_terminal<T>(
+        f: impl FnOnce(&mut SystemTerminal) -> termwiz::Result<T>,
+    ) -> io::Result<T> {
+        match terminal().as_mut() {
+            Some(terminal) => f(terminal).map_err(error),
+            None => Err(io::Error::other("the terminal isn't set up")),
+        }
+    }
+
+    pub fn enter(_writer: &mut impl Write) -> io::Result<()> {
+        let caps = Capabilities::new_from_env().map_err(error)?;
+        let mut terminal = SystemTerminal::new(caps).map_err(error)?;
+        terminal.set_raw_mode().map_err(error)?;
+        terminal.enter_alternate_screen().map_err(error)?;
+        terminal.flush().map_err(error)?;
+        *self::terminal() = Some(terminal);
+        Ok(())
+    }
+
+    pub fn leave(_writer: &mut impl Write) -> io::Result<()> {
+        terminal().take();
+        Ok(())
+    }
+
+    pub fn reports_releases() -> bool {
+        false
+    }
+
+    pub fn report_focus(_writer: &mut impl Write, _on: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// The event polled for but not yet read.
+    static WAITING: Mutex<Option<Event>> = Mutex::new(None);
+
+    fn waiting() -> MutexGuard<'static, Option<Event>> {
+        WAITING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn poll(timeout: Duration) -> io::Result<bool> {
+        if waiting().is_some() {
+            return Ok(true);
+        }
+        let event = with_terminal(|terminal| terminal.poll_input(Some(timeout)))?;
+        let found = event.is_some();
+        *waiting() = event.map(convert);
+        Ok(found)
+    }
+
+    /// The next event, waiting for one if none has come in.
+    pub fn read() -> io::Result<Event> {
+        if let Some(event) = waiting().take() {
+            return Ok(event);
+        }
+        loop {
+            if let Some(event) = with_terminal(|terminal| terminal.poll_input(None))? {
+                return Ok(convert(event));
+            }
+        }
+    }
+
+    fn convert(event: InputEvent) -> Event {
+        let key = match event {
+            InputEvent::Key(key) => key,
+            InputEvent::Paste(text) => return Event::Paste(text),
+            InputEvent::Resized { .. } => return Event::Resize,
+            InputEvent::Mouse(_) | InputEvent::PixelMouse(_) | InputEvent::Wake => {
+                return Event::Other
+            }
+        };
+        let code = match key.key {
+            input::KeyCode::Tab if key.modifiers.contains(Modifiers::SHIFT) => KeyCode::BackTab,
+            input::KeyCode::Char(c) => KeyCode::Char(c),
+            input::KeyCode::Enter => KeyCode::Enter,
+            input::KeyCode::Escape => KeyCode::Esc,
+            input::KeyCode::Backspace => KeyCode::Backspace,
+            input::KeyCode::Tab => KeyCode::Tab,
+            input::KeyCode::UpArrow | input::KeyCode::ApplicationUpArrow => KeyCode::Up,
+            input::KeyCode::DownArrow | input::KeyCode::ApplicationDownArrow => KeyCode::Down,
+            input::KeyCode::LeftArrow | input::KeyCode::ApplicationLeftArrow => KeyCode::Left,
+            input::KeyCode::RightArrow | input::KeyCode::ApplicationRightArrow => KeyCode::Right,
+            input::KeyCode::Home => KeyCode::Home,
+            input::KeyCode::End => KeyCode::End,
+            _ => KeyCode::Other,
+        };
+        Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ctrl: key.modifiers.contains(Modifiers::CTRL),
+            alt: key.modifiers.intersects(Modifiers::ALT | Modifiers::SUPER),
+        })
+    }
+
+    /// Draws frames on `writer` as escape sequences, sized by the terminal termwiz has
+    /// set up.
+    #[derive(Debug)]
+    pub struct Backend<W: Write> {
+        writer: W,
+        /// where the cursor was last put
+        cursor: Position,
+    }
+    impl<W: Write> Backend<W> {
+        pub fn new(writer: W) -> Self {
+            Self {
+                writer,
+                cursor: Position::ORIGIN,
+            }
+        }
+    }
+    impl<W: Write> Write for Backend<W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writer.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.writer.flush()
+        }
+    }
+    impl<W: Write> ratatui::backend::Backend for Backend<W> {
+        fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+        where
+            I: Iterator<Item = (u16, u16, &'a Cell)>,
+        {
+            use std::fmt::Write as _;
+
+            let mut text = String::new();
+            let (mut fg, mut bg, mut modifier) = (Color::Reset, Color::Reset, Modifier::empty());
+            let mut last: Option<Position> = None;
+            for (x, y, cell) in content {
+                if !last.is_some_and(|last| x == last.x + 1 && y == last.y) {
+                    let _ = write!(text, "{}", goto(x, y));
+                }
+                last = Some(Position { x, y });
+                // switching attributes off one by one isn't spelled the same everywhere,
+                // so they're all reset and the new ones set
+                if cell.modifier != modifier {
+                    let _ = write!(text, "{}", CSI::Sgr(Sgr::Reset));
+                    for sgr in modifier_sgrs(cell.modifier) {
+                        let _ = write!(text, "{}", CSI::Sgr(sgr));
+                    }
+                    (fg, bg, modifier) = (Color::Reset, Color::Reset, cell.modifier);
+                }
+                if cell.fg != fg {
+                    let _ = write!(text, "{}", CSI::Sgr(Sgr::Foreground(color(cell.fg))));
+                    fg = cell.fg;
+                }
+                if cell.bg != bg {
+                    let _ = write!(text, "{}", CSI::Sgr(Sgr::Background(color(cell.bg))));
+                    bg = cell.bg;
+                }
+                text.push_str(cell.symbol());
+            }
+            write!(self.writer, "{text}{}", CSI::Sgr(Sgr::Reset))
+        }
+
+        fn hide_cursor(&mut self) -> io::Result<()> {
+            self.cursor_visible(false)
+        }
+
+        fn show_cursor(&mut self) -> io::Result<()> {
+            self.cursor_visible(true)
+        }
+
+        fn get_cursor_position(&mut self) -> io::Result<Position> {
+            Ok(self.cursor)
+        }
+
+        fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+            self.cursor = position.into();
+            write!(self.writer, "{}", goto(self.cursor.x, self.cursor.y))?;
+            self.writer.flush()
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            self.clear_region(ClearType::All)
+        }
+
+        fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+            let edit = match clear_type {
+                ClearType::All => Edit::EraseInDisplay(EraseInDisplay::EraseDisplay),
+                ClearType::AfterCursor => Edit::EraseInDisplay(EraseInDisplay::EraseToEndOfDisplay),
+                ClearType::BeforeCursor => {
+                    Edit::EraseInDisplay(EraseInDisplay::EraseToStartOfDisplay)
+                }
+                ClearType::CurrentLine => Edit::EraseInLine(EraseInLine::EraseLine),
+                ClearType::UntilNewLine => Edit::EraseInLine(EraseInLine::EraseToEndOfLine),
+            };
+            write!(self.writer, "{}", CSI::Edit(edit))?;
+            self.writer.flush()
+        }
+
+        fn size(&self) -> io::Result<Size> {
+            let size = with_terminal(|terminal| terminal.get_screen_size())?;
+            Ok(Size::new(clamp(size.cols), clamp(size.rows)))
+        }
+
+        fn window_size(&mut self) -> io::Result<WindowSize> {
+            let size = with_terminal(|terminal| terminal.get_screen_size())?;
+            Ok(WindowSize {
+                columns_rows: Size::new(clamp(size.cols), clamp(size.rows)),
+                pixels: Size::new(
+                    clamp(size.cols * size.xpixel),
+                    clamp(size.rows * size.ypixel),
+                ),
+            })
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.writer.flush()
+        }
+    }
+    impl<W: Write> Backend<W> {
+        fn cursor_visible(&mut self, visible: bool) -> io::Result<()> {
+            let mode = DecPrivateMode::Code(DecPrivateModeCode::ShowCursor);
+            let mode = match visible {
+                true => Mode::SetDecPrivateMode(mode),
+                false => Mode::ResetDecPrivateMode(mode),
+            };
+            write!(self.writer, "{}", CSI::Mode(mode))?;
+            self.writer.flush()
+        }
+    }
+
+    fn goto(x: u16, y: u16) -> CSI {
+        CSI::Cursor(Cursor::Position {
+            line: OneBased::from_zero_based(u32::from(y)),
+            col: OneBased::from_zero_based(u32::from(x)),
+        })
+    }
+
+    fn clamp(n: usize) -> u16 {
+        u16::try_from(n).unwrap_or(u16::MAX)
+    }
+
+    /// The attributes to set for `modifier`, starting from none.
+    fn modifier_sgrs(modifier: Modifier) -> Vec<Sgr> {
+        [
+            (Modifier::BOLD, Sgr::Intensity(Intensity::Bold)),
+            (Modifier::DIM, Sgr::Intensity(Intensity::Half)),
+            (Modifier::ITALIC, Sgr::Italic(true)),
+            (Modifier::UNDERLINED, Sgr::Underline(Underline::Single)),
+            (Modifier::SLOW_BLINK, Sgr::Blink(Blink::Slow)),
+            (Modifier::RAPID_BLINK, Sgr::Blink(Blink::Rapid)),
+            (Modifier::REVERSED, Sgr::Inverse(true)),
+            (Modifier::HIDDEN, Sgr::Invisible(true)),
+            (Modifier::CROSSED_OUT, Sgr::StrikeThrough(true)),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| modifier.contains(*flag))
+        .map(|(_, sgr)| sgr)
+        .collect()
+    }
+
+    fn color(color: Color) -> ColorSpec {
+        match color {
+            Color::Reset => ColorSpec::Default,
+            Color::Black => ColorSpec::PaletteIndex(0),
+            Color::Red => ColorSpec::PaletteIndex(1),
+            Color::Green => ColorSpec::PaletteIndex(2),
+            Color::Yellow => ColorSpec::PaletteIndex(3),
+            Color::Blue => ColorSpec::PaletteIndex(4),
+            Color::Magenta => ColorSpec::PaletteIndex(5),
+            Color::Cyan => ColorSpec::PaletteIndex(6),
+            Color::Gray => ColorSpec::PaletteIndex(7),
+            Color::DarkGray => ColorSpec::PaletteIndex(8),
+            Color::LightRed => ColorSpec::PaletteIndex(9),
+            Color::LightGreen => ColorSpec::PaletteIndex(10),
+            Color::LightYellow => ColorSpec::PaletteIndex(11),
+            Color::LightBlue => ColorSpec::PaletteIndex(12),
+            Color::LightMagenta => ColorSpec::PaletteIndex(13),
+            Color::LightCyan => ColorSpec::PaletteIndex(14),
+            Color::White => ColorSpec::PaletteIndex(15),
+            Color::Indexed(index) => ColorSpec::PaletteIndex(index),
+            Color::Rgb(r, g, b) => ColorSpec::TrueColor((r, g, b).into()),
+        }
+    }
+}
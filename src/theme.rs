@@ -0,0 +1,74 @@
+use ratatui::{buffer::Buffer, style::Color};
+
+/// The colors a frame is drawn in, picked in the config file and changed live when it's
+/// saved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// the terminal's own colors
+    #[default]
+    Classic,
+    /// white on the green cloth of a casino table
+    Felt,
+    /// bright colors on black, for screens and eyes that need more contrast
+    HighContrast,
+}
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Classic, Theme::Felt, Theme::HighContrast];
+
+    /// The green of the table cloth.
+    const FELT: Color = Color::Rgb(0x0b, 0x4f, 0x2c);
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Classic => "classic",
+            Theme::Felt => "felt",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name() == name)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(Theme::name).join(", ");
+                format!("unknown theme '{name}', expected one of {names}")
+            })
+    }
+
+    /// Recolor a frame once everything is drawn on it, before it's made
+    /// [plain](crate::plain::Plain).
+    pub fn apply(self, buf: &mut Buffer) {
+        let (fg, bg) = match self {
+            Theme::Classic => return,
+            Theme::Felt => (Color::White, Self::FELT),
+            Theme::HighContrast => (Color::White, Color::Black),
+        };
+        for cell in buf.content.iter_mut() {
+            if cell.fg == Color::Reset {
+                cell.fg = fg;
+            }
+            if cell.bg == Color::Reset {
+                cell.bg = bg;
+            }
+            // what's drawn on a background of its own, like a card, keeps its colors
+            if self == Theme::HighContrast && cell.bg == Color::Black {
+                cell.fg = brighten(cell.fg);
+            }
+        }
+    }
+}
+
+/// The bright version of a dim terminal color, to stand out on black.
+fn brighten(color: Color) -> Color {
+    match color {
+        Color::DarkGray | Color::Gray => Color::White,
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue => Color::LightBlue,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        color => color,
+    }
+}
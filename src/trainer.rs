@@ -0,0 +1,342 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::{
+    config::*,
+    game::Game,
+    paths,
+    strategy::{Action, ChartRow, Situation},
+};
+
+/// Lifetime count of trainer mistakes in each cell of the strategy chart.
+#[derive(Debug, Default)]
+pub struct MistakeStats(HashMap<String, u32>);
+impl MistakeStats {
+    const FILE: &str = "mistakes.toml";
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    pub fn load() -> Self {
+        let mut stats = MistakeStats::default();
+        let Some(Ok(Some(entries))) = Self::path().map(|path| read_key_values(&path)) else {
+            return stats;
+        };
+        for (_, key, value) in entries {
+            if let Ok(mistakes) = parse_number(&value) {
+                stats.0.insert(key, mistakes);
+            }
+        }
+        stats
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort();
+        let contents: String = entries
+            .into_iter()
+            .map(|(key, mistakes)| format!("{key} = {mistakes}\n"))
+            .collect();
+        fs::write(path, contents)
+    }
+
+    fn record(&mut self, situation: &Situation) {
+        if let Some(row) = ChartRow::of(situation) {
+            *self.0.entry(row.cell_name(situation.up)).or_default() += 1;
+        }
+    }
+
+    /// Mistakes made in one cell of the chart.
+    pub fn get(&self, row: &ChartRow, up: u8) -> u32 {
+        self.0.get(&row.cell_name(up)).copied().unwrap_or_default()
+    }
+
+    /// The cells with the most mistakes, worst first.
+    pub fn worst(&self, count: usize) -> Vec<(&str, u32)> {
+        let mut cells: Vec<_> = self.0.iter().map(|(key, n)| (key.as_str(), *n)).collect();
+        cells.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        cells.truncate(count);
+        cells
+    }
+}
+
+/// How often the player hit and stood in one cell of the strategy chart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub hits: u32,
+    pub stands: u32,
+}
+impl Tally {
+    pub fn decisions(&self) -> u32 {
+        self.hits + self.stands
+    }
+
+    /// Percentage of decisions that were hits, `None` before any were made.
+    pub fn hit_rate(&self) -> Option<f64> {
+        match self.decisions() {
+            0 => None,
+            decisions => Some(f64::from(self.hits) / f64::from(decisions) * 100.0),
+        }
+    }
+
+    /// The action taken more often, `None` before any decisions or on a tie.
+    pub fn habit(&self) -> Option<Action> {
+        match self.hits.cmp(&self.stands) {
+            std::cmp::Ordering::Greater => Some(Action::Hit),
+            std::cmp::Ordering::Less => Some(Action::Stand),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    fn add(self, other: Tally) -> Tally {
+        Tally {
+            hits: self.hits + other.hits,
+            stands: self.stands + other.stands,
+        }
+    }
+}
+
+/// How often each action was taken in each cell of the strategy chart, over earlier
+/// sessions and this one, to show the player's habits and how they drift.
+#[derive(Debug, Default)]
+pub struct ActionStats {
+    /// decisions of earlier sessions
+    past: HashMap<String, Tally>,
+    session: HashMap<String, Tally>,
+}
+impl ActionStats {
+    const FILE: &str = "actions.toml";
+
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// Decisions saved by earlier sessions, saved as `hard 16 vs 10 = 12 hit, 30 stand`.
+    pub fn load() -> Self {
+        let mut stats = ActionStats::default();
+        let Some(Ok(Some(entries))) = Self::path().map(|path| read_key_values(&path)) else {
+            return stats;
+        };
+        for (_, key, value) in entries {
+            let Some((hits, stands)) = value.split_once(',') else {
+                continue;
+            };
+            let count = |text: &str, action: &str| {
+                parse_number(text.trim().strip_suffix(action)?.trim()).ok()
+            };
+            if let (Some(hits), Some(stands)) = (count(hits, "hit"), count(stands, "stand")) {
+                stats.past.insert(key, Tally { hits, stands });
+            }
+        }
+        stats
+    }
+
+    /// Add the session's decisions to the saved ones.
+    pub fn save_session(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut lifetime = self.past.clone();
+        for (key, tally) in &self.session {
+            let total = lifetime.entry(key.clone()).or_default();
+            *total = total.add(*tally);
+        }
+        let mut entries: Vec<_> = lifetime.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let contents: String = entries
+            .into_iter()
+            .map(|(key, tally)| format!("{key} = {} hit, {} stand\n", tally.hits, tally.stands))
+            .collect();
+        fs::write(path, contents)
+    }
+
+    /// Count `taken` in the situation's cell. Only hits and stands are counted.
+    pub fn record(&mut self, situation: &Situation, taken: Action) {
+        let Some(row) = ChartRow::of(situation) else {
+            return;
+        };
+        let tally = self.session.entry(row.cell_name(situation.up)).or_default();
+        match taken {
+            Action::Hit => tally.hits += 1,
+            Action::Stand => tally.stands += 1,
+            _ => (),
+        }
+    }
+
+    /// Decisions made in one cell of the chart this session.
+    pub fn session(&self, row: &ChartRow, up: u8) -> Tally {
+        self.session
+            .get(&row.cell_name(up))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Decisions made in one cell of the chart before this session.
+    pub fn past(&self, row: &ChartRow, up: u8) -> Tally {
+        self.past
+            .get(&row.cell_name(up))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Decisions made in one cell of the chart over every session.
+    pub fn lifetime(&self, row: &ChartRow, up: u8) -> Tally {
+        self.past(row, up).add(self.session(row, up))
+    }
+
+    /// Whether this session's habit in a cell differs from the one of earlier sessions.
+    pub fn drifted(&self, row: &ChartRow, up: u8) -> bool {
+        match (self.session(row, up).habit(), self.past(row, up).habit()) {
+            (Some(now), Some(before)) => now != before,
+            _ => false,
+        }
+    }
+}
+
+/// Grades each of the player's decisions against basic strategy.
+#[derive(Debug, Default)]
+pub struct Trainer {
+    pub decisions: u32,
+    pub correct: u32,
+    pub streak: u32,
+    pub best_streak: u32,
+    /// explanation of the last decision, if it was a mistake
+    pub mistake: Option<String>,
+    pub mistakes: MistakeStats,
+}
+impl Trainer {
+    /// A trainer that adds to the saved mistake statistics.
+    pub fn new() -> Self {
+        Self {
+            mistakes: MistakeStats::load(),
+            ..Self::default()
+        }
+    }
+
+    /// Grade `taken` against the current hand. Call before the action is applied.
+    pub fn grade(&mut self, game: &Game, taken: Action) -> bool {
+        let (recommended, situation) = game.hint();
+        self.decisions += 1;
+        if taken == recommended {
+            self.correct += 1;
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+            self.mistake = None;
+            true
+        } else {
+            self.streak = 0;
+            self.mistakes.record(&situation);
+            self.mistake = Some(format!(
+                "Basic strategy: {} on {situation}",
+                recommended.to_string().to_lowercase()
+            ));
+            false
+        }
+    }
+
+    /// Percentage of decisions that matched basic strategy.
+    pub fn accuracy(&self) -> f64 {
+        if self.decisions == 0 {
+            return 0.0;
+        }
+        f64::from(self.correct) / f64::from(self.decisions) * 100.0
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Streak {} (best {}) · Accuracy {:.0}% ({}/{})",
+            self.streak,
+            self.best_streak,
+            self.accuracy(),
+            self.correct,
+            self.decisions
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shuffle::Shuffler;
+
+    /// A game with 10 bet on a hard 16 against a 10.
+    fn sixteen_against_ten() -> Game {
+        let config = Config {
+            shuffle: Shuffler::stacked("10S 6S 9H 10H"),
+            ..Config::default()
+        };
+        let mut game = Game::new(&config);
+        game.place_bet(10).unwrap();
+        game
+    }
+
+    #[test]
+    fn grades_decisions_against_basic_strategy() {
+        let game = sixteen_against_ten();
+        let mut trainer = Trainer::default();
+        assert!(trainer.grade(&game, Action::Hit));
+        assert!(trainer.grade(&game, Action::Hit));
+        assert!(!trainer.grade(&game, Action::Stand));
+        assert_eq!(
+            trainer.mistake.as_deref(),
+            Some("Basic strategy: hit on hard 16 vs 10")
+        );
+        assert_eq!((trainer.streak, trainer.best_streak), (0, 2));
+        assert_eq!(trainer.summary(), "Streak 0 (best 2) · Accuracy 67% (2/3)");
+        assert!(trainer.grade(&game, Action::Hit));
+        assert_eq!(trainer.mistake, None);
+    }
+
+    #[test]
+    fn mistakes_are_kept_per_cell_across_sessions() {
+        paths::use_test_dir();
+        let game = sixteen_against_ten();
+        let mut trainer = Trainer::default();
+        trainer.grade(&game, Action::Stand);
+        trainer.grade(&game, Action::Stand);
+        trainer.grade(&game, Action::Double);
+        let row = ChartRow::of(&game.hint().1).unwrap();
+        assert_eq!(trainer.mistakes.get(&row, 10), 3);
+        assert_eq!(trainer.mistakes.worst(5), [("hard 16 vs 10", 3)]);
+
+        trainer.mistakes.save().unwrap();
+        assert_eq!(MistakeStats::load().get(&row, 10), 3);
+        assert_eq!(MistakeStats::load().get(&row, 9), 0);
+    }
+
+    #[test]
+    fn tallies_habits_and_how_they_drift() {
+        paths::use_test_dir();
+        let (_, situation) = sixteen_against_ten().hint();
+        let row = ChartRow::of(&situation).unwrap();
+        let mut stats = ActionStats::default();
+        for taken in [Action::Stand, Action::Stand, Action::Hit, Action::Double] {
+            stats.record(&situation, taken);
+        }
+        let tally = stats.session(&row, 10);
+        assert_eq!(tally, Tally { hits: 1, stands: 2 });
+        assert_eq!(tally.habit(), Some(Action::Stand));
+        assert_eq!(Tally::default().hit_rate(), None);
+        assert!(!stats.drifted(&row, 10));
+        stats.save_session().unwrap();
+
+        // the next session reads the last one back and hits instead
+        let mut stats = ActionStats::load();
+        assert_eq!(stats.past(&row, 10), tally);
+        for _ in 0..2 {
+            stats.record(&situation, Action::Hit);
+        }
+        assert!(stats.drifted(&row, 10));
+        assert_eq!(stats.lifetime(&row, 10), Tally { hits: 3, stands: 2 });
+        assert_eq!(stats.lifetime(&row, 10).hit_rate(), Some(60.0));
+    }
+}
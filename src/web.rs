@@ -0,0 +1,321 @@
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::{Constraint::*, Flex, Layout, Margin},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::Paragraph,
+    Frame, Terminal,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    config::Config,
+    game::{Game, GameState, HandResult},
+    money::Money,
+    strategy::Action,
+    widgets::BetScreen,
+};
+
+/// The table in a browser, drawn into an xterm.js terminal by `web/index.html`. The page
+/// hands it the keys typed, calls [`WebTable::tick`] at the dealer's pace, and writes
+/// [`WebTable::frame`] to the terminal whenever it changes.
+#[wasm_bindgen]
+pub struct WebTable {
+    config: Config,
+    game: Game,
+    money: Money,
+    terminal: Terminal<TestBackend>,
+    /// why the last key did nothing, until the next one
+    notice: Option<String>,
+}
+
+#[wasm_bindgen]
+impl WebTable {
+    /// A table with the default rules, drawn `width` columns by `height` rows.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u16, height: u16) -> WebTable {
+        let config = Config::default();
+        let terminal =
+            Terminal::new(TestBackend::new(width, height)).expect("the test backend never fails");
+        WebTable {
+            game: Game::new(&config),
+            money: config.money(),
+            config,
+            terminal,
+            notice: None,
+        }
+    }
+
+    /// Draw `width` columns by `height` rows from the next frame on, after the terminal
+    /// is resized.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.terminal.backend_mut().resize(width, height);
+    }
+
+    /// How long the dealer takes over each card, in milliseconds, to call
+    /// [`WebTable::tick`] at.
+    pub fn dealer_pace(&self) -> u32 {
+        self.config.dealer_pace().as_millis() as u32
+    }
+
+    /// Play `data`, the keys typed as xterm.js hands them over: the arrows or +/- change
+    /// the bet, Enter or space deals and moves on from a hand, and the number keys play
+    /// moves.
+    pub fn keys(&mut self, data: &str) {
+        match data {
+            "\x1b[A" => self.key('+'),
+            "\x1b[B" => self.key('-'),
+            data => data.chars().for_each(|c| self.key(c)),
+        }
+    }
+
+    /// Play the dealer's next card if it's the dealer's turn, returning whether there's
+    /// a new frame to draw.
+    pub fn tick(&mut self) -> bool {
+        let dealing = self.game.is_dealer_turn();
+        self.game.dealer_step();
+        dealing
+    }
+
+    /// The whole table as text with ANSI escapes, to write over the terminal's screen.
+    pub fn frame(&mut self) -> String {
+        let Self {
+            game,
+            money,
+            terminal,
+            notice,
+            ..
+        } = self;
+        let completed = terminal
+            .draw(|frame| draw(frame, game, money, notice.as_deref()))
+            .expect("the test backend never fails");
+        ansi(completed.buffer)
+    }
+}
+impl WebTable {
+    fn key(&mut self, c: char) {
+        let unit = self.config.bet_unit;
+        let game = &mut self.game;
+        let played = match (game.state, c) {
+            (GameState::Betting, '+' | '=') => {
+                game.bet = (game.bet + unit).min(game.bankroll);
+                Ok(())
+            }
+            (GameState::Betting, '-') => {
+                game.bet = game.bet.saturating_sub(unit).max(unit.min(game.bankroll));
+                Ok(())
+            }
+            (GameState::Betting, 'r') if game.bankroll == 0 => {
+                game.bankroll = self.config.bankroll;
+                game.bet = unit.min(game.bankroll);
+                Ok(())
+            }
+            (GameState::Betting, '\r' | ' ') => game.place_bet(game.bet),
+            (GameState::HandScoreScreen(_), '\r' | ' ') => {
+                game.new_hand();
+                Ok(())
+            }
+            (_, c) => match Action::of_key(c) {
+                Some(action) => game.play(action),
+                None => Ok(()),
+            },
+        };
+        self.notice = played.err().map(|err| format!("Not allowed, {err}"));
+    }
+}
+
+fn draw(frame: &mut Frame, game: &Game, money: &Money, notice: Option<&str>) {
+    let [title_area, table_area, status_area] =
+        Layout::vertical([Length(1), Fill(1), Length(1)]).areas(frame.area());
+    frame.render_widget(Line::from(" Blackjack").bold(), title_area);
+    frame.render_widget(
+        Line::from(format!("Bankroll: {} ", money.amount(game.bankroll))).right_aligned(),
+        title_area,
+    );
+
+    match game.state {
+        GameState::Betting => {
+            let rows = 7 + game.side_bets.len() as u16;
+            let [area] = Layout::vertical([Length(rows + 2)])
+                .flex(Flex::Center)
+                .areas(table_area);
+            let [area] = Layout::horizontal([Length(44)])
+                .flex(Flex::Center)
+                .areas(area);
+            frame.render_widget(
+                BetScreen {
+                    bankroll: game.bankroll,
+                    bet: game.bet,
+                    money,
+                    side_bets: game.side_bets.clone(),
+                    suggested: None,
+                    kelly: None,
+                    advice: None,
+                },
+                area,
+            );
+        }
+        _ => {
+            let [left_area, right_area] = Layout::horizontal([Fill(1), Fill(1)]).areas(table_area);
+            frame.render_widget(&game.player_hand, left_area);
+            if let Some(split) = game.split_summary() {
+                let [top, _] = Layout::vertical([Length(1), Fill(1)]).areas(left_area);
+                let line = Line::from(format!(" {split} ")).centered();
+                frame.render_widget(line, top.inner(Margin::new(9, 0)));
+            }
+            match game.rules.double_exposure {
+                // there's no hole card to hide
+                true => {
+                    let mut dealer_hand = game.dealer_hand.clone();
+                    dealer_hand.reveal();
+                    frame.render_widget(&dealer_hand, right_area);
+                }
+                false => frame.render_widget(&game.dealer_hand, right_area),
+            }
+        }
+    }
+
+    let status = match (notice, game.state) {
+        (Some(notice), _) => Line::from(notice.to_string()).red(),
+        (None, GameState::Betting) if game.bankroll == 0 => Line::from("Out of chips.    r) Rebuy"),
+        (None, GameState::Betting) => Line::from("+/-) Bet    Enter) Deal"),
+        (None, GameState::PlayingHand) if game.is_dealer_turn() => Line::from("Dealer plays..."),
+        (None, GameState::PlayingHand) => {
+            let moves: Vec<_> = (1..)
+                .zip(&Action::MOVES)
+                .filter(|(_, action)| game.is_legal(**action))
+                .map(|(key, action)| match action {
+                    Action::Insurance(true) if game.player_hand.is_blackjack() => {
+                        format!("{key}) Even money")
+                    }
+                    action => format!("{key}) {action}"),
+                })
+                .collect();
+            Line::from(moves.join("    "))
+        }
+        (None, GameState::HandScoreScreen(result)) => {
+            let text = format!(
+                "{result:?}, {}    Enter) Next hand",
+                money.signed(game.payout)
+            );
+            match result {
+                HandResult::Blackjack | HandResult::PlayerWin => Line::from(text).green(),
+                HandResult::Push | HandResult::Surrender => Line::from(text).yellow(),
+                HandResult::DealerWin | HandResult::Bust => Line::from(text).red(),
+            }
+        }
+    };
+    frame.render_widget(Paragraph::new(status).centered(), status_area);
+}
+
+/// `buffer` as text with ANSI escapes, from the top left corner, a row a line. Each change
+/// of style resets and sets the whole style, which xterm.js takes in its stride.
+fn ansi(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::from("\x1b[H");
+    for y in area.top()..area.bottom() {
+        let mut style = None;
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+            if cell.skip {
+                continue;
+            }
+            if style != Some(cell.style()) {
+                style = Some(cell.style());
+                text.push_str(&sgr(cell.style()));
+            }
+            text.push_str(cell.symbol());
+        }
+        text.push_str("\x1b[0m");
+        if y + 1 < area.bottom() {
+            text.push_str("\r\n");
+        }
+    }
+    text
+}
+
+/// The escape setting `style`, from no style at all.
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+    if let Some(fg) = style.fg.and_then(|color| color_code(color, 30)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|color| color_code(color, 40)) {
+        codes.push(bg);
+    }
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// The SGR code of `color`, counting from `base`: 30 for the foreground, 40 for the
+/// background. The terminal's own color has none, since every style starts from it.
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let bright = base + 60;
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => bright,
+        Color::LightRed => bright + 1,
+        Color::LightGreen => bright + 2,
+        Color::LightYellow => bright + 3,
+        Color::LightBlue => bright + 4,
+        Color::LightMagenta => bright + 5,
+        Color::LightCyan => bright + 6,
+        Color::White => bright + 7,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{r};{g};{b}", base + 8)),
+        Color::Indexed(i) => return Some(format!("{};5;{i}", base + 8)),
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use super::*;
+
+    #[test]
+    fn writes_styles_as_they_change() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 2));
+        buffer.set_string(0, 0, "ab", Style::new().red().bold());
+        buffer.set_string(0, 1, "c", Style::new().bg(Color::Rgb(1, 2, 3)));
+        assert_eq!(
+            ansi(&buffer),
+            "\x1b[H\x1b[0;31;1mab\x1b[0m \x1b[0m\r\n\x1b[0;48;2;1;2;3mc\x1b[0m  \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn plays_a_hand_from_keys() {
+        let mut table = WebTable::new(80, 24);
+        table.keys("\r");
+        assert!(!matches!(table.game.state, GameState::Betting));
+        // turning down insurance first, in case the dealer shows an ace
+        table.keys("62");
+        while table.tick() {}
+        assert!(matches!(table.game.state, GameState::HandScoreScreen(_)));
+        assert!(table.frame().contains("Enter) Next hand"));
+    }
+}
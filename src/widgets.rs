@@ -2,40 +2,299 @@ use std::{
     fmt::{Display, Write as _},
     iter::zip,
     marker::PhantomData,
+    sync::LazyLock,
 };
 
-use rand::prelude::{thread_rng, SliceRandom};
+use rand::{prelude::SliceRandom, Rng};
 use ratatui::{
     prelude::*,
-    widgets::{Block, List, Widget, WidgetRef},
+    symbols::Marker,
+    widgets::{
+        Axis, Block, Cell, Chart, Clear, Dataset, Gauge, GraphType, LineGauge, List, Paragraph,
+        Row, Table, Tabs, Widget, WidgetRef,
+    },
 };
 
-#[derive(Debug)]
-pub struct Deck(Vec<Card>);
-impl Deck {
-    pub fn new() -> Self {
-        let mut deck = Deck(NEW_DECK.to_vec());
-        deck.shuffle(1);
-        deck
+use crate::{
+    analysis,
+    counting::{Composition, Count},
+    keymap::Keymap,
+    money::Money,
+    rules::Rules,
+    shuffle::{self, Shuffler},
+    side_bets::Wager,
+    stats::{self, GameStats},
+    strategy::*,
+    trainer::{ActionStats, MistakeStats},
+};
+
+/// The cards dealt from, several decks shuffled together. Cards cleared off the table
+/// go to the discard tray, and the shoe is only shuffled whole between hands, once the
+/// cut card has come out. Should the shoe run dry in the middle of a hand, the discards
+/// are shuffled and dealt from, so the cards still on the table stay out of play.
+///
+/// After a shuffle the dealer may burn a card or more face down, and a shuffle may be
+/// made of a few riffles that leave traces of the order the cards were picked up in,
+/// instead of mixing them perfectly.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shoe {
+    /// cards left to draw, the next one last
+    cards: Vec<Card>,
+    /// cards cleared off the table since the shoe was shuffled
+    discards: Vec<Card>,
+    /// how many times the shoe has been shuffled
+    shuffles: u32,
+    decks: u8,
+    /// the cards in the order they were last shuffled to
+    order: Vec<Card>,
+    shuffler: Shuffler,
+    /// cards dealt since the shoe was shuffled whole
+    dealt: usize,
+    /// cards dealt before the cut card comes out, if one is placed
+    cut: Option<usize>,
+    /// the cut card came out and nobody has taken note of it yet
+    cut_out: bool,
+    /// cards burned after every shuffle of the whole shoe
+    burn: u8,
+    /// cards burned since the last shuffle
+    burned: u8,
+    /// riffles in a shuffle, or zero to mix the cards perfectly
+    riffles: u8,
+}
+impl Default for Shoe {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+impl Shoe {
+    /// A shoe of several decks shuffled together, without a cut card.
+    pub fn new(decks: u8) -> Self {
+        let mut shoe = Shoe::unshuffled(decks, Shuffler::Secure);
+        shoe.shuffle_up();
+        shoe
+    }
+
+    /// The shoe `rules` call for, shuffled by `shuffler` every time, like from a seed so
+    /// it and every shoe after it are dealt in the same order each time.
+    pub fn for_table(rules: &Rules, shuffler: Shuffler) -> Self {
+        let mut shoe = Shoe::unshuffled(rules.decks, shuffler);
+        shoe.cut = Some(shoe.size() * usize::from(rules.penetration) / 100);
+        shoe.burn = rules.burn;
+        shoe.riffles = rules.riffles;
+        shoe.shuffle_up();
+        shoe
+    }
+
+    fn unshuffled(decks: u8, shuffler: Shuffler) -> Self {
+        let decks = decks.max(1);
+        Shoe {
+            cards: NEW_DECK.repeat(usize::from(decks)),
+            discards: Vec::new(),
+            shuffles: 0,
+            decks,
+            order: Vec::new(),
+            shuffler,
+            dealt: 0,
+            cut: None,
+            cut_out: false,
+            burn: 0,
+            burned: 0,
+            riffles: 0,
+        }
+    }
+
+    /// Start over with every card shuffled back into the shoe, between hands.
+    pub fn reshuffle(&mut self) {
+        // riffles leave traces of how the cards were picked up, so they're picked up as
+        // they lie, unless some never came back
+        let gathered = self.cards.len() + self.discards.len() == self.size();
+        self.cards = match self.riffles > 0 && gathered {
+            true => {
+                let mut cards = std::mem::take(&mut self.cards);
+                cards.append(&mut self.discards);
+                cards
+            }
+            false => NEW_DECK.repeat(usize::from(self.decks)),
+        };
+        self.discards.clear();
+        self.shuffles += 1;
+        self.dealt = 0;
+        self.cut_out = false;
+        self.shuffle_up();
+        tracing::debug!(
+            shoe = self.shuffles,
+            decks = self.decks,
+            "reshuffled the shoe"
+        );
+    }
+
+    /// Shuffle the whole shoe and burn the cards the rules call for.
+    fn shuffle_up(&mut self) {
+        self.mix();
+        self.burned = 0;
+        for _ in 0..self.burn {
+            let card = self.draw();
+            self.discards.push(card);
+            self.burned += 1;
+        }
+    }
+
+    /// Shuffle the cards left with the shoe's shuffler, riffling them if it's dealt that
+    /// way. A stacked shoe is dealt in its given order the first time round, and a
+    /// scripted one is never riffled, as its script places every card.
+    fn mix(&mut self) {
+        if let (Shuffler::Stacked(order), 0) = (&self.shuffler, self.shuffles) {
+            self.cards = order.iter().rev().copied().collect();
+            self.order.clone_from(&self.cards);
+            return;
+        }
+        if let Shuffler::Scripted(values) = &self.shuffler {
+            shuffle::play_script(&mut self.cards, values);
+        } else if let Some(mut rng) = self.shuffler.rng(self.shuffles) {
+            match self.riffles {
+                0 => self.cards.shuffle(&mut rng),
+                riffles => (0..riffles).for_each(|_| riffle(&mut self.cards, &mut rng)),
+            }
+        }
+        self.order.clone_from(&self.cards);
+    }
+
+    /// Shuffle the discards to deal on from, when the shoe runs dry in the middle of a
+    /// hand.
+    fn shuffle_discards(&mut self) {
+        self.cards.append(&mut self.discards);
+        self.shuffles += 1;
+        self.burned = 0;
+        self.mix();
+        tracing::debug!(
+            shoe = self.shuffles,
+            cards = self.cards.len(),
+            "shuffled the discards"
+        );
     }
 
     pub fn new_hand<T>(&mut self) -> Hand<T> {
         Hand::new([self.draw(), self.draw()])
     }
 
-    fn draw(&mut self) -> Card {
-        if let Some(card) = self.0.pop() {
-            card
-        } else {
-            *self = Deck::new();
-            self.0.pop().unwrap()
+    pub fn draw(&mut self) -> Card {
+        if self.cards.is_empty() {
+            self.shuffle_discards();
         }
+        let card = self
+            .cards
+            .pop()
+            .expect("the cards on the table are never a whole shoe");
+        self.deal_out(card)
+    }
+
+    /// Take the next card matching `pred` out of the shoe, shuffling the discards in if
+    /// none are left.
+    pub fn take(&mut self, pred: impl Fn(&Card) -> bool) -> Card {
+        let index = match self.cards.iter().rposition(&pred) {
+            Some(index) => index,
+            None => {
+                self.shuffle_discards();
+                self.cards
+                    .iter()
+                    .rposition(&pred)
+                    .expect("the cards on the table are never every card of a value")
+            }
+        };
+        let card = self.cards.remove(index);
+        self.deal_out(card)
+    }
+
+    /// Count `card` as dealt, bringing out the cut card when it's reached.
+    fn deal_out(&mut self, card: Card) -> Card {
+        self.dealt += 1;
+        if self.cut == Some(self.dealt) {
+            self.cut_out = true;
+            tracing::debug!(dealt = self.dealt, "the cut card came out");
+        }
+        tracing::trace!(card = %card.short(), remaining = self.cards.len(), "drew a card");
+        card
+    }
+
+    /// Clear cards off the table into the discard tray.
+    pub fn discard(&mut self, cards: impl IntoIterator<Item = Card>) {
+        self.discards.extend(cards);
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// The cards left to draw, the next one last.
+    pub fn undealt(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Every card in the shoe when it's full.
+    pub fn full(&self) -> Vec<Card> {
+        NEW_DECK.repeat(usize::from(self.decks))
+    }
+
+    /// Replace the cards left to draw, to try out the cards that might come, like in an
+    /// analysis.
+    pub fn restock(&mut self, cards: Vec<Card>) {
+        self.cards = cards;
+    }
+
+    /// Cards in the shoe when it's full.
+    fn size(&self) -> usize {
+        NEW_DECK.len() * usize::from(self.decks)
+    }
+
+    /// Whether the cut card has come out, so the shoe is due a shuffle after the hand.
+    pub fn needs_shuffle(&self) -> bool {
+        self.cut.is_some_and(|cut| self.dealt >= cut)
+    }
+
+    /// Whether the cut card came out since the last call.
+    pub fn take_cut_card(&mut self) -> bool {
+        std::mem::take(&mut self.cut_out)
+    }
+
+    pub fn shuffles(&self) -> u32 {
+        self.shuffles
+    }
+
+    /// Cards burned face down since the last shuffle.
+    pub fn burned(&self) -> u8 {
+        self.burned
+    }
+
+    /// Every card the shoe held when it was last shuffled, in the order they're drawn.
+    pub fn shuffled(&self) -> impl Iterator<Item = &Card> {
+        self.order.iter().rev()
     }
 
-    pub fn shuffle(&mut self, num: u8) {
-        let mut rng = thread_rng();
-        for _ in 0..num {
-            self.0.shuffle(&mut rng);
+    /// Shuffle the cards left with `rng`. Dealing shuffles with the shoe's own shuffler,
+    /// so this is for trying out the cards that might come, like in an analysis.
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.cards.shuffle(rng);
+        self.order.clone_from(&self.cards);
+    }
+}
+
+/// One riffle of `cards` as the Gilbert-Shannon-Reeds model has it: cut in two near the
+/// middle, then the cards fall from each half in proportion to how many it has left.
+fn riffle(cards: &mut Vec<Card>, rng: &mut impl Rng) {
+    let cut = (0..cards.len()).filter(|_| rng.gen_bool(0.5)).count();
+    let right = cards.split_off(cut);
+    let left = std::mem::take(cards);
+    let (mut from_left, mut from_right) = (0, 0);
+    while from_left + from_right < left.len() + right.len() {
+        let left_over = left.len() - from_left;
+        let right_over = right.len() - from_right;
+        if rng.gen_range(0..left_over + right_over) < left_over {
+            cards.push(left[from_left]);
+            from_left += 1;
+        } else {
+            cards.push(right[from_right]);
+            from_right += 1;
         }
     }
 }
@@ -46,69 +305,129 @@ pub struct Player;
 pub struct Dealer;
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum HandStatus {
     Active,
     Hold,
+    /// the dealer turned the hole card over and is still drawing
+    Drawing,
+    /// the dealer turned the hole card over and is done
     Revealed,
 }
 
 #[derive(Clone, Copy, Debug)]
 enum HandOwner {
-    Player,
+    /// with the keys that make the hand's moves
+    Player(Keymap),
     Dealer,
 }
 impl Display for HandOwner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let owner = match self {
-            HandOwner::Player => "Player",
+            HandOwner::Player(_) => "Player",
             HandOwner::Dealer => "Dealer",
         };
         write!(f, "{}", owner)
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct Hand<T>(Vec<Card>, HandStatus, PhantomData<T>);
+impl<T> Default for Hand<T> {
+    fn default() -> Self {
+        Self(Vec::new(), HandStatus::Active, PhantomData)
+    }
+}
 impl<T> Hand<T> {
-    fn new(initial: [Card; 2]) -> Self {
+    pub fn new(initial: [Card; 2]) -> Self {
         Self(initial.to_vec(), HandStatus::Active, PhantomData)
     }
 
     pub fn count_value(&self) -> u8 {
-        // sum all non-aces
-        let val = self
-            .0
-            .iter()
-            .filter(|Card(kind, _)| !matches!(kind, Rank::Ace))
-            .fold(0, |acc, Card(kind, _)| acc + kind.get_value());
+        if self.is_soft() {
+            self.hard_value() + 10
+        } else {
+            self.hard_value()
+        }
+    }
 
-        // determine ace values based on existing sum
+    /// Sum of the hand with every ace counted as one.
+    fn hard_value(&self) -> u8 {
         self.0
             .iter()
-            .filter(|Card(kind, _)| matches!(kind, Rank::Ace))
-            .fold(val, |acc, Card(kind, _)| {
-                if (acc + kind.get_value()) > 21 {
-                    acc + 1
-                } else {
-                    acc + kind.get_value()
-                }
+            .map(|Card(kind, _)| match kind {
+                Rank::Ace => 1,
+                _ => kind.get_value(),
             })
+            .sum()
+    }
+
+    /// Whether an ace in the hand is being counted as eleven.
+    pub fn is_soft(&self) -> bool {
+        self.0.iter().any(|Card(kind, _)| matches!(kind, Rank::Ace)) && self.hard_value() + 10 <= 21
+    }
+
+    /// Two cards of the same value, ie. a hand that could be split.
+    pub fn is_pair(&self) -> bool {
+        matches!(self.0[..], [Card(a, _), Card(b, _)] if a.get_value() == b.get_value())
+    }
+
+    /// 21 on the first two cards.
+    pub fn is_blackjack(&self) -> bool {
+        self.0.len() == 2 && self.count_value() == 21
     }
 
     pub fn is_bust(&self) -> bool {
         self.count_value() > 21
     }
 
-    pub fn hit(&mut self, deck: &mut Deck) {
-        self.0.push(deck.draw());
+    pub fn cards(&self) -> &[Card] {
+        &self.0
+    }
+
+    /// The cards in a few characters each, like `A♠ 10♥`.
+    pub fn short(&self) -> String {
+        let cards: Vec<_> = self.0.iter().map(Card::short).collect();
+        cards.join(" ")
+    }
+
+    pub fn hit(&mut self, deck: &mut Shoe) -> Card {
+        let card = deck.draw();
+        self.0.push(card);
+        card
     }
 
     pub fn is_active(&self) -> bool {
-        matches!(self.1, HandStatus::Active)
+        matches!(self.1, HandStatus::Active | HandStatus::Drawing)
     }
 
+    /// Stop drawing. A dealer whose hole card is face up stays that way.
     pub fn hold(&mut self) {
-        self.1 = HandStatus::Hold
+        self.1 = match self.1 {
+            HandStatus::Drawing | HandStatus::Revealed => HandStatus::Revealed,
+            HandStatus::Active | HandStatus::Hold => HandStatus::Hold,
+        }
+    }
+
+    /// Whether every card is face up, which only the dealer's hand ever isn't.
+    fn is_face_up(&self) -> bool {
+        matches!(self.1, HandStatus::Drawing | HandStatus::Revealed)
+    }
+
+    /// A badge for a hand that just made blackjack, 21, or bust, if it did.
+    fn badge(&self) -> Option<Span<'static>> {
+        let (text, color) = match self.count_value() {
+            _ if self.is_blackjack() => (" BLACKJACK! ", Color::Green),
+            21 => (" 21 ", Color::Yellow),
+            22.. => (" BUST ", Color::Red),
+            _ => return None,
+        };
+        Some(Span::styled(
+            text,
+            Style::new().fg(Color::Black).bg(color).bold(),
+        ))
     }
 
     fn render_hand(
@@ -121,11 +440,18 @@ impl<T> Hand<T> {
     {
         let constraints = Constraint::from_lengths((0..6).map(|_| Card::WIDTH).collect::<Vec<_>>());
 
+        // the dealer's hole card stays hidden, and says nothing, until it's turned over
+        let hidden = matches!(owner, HandOwner::Dealer) && !self.is_face_up();
         let mut block = Block::bordered().title(owner.to_string());
-        if matches!(owner, HandOwner::Player) {
+        if let Some(badge) = self.badge().filter(|_| !hidden) {
+            block = block.title(Line::from(badge).right_aligned());
+        }
+        if let HandOwner::Player(keys) = owner {
+            let hit = format!("{}) Hit", keys.key(Action::Hit));
+            let hold = format!("{}) Hold", keys.key(Action::Stand));
             block = block
-                .title_bottom(Line::from("1) Hit").left_aligned())
-                .title_bottom(Line::from("2) Hold").centered())
+                .title_bottom(Line::from(hit).left_aligned())
+                .title_bottom(Line::from(hold).centered())
                 .title_bottom(Line::from("Q) Quit").right_aligned());
         }
 
@@ -158,10 +484,7 @@ impl<T> Hand<T> {
             } else {
                 card_bottom_row[index - 6]
             };
-            if matches!(owner, HandOwner::Dealer)
-                && !matches!(self.1, HandStatus::Revealed)
-                && index == 0
-            {
+            if hidden && index == 0 {
                 FaceDownCard::render(FaceDownCard, layout_rect, buf);
             } else {
                 card.render(layout_rect, buf);
@@ -170,11 +493,13 @@ impl<T> Hand<T> {
 
         // render hand status
         if matches!(owner, HandOwner::Dealer) {
-            Widget::render(
-                List::new([format!("Status: {:?}", self.1)]),
-                status_area,
-                buf,
-            );
+            let mut lines = vec![format!("Status: {:?}", self.1)];
+            // the total only means something once the hole card is turned over
+            if self.is_face_up() {
+                let soft = if self.is_soft() { "Soft " } else { "" };
+                lines.push(format!("Value: {soft}{}", self.count_value()));
+            }
+            Widget::render(List::new(lines), status_area, buf);
         } else {
             Widget::render(
                 List::new([
@@ -188,16 +513,37 @@ impl<T> Hand<T> {
     }
 }
 impl Hand<Dealer> {
-    pub fn do_dealer_action(&mut self, deck: &mut Deck) {
-        if self.count_value() < 16 {
-            self.hit(deck);
+    /// Draw to 17, and on soft 17 as well if the table says so.
+    pub fn do_dealer_action(&mut self, deck: &mut Shoe, hits_soft_17: bool) -> Option<Card> {
+        let value = self.count_value();
+        if value < 17 || (value == 17 && hits_soft_17 && self.is_soft()) {
+            Some(self.hit(deck))
         } else {
             self.hold();
+            None
         }
     }
 
+    /// Turn the hole card over, to draw on if the dealer is still to play.
     pub fn reveal(&mut self) {
-        self.1 = HandStatus::Revealed;
+        self.1 = match self.1 {
+            HandStatus::Active | HandStatus::Drawing => HandStatus::Drawing,
+            HandStatus::Hold | HandStatus::Revealed => HandStatus::Revealed,
+        }
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.is_face_up()
+    }
+
+    /// Swap the face-down card for another, returning the old one.
+    pub fn replace_hole_card(&mut self, card: Card) -> Card {
+        std::mem::replace(&mut self.0[0], card)
+    }
+
+    /// The face-up card, the first card dealt is the hole card.
+    pub fn up_card(&self) -> Card {
+        self.0[1]
     }
 }
 impl<T> Display for Hand<T> {
@@ -241,14 +587,45 @@ impl WidgetRef for Hand<Player> {
     where
         Self: Sized,
     {
-        self.render_hand(area, buf, HandOwner::Player);
+        self.render_hand(area, buf, HandOwner::Player(Keymap::default()));
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Card(Rank, Suit);
+/// The player's hand, with the keys `keys` maps hitting and holding to on its border.
+pub struct KeyedHand<'a> {
+    pub hand: &'a Hand<Player>,
+    pub keys: Keymap,
+}
+impl Widget for KeyedHand<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        self.hand
+            .render_hand(area, buf, HandOwner::Player(self.keys));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Card(Rank, Suit);
 impl Card {
-    const WIDTH: u16 = 11;
+    pub const WIDTH: u16 = 11;
+    pub const HEIGHT: u16 = 7;
+
+    pub const fn new(rank: Rank, suit: Suit) -> Self {
+        Card(rank, suit)
+    }
+
+    pub fn rank(&self) -> Rank {
+        self.0
+    }
+
+    pub fn suit(&self) -> Suit {
+        self.1
+    }
+
+    /// Rank and suit in a few characters, like `A♠`.
+    pub fn short(&self) -> String {
+        format!("{}{}", self.0.get_rank(), self.1)
+    }
 }
 impl Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -262,47 +639,825 @@ impl Widget for Card {
         Self: Sized,
     {
         let Card(rank, suit) = self;
-        let mut card = String::new();
-        let color = suit.color();
-        let _ = writeln!(card, "╭─────────╮");
-        let _ = writeln!(card, "|{:<9}|", format!("{}{}", suit, rank.get_rank()));
-        let _ = writeln!(card, "|         |");
-        let _ = writeln!(card, "|{:^9}|", format!("{}", rank));
-        let _ = writeln!(card, "|         |");
-        let _ = writeln!(card, "|{:>9}|", format!("{}{}", rank.get_rank(), suit));
-        let _ = writeln!(card, "╰─────────╯");
-
-        for (line, row) in zip(card.lines(), area.rows()) {
-            let span = line.fg(color).bg(Color::White);
-            span.render(row, buf);
+        let face = &CARD_FACES[suit as usize * 13 + rank as usize];
+        for (line, row) in zip(face, area.rows()) {
+            line.render(row, buf);
         }
     }
 }
 
-struct FaceDownCard;
+pub struct FaceDownCard;
 impl Widget for FaceDownCard {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
     where
         Self: Sized,
     {
-        let mut card = String::new();
-        let _ = writeln!(card, "╭─────────╮");
-        let _ = writeln!(card, "|{:x<9}|", "");
-        let _ = writeln!(card, "|{:x<9}|", "");
-        let _ = writeln!(card, "|{:x^9}|", "");
-        let _ = writeln!(card, "|{:x<9}|", "");
-        let _ = writeln!(card, "|{:x>9}|", "");
-        let _ = writeln!(card, "╰─────────╯");
+        for (line, row) in zip(&*CARD_BACK, area.rows()) {
+            line.render(row, buf);
+        }
+    }
+}
+
+/// The face of every card, in the order of a new deck, drawn once rather than every frame.
+static CARD_FACES: LazyLock<Vec<Vec<Line<'static>>>> = LazyLock::new(|| {
+    NEW_DECK
+        .iter()
+        .map(|&Card(rank, suit)| {
+            let mut card = String::new();
+            let _ = writeln!(card, "╭─────────╮");
+            let _ = writeln!(card, "|{:<9}|", format!("{}{}", suit, rank.get_rank()));
+            let _ = writeln!(card, "|         |");
+            let _ = writeln!(card, "|{:^9}|", format!("{}", rank));
+            let _ = writeln!(card, "|         |");
+            let _ = writeln!(card, "|{:>9}|", format!("{}{}", rank.get_rank(), suit));
+            let _ = writeln!(card, "╰─────────╯");
+            card_lines(&card, suit.color())
+        })
+        .collect()
+});
 
-        for (line, row) in zip(card.lines(), area.rows()) {
-            let span = line.fg(Color::Blue).bg(Color::White);
-            span.render(row, buf);
+static CARD_BACK: LazyLock<Vec<Line<'static>>> = LazyLock::new(|| {
+    let mut card = String::new();
+    let _ = writeln!(card, "╭─────────╮");
+    let _ = writeln!(card, "|{:x<9}|", "");
+    let _ = writeln!(card, "|{:x<9}|", "");
+    let _ = writeln!(card, "|{:x^9}|", "");
+    let _ = writeln!(card, "|{:x<9}|", "");
+    let _ = writeln!(card, "|{:x>9}|", "");
+    let _ = writeln!(card, "╰─────────╯");
+    card_lines(&card, Color::Blue)
+});
+
+fn card_lines(card: &str, color: Color) -> Vec<Line<'static>> {
+    card.lines()
+        .map(|line| Line::from(line.to_string().fg(color).bg(Color::White)))
+        .collect()
+}
+
+/// A short message popped up at the bottom of the area it's rendered in.
+#[derive(Clone, Debug)]
+pub struct Notice {
+    pub title: String,
+    pub message: String,
+    pub color: Color,
+}
+impl Widget for Notice {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let width = self.message.chars().count().max(self.title.len()) as u16 + 4;
+        let [area] = Layout::vertical([Constraint::Length(3)])
+            .flex(ratatui::layout::Flex::End)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Length(width)])
+            .flex(ratatui::layout::Flex::Center)
+            .areas(area);
+
+        let block = Block::bordered()
+            .title(self.title)
+            .border_style(Style::new().fg(self.color));
+        Clear.render(area, buf);
+        Widget::render(
+            Paragraph::new(self.message).centered().block(block),
+            area,
+            buf,
+        );
+    }
+}
+
+/// Bet entry shown before each hand is dealt.
+pub struct BetScreen<'a> {
+    pub bankroll: u32,
+    pub bet: u32,
+    pub money: &'a Money,
+    /// side bets offered at the table and their stakes
+    pub side_bets: Vec<Wager>,
+    /// bet suggested by the count, when counting is enabled
+    pub suggested: Option<u32>,
+    /// the Kelly bet for the edge the count gives, and that edge, when counting is enabled
+    pub kelly: Option<(u32, f64)>,
+    /// how closely bets have followed the suggestions
+    pub advice: Option<String>,
+}
+impl Widget for BetScreen<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let mut block = Block::bordered()
+            .title("Place Your Bet")
+            .title_bottom(Line::from("Up/Down) Bet").left_aligned())
+            .title_bottom(Line::from("Enter) Deal").centered())
+            .title_bottom(Line::from("q) Quit").right_aligned());
+        let money = self.money;
+
+        let mut lines = vec![
+            Line::from(format!("Bankroll: {}", money.amount(self.bankroll))),
+            Line::from(format!("Bet: {}", money.amount(self.bet))).bold(),
+        ];
+        for Wager { bet, stake } in self.side_bets {
+            lines.push(match stake {
+                0 => Line::from(format!("{}: off  {}) On", bet.name(), bet.key())).dark_gray(),
+                stake => Line::from(format!(
+                    "{}: {}  {}) Off",
+                    bet.name(),
+                    money.amount(stake),
+                    bet.key()
+                )),
+            });
+        }
+        if self.bankroll == 0 {
+            lines.push(Line::from("Out of chips, r) Rebuy").red());
+        }
+        if let Some(suggested) = self.suggested {
+            lines.push(
+                Line::from(format!("Suggested: {}  a) Apply", money.amount(suggested))).cyan(),
+            );
+            block = block.title(Line::from("Counting").right_aligned());
         }
+        if let Some((kelly, edge)) = self.kelly {
+            lines.push(
+                Line::from(format!(
+                    "Kelly: {} at {:+.1}% edge  k) Apply",
+                    money.amount(kelly),
+                    edge * 100.0
+                ))
+                .cyan(),
+            );
+        }
+        if let Some(advice) = self.advice {
+            lines.push(Line::from(format!("Advice: {advice}")));
+        }
+
+        Clear.render(area, buf);
+        Widget::render(Paragraph::new(lines).block(block), area, buf);
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Rank {
+/// Tabs of the statistics screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsTab {
+    Session,
+    Lifetime,
+}
+
+/// Dashboard of the results of this session or of every session.
+pub struct StatsScreen<'a> {
+    pub stats: &'a GameStats,
+    pub tab: StatsTab,
+    /// the bankroll, or net for lifetime, before the first hand and after each one
+    pub trajectory: &'a [i64],
+    /// house edge of basic strategy under the table rules, in percent, and the standard
+    /// deviation of one hand in bets, when the results were all played under them
+    pub odds: Option<(f64, f64)>,
+    /// `odds` are still being simulated
+    pub estimating: bool,
+    /// the rules the results were played under, or `all` for every table
+    pub rules: &'a str,
+    pub money: &'a Money,
+}
+impl StatsScreen<'_> {
+    /// Largest fall from a high point of the trajectory.
+    fn drawdown(&self) -> i64 {
+        let mut peak = i64::MIN;
+        let mut drawdown = 0;
+        for &value in self.trajectory {
+            peak = peak.max(value);
+            drawdown = drawdown.max(peak - value);
+        }
+        drawdown
+    }
+
+    fn summary(&self) -> Vec<Line<'static>> {
+        let stats = self.stats;
+        let mut lines = vec![
+            Line::from(format!("Hands: {}", stats.hands)),
+            Line::from(format!(
+                "Net: {} on {} wagered",
+                self.money.signed(stats.net),
+                self.money.amount(stats.wagered as i64)
+            )),
+        ];
+        let hourly = stats.hands_per_hour();
+        lines.push(Line::from(match hourly {
+            Some(hourly) => format!(
+                "Time: {}, {hourly:.0} hands/hour",
+                stats::clock(stats.seconds)
+            ),
+            None => format!("Time: {}", stats::clock(stats.seconds)),
+        }));
+        if let Some((edge, std_dev)) = self.odds {
+            let average_bet = stats.wagered as f64 / stats.hands.max(1) as f64;
+            let expected = -edge / 100.0 * stats.hands as f64 * average_bet;
+            let luck = stats.net as f64 - expected;
+            lines.push(Line::from(format!(
+                "Expected: {expected:+.0} at a {edge:.2}% edge"
+            )));
+            if let Some(hourly) = hourly {
+                let per_hour = -edge / 100.0 * average_bet * hourly;
+                lines.push(Line::from(format!("Expected an hour: {per_hour:+.1}")));
+            }
+            let line = Line::from(format!("Luck: {luck:+.0} against expected"));
+            lines.push(if luck >= 0.0 {
+                line.green()
+            } else {
+                line.red()
+            });
+            // a net this far from expected, in standard deviations of the net over as many
+            // hands at the average bet
+            let spread = std_dev * average_bet * (stats.hands as f64).sqrt();
+            let deviations = luck / spread.max(f64::EPSILON);
+            let swing = match deviations.abs() {
+                ..1.0 => "an everyday swing",
+                ..2.0 => "a notable swing",
+                ..3.0 => "a rare swing",
+                _ => "a very rare swing",
+            };
+            lines.push(Line::from(format!(
+                "Deviation: {deviations:+.1} SD, {swing}"
+            )));
+        } else if self.estimating {
+            lines.push(Line::from("Expected: estimating the house edge"));
+        }
+        if let Some((mean, std_dev)) = stats.result_spread() {
+            // the 95% confidence interval of the average result
+            let margin = 1.96 * std_dev / (stats.hands as f64).sqrt();
+            lines.push(Line::from(format!(
+                "Per hand: {mean:+.2} ± {margin:.2} bets, SD {std_dev:.2}"
+            )));
+        }
+        lines.push(Line::from(format!("Max drawdown: {}", self.drawdown())));
+        if stats.limits_hit > 0 {
+            lines.push(Line::from(format!("Limits reached: {}", stats.limits_hit)));
+        }
+        if self.tab == StatsTab::Lifetime {
+            lines.push(Line::from(format!("Sessions: {}", stats.sessions)));
+        }
+        lines
+    }
+
+    /// One bar split between the outcomes, with a legend below.
+    fn outcomes(&self, width: u16) -> Vec<Line<'static>> {
+        let stats = self.stats;
+        let outcomes = [
+            ("Blackjack", stats.blackjacks, Color::LightGreen),
+            ("Win", stats.wins - stats.blackjacks, Color::Green),
+            ("Push", stats.pushes, Color::Yellow),
+            ("Loss", stats.losses - stats.busts, Color::Red),
+            ("Bust", stats.busts, Color::LightRed),
+        ];
+        let mut filled = 0;
+        let mut bar = Vec::new();
+        let mut total = 0;
+        for (_, count, color) in outcomes {
+            total += count;
+            let end = (total * u64::from(width)).div_ceil(stats.hands.max(1)) as usize;
+            bar.push(Span::from(" ".repeat(end.saturating_sub(filled))).bg(color));
+            filled = filled.max(end);
+        }
+        let legend = |outcomes: &[(&str, u64, Color)]| {
+            Line::from(
+                outcomes
+                    .iter()
+                    .flat_map(|(name, count, color)| {
+                        [
+                            Span::from("■ ").fg(*color),
+                            Span::from(format!("{name} {:.0}%  ", stats.rate(*count))),
+                        ]
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        };
+        vec![
+            Line::from(bar),
+            legend(&outcomes[..2]),
+            legend(&outcomes[2..]),
+        ]
+    }
+
+    fn render_chart(&self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        let points: Vec<(f64, f64)> = self
+            .trajectory
+            .iter()
+            .enumerate()
+            .map(|(hand, value)| (hand as f64, *value as f64))
+            .collect();
+        let low = self.trajectory.iter().min().copied().unwrap_or_default();
+        let high = self.trajectory.iter().max().copied().unwrap_or_default();
+        let last = self.trajectory.len().saturating_sub(1).max(1);
+        let name = match self.tab {
+            StatsTab::Session => "Bankroll",
+            StatsTab::Lifetime => "Net",
+        };
+        let dataset = Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().cyan())
+            .data(&points);
+        let chart = Chart::new(vec![dataset])
+            .x_axis(
+                Axis::default()
+                    .bounds([0.0, last as f64])
+                    .labels(["0".to_string(), last.to_string()]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title(name)
+                    .bounds([low as f64, high.max(low + 1) as f64])
+                    .labels([low.to_string(), high.to_string()]),
+            );
+        chart.render(area, buf);
+    }
+}
+impl Widget for StatsScreen<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        use Constraint::{Fill, Length, Min};
+
+        let block = Block::bordered()
+            .title("Statistics")
+            .title_bottom(Line::from("Tab) Session/Lifetime").left_aligned())
+            .title_bottom(Line::from("x) Export    a) Actions").centered())
+            .title_bottom(Line::from("s) Close").right_aligned());
+        let inner_area = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let [tabs_area, stats_area] = Layout::vertical([Length(2), Min(0)]).areas(inner_area);
+        Tabs::new(["Session", "Lifetime"])
+            .select(match self.tab {
+                StatsTab::Session => 0,
+                StatsTab::Lifetime => 1,
+            })
+            .highlight_style(Style::new().bold().reversed())
+            .render(tabs_area, buf);
+        let rules = match self.tab {
+            StatsTab::Session => format!("Rules: {}", self.rules),
+            StatsTab::Lifetime => format!("r) Rules: {}", self.rules),
+        };
+        Line::from(rules).right_aligned().render(tabs_area, buf);
+
+        let stats = self.stats;
+        if stats.hands == 0 {
+            Line::from("No hands played yet").render(stats_area, buf);
+            return;
+        }
+
+        let [left_area, chart_area] = Layout::horizontal([Length(40), Fill(1)])
+            .spacing(1)
+            .areas(stats_area);
+        let summary = self.summary();
+        let [summary_area, win_area, outcomes_area, actions_area] = Layout::vertical([
+            Length(summary.len() as u16 + 1),
+            Length(3),
+            Length(5),
+            Length(5),
+        ])
+        .areas(left_area);
+        Paragraph::new(summary).render(summary_area, buf);
+
+        Gauge::default()
+            .block(Block::bordered().title("Win rate"))
+            .gauge_style(Style::new().green())
+            .ratio(stats.rate(stats.wins) / 100.0)
+            .label(format!(
+                "{:.1}% ± {:.1}%",
+                stats.rate(stats.wins),
+                stats.win_rate_margin()
+            ))
+            .render(win_area, buf);
+
+        let outcomes_block = Block::bordered().title("Outcomes");
+        let outcomes = self.outcomes(outcomes_block.inner(outcomes_area).width);
+        Paragraph::new(outcomes)
+            .block(outcomes_block)
+            .render(outcomes_area, buf);
+
+        let actions_block = Block::bordered().title("Actions");
+        let [hit_area, stand_area, double_area] =
+            Layout::vertical([Length(1); 3]).areas(actions_block.inner(actions_area));
+        actions_block.render(actions_area, buf);
+        let decisions = (stats.hits + stats.stands + stats.doubles).max(1) as f64;
+        for (name, count, area) in [
+            ("Hit", stats.hits, hit_area),
+            ("Stand", stats.stands, stand_area),
+            ("Double", stats.doubles, double_area),
+        ] {
+            let ratio = count as f64 / decisions;
+            LineGauge::default()
+                .label(format!("{name:<6} {:>3.0}%", ratio * 100.0))
+                .filled_style(Style::new().cyan())
+                .ratio(ratio)
+                .render(area, buf);
+        }
+
+        if self.trajectory.len() > 1 {
+            self.render_chart(chart_area, buf);
+        }
+    }
+}
+
+/// Expected value of each legal action for the current hand.
+pub struct AnalysisPanel<'a> {
+    pub evs: &'a [(Action, f64)],
+    /// the chance the hole card was a ten, when the dealer showed an ace
+    pub insurance_tens: Option<f64>,
+}
+impl Widget for AnalysisPanel<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let best = self
+            .evs
+            .iter()
+            .map(|(_, ev)| *ev)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let mut lines: Vec<Line> = self
+            .evs
+            .iter()
+            .map(|(action, ev)| {
+                let line = Line::from(format!("{action:<9} {ev:+.3}"));
+                if *ev == best {
+                    line.green()
+                } else {
+                    line
+                }
+            })
+            .collect();
+        if let Some(tens) = self.insurance_tens {
+            let ev = analysis::insurance_ev(tens);
+            let line = Line::from(format!("{:<9} {ev:+.3}", "Insurance"));
+            lines.push(match ev > 0.0 {
+                true => line.green(),
+                false => line.red(),
+            });
+            let sign = match ev > 0.0 {
+                true => '>',
+                false => '<',
+            };
+            lines.push(Line::from(format!("Tens {:.1}% {sign} 1/3", tens * 100.0)).dark_gray());
+        }
+
+        Widget::render(
+            Paragraph::new(lines).block(Block::bordered().title("EV")),
+            area,
+            buf,
+        );
+    }
+}
+
+/// The chance of each value of the next card, read off the cards left in the shoe. It's a
+/// cheat, so it says so.
+pub struct NextCardPanel {
+    pub shoe: Composition,
+}
+impl NextCardPanel {
+    pub const HEIGHT: u16 = 6;
+
+    const BUCKETS: [(&str, &[Rank]); 4] = [
+        (
+            "2-6",
+            &[Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six],
+        ),
+        ("7-9", &[Rank::Seven, Rank::Eight, Rank::Nine]),
+        ("10", &[Rank::Ten, Rank::Jack, Rank::Queen, Rank::King]),
+        ("A", &[Rank::Ace]),
+    ];
+}
+impl Widget for NextCardPanel {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let lines = Self::BUCKETS
+            .map(|(name, ranks)| format!("{name:<4} {:>5.1}%", self.shoe.chance(ranks) * 100.0));
+        let block = Block::bordered()
+            .title("Cheat: next")
+            .border_style(Style::new().red());
+        Widget::render(List::new(lines).block(block), area, buf);
+    }
+}
+
+/// Running count, true count, and decks remaining for the current shoe.
+pub struct CountPanel {
+    pub count: Count,
+    pub decks_remaining: f64,
+}
+impl Widget for CountPanel {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let mut lines = vec![format!("Running: {:+}", self.count.running)];
+        if self.count.system.is_balanced() {
+            let true_count = self.count.true_count(self.decks_remaining);
+            lines.push(format!("True: {true_count:+.1}"));
+        }
+        lines.push(format!("Decks: {:.1}", self.decks_remaining));
+
+        Widget::render(
+            List::new(lines).block(Block::bordered().title(self.count.system.name())),
+            area,
+            buf,
+        );
+    }
+}
+
+/// The cards left to come by rank, with the chance of each being the next card and how
+/// rich the shoe is in tens and aces against a full deck.
+pub struct CompositionPanel {
+    pub unseen: Composition,
+    /// only the cards the player has seen are known
+    pub realistic: bool,
+}
+impl CompositionPanel {
+    pub const HEIGHT: u16 = 19;
+    pub const WIDTH: u16 = 26;
+
+    /// A density in percent, and how far it is from a full deck's, green where it's in the
+    /// player's favor.
+    fn density(name: &str, density: f64, full: f64) -> Line<'static> {
+        let difference = (density - full) * 100.0;
+        let style = match difference {
+            _ if difference >= 0.05 => Style::new().fg(Color::Green),
+            _ if difference <= -0.05 => Style::new().fg(Color::Red),
+            _ => Style::new(),
+        };
+        Line::from(vec![
+            Span::from(format!("{name}: {:.1}% ", density * 100.0)),
+            Span::styled(format!("({difference:+.1})"), style),
+        ])
+    }
+}
+impl Widget for CompositionPanel {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let header = Row::new(["Rank", "Left", "Next"].map(|label| Cell::from(label).bold()));
+        let rows = Rank::ALL.map(|rank| {
+            Row::new([
+                rank.get_rank().to_string(),
+                self.unseen.count(rank).to_string(),
+                format!("{:.1}%", self.unseen.chance(&[rank]) * 100.0),
+            ])
+        });
+        let (title, toggle) = match self.realistic {
+            true => (
+                format!("Seen only · {} unseen", self.unseen.total()),
+                "i) Exact",
+            ),
+            false => (
+                format!("Shoe · {} left", self.unseen.total()),
+                "i) Seen only",
+            ),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::from(toggle).left_aligned())
+            .title_bottom(Line::from("x) Save").centered())
+            .title_bottom(Line::from("d) Close").right_aligned());
+        let inner_area = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let [table_area, density_area] =
+            Layout::vertical([Constraint::Length(14), Constraint::Length(2)])
+                .spacing(1)
+                .areas(inner_area);
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(6),
+        ];
+        Widget::render(Table::new(rows, widths).header(header), table_area, buf);
+        Paragraph::new(vec![
+            Self::density("Tens", self.unseen.ten_density(), 16.0 / 52.0),
+            Self::density("Aces", self.unseen.ace_density(), 4.0 / 52.0),
+        ])
+        .render(density_area, buf);
+    }
+}
+
+/// Basic strategy chart overlay, generated from the same tables as the hints.
+pub struct StrategyChart<'a> {
+    pub rules: &'a Rules,
+    pub highlight: Option<Situation>,
+    pub scroll: usize,
+}
+impl StrategyChart<'_> {
+    pub const ROWS: usize = 28;
+
+    fn play_style(play: Play) -> Style {
+        match play {
+            Play::Hit => Style::new().fg(Color::White),
+            Play::Stand => Style::new().fg(Color::Yellow),
+            Play::DoubleHit | Play::DoubleStand => Style::new().fg(Color::Green),
+            Play::Split | Play::SplitHit => Style::new().fg(Color::Cyan),
+            Play::SurrenderHit | Play::SurrenderStand | Play::SurrenderSplit => {
+                Style::new().fg(Color::Magenta)
+            }
+        }
+    }
+}
+impl Widget for StrategyChart<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let header = Row::new(
+            ["", "2", "3", "4", "5", "6", "7", "8", "9", "10", "A"]
+                .map(|label| Cell::from(label).bold()),
+        );
+
+        let rows = ChartRow::all().skip(self.scroll).map(|row| {
+            let highlight_row = self.highlight.filter(|situation| row.contains(situation));
+            let mut cells = vec![Cell::from(format!("{:?} {}", row.kind, row.label()))];
+            cells.extend((2..=11).map(|up| {
+                let play = chart_play(row.kind, row.total, up, self.rules);
+                let mut style = Self::play_style(play);
+                if highlight_row.is_some_and(|situation| situation.up == up) {
+                    style = style.reversed();
+                }
+                Cell::from(play.code()).style(style)
+            }));
+            Row::new(cells)
+        });
+
+        let widths = [Constraint::Length(10)]
+            .into_iter()
+            .chain([Constraint::Length(3); 10]);
+        let block = Block::bordered()
+            .title("Basic Strategy")
+            .title_bottom(Line::from("Up/Down) Scroll").left_aligned())
+            .title_bottom(Line::from("c) Close").right_aligned());
+
+        Clear.render(area, buf);
+        Widget::render(
+            Table::new(rows, widths).header(header).block(block),
+            area,
+            buf,
+        );
+    }
+}
+
+/// Trainer mistakes laid out like the strategy chart, hotter where a larger share of the
+/// decisions made were mistakes.
+pub struct MistakeHeatmap<'a> {
+    pub mistakes: &'a MistakeStats,
+    /// every decision made in each cell, to tell how often it goes wrong
+    pub actions: &'a ActionStats,
+    pub scroll: usize,
+}
+impl MistakeHeatmap<'_> {
+    /// Share of the decisions in a cell that were mistakes. Mistakes made before decisions
+    /// were counted could outnumber them, so they're counted as decisions too.
+    fn mistake_rate(&self, row: &ChartRow, up: u8) -> f64 {
+        let mistakes = self.mistakes.get(row, up);
+        let decisions = self.actions.lifetime(row, up).decisions().max(mistakes);
+        match decisions {
+            0 => 0.0,
+            decisions => f64::from(mistakes) / f64::from(decisions),
+        }
+    }
+
+    fn heat_style(rate: f64, worst: f64) -> Style {
+        match rate {
+            0.0 => Style::new().fg(Color::DarkGray),
+            _ if rate * 3.0 <= worst => Style::new().fg(Color::Black).bg(Color::Yellow),
+            _ if rate * 3.0 <= worst * 2.0 => Style::new().fg(Color::Black).bg(Color::LightRed),
+            _ => Style::new().fg(Color::White).bg(Color::Red),
+        }
+    }
+}
+impl Widget for MistakeHeatmap<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let header = Row::new(
+            ["", "2", "3", "4", "5", "6", "7", "8", "9", "10", "A"]
+                .map(|label| Cell::from(label).bold()),
+        );
+
+        let worst = ChartRow::all()
+            .flat_map(|row| (2..=11).map(move |up| (row, up)))
+            .map(|(row, up)| self.mistake_rate(&row, up))
+            .fold(0.0, f64::max);
+        let rows = ChartRow::all().skip(self.scroll).map(|row| {
+            let mut cells = vec![Cell::from(format!("{:?} {}", row.kind, row.label()))];
+            cells.extend((2..=11).map(|up| {
+                let mistakes = self.mistakes.get(&row, up);
+                let text = if mistakes == 0 {
+                    "·".to_string()
+                } else {
+                    mistakes.to_string()
+                };
+                Cell::from(text).style(Self::heat_style(self.mistake_rate(&row, up), worst))
+            }));
+            Row::new(cells)
+        });
+
+        let widths = [Constraint::Length(10)]
+            .into_iter()
+            .chain([Constraint::Length(3); 10]);
+        let block = Block::bordered()
+            .title("Mistakes")
+            .title_bottom(Line::from("Up/Down) Scroll").left_aligned())
+            .title_bottom(Line::from("m) Close").right_aligned());
+
+        Clear.render(area, buf);
+        Widget::render(
+            Table::new(rows, widths).header(header).block(block),
+            area,
+            buf,
+        );
+    }
+}
+
+/// How often the player hits in each cell of the strategy chart, green where their habit
+/// matches basic strategy and red where it doesn't. Cells where this session's habit
+/// differs from earlier sessions' are underlined.
+pub struct ActionChart<'a> {
+    pub actions: &'a ActionStats,
+    pub rules: &'a Rules,
+    /// show this session's decisions rather than every session's
+    pub session: bool,
+    pub scroll: usize,
+}
+impl Widget for ActionChart<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let header = Row::new(
+            ["", "2", "3", "4", "5", "6", "7", "8", "9", "10", "A"]
+                .map(|label| Cell::from(label).bold()),
+        );
+
+        let rows = ChartRow::all().skip(self.scroll).map(|row| {
+            let mut cells = vec![Cell::from(format!("{:?} {}", row.kind, row.label()))];
+            cells.extend((2..=11).map(|up| {
+                let tally = match self.session {
+                    true => self.actions.session(&row, up),
+                    false => self.actions.lifetime(&row, up),
+                };
+                let Some(rate) = tally.hit_rate() else {
+                    return Cell::from("·").dark_gray();
+                };
+                let situation = Situation {
+                    kind: row.kind,
+                    total: row.total,
+                    up,
+                    first_two: false,
+                };
+                // a double or surrender can't be told from hits and stands, so it's neither
+                // right nor wrong
+                let mut style = match (tally.habit(), recommend(&situation, self.rules)) {
+                    (Some(habit), recommended) if habit == recommended => {
+                        Style::new().fg(Color::Green)
+                    }
+                    (Some(_), Action::Hit | Action::Stand) => Style::new().fg(Color::Red),
+                    _ => Style::new().fg(Color::Yellow),
+                };
+                if self.actions.drifted(&row, up) {
+                    style = style.underlined().bold();
+                }
+                // a whole hundred doesn't fit the column
+                Cell::from(format!("{:.0}", rate.min(99.0))).style(style)
+            }));
+            Row::new(cells)
+        });
+
+        let widths = [Constraint::Length(10)]
+            .into_iter()
+            .chain([Constraint::Length(3); 10]);
+        let title = match self.session {
+            true => "Hit % · Session",
+            false => "Hit % · Lifetime",
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title(Line::from("underlined) Drifted").right_aligned())
+            .title_bottom(Line::from("Tab) Session/Lifetime").left_aligned())
+            .title_bottom(Line::from("a) Close").right_aligned());
+
+        Clear.render(area, buf);
+        Widget::render(
+            Table::new(rows, widths).header(header).block(block),
+            area,
+            buf,
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rank {
     Two,
     Three,
     Four,
@@ -318,6 +1473,22 @@ enum Rank {
     Ace,
 }
 impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
     pub const fn get_value(&self) -> u8 {
         match self {
             Rank::Two => 2,
@@ -361,8 +1532,9 @@ impl Display for Rank {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Suit {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Suit {
     Spade,
     Club,
     Diamond,
@@ -449,3 +1621,101 @@ const NEW_DECK: [Card; 52] = [
     Card(Rank::King, Suit::Heart),
     Card(Rank::Ace, Suit::Heart),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::HandResult;
+
+    fn table_shoe(rules: &str) -> Shoe {
+        let mut table = Rules::default();
+        for setting in rules.split(',') {
+            let (key, value) = setting.split_once('=').unwrap();
+            table.set(key, value).unwrap();
+        }
+        Shoe::for_table(&table, Shuffler::Seeded(1))
+    }
+
+    #[test]
+    fn counts_aces_as_one_or_eleven() {
+        let card = |rank| Card::new(rank, Suit::Spade);
+        let hand = Hand::<Player>::new([card(Rank::Ace), card(Rank::Ace)]);
+        assert_eq!((hand.count_value(), hand.is_soft()), (12, true));
+        let mut draws = Shoe::for_table(
+            &Rules::default(),
+            Shuffler::Stacked(vec![card(Rank::King), card(Rank::Nine)]),
+        );
+        let mut hand = Hand::<Player>::new([card(Rank::Ace), card(Rank::Five)]);
+        hand.hit(&mut draws);
+        assert_eq!((hand.count_value(), hand.is_soft()), (16, false));
+        hand.hit(&mut draws);
+        assert!(hand.is_bust());
+        assert!(Hand::<Player>::new([card(Rank::Ace), card(Rank::Jack)]).is_blackjack());
+    }
+
+    #[test]
+    fn the_cut_card_comes_out_at_the_penetration() {
+        let mut shoe = table_shoe("penetration=50");
+        for _ in 0..25 {
+            shoe.draw();
+        }
+        assert!(!shoe.needs_shuffle());
+        shoe.draw();
+        assert!(shoe.needs_shuffle());
+        assert!(shoe.take_cut_card());
+        assert!(!shoe.take_cut_card());
+
+        shoe.reshuffle();
+        assert!(!shoe.needs_shuffle());
+        assert_eq!((shoe.remaining(), shoe.shuffles()), (52, 1));
+    }
+
+    #[test]
+    fn burns_cards_after_every_shuffle() {
+        let mut shoe = table_shoe("decks=2,burn=2");
+        assert_eq!((shoe.remaining(), shoe.burned()), (102, 2));
+        shoe.reshuffle();
+        assert_eq!((shoe.remaining(), shoe.burned()), (102, 2));
+    }
+
+    #[test]
+    fn deals_on_from_the_discards_when_the_shoe_runs_dry() {
+        let mut shoe = Shoe::new(1);
+        let on_table: Vec<_> = (0..4).map(|_| shoe.draw()).collect();
+        let cleared: Vec<_> = (0..48).map(|_| shoe.draw()).collect();
+        shoe.discard(cleared);
+        assert_eq!(shoe.remaining(), 0);
+
+        let card = shoe.draw();
+        assert!(!on_table.contains(&card));
+        assert_eq!((shoe.remaining(), shoe.shuffles()), (47, 1));
+    }
+
+    #[test]
+    fn the_dashboard_counts_doubles_apart() {
+        let mut stats = GameStats::default();
+        stats.record(
+            HandResult::PlayerWin,
+            20,
+            20,
+            &[Action::Hit, Action::Double],
+        );
+        stats.record(HandResult::DealerWin, 10, -10, &[Action::Stand]);
+        let screen = StatsScreen {
+            stats: &stats,
+            tab: StatsTab::Session,
+            trajectory: &[1000, 1020, 1010],
+            odds: None,
+            estimating: false,
+            rules: "1 deck, S17, 3:2",
+            money: &Money::default(),
+        };
+        let area = Rect::new(0, 0, 100, 40);
+        let mut buf = Buffer::empty(area);
+        screen.render(area, &mut buf);
+        let text: String = buf.content.iter().map(|cell| cell.symbol()).collect();
+        for row in ["Hit     33%", "Stand   33%", "Double  33%"] {
+            assert!(text.contains(row), "no '{row}' row");
+        }
+    }
+}